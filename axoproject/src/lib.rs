@@ -32,8 +32,8 @@ pub mod rust;
 #[cfg(test)]
 mod tests;
 
-pub use crate::repo::GithubRepo;
 use crate::repo::GithubRepoInput;
+pub use crate::repo::{ForgeRepo, GithubRepo};
 /// A sorted map impl
 pub type SortedMap<K, V> = std::collections::BTreeMap<K, V>;
 
@@ -394,6 +394,11 @@ impl RepositoryUrl {
     pub fn github_repo(&self) -> Result<GithubRepo> {
         GithubRepoInput::new(self.0.clone())?.parse()
     }
+
+    /// Returns a struct which contains the forge's base URL and the repository's owner and name.
+    pub fn forge_repo(&self) -> Result<ForgeRepo> {
+        ForgeRepo::from_url(&self.0)
+    }
 }
 
 /// Computed info about a package
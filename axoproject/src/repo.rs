@@ -38,6 +38,73 @@ impl fmt::Display for GithubRepo {
     }
 }
 
+/// Represents a repository on a self-hostable forge (Gitea, Forgejo, ...).
+///
+/// Unlike [`GithubRepo`][] this doesn't assume a particular domain, so the
+/// base URL of the forge is kept around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRepo {
+    /// The base URL of the forge, e.g. `https://codeberg.org`
+    pub base_url: String,
+    /// The repository owner.
+    pub owner: String,
+    /// The repository name.
+    pub name: String,
+}
+
+impl ForgeRepo {
+    /// Returns a URL suitable for web access to the repository.
+    pub fn web_url(&self) -> String {
+        format!("{}/{}/{}", self.base_url, self.owner, self.name)
+    }
+
+    /// Parses a repository URL hosted on any forge. Notably, this does not check
+    /// whether the repo actually exists.
+    pub fn from_url(repo_url: &str) -> Result<Self> {
+        let repo_string = repo_url.strip_prefix("git+").unwrap_or(repo_url);
+        if repo_string.starts_with("https://") || repo_string.starts_with("http://") {
+            let parsed = Url::parse(repo_string)?;
+            let segments = parsed
+                .path_segments()
+                .map(|c| c.filter(|s| !s.trim().is_empty()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            // The forge may itself live in a subdirectory, so the owner/name
+            // are always the last two segments
+            if let [prefix @ .., owner, name] = &segments[..] {
+                let mut base_url = parsed.origin().ascii_serialization();
+                for segment in prefix {
+                    base_url.push('/');
+                    base_url.push_str(segment);
+                }
+                return Ok(Self {
+                    base_url,
+                    owner: owner.to_string(),
+                    name: GithubRepoInput::remove_git_suffix(name.to_string()),
+                });
+            }
+        } else if let Some(rest) = repo_string.strip_prefix("git@") {
+            if let Some((host, path)) = rest.split_once(':') {
+                if let Some((owner, name)) = path.trim_end_matches('/').split_once('/') {
+                    if !name.contains('/') {
+                        return Ok(Self {
+                            base_url: format!("https://{host}"),
+                            owner: owner.to_owned(),
+                            name: GithubRepoInput::remove_git_suffix(name.to_owned()),
+                        });
+                    }
+                }
+            }
+        } else {
+            return Err(AxoprojectError::UnknownRepoStyle {
+                url: repo_url.to_owned(),
+            });
+        }
+        Err(AxoprojectError::RepoParseError {
+            repo: repo_url.to_owned(),
+        })
+    }
+}
+
 impl GithubRepoInput {
     pub fn new(repo_string: String) -> Result<Self> {
         // Handle git+https just the same as https
@@ -142,4 +209,26 @@ mod tests {
         assert_eq!(parsed.owner, actual_owner);
         assert_eq!(parsed.name, actual_name);
     }
+
+    #[test]
+    fn it_parses_a_forge_repo_string() {
+        let parsed = ForgeRepo::from_url("https://codeberg.org/forgejo/forgejo.git").unwrap();
+        assert_eq!(parsed.base_url, "https://codeberg.org");
+        assert_eq!(parsed.owner, "forgejo");
+        assert_eq!(parsed.name, "forgejo");
+        assert_eq!(parsed.web_url(), "https://codeberg.org/forgejo/forgejo");
+
+        let parsed = ForgeRepo::from_url("http://localhost:3000/git/me/app/").unwrap();
+        assert_eq!(parsed.base_url, "http://localhost:3000/git");
+        assert_eq!(parsed.owner, "me");
+        assert_eq!(parsed.name, "app");
+    }
+
+    #[test]
+    fn it_parses_a_forge_ssh_repo_string() {
+        let parsed = ForgeRepo::from_url("git@git.example.com:me/app.git").unwrap();
+        assert_eq!(parsed.base_url, "https://git.example.com");
+        assert_eq!(parsed.owner, "me");
+        assert_eq!(parsed.name, "app");
+    }
 }
@@ -256,6 +256,10 @@ pub struct CiInfo {
     /// GitHub CI backend
     #[serde(skip_serializing_if = "Option::is_none")]
     pub github: Option<GithubCiInfo>,
+    /// Forgejo CI backend (uses the same format as GitHub)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forgejo: Option<GithubCiInfo>,
 }
 
 /// Github CI backend
@@ -525,6 +529,9 @@ pub enum ArtifactKind {
     /// An updater executable
     #[serde(rename = "updater")]
     Updater,
+    /// An OCI image layout (container image) tarball
+    #[serde(rename = "oci-image")]
+    OciImage,
     /// Unknown to this version of cargo-dist-schema
     ///
     /// This is a fallback for forward/backward-compat
@@ -677,6 +684,11 @@ impl DistManifest {
 
         // If the url changed, update install_hints
         if let Some(old_url) = old_url {
+            for url in &mut release.hosting.download_urls {
+                if *url == old_url {
+                    url.clone_from(&new_url);
+                }
+            }
             for artifact_name in &release.artifacts {
                 let artifact = self
                     .artifacts
@@ -753,6 +765,15 @@ impl Release {
     pub fn artifact_download_url(&self) -> Option<&str> {
         self.hosting.artifact_download_url()
     }
+    /// Get the base URLs installers should try to download artifacts from, in order
+    pub fn artifact_download_urls(&self) -> Vec<&str> {
+        self.hosting.artifact_download_urls()
+    }
+    /// Get how the artifacts of other versions of this release can be found,
+    /// given the tag this version was announced under
+    pub fn release_url_scheme(&self, tag: &str) -> Option<ReleaseUrlScheme> {
+        self.hosting.release_url_scheme(tag, &self.app_version)
+    }
 }
 
 /// Possible hosting providers
@@ -767,6 +788,51 @@ pub struct Hosting {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub axodotdev: Option<gazenot::ArtifactSet>,
+    /// Hosted on Gitea (or Forgejo) Releases
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gitea: Option<GiteaHosting>,
+    /// Hosted in an S3-compatible bucket
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3: Option<S3Hosting>,
+    /// Hosted in a static directory tree (e.g. an internal mirror)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorHosting>,
+    /// The base URLs installers should try to download artifacts from, in order
+    ///
+    /// If this is empty, installers only use [`Hosting::artifact_download_url`][]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub download_urls: Vec<String>,
+}
+
+/// The placeholder for the version in [`ReleaseUrlScheme::artifact_download_url`][]
+pub const VERSION_PLACEHOLDER: &str = "{version}";
+
+/// How to find the artifacts of any version of an app, on hosts that lay releases out predictably
+#[derive(Clone, Debug)]
+pub struct ReleaseUrlScheme {
+    /// The base URL that a version's artifacts can be downloaded from,
+    /// with [`VERSION_PLACEHOLDER`][] standing in for the version
+    ///
+    /// e.g. `https://github.com/myowner/myrepo/releases/download/v{version}`
+    pub artifact_download_url: String,
+    /// The tag a version is announced under, with [`VERSION_PLACEHOLDER`][]
+    /// standing in for the version
+    ///
+    /// e.g. `v{version}`
+    pub tag: String,
+    /// The base URL that always has the latest stable release's artifacts, if the host has one
+    ///
+    /// e.g. `https://github.com/myowner/myrepo/releases/latest/download`
+    pub latest_artifact_download_url: Option<String>,
+    /// An API endpoint that lists the releases (with their `tag_name`s) newest first,
+    /// if the host has one
+    ///
+    /// e.g. `https://api.github.com/repos/myowner/myrepo/releases`
+    pub releases_api_url: Option<String>,
 }
 
 /// Github Hosting
@@ -782,23 +848,259 @@ pub struct GithubHosting {
     pub repo: String,
 }
 
+/// Gitea Hosting
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct GiteaHosting {
+    /// The URL of the Gitea Release's artifact downloads
+    ///
+    /// e.g. `https://codeberg.org/myowner/myrepo/releases/download/v1.0.0/`
+    pub artifact_download_url: String,
+    /// The URL of the Gitea instance's API
+    ///
+    /// e.g. `https://codeberg.org/api/v1`
+    pub api_url: String,
+    /// The owner of the repo
+    pub owner: String,
+    /// The name of the repo
+    pub repo: String,
+}
+
+/// S3 Hosting
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct S3Hosting {
+    /// The public URL the release's artifacts can be downloaded from
+    ///
+    /// e.g. `https://cdn.example.com/myapp/v1.0.0`
+    pub artifact_download_url: String,
+    /// The S3 API endpoint the bucket lives on
+    ///
+    /// e.g. `https://s3.us-east-1.amazonaws.com`
+    pub endpoint: String,
+    /// The region of the bucket
+    pub region: String,
+    /// The name of the bucket
+    pub bucket: String,
+    /// The key prefix the release's artifacts are uploaded under
+    ///
+    /// e.g. `myapp/v1.0.0`
+    pub prefix: String,
+    /// The key prefix that should be kept pointing at the latest stable release, if any
+    ///
+    /// e.g. `myapp/latest`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_prefix: Option<String>,
+}
+
+/// Static directory mirror Hosting
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MirrorHosting {
+    /// The URL the release's artifacts can be downloaded from
+    ///
+    /// e.g. `https://mirror.example.com/myapp/1.0.0`
+    pub artifact_download_url: String,
+    /// The directory of the release, relative to the root of the mirror
+    ///
+    /// e.g. `myapp/1.0.0`
+    pub path: String,
+}
+
+impl GithubHosting {
+    /// Get the URL of the repo, e.g. `https://github.com/myowner/myrepo`
+    fn repo_url(&self) -> Option<&str> {
+        let (repo_url, _tag) = self
+            .artifact_download_url
+            .rsplit_once("/releases/download/")?;
+        Some(repo_url)
+    }
+    /// Get the base URL the artifacts of the release with the given tag can be downloaded from
+    pub fn artifact_download_url_for_tag(&self, tag: &str) -> Option<String> {
+        Some(format!("{}/releases/download/{tag}", self.repo_url()?))
+    }
+    /// Get the base URL that always has the latest stable release's artifacts
+    pub fn latest_artifact_download_url(&self) -> Option<String> {
+        Some(format!("{}/releases/latest/download", self.repo_url()?))
+    }
+    /// Get the API endpoint that lists the repo's releases
+    pub fn releases_api_url(&self) -> Option<String> {
+        let Self { owner, repo, .. } = self;
+        let host = self.repo_url()?.strip_suffix(&format!("/{owner}/{repo}"))?;
+        if host == "https://github.com" {
+            Some(format!(
+                "https://api.github.com/repos/{owner}/{repo}/releases"
+            ))
+        } else {
+            // GitHub Enterprise Server serves the API under the same host
+            Some(format!("{host}/api/v3/repos/{owner}/{repo}/releases"))
+        }
+    }
+}
+
+impl GiteaHosting {
+    /// Get the base URL the artifacts of the release with the given tag can be downloaded from
+    pub fn artifact_download_url_for_tag(&self, tag: &str) -> Option<String> {
+        let (repo_url, _tag) = self
+            .artifact_download_url
+            .rsplit_once("/releases/download/")?;
+        Some(format!("{repo_url}/releases/download/{tag}"))
+    }
+    /// Get the API endpoint that lists the repo's releases
+    pub fn releases_api_url(&self) -> String {
+        let Self {
+            api_url,
+            owner,
+            repo,
+            ..
+        } = self;
+        format!("{api_url}/repos/{owner}/{repo}/releases")
+    }
+}
+
+impl S3Hosting {
+    /// Get the public URL the bucket's keys can be downloaded under
+    fn public_url(&self) -> Option<&str> {
+        self.artifact_download_url
+            .strip_suffix(&self.prefix)?
+            .strip_suffix('/')
+    }
+    /// Get the base URL the artifacts of the release with the given tag can be downloaded from,
+    /// given the tag of this one
+    pub fn artifact_download_url_for_tag(&self, this_tag: &str, tag: &str) -> Option<String> {
+        let root = self.artifact_download_url.strip_suffix(this_tag)?;
+        Some(format!("{root}{tag}"))
+    }
+    /// Get the base URL that always has the latest stable release's artifacts, if it's kept up
+    pub fn latest_artifact_download_url(&self) -> Option<String> {
+        let latest_prefix = self.latest_prefix.as_ref()?;
+        Some(format!("{}/{latest_prefix}", self.public_url()?))
+    }
+}
+
+impl MirrorHosting {
+    /// Get the base URL the artifacts of the given version can be downloaded from,
+    /// given this one's version
+    pub fn artifact_download_url_for_version(
+        &self,
+        this_version: &str,
+        version: &str,
+    ) -> Option<String> {
+        let root = self.artifact_download_url.strip_suffix(this_version)?;
+        Some(format!("{root}{version}"))
+    }
+}
+
 impl Hosting {
     /// Get the base URL that artifacts should be downloaded from (append the artifact name to the URL)
     pub fn artifact_download_url(&self) -> Option<&str> {
-        let Hosting { axodotdev, github } = &self;
-        // Prefer axodotdev is present, otherwise github
+        let Hosting {
+            axodotdev,
+            github,
+            gitea,
+            s3,
+            mirror,
+            download_urls: _,
+        } = &self;
+        // Prefer axodotdev is present, otherwise s3, otherwise a mirror,
+        // otherwise github, otherwise gitea
         if let Some(host) = &axodotdev {
             return host.set_download_url.as_deref();
         }
+        if let Some(host) = &s3 {
+            return Some(&host.artifact_download_url);
+        }
+        if let Some(host) = &mirror {
+            return Some(&host.artifact_download_url);
+        }
         if let Some(host) = &github {
             return Some(&host.artifact_download_url);
         }
+        if let Some(host) = &gitea {
+            return Some(&host.artifact_download_url);
+        }
         None
     }
+    /// Get how the artifacts of any version can be found, given the tag and version of this one
+    ///
+    /// This uses the same host as [`Hosting::artifact_download_url`][], and is None if that
+    /// host doesn't lay out releases predictably (like axo releases), or if the version
+    /// isn't part of the tag.
+    pub fn release_url_scheme(&self, tag: &str, version: &str) -> Option<ReleaseUrlScheme> {
+        let Hosting {
+            axodotdev,
+            github,
+            gitea,
+            s3,
+            mirror,
+            download_urls: _,
+        } = &self;
+        // Only template the version itself, which comes after the tag's prefix
+        // (like the `my-app-v` of `my-app-v1.0.0`), since the prefix could contain it too
+        let (tag_prefix, tag_suffix) = tag.rsplit_once(version)?;
+        let tag_template = format!("{tag_prefix}{VERSION_PLACEHOLDER}{tag_suffix}");
+        let scheme = |artifact_download_url, latest_artifact_download_url, releases_api_url| {
+            Some(ReleaseUrlScheme {
+                artifact_download_url,
+                tag: tag_template.clone(),
+                latest_artifact_download_url,
+                releases_api_url,
+            })
+        };
+        if axodotdev.is_some() {
+            return None;
+        }
+        if let Some(host) = &s3 {
+            return scheme(
+                host.artifact_download_url_for_tag(tag, &tag_template)?,
+                host.latest_artifact_download_url(),
+                None,
+            );
+        }
+        if let Some(host) = &mirror {
+            return scheme(
+                host.artifact_download_url_for_version(version, VERSION_PLACEHOLDER)?,
+                None,
+                None,
+            );
+        }
+        if let Some(host) = &github {
+            return scheme(
+                host.artifact_download_url_for_tag(&tag_template)?,
+                host.latest_artifact_download_url(),
+                host.releases_api_url(),
+            );
+        }
+        if let Some(host) = &gitea {
+            return scheme(
+                host.artifact_download_url_for_tag(&tag_template)?,
+                None,
+                Some(host.releases_api_url()),
+            );
+        }
+        None
+    }
+    /// Get the base URLs installers should try to download artifacts from, in order
+    pub fn artifact_download_urls(&self) -> Vec<&str> {
+        if self.download_urls.is_empty() {
+            self.artifact_download_url().into_iter().collect()
+        } else {
+            self.download_urls.iter().map(|url| url.as_str()).collect()
+        }
+    }
     /// Gets whether there's no hosting
     pub fn is_empty(&self) -> bool {
-        let Hosting { axodotdev, github } = &self;
-        axodotdev.is_none() && github.is_none()
+        let Hosting {
+            axodotdev,
+            github,
+            gitea,
+            s3,
+            mirror,
+            download_urls: _,
+        } = &self;
+        axodotdev.is_none()
+            && github.is_none()
+            && gitea.is_none()
+            && s3.is_none()
+            && mirror.is_none()
     }
 }
 
@@ -975,3 +1277,57 @@ fn emit() {
     let json_schema = serde_json::to_string_pretty(&schema).unwrap();
     insta::assert_snapshot!(json_schema);
 }
+
+#[test]
+fn release_url_scheme() {
+    let github = Hosting {
+        github: Some(GithubHosting {
+            artifact_download_url:
+                "https://github.com/axodotdev/axolotlsay/releases/download/axolotlsay-v0.2.2"
+                    .to_owned(),
+            owner: "axodotdev".to_owned(),
+            repo: "axolotlsay".to_owned(),
+        }),
+        ..Default::default()
+    };
+    let scheme = github
+        .release_url_scheme("axolotlsay-v0.2.2", "0.2.2")
+        .unwrap();
+    assert_eq!(
+        scheme.artifact_download_url,
+        "https://github.com/axodotdev/axolotlsay/releases/download/axolotlsay-v{version}"
+    );
+    assert_eq!(scheme.tag, "axolotlsay-v{version}");
+    assert_eq!(
+        scheme.latest_artifact_download_url.as_deref(),
+        Some("https://github.com/axodotdev/axolotlsay/releases/latest/download")
+    );
+    assert_eq!(
+        scheme.releases_api_url.as_deref(),
+        Some("https://api.github.com/repos/axodotdev/axolotlsay/releases")
+    );
+    // Only the version is templated, even if the tag's prefix contains it too
+    let scheme = github
+        .release_url_scheme("axolotlsay0.2.2-v0.2.2", "0.2.2")
+        .unwrap();
+    assert_eq!(scheme.tag, "axolotlsay0.2.2-v{version}");
+    assert_eq!(
+        scheme.artifact_download_url,
+        "https://github.com/axodotdev/axolotlsay/releases/download/axolotlsay0.2.2-v{version}"
+    );
+
+    let mirror = Hosting {
+        mirror: Some(MirrorHosting {
+            artifact_download_url: "https://mirror.example.com/axolotlsay/0.2.2".to_owned(),
+            path: "axolotlsay/0.2.2".to_owned(),
+        }),
+        ..Default::default()
+    };
+    let scheme = mirror.release_url_scheme("v0.2.2", "0.2.2").unwrap();
+    assert_eq!(
+        scheme.artifact_download_url,
+        "https://mirror.example.com/axolotlsay/{version}"
+    );
+    assert_eq!(scheme.latest_artifact_download_url, None);
+    assert_eq!(scheme.releases_api_url, None);
+}
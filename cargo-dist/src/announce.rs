@@ -854,8 +854,8 @@ pub fn announcement_github(manifest: &mut DistManifest) {
     // Add the contents of each Release to the body
     let mut announcing_github = false;
     for release in &manifest.releases {
-        // Only bother if there's actually github hosting
-        if release.hosting.github.is_none() {
+        // Only bother if there's actually github (or gitea) hosting
+        if release.hosting.github.is_none() && release.hosting.gitea.is_none() {
             continue;
         }
         // Skip "hidden" apps
@@ -877,11 +877,13 @@ pub fn announcement_github(manifest: &mut DistManifest) {
         let mut local_installers = vec![];
         let mut bundles = vec![];
         let mut symbols = vec![];
+        let mut images = vec![];
 
         for (_name, artifact) in manifest.artifacts_for_release(release) {
             match artifact.kind {
                 cargo_dist_schema::ArtifactKind::ExecutableZip => bundles.push(artifact),
                 cargo_dist_schema::ArtifactKind::Symbols => symbols.push(artifact),
+                cargo_dist_schema::ArtifactKind::OciImage => images.push(artifact),
                 cargo_dist_schema::ArtifactKind::Installer => {
                     if let (Some(desc), Some(hint)) =
                         (&artifact.description, &artifact.install_hint)
@@ -914,6 +916,7 @@ pub fn announcement_github(manifest: &mut DistManifest) {
         let mut other_artifacts: Vec<_> = bundles
             .into_iter()
             .chain(local_installers)
+            .chain(images)
             .chain(symbols)
             .collect();
 
@@ -0,0 +1,111 @@
+//! CI script generation for Forgejo (and Gitea) Actions
+//!
+//! Forgejo Actions is close enough to GitHub Actions that we reuse the
+//! GitHub backend and its templates, and only swap out the parts that differ:
+//! where workflows live, which actions are available, and the runner labels.
+
+use cargo_dist_schema::{GithubRunner, TargetTriple, TargetTripleRef};
+use tracing::warn;
+
+use super::github::{ActionsForge, GithubCiInfo};
+use crate::{
+    config::v1::ci::{forgejo::ForgejoCiConfig, github::GithubCiConfig},
+    errors::DistResult,
+    DistGraph, SortedMap,
+};
+
+impl ActionsForge {
+    /// Forgejo (or Gitea) Actions
+    pub const FORGEJO: ActionsForge = ActionsForge {
+        name: "Forgejo",
+        manifest_key: "forgejo",
+        workflows_dir: ".forgejo/workflows",
+        // actions/{upload,download}-artifact@v4 only work against github.com
+        upload_artifact: "https://code.forgejo.org/forgejo/upload-artifact@v4",
+        download_artifact: "https://code.forgejo.org/forgejo/download-artifact@v4",
+        rust_cache: "https://github.com/Swatinem/rust-cache@v2",
+    };
+}
+
+/// The Forgejo runner label to use for Linux
+///
+/// This is the label `forgejo-runner register` gives its default docker runner.
+const FORGEJO_LINUX_RUNNER: &str = "docker";
+/// The Forgejo runner label to use for macos
+const FORGEJO_MACOS_RUNNER: &str = "macos";
+/// The Forgejo runner label to use for windows
+const FORGEJO_WINDOWS_RUNNER: &str = "windows";
+
+impl GithubCiInfo {
+    /// Compute the Forgejo CI stuff
+    pub fn new_forgejo(dist: &DistGraph, ci_config: &ForgejoCiConfig) -> DistResult<GithubCiInfo> {
+        // Resolve every runner up front so the github machinery never picks
+        // one of its own defaults
+        let mut runners = SortedMap::new();
+        for release in &dist.releases {
+            for target in &release.targets {
+                let runner = forgejo_runner_for_target(target, &ci_config.runners);
+                runners.insert(target.clone(), GithubRunner::new(runner));
+            }
+        }
+        let global_runner = ci_config
+            .runners
+            .get("global")
+            .map(|s| s.as_str())
+            .unwrap_or(FORGEJO_LINUX_RUNNER);
+        runners.insert(
+            TargetTriple::new("global".to_owned()),
+            GithubRunner::new(global_runner.to_owned()),
+        );
+
+        let github_config = GithubCiConfig {
+            common: ci_config.common.clone(),
+            runners,
+            permissions: Default::default(),
+            build_setup: None,
+        };
+        GithubCiInfo::new_for_forge(dist, &github_config, ActionsForge::FORGEJO)
+    }
+}
+
+/// Get the appropriate Forgejo runner label for building a target
+fn forgejo_runner_for_target(
+    target: &TargetTripleRef,
+    custom_runners: &SortedMap<TargetTriple, String>,
+) -> String {
+    if let Some(runner) = custom_runners.get(target) {
+        return runner.clone();
+    }
+
+    let runner = if target.is_linux() {
+        FORGEJO_LINUX_RUNNER
+    } else if target.is_apple() {
+        FORGEJO_MACOS_RUNNER
+    } else if target.is_windows() {
+        FORGEJO_WINDOWS_RUNNER
+    } else {
+        warn!("not sure which forgejo runner should be used for {target}, assuming {FORGEJO_LINUX_RUNNER}");
+        FORGEJO_LINUX_RUNNER
+    };
+    runner.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_runners_win() {
+        let mut custom = SortedMap::new();
+        custom.insert(
+            TargetTriple::new("aarch64-unknown-linux-gnu".to_owned()),
+            "arm64".to_owned(),
+        );
+        let arm = TargetTriple::new("aarch64-unknown-linux-gnu".to_owned());
+        let x64 = TargetTriple::new("x86_64-unknown-linux-gnu".to_owned());
+        let mac = TargetTriple::new("aarch64-apple-darwin".to_owned());
+        assert_eq!(forgejo_runner_for_target(&arm, &custom), "arm64");
+        assert_eq!(forgejo_runner_for_target(&x64, &custom), "docker");
+        assert_eq!(forgejo_runner_for_target(&mac, &custom), "macos");
+    }
+}
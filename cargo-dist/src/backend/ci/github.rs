@@ -14,9 +14,12 @@ use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use crate::{
-    backend::{diff_files, templates::TEMPLATE_CI_GITHUB},
+    backend::{ci::NpmPublishInfo, diff_files, templates::TEMPLATE_CI_GITHUB},
     config::{
-        v1::{ci::github::GithubCiConfig, publishers::PublisherConfig},
+        v1::{
+            ci::github::GithubCiConfig, hosts::github::GithubHostConfig,
+            publishers::PublisherConfig,
+        },
         DependencyKind, GithubPermission, GithubPermissionMap, GithubReleasePhase, HostingStyle,
         JinjaGithubRepoPair, JobStyle, ProductionMode, PublishStyle, SystemDependencies,
     },
@@ -24,15 +27,44 @@ use crate::{
     DistError, DistGraph, SortedMap, SortedSet,
 };
 
-#[cfg(not(windows))]
-const GITHUB_CI_DIR: &str = ".github/workflows/";
-#[cfg(windows)]
-const GITHUB_CI_DIR: &str = r".github\workflows\";
 const GITHUB_CI_FILE: &str = "release.yml";
 
+/// The parts of a workflow that differ between GitHub Actions and
+/// the forges that reimplement it (i.e. Forgejo Actions)
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionsForge {
+    /// Name of the forge, for messages
+    #[serde(skip_serializing)]
+    pub name: &'static str,
+    /// Key of the `ci` entry in dist-manifest.json the workflow reads
+    pub manifest_key: &'static str,
+    /// Directory the workflows live in, relative to the repo root
+    pub workflows_dir: &'static str,
+    /// Action to use for uploading artifacts
+    pub upload_artifact: &'static str,
+    /// Action to use for downloading artifacts
+    pub download_artifact: &'static str,
+    /// Action to use for caching builds
+    pub rust_cache: &'static str,
+}
+
+impl ActionsForge {
+    /// GitHub Actions
+    pub const GITHUB: ActionsForge = ActionsForge {
+        name: "Github",
+        manifest_key: "github",
+        workflows_dir: ".github/workflows",
+        upload_artifact: "actions/upload-artifact@v4",
+        download_artifact: "actions/download-artifact@v4",
+        rust_cache: "swatinem/rust-cache@v2",
+    };
+}
+
 /// Info about running dist in Github CI
 #[derive(Debug, Serialize)]
 pub struct GithubCiInfo {
+    /// Which implementation of Actions this is for
+    pub forge: ActionsForge,
     /// Cached path to github CI workflows dir
     #[serde(skip_serializing)]
     pub github_ci_workflow_dir: Utf8PathBuf,
@@ -60,6 +92,10 @@ pub struct GithubCiInfo {
     pub global_task: GithubMatrixEntry,
     /// homebrew tap
     pub tap: Option<String>,
+    /// scoop bucket
+    pub scoop_bucket: Option<String>,
+    /// where to publish npm packages
+    pub npm_publish: Option<NpmPublishInfo>,
     /// plan jobs
     pub plan_jobs: Vec<GithubCiJob>,
     /// local artifacts jobs
@@ -179,6 +215,15 @@ pub struct GithubCiJob {
 impl GithubCiInfo {
     /// Compute the Github CI stuff
     pub fn new(dist: &DistGraph, ci_config: &GithubCiConfig) -> DistResult<GithubCiInfo> {
+        Self::new_for_forge(dist, ci_config, ActionsForge::GITHUB)
+    }
+
+    /// Compute the CI stuff for a particular implementation of Actions
+    pub(super) fn new_for_forge(
+        dist: &DistGraph,
+        ci_config: &GithubCiConfig,
+        forge: ActionsForge,
+    ) -> DistResult<GithubCiInfo> {
         // Legacy deprecated support
         let rust_version = dist.config.builds.cargo.rust_toolchain_version.clone();
 
@@ -250,6 +295,7 @@ impl GithubCiInfo {
         };
 
         let tap = dist.global_homebrew_tap.clone();
+        let scoop_bucket = dist.global_scoop_bucket.clone();
 
         let mut job_permissions = ci_config.permissions.clone();
         // user publish jobs default to elevated priviledges
@@ -274,12 +320,34 @@ impl GithubCiInfo {
         }
 
         let mut publish_jobs = vec![];
-        if let Some(PublisherConfig { homebrew, npm }) = &dist.global_publishers {
+        let mut npm_publish = None;
+        if let Some(PublisherConfig {
+            homebrew,
+            npm,
+            crates,
+            scoop,
+            aur,
+            pypi,
+        }) = &dist.global_publishers
+        {
             if homebrew.is_some() {
                 publish_jobs.push(PublishStyle::Homebrew.to_string());
             }
-            if npm.is_some() {
+            if let Some(npm) = npm {
                 publish_jobs.push(PublishStyle::Npm.to_string());
+                npm_publish = Some(NpmPublishInfo::new(npm));
+            }
+            if crates.is_some() {
+                publish_jobs.push(PublishStyle::Crates.to_string());
+            }
+            if scoop.is_some() {
+                publish_jobs.push(PublishStyle::Scoop.to_string());
+            }
+            if aur.is_some() {
+                publish_jobs.push(PublishStyle::Aur.to_string());
+            }
+            if pypi.is_some() {
+                publish_jobs.push(PublishStyle::Pypi.to_string());
             }
         }
 
@@ -316,7 +384,10 @@ impl GithubCiInfo {
             });
         }
 
-        let github_ci_workflow_dir = dist.repo_dir.join(GITHUB_CI_DIR);
+        let github_ci_workflow_dir = forge
+            .workflows_dir
+            .split('/')
+            .fold(dist.repo_dir.clone(), |dir, part| dir.join(part));
         let github_build_setup = ci_config
             .build_setup
             .as_ref()
@@ -331,6 +402,7 @@ impl GithubCiInfo {
             .unwrap_or_default();
 
         Ok(GithubCiInfo {
+            forge,
             github_ci_workflow_dir,
             tag_namespace,
             rust_version,
@@ -342,6 +414,8 @@ impl GithubCiInfo {
             dispatch_releases,
             release_branch,
             tap,
+            scoop_bucket,
+            npm_publish,
             plan_jobs,
             local_artifacts_jobs,
             global_artifacts_jobs,
@@ -388,7 +462,7 @@ impl GithubCiInfo {
         let rendered = self.generate_github_ci(dist)?;
 
         LocalAsset::write_new_all(&rendered, &ci_file)?;
-        eprintln!("generated Github CI to {}", ci_file);
+        eprintln!("generated {} CI to {}", self.forge.name, ci_file);
 
         Ok(())
     }
@@ -420,23 +494,7 @@ impl GithubReleaseInfo {
             .transpose()?
             .flatten();
 
-        let release_phase = if host_config.during == GithubReleasePhase::Auto {
-            // We typically prefer to release in announce.
-            // If Axo is in use, we also want the release to come late
-            // because the release body will contain links to Axo URLs
-            // that won't become live until the announce phase.
-            if dist.config.hosts.axodotdev.is_some() {
-                GithubReleasePhase::Announce
-            // Otherwise, if Axo isn't present, we lean on host for
-            // safety reasons - because npm/Homebrew contain links to
-            // URLs that won't exist until the GitHub release happens.
-            } else {
-                GithubReleasePhase::Host
-            }
-        // If the user chose a non-auto option, respect that.
-        } else {
-            host_config.during
-        };
+        let release_phase = github_release_phase(dist, host_config);
 
         let mut release_args = vec![];
         let action;
@@ -476,6 +534,30 @@ impl GithubReleaseInfo {
     }
 }
 
+/// Pick the phase of the pipeline the GitHub Release should be created in
+pub(super) fn github_release_phase(
+    dist: &DistGraph,
+    host_config: &GithubHostConfig,
+) -> GithubReleasePhase {
+    if host_config.during == GithubReleasePhase::Auto {
+        // We typically prefer to release in announce.
+        // If Axo is in use, we also want the release to come late
+        // because the release body will contain links to Axo URLs
+        // that won't become live until the announce phase.
+        if dist.config.hosts.axodotdev.is_some() {
+            GithubReleasePhase::Announce
+        // Otherwise, if Axo isn't present, we lean on host for
+        // safety reasons - because npm/Homebrew contain links to
+        // URLs that won't exist until the GitHub release happens.
+        } else {
+            GithubReleasePhase::Host
+        }
+    // If the user chose a non-auto option, respect that.
+    } else {
+        host_config.during
+    }
+}
+
 // Determines the *cached* HEAD for a submodule within the workspace.
 // Note that any unstaged commits, and any local changes to commit
 // history that aren't reflected by the submodule commit history,
@@ -665,7 +747,7 @@ brew bundle install"#,
     )
 }
 
-fn package_install_for_targets(
+pub(super) fn package_install_for_targets(
     targets: &[&TargetTripleRef],
     packages: &SystemDependencies,
 ) -> Option<String> {
@@ -0,0 +1,417 @@
+//! CI script generation for GitLab
+//!
+//! This mirrors the job structure of the Github CI backend (plan, build-local,
+//! build-global, host, publish, announce), but because GitLab pipelines can't
+//! consume a dynamic matrix we bake the build jobs into the file directly.
+
+use std::collections::BTreeMap;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{TargetTriple, TargetTripleRef};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::{
+    backend::{diff_files, templates::TEMPLATE_CI_GITLAB},
+    config::{
+        v1::{ci::gitlab::GitlabCiConfig, publishers::PublisherConfig},
+        GithubReleasePhase, HostingStyle, JinjaGithubRepoPair, JobStyle, PublishStyle,
+        SystemDependencies,
+    },
+    errors::DistResult,
+    DistGraph, SortedMap, SortedSet,
+};
+
+use super::github::{github_release_phase, package_install_for_targets};
+use super::NpmPublishInfo;
+
+const GITLAB_CI_FILE: &str = ".gitlab-ci.yml";
+
+/// Info about running dist in GitLab CI
+#[derive(Debug, Serialize)]
+pub struct GitlabCiInfo {
+    /// Cached path to the .gitlab-ci.yml
+    #[serde(skip_serializing)]
+    pub gitlab_ci_path: Utf8PathBuf,
+    /// Version of rust toolchain to install (deprecated)
+    pub rust_version: Option<String>,
+    /// expression to use for installing dist via shell script
+    pub install_dist_sh: String,
+    /// expression to use for installing dist via powershell script
+    pub install_dist_ps1: String,
+    /// Docker image for jobs on linux runners
+    pub image: String,
+    /// Whether to cache builds
+    pub cache_builds: bool,
+    /// Whether to include builtin local artifacts tasks
+    pub build_local_artifacts: bool,
+    /// Whether to make CI get dispatched manually instead of by tag
+    pub dispatch_releases: bool,
+    /// Trigger releases on pushes to this branch instead of ci
+    pub release_branch: Option<String>,
+    /// What kind of job to run on merge request
+    pub pr_run_mode: cargo_dist_schema::PrRunMode,
+    /// Regex that a tag must match to start a release
+    pub tag_pattern: String,
+    /// global task
+    pub global_task: GitlabBuildTask,
+    /// local artifacts tasks
+    pub local_tasks: Vec<GitlabBuildTask>,
+    /// homebrew tap
+    pub tap: Option<String>,
+    /// scoop bucket
+    pub scoop_bucket: Option<String>,
+    /// where to publish npm packages
+    pub npm_publish: Option<NpmPublishInfo>,
+    /// plan jobs
+    pub plan_jobs: Vec<String>,
+    /// local artifacts jobs
+    pub local_artifacts_jobs: Vec<String>,
+    /// global artifacts jobs
+    pub global_artifacts_jobs: Vec<String>,
+    /// host jobs
+    pub host_jobs: Vec<String>,
+    /// publish jobs
+    pub publish_jobs: Vec<String>,
+    /// user-specified publish jobs
+    pub user_publish_jobs: Vec<String>,
+    /// post-announce jobs
+    pub post_announce_jobs: Vec<String>,
+    /// what hosting provider we're using
+    pub hosting_providers: Vec<HostingStyle>,
+    /// whether to create the github release or assume an existing one
+    pub create_release: bool,
+    /// external repo to make github releases in
+    pub github_releases_repo: Option<JinjaGithubRepoPair>,
+    /// Which phase to create the github release at
+    pub release_phase: Option<GithubReleasePhase>,
+}
+
+/// A job that runs `dist build` on a GitLab runner
+#[derive(Debug, Serialize)]
+pub struct GitlabBuildTask {
+    /// Suffix for the job name
+    pub name: String,
+    /// Runner tag to select the machine with
+    pub runner: Option<String>,
+    /// OS family of the runner ("linux", "macos", or "windows")
+    pub os: &'static str,
+    /// cli flags to pass to dist
+    pub dist_args: String,
+    /// expression to run to install dist on the runner
+    pub install_dist: String,
+    /// Command to install system dependencies
+    pub packages_install: Option<String>,
+}
+
+impl GitlabCiInfo {
+    /// Compute the GitLab CI stuff
+    pub fn new(dist: &DistGraph, ci_config: &GitlabCiConfig) -> DistResult<GitlabCiInfo> {
+        // Legacy deprecated support
+        let rust_version = dist.config.builds.cargo.rust_toolchain_version.clone();
+
+        // If they don't specify a dist version, use this one
+        let self_dist_version = super::SELF_DIST_VERSION.parse().unwrap();
+        let dist_version = dist
+            .config
+            .dist_version
+            .as_ref()
+            .unwrap_or(&self_dist_version);
+        let install_dist_sh = super::install_dist_sh_for_version(dist_version);
+        let install_dist_ps1 = super::install_dist_ps1_for_version(dist_version);
+
+        let release_branch = ci_config.release_branch.clone();
+        let pr_run_mode = ci_config.pr_run_mode;
+        let caching_could_be_profitable =
+            release_branch.is_some() || pr_run_mode == cargo_dist_schema::PrRunMode::Upload;
+        let cache_builds = ci_config
+            .cache_builds
+            .unwrap_or(caching_could_be_profitable);
+
+        // Unlike Github CI, GitLab CI has no hosting of its own, so the pipeline
+        // may legitimately have nothing to upload to.
+        let hosting_providers = dist
+            .hosting
+            .as_ref()
+            .map(|hosting| hosting.hosts.clone())
+            .unwrap_or_default();
+
+        let mut dependencies = SystemDependencies::default();
+        let mut local_targets: SortedSet<&TargetTripleRef> = SortedSet::new();
+        for release in &dist.releases {
+            for target in &release.targets {
+                local_targets.insert(target);
+            }
+            dependencies.append(&mut release.config.builds.system_dependencies.clone());
+        }
+
+        let global_runner = ci_config
+            .runners
+            .get("global")
+            .cloned()
+            .unwrap_or_else(|| GITLAB_LINUX_RUNNER.to_owned());
+        let global_task = GitlabBuildTask {
+            name: "global".to_owned(),
+            runner: Some(global_runner),
+            os: "linux",
+            dist_args: "--artifacts=global".to_owned(),
+            install_dist: install_dist_sh.clone(),
+            packages_install: None,
+        };
+
+        let local_runs = if ci_config.merge_tasks {
+            distribute_targets_to_runners_merged(local_targets, &ci_config.runners)
+        } else {
+            distribute_targets_to_runners_split(local_targets, &ci_config.runners)
+        };
+        let mut local_tasks = vec![];
+        for (runner, targets) in local_runs {
+            use std::fmt::Write;
+            let os = os_for_targets(&targets);
+            let install_dist = if os == "windows" {
+                install_dist_ps1.clone()
+            } else {
+                install_dist_sh.clone()
+            };
+            let mut dist_args = String::from("--artifacts=local");
+            for target in &targets {
+                write!(dist_args, " --target={target}").unwrap();
+            }
+            local_tasks.push(GitlabBuildTask {
+                name: targets
+                    .iter()
+                    .map(|t| t.as_str())
+                    .collect::<Vec<_>>()
+                    .join("_"),
+                runner: Some(runner),
+                os,
+                dist_args,
+                install_dist,
+                packages_install: package_install_for_targets(&targets, &dependencies),
+            });
+        }
+
+        let mut publish_jobs = vec![];
+        let mut npm_publish = None;
+        if let Some(PublisherConfig {
+            homebrew,
+            npm,
+            crates,
+            scoop,
+            aur,
+            pypi,
+        }) = &dist.global_publishers
+        {
+            if homebrew.is_some() {
+                publish_jobs.push(PublishStyle::Homebrew.to_string());
+            }
+            if let Some(npm) = npm {
+                publish_jobs.push(PublishStyle::Npm.to_string());
+                npm_publish = Some(NpmPublishInfo::new(npm));
+            }
+            if crates.is_some() {
+                publish_jobs.push(PublishStyle::Crates.to_string());
+            }
+            if scoop.is_some() {
+                publish_jobs.push(PublishStyle::Scoop.to_string());
+            }
+            if aur.is_some() {
+                publish_jobs.push(PublishStyle::Aur.to_string());
+            }
+            if pypi.is_some() {
+                publish_jobs.push(PublishStyle::Pypi.to_string());
+            }
+        }
+
+        let (create_release, github_releases_repo, release_phase) =
+            if let Some(host_config) = &dist.config.hosts.github {
+                (
+                    host_config.create,
+                    host_config.repo.clone().map(|r| r.into_jinja()),
+                    Some(github_release_phase(dist, host_config)),
+                )
+            } else {
+                (false, None, None)
+            };
+
+        Ok(GitlabCiInfo {
+            gitlab_ci_path: dist.repo_dir.join(GITLAB_CI_FILE),
+            rust_version,
+            install_dist_sh,
+            install_dist_ps1,
+            image: ci_config
+                .image
+                .clone()
+                .unwrap_or_else(|| GITLAB_LINUX_IMAGE.to_owned()),
+            cache_builds,
+            build_local_artifacts: ci_config.build_local_artifacts,
+            dispatch_releases: ci_config.dispatch_releases,
+            release_branch,
+            pr_run_mode,
+            tag_pattern: tag_pattern(ci_config.tag_namespace.as_deref()),
+            global_task,
+            local_tasks,
+            tap: dist.global_homebrew_tap.clone(),
+            scoop_bucket: dist.global_scoop_bucket.clone(),
+            npm_publish,
+            plan_jobs: job_names(&ci_config.plan_jobs),
+            local_artifacts_jobs: job_names(&ci_config.build_local_jobs),
+            global_artifacts_jobs: job_names(&ci_config.build_global_jobs),
+            host_jobs: job_names(&ci_config.host_jobs),
+            publish_jobs,
+            user_publish_jobs: job_names(&ci_config.publish_jobs),
+            post_announce_jobs: job_names(&ci_config.post_announce_jobs),
+            hosting_providers,
+            create_release,
+            github_releases_repo,
+            release_phase,
+        })
+    }
+
+    /// Generate the requested configuration and returns it as a string.
+    pub fn generate_gitlab_ci(&self, dist: &DistGraph) -> DistResult<String> {
+        let rendered = dist
+            .templates
+            .render_file_to_clean_string(TEMPLATE_CI_GITLAB, self)?;
+
+        Ok(rendered)
+    }
+
+    /// Write .gitlab-ci.yml to disk
+    pub fn write_to_disk(&self, dist: &DistGraph) -> DistResult<()> {
+        let rendered = self.generate_gitlab_ci(dist)?;
+
+        LocalAsset::write_new_all(&rendered, &self.gitlab_ci_path)?;
+        eprintln!("generated GitLab CI to {}", self.gitlab_ci_path);
+
+        Ok(())
+    }
+
+    /// Check whether the new configuration differs from the config on disk
+    /// without actually writing the result.
+    pub fn check(&self, dist: &DistGraph) -> DistResult<()> {
+        let rendered = self.generate_gitlab_ci(dist)?;
+        diff_files(&self.gitlab_ci_path, &rendered)
+    }
+}
+
+fn job_names(jobs: &[JobStyle]) -> Vec<String> {
+    jobs.iter()
+        .map(|JobStyle::User(name)| name.clone())
+        .collect()
+}
+
+/// Build the regex a release tag must match, anchored on the tag-namespace (if any)
+fn tag_pattern(tag_namespace: Option<&str>) -> String {
+    let mut pattern = String::from("^");
+    for c in tag_namespace.unwrap_or_default().chars() {
+        if "\\^$.|?*+()[]{}/".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push_str(".*[0-9]+\\.[0-9]+\\.[0-9]+");
+    pattern
+}
+
+/// Given a set of targets we want to build local artifacts for, map them to GitLab runners
+/// while preferring to merge builds that can happen on the same machine.
+fn distribute_targets_to_runners_merged<'a>(
+    targets: SortedSet<&'a TargetTripleRef>,
+    custom_runners: &BTreeMap<TargetTriple, String>,
+) -> std::vec::IntoIter<(String, Vec<&'a TargetTripleRef>)> {
+    let mut groups = SortedMap::<String, Vec<&TargetTripleRef>>::new();
+    for target in targets {
+        let runner = gitlab_runner_for_target(target, custom_runners);
+        groups.entry(runner).or_default().push(target);
+    }
+    groups.into_iter().collect::<Vec<_>>().into_iter()
+}
+
+/// Given a set of targets we want to build local artifacts for, map them to GitLab runners
+/// while preferring each target gets its own runner for latency and fault-isolation.
+fn distribute_targets_to_runners_split<'a>(
+    targets: SortedSet<&'a TargetTripleRef>,
+    custom_runners: &BTreeMap<TargetTriple, String>,
+) -> std::vec::IntoIter<(String, Vec<&'a TargetTripleRef>)> {
+    let mut groups = vec![];
+    for target in targets {
+        let runner = gitlab_runner_for_target(target, custom_runners);
+        groups.push((runner, vec![target]));
+    }
+    groups.into_iter()
+}
+
+/// The docker image to use for jobs on linux runners
+///
+/// Like the Github runners, we default to an older distro so that newer
+/// glibc symbols can't creep into the binaries we build.
+const GITLAB_LINUX_IMAGE: &str = "rust:bullseye";
+/// The GitLab runner tag to use for Linux
+const GITLAB_LINUX_RUNNER: &str = "saas-linux-medium-amd64";
+/// The GitLab runner tag to use for macos
+const GITLAB_MACOS_RUNNER: &str = "saas-macos-medium-m1";
+/// The GitLab runner tag to use for windows
+const GITLAB_WINDOWS_RUNNER: &str = "saas-windows-medium-amd64";
+
+/// Get the appropriate GitLab runner tag for building a target
+fn gitlab_runner_for_target(
+    target: &TargetTripleRef,
+    custom_runners: &BTreeMap<TargetTriple, String>,
+) -> String {
+    if let Some(runner) = custom_runners.get(target) {
+        return runner.to_owned();
+    }
+
+    if target.is_linux() {
+        GITLAB_LINUX_RUNNER.to_owned()
+    } else if target.is_apple() {
+        GITLAB_MACOS_RUNNER.to_owned()
+    } else if target.is_windows() {
+        GITLAB_WINDOWS_RUNNER.to_owned()
+    } else {
+        let default = GITLAB_LINUX_RUNNER;
+        warn!("not sure which gitlab runner should be used for {target}, assuming {default}");
+        default.to_owned()
+    }
+}
+
+/// Get the OS family a set of targets will be built on
+fn os_for_targets(targets: &[&TargetTripleRef]) -> &'static str {
+    for target in targets {
+        if target.is_apple() {
+            return "macos";
+        } else if target.is_windows() {
+            return "windows";
+        }
+    }
+    "linux"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_pattern_escapes_namespace() {
+        assert_eq!(tag_pattern(None), r"^.*[0-9]+\.[0-9]+\.[0-9]+");
+        assert_eq!(
+            tag_pattern(Some("my.app/")),
+            r"^my\.app\/.*[0-9]+\.[0-9]+\.[0-9]+"
+        );
+    }
+
+    #[test]
+    fn custom_runners_win() {
+        let mut custom = BTreeMap::new();
+        custom.insert(
+            TargetTriple::new("x86_64-unknown-linux-gnu".to_owned()),
+            "my-linux-box".to_owned(),
+        );
+        let linux = TargetTripleRef::from_str("x86_64-unknown-linux-gnu");
+        let mac = TargetTripleRef::from_str("aarch64-apple-darwin");
+        assert_eq!(gitlab_runner_for_target(linux, &custom), "my-linux-box");
+        assert_eq!(gitlab_runner_for_target(mac, &custom), GITLAB_MACOS_RUNNER);
+    }
+}
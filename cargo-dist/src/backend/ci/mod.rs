@@ -1,10 +1,14 @@
 //! Support for generating CI scripts for running dist
 
 use semver::Version;
+use serde::Serialize;
 
-use self::github::GithubCiInfo;
+use self::{github::GithubCiInfo, gitlab::GitlabCiInfo};
+use crate::config::v1::publishers::npm::NpmPublisherConfig;
 
+pub mod forgejo;
 pub mod github;
+pub mod gitlab;
 
 /// The current version of dist
 const SELF_DIST_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -15,6 +19,41 @@ const BASE_DIST_FETCH_URL: &str = "https://github.com/axodotdev/cargo-dist/relea
 pub struct CiInfo {
     /// Github CI
     pub github: Option<GithubCiInfo>,
+    /// GitLab CI
+    pub gitlab: Option<GitlabCiInfo>,
+    /// Forgejo CI (shares the Github CI machinery)
+    pub forgejo: Option<GithubCiInfo>,
+}
+
+/// Info about publishing npm packages
+#[derive(Debug, Clone, Serialize)]
+pub struct NpmPublishInfo {
+    /// URL of the registry to publish to
+    pub registry: String,
+    /// The registry URL without its scheme, which is how .npmrc keys auth tokens
+    pub registry_auth_prefix: String,
+    /// Name of the secret (or CI variable) with the token to publish with
+    pub token_env_var: String,
+}
+
+impl NpmPublishInfo {
+    /// Get the info for publishing with the given config
+    pub fn new(config: &NpmPublisherConfig) -> Self {
+        Self {
+            registry: config.registry.clone(),
+            registry_auth_prefix: npm_registry_auth_prefix(&config.registry),
+            token_env_var: config.token_env_var.clone(),
+        }
+    }
+}
+
+fn npm_registry_auth_prefix(registry: &str) -> String {
+    let without_scheme = registry
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(registry);
+    let without_slash = without_scheme.trim_end_matches('/');
+    format!("//{without_slash}/")
 }
 
 /// Get the command to invoke to install dist via sh script
@@ -66,3 +105,24 @@ fn install_dist_git(version: &Version) -> Option<String> {
         format!("cargo install --git https://github.com/axodotdev/cargo-dist/ --branch={branch} cargo-dist")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn npm_registry_auth_prefixes() {
+        assert_eq!(
+            npm_registry_auth_prefix("https://registry.npmjs.org"),
+            "//registry.npmjs.org/"
+        );
+        assert_eq!(
+            npm_registry_auth_prefix("http://localhost:4873/"),
+            "//localhost:4873/"
+        );
+        assert_eq!(
+            npm_registry_auth_prefix("https://example.jfrog.io/artifactory/api/npm/npm-local"),
+            "//example.jfrog.io/artifactory/api/npm/npm-local/"
+        );
+    }
+}
@@ -0,0 +1,563 @@
+//! Code for generating Arch User Repository packages
+//!
+//! An AUR package is just a git repo with a PKGBUILD and a .SRCINFO in it,
+//! so we write both out ourselves instead of requiring makepkg on the machine
+//! doing the build. The PKGBUILD is a `-bin` package that repackages the
+//! linux-gnu archives.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{DistManifest, Linkage, TargetTripleRef};
+use serde::Serialize;
+use tracing::warn;
+
+use super::{ExecutableZipFragment, InstallerInfo};
+use crate::{
+    config::{ChecksumStyle, LibraryStyle, ZipStyle},
+    errors::DistResult,
+    tasks::DistGraph,
+};
+
+/// Info about an AUR package
+#[derive(Debug, Clone, Serialize)]
+pub struct AurInstallerInfo {
+    /// Name of the package (`{app}-bin`)
+    pub package_name: String,
+    /// Name of the app, which the package provides
+    pub provides: String,
+    /// Version of the package, in Arch's format
+    pub pkgver: String,
+    /// Who maintains the package
+    pub maintainer: Option<String>,
+    /// A brief description of the application
+    pub desc: Option<String>,
+    /// The URL to the application's homepage
+    pub homepage: Option<String>,
+    /// The application's license, in SPDX format
+    pub license: Option<String>,
+    /// The archive to fetch for each architecture
+    pub sources: Vec<AurSource>,
+    /// LICENSEs in the archives, to install to /usr/share/licenses
+    pub licenses: Vec<String>,
+    /// READMEs and CHANGELOGs in the archives, to install to /usr/share/doc
+    pub docs: Vec<String>,
+    /// Dir the PKGBUILD and .SRCINFO get written to before archiving
+    pub package_dir: Utf8PathBuf,
+    /// Generic installer info
+    pub inner: InstallerInfo,
+}
+
+/// An archive a PKGBUILD fetches for one architecture
+#[derive(Debug, Clone, Serialize)]
+pub struct AurSource {
+    /// Arch's name for the architecture
+    pub arch: String,
+    /// The archive
+    pub fragment: ExecutableZipFragment,
+}
+
+/// The names of the files in an AUR package
+pub const AUR_FILES: [&str; 2] = ["PKGBUILD", ".SRCINFO"];
+
+pub(crate) fn write_aur_package(
+    _dist: &DistGraph,
+    info: &AurInstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let mut linkage = Linkage::default();
+    for source in &info.sources {
+        linkage.extend(&manifest.linkage_for_artifact(&source.fragment.id));
+    }
+    let (depends, unknown) = arch_dependencies(&linkage);
+    if !unknown.is_empty() {
+        warn!(
+            "couldn't tell which Arch packages provide these libraries, so the PKGBUILD for {} doesn't depend on them:\n  {}",
+            info.package_name,
+            unknown.into_iter().collect::<Vec<_>>().join("\n  ")
+        );
+    }
+    let sha256sums = info
+        .sources
+        .iter()
+        .map(|source| {
+            let checksum = sha256_checksum(manifest, &source.fragment);
+            if checksum.is_none() {
+                warn!(
+                    "no sha256 checksum for {}, so the PKGBUILD for {} skips verifying it until its sha256sums are filled in",
+                    source.fragment.id, info.package_name
+                );
+            }
+            checksum
+        })
+        .collect::<Vec<_>>();
+
+    // Don't let the files of old versions sneak into the archive
+    if info.package_dir.exists() {
+        LocalAsset::remove_dir_all(&info.package_dir)?;
+    }
+    LocalAsset::create_dir_all(&info.package_dir)?;
+    LocalAsset::write_new(
+        &info.pkgbuild(&depends, &sha256sums),
+        info.package_dir.join("PKGBUILD"),
+    )?;
+    LocalAsset::write_new(
+        &info.srcinfo(&depends, &sha256sums),
+        info.package_dir.join(".SRCINFO"),
+    )?;
+    Ok(())
+}
+
+impl AurInstallerInfo {
+    /// Where makepkg should save the archive for this source
+    ///
+    /// Archive names don't include the version, so they get renamed to keep
+    /// makepkg's cache from mixing them up between versions.
+    fn source(&self, source: &AurSource) -> String {
+        let ext = source.fragment.zip_style.ext();
+        format!(
+            "{}-{}-{}{ext}::{}/{}",
+            self.package_name, self.pkgver, source.arch, self.inner.base_url, source.fragment.id
+        )
+    }
+
+    /// Render the PKGBUILD
+    fn pkgbuild(&self, depends: &[String], sha256sums: &[Option<String>]) -> String {
+        let mut out = String::new();
+        if let Some(maintainer) = &self.maintainer {
+            writeln!(out, "# Maintainer: {maintainer}").unwrap();
+        }
+        writeln!(out, "pkgname={}", self.package_name).unwrap();
+        writeln!(out, "pkgver={}", self.pkgver).unwrap();
+        writeln!(out, "pkgrel=1").unwrap();
+        if let Some(desc) = self.pkgdesc() {
+            writeln!(out, "pkgdesc={}", quote(desc)).unwrap();
+        }
+        let arches = self.sources.iter().map(|s| s.arch.as_str());
+        writeln!(out, "arch=({})", quote_all(arches)).unwrap();
+        if let Some(homepage) = &self.homepage {
+            writeln!(out, "url={}", quote(homepage)).unwrap();
+        }
+        if let Some(license) = &self.license {
+            writeln!(out, "license=({})", quote(license)).unwrap();
+        }
+        writeln!(out, "depends=({})", quote_all(depends)).unwrap();
+        writeln!(out, "provides=({})", quote(&self.provides)).unwrap();
+        writeln!(out, "conflicts=({})", quote(&self.provides)).unwrap();
+        // These are release builds, makepkg shouldn't touch them
+        writeln!(out, "options=('!strip' '!debug')").unwrap();
+        for (source, sha256sum) in self.sources.iter().zip(sha256sums) {
+            let arch = &source.arch;
+            let sha256sum = sha256sum.as_deref().unwrap_or("SKIP");
+            writeln!(out, "source_{arch}=({})", quote(&self.source(source))).unwrap();
+            writeln!(out, "sha256sums_{arch}=({})", quote(sha256sum)).unwrap();
+        }
+
+        writeln!(out).unwrap();
+        writeln!(out, "package() {{").unwrap();
+        writeln!(out, "  case \"$CARCH\" in").unwrap();
+        for source in &self.sources {
+            let fragment = &source.fragment;
+            writeln!(out, "    {})", source.arch).unwrap();
+            // Tarballs have everything in a dir named after the archive, zips are flat
+            if let ZipStyle::Tar(_) = fragment.zip_style {
+                let root = fragment
+                    .id
+                    .strip_suffix(fragment.zip_style.ext())
+                    .unwrap_or(&fragment.id);
+                writeln!(out, "      cd {}", quote(root)).unwrap();
+            }
+            for exe in &fragment.executables {
+                writeln!(
+                    out,
+                    "      install -Dm755 {} -t \"$pkgdir/usr/bin\"",
+                    quote(exe)
+                )
+                .unwrap();
+            }
+            let aliases = self.inner.bin_aliases.get(&fragment.target_triple);
+            for (bin, aliases) in aliases.into_iter().flatten() {
+                for alias in aliases {
+                    writeln!(
+                        out,
+                        "      ln -s {} \"$pkgdir/usr/bin/\"{}",
+                        quote(bin),
+                        quote(alias)
+                    )
+                    .unwrap();
+                }
+            }
+            let mut libs = vec![];
+            if self
+                .inner
+                .install_libraries
+                .contains(&LibraryStyle::CDynamic)
+            {
+                libs.extend(fragment.cdylibs.iter().map(|lib| (lib, 755)));
+            }
+            if self
+                .inner
+                .install_libraries
+                .contains(&LibraryStyle::CStatic)
+            {
+                libs.extend(fragment.cstaticlibs.iter().map(|lib| (lib, 644)));
+            }
+            for (lib, mode) in libs {
+                writeln!(
+                    out,
+                    "      install -Dm{mode} {} -t \"$pkgdir/usr/lib\"",
+                    quote(lib)
+                )
+                .unwrap();
+            }
+            writeln!(out, "      ;;").unwrap();
+        }
+        writeln!(out, "  esac").unwrap();
+        for license in &self.licenses {
+            writeln!(
+                out,
+                "  install -Dm644 {} -t \"$pkgdir/usr/share/licenses/$pkgname\"",
+                quote(license)
+            )
+            .unwrap();
+        }
+        for doc in &self.docs {
+            writeln!(
+                out,
+                "  install -Dm644 {} -t \"$pkgdir/usr/share/doc/$pkgname\"",
+                quote(doc)
+            )
+            .unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+
+    /// Render the .SRCINFO, in the same order `makepkg --printsrcinfo` would
+    fn srcinfo(&self, depends: &[String], sha256sums: &[Option<String>]) -> String {
+        let mut out = String::new();
+        writeln!(out, "pkgbase = {}", self.package_name).unwrap();
+        if let Some(desc) = self.pkgdesc() {
+            writeln!(out, "\tpkgdesc = {desc}").unwrap();
+        }
+        writeln!(out, "\tpkgver = {}", self.pkgver).unwrap();
+        writeln!(out, "\tpkgrel = 1").unwrap();
+        if let Some(homepage) = &self.homepage {
+            writeln!(out, "\turl = {homepage}").unwrap();
+        }
+        for source in &self.sources {
+            writeln!(out, "\tarch = {}", source.arch).unwrap();
+        }
+        if let Some(license) = &self.license {
+            writeln!(out, "\tlicense = {license}").unwrap();
+        }
+        for depend in depends {
+            writeln!(out, "\tdepends = {depend}").unwrap();
+        }
+        writeln!(out, "\tprovides = {}", self.provides).unwrap();
+        writeln!(out, "\tconflicts = {}", self.provides).unwrap();
+        writeln!(out, "\toptions = !strip").unwrap();
+        writeln!(out, "\toptions = !debug").unwrap();
+        for (source, sha256sum) in self.sources.iter().zip(sha256sums) {
+            let arch = &source.arch;
+            let sha256sum = sha256sum.as_deref().unwrap_or("SKIP");
+            writeln!(out, "\tsource_{arch} = {}", self.source(source)).unwrap();
+            writeln!(out, "\tsha256sums_{arch} = {sha256sum}").unwrap();
+        }
+        writeln!(out).unwrap();
+        writeln!(out, "pkgname = {}", self.package_name).unwrap();
+        out
+    }
+
+    /// pkgdesc has to be a single line
+    fn pkgdesc(&self) -> Option<&str> {
+        self.desc
+            .as_deref()
+            .and_then(|desc| desc.lines().map(str::trim).find(|line| !line.is_empty()))
+    }
+}
+
+/// Grab the sha256 checksum for this artifact from the manifest
+fn sha256_checksum(manifest: &DistManifest, fragment: &ExecutableZipFragment) -> Option<String> {
+    manifest
+        .artifacts
+        .get(&fragment.id)
+        .and_then(|a| a.checksums.get(ChecksumStyle::Sha256.ext()))
+        .cloned()
+}
+
+/// Work out which Arch packages provide the libraries a build links against
+///
+/// Returns the packages, and the libraries we don't know a package for.
+fn arch_dependencies(linkage: &Linkage) -> (Vec<String>, BTreeSet<String>) {
+    let Linkage {
+        system,
+        homebrew: _,
+        public_unmanaged,
+        other,
+        frameworks: _,
+    } = linkage;
+    let mut depends = BTreeSet::new();
+    let mut unknown = BTreeSet::new();
+    for lib in system.iter().chain(public_unmanaged).chain(other) {
+        let file_name = lib.path.rsplit('/').next().unwrap_or(&lib.path);
+        let soname = file_name.split(".so").next().unwrap_or(file_name);
+        if let Some(package) = arch_package_for_library(soname) {
+            depends.insert(package.to_owned());
+        } else {
+            unknown.insert(file_name.to_owned());
+        }
+    }
+    (depends.into_iter().collect(), unknown)
+}
+
+/// The Arch package that provides a library (given without its `.so` suffix)
+fn arch_package_for_library(name: &str) -> Option<&'static str> {
+    let package = match name {
+        "libc" | "libm" | "libdl" | "libpthread" | "librt" | "libresolv" | "libutil" | "libanl" => {
+            "glibc"
+        }
+        _ if name.starts_with("ld-linux") => "glibc",
+        "libgcc_s" | "libstdc++" | "libatomic" | "libgomp" => "gcc-libs",
+        "libssl" | "libcrypto" => "openssl",
+        "libz" => "zlib",
+        "liblzma" => "xz",
+        "libzstd" => "zstd",
+        "libbz2" => "bzip2",
+        "libcurl" => "curl",
+        "libsqlite3" => "sqlite",
+        "libssh2" => "libssh2",
+        "libgit2" => "libgit2",
+        "libxml2" => "libxml2",
+        "libpcre2-8" => "pcre2",
+        "libffi" => "libffi",
+        "libgmp" => "gmp",
+        "libdbus-1" => "dbus",
+        "libudev" | "libsystemd" => "systemd-libs",
+        "libasound" => "alsa-lib",
+        "libfontconfig" => "fontconfig",
+        "libfreetype" => "freetype2",
+        "libX11" => "libx11",
+        "libxcb" => "libxcb",
+        "libwayland-client" => "wayland",
+        _ => return None,
+    };
+    Some(package)
+}
+
+/// Get Arch's name for the architecture of a linux-gnu target, if it has one
+pub fn aur_architecture(target: &TargetTripleRef) -> Option<&'static str> {
+    if !target.is_linux() || target.is_musl() {
+        return None;
+    }
+    let arch = target.as_str().split('-').next().unwrap_or_default();
+    let aur_arch = match arch {
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        "i686" => "i686",
+        "armv7" if target.as_str().ends_with("gnueabihf") => "armv7h",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(aur_arch)
+}
+
+/// Turn a package name into something the AUR accepts
+///
+/// Arch package names are lowercase and can only contain alphanumerics and `@._+-`
+pub fn aur_package_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '@' | '.' | '_' | '+' | '-' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Turn a semver version into an Arch pkgver
+///
+/// pkgver can't contain hyphens, and pacman considers `1.0.0_alpha` older
+/// than `1.0.0`, so prereleases still sort before their release.
+pub fn aur_pkgver(version: &semver::Version) -> String {
+    version
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+/// Quote something for bash
+fn quote(val: &str) -> String {
+    format!("'{}'", val.replace('\'', r"'\''"))
+}
+
+/// Quote a list of things for a bash array
+fn quote_all(vals: impl IntoIterator<Item = impl AsRef<str>>) -> String {
+    vals.into_iter()
+        .map(|val| quote(val.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use cargo_dist_schema::{Library, PackageManager, TargetTriple};
+
+    use super::*;
+    use crate::{config::CompressionImpl, platform::RuntimeConditions, ReleaseIdx};
+
+    fn info() -> AurInstallerInfo {
+        let target = TargetTriple::new("x86_64-unknown-linux-gnu".to_owned());
+        let fragment = ExecutableZipFragment {
+            id: "axolotlsay-x86_64-unknown-linux-gnu.tar.xz".to_owned(),
+            target_triple: target.clone(),
+            executables: vec!["axolotlsay".to_owned()],
+            cdylibs: vec![],
+            cstaticlibs: vec![],
+            zip_style: ZipStyle::Tar(CompressionImpl::Xzip),
+            updater: None,
+            runtime_conditions: RuntimeConditions::default(),
+        };
+        AurInstallerInfo {
+            package_name: "axolotlsay-bin".to_owned(),
+            provides: "axolotlsay".to_owned(),
+            pkgver: "0.1.0_alpha.1".to_owned(),
+            maintainer: Some("axodotdev <hello@axo.dev>".to_owned()),
+            desc: Some("say things\n\nlike an axolotl's".to_owned()),
+            homepage: Some("https://github.com/axodotdev/axolotlsay".to_owned()),
+            license: Some("MIT OR Apache-2.0".to_owned()),
+            sources: vec![AurSource {
+                arch: "x86_64".to_owned(),
+                fragment: fragment.clone(),
+            }],
+            licenses: vec!["LICENSE-MIT".to_owned()],
+            docs: vec!["README.md".to_owned()],
+            package_dir: "axolotlsay-aur".into(),
+            inner: InstallerInfo {
+                release: ReleaseIdx(0),
+                dest_path: "axolotlsay-aur.tar.gz".into(),
+                app_name: "axolotlsay".to_owned(),
+                app_version: "0.1.0-alpha.1".to_owned(),
+                base_url: "https://example.com/v0.1.0-alpha.1".to_owned(),
+                artifacts: vec![fragment],
+                desc: String::new(),
+                hint: String::new(),
+                install_paths: vec![],
+                install_success_msg: String::new(),
+                receipt: None,
+                bin_aliases: BTreeMap::from([(
+                    target,
+                    BTreeMap::from([("axolotlsay".to_owned(), vec!["say".to_owned()])]),
+                )]),
+                install_libraries: vec![],
+                runtime_conditions: RuntimeConditions::default(),
+                platform_support: None,
+                install_dir_env_var: String::new(),
+                unmanaged_dir_env_var: String::new(),
+                disable_update_env_var: String::new(),
+                no_modify_path_env_var: String::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn pkgbuild() {
+        let pkgbuild = info().pkgbuild(&["glibc".to_owned()], &[Some("abc123".to_owned())]);
+        assert_eq!(
+            pkgbuild,
+            r#"# Maintainer: axodotdev <hello@axo.dev>
+pkgname=axolotlsay-bin
+pkgver=0.1.0_alpha.1
+pkgrel=1
+pkgdesc='say things'
+arch=('x86_64')
+url='https://github.com/axodotdev/axolotlsay'
+license=('MIT OR Apache-2.0')
+depends=('glibc')
+provides=('axolotlsay')
+conflicts=('axolotlsay')
+options=('!strip' '!debug')
+source_x86_64=('axolotlsay-bin-0.1.0_alpha.1-x86_64.tar.xz::https://example.com/v0.1.0-alpha.1/axolotlsay-x86_64-unknown-linux-gnu.tar.xz')
+sha256sums_x86_64=('abc123')
+
+package() {
+  case "$CARCH" in
+    x86_64)
+      cd 'axolotlsay-x86_64-unknown-linux-gnu'
+      install -Dm755 'axolotlsay' -t "$pkgdir/usr/bin"
+      ln -s 'axolotlsay' "$pkgdir/usr/bin/"'say'
+      ;;
+  esac
+  install -Dm644 'LICENSE-MIT' -t "$pkgdir/usr/share/licenses/$pkgname"
+  install -Dm644 'README.md' -t "$pkgdir/usr/share/doc/$pkgname"
+}
+"#
+        );
+    }
+
+    #[test]
+    fn srcinfo() {
+        let srcinfo = info().srcinfo(&["glibc".to_owned()], &[None]);
+        assert_eq!(
+            srcinfo,
+            "pkgbase = axolotlsay-bin
+\tpkgdesc = say things
+\tpkgver = 0.1.0_alpha.1
+\tpkgrel = 1
+\turl = https://github.com/axodotdev/axolotlsay
+\tarch = x86_64
+\tlicense = MIT OR Apache-2.0
+\tdepends = glibc
+\tprovides = axolotlsay
+\tconflicts = axolotlsay
+\toptions = !strip
+\toptions = !debug
+\tsource_x86_64 = axolotlsay-bin-0.1.0_alpha.1-x86_64.tar.xz::https://example.com/v0.1.0-alpha.1/axolotlsay-x86_64-unknown-linux-gnu.tar.xz
+\tsha256sums_x86_64 = SKIP
+
+pkgname = axolotlsay-bin
+"
+        );
+    }
+
+    #[test]
+    fn depends_on_arch_packages() {
+        let lib = |path: &str| Library {
+            path: path.to_owned(),
+            source: Some("libc6".to_owned()),
+            package_manager: Some(PackageManager::Apt),
+        };
+        let mut linkage = Linkage::default();
+        for path in [
+            "/lib/x86_64-linux-gnu/libc.so.6",
+            "/lib64/ld-linux-x86-64.so.2",
+            "/lib/x86_64-linux-gnu/libgcc_s.so.1",
+            "/lib/x86_64-linux-gnu/libssl.so.3",
+            "/usr/lib/libaxolotl.so",
+        ] {
+            linkage.system.insert(lib(path));
+        }
+        let (depends, unknown) = arch_dependencies(&linkage);
+        assert_eq!(depends, vec!["gcc-libs", "glibc", "openssl"]);
+        assert_eq!(
+            unknown.into_iter().collect::<Vec<_>>(),
+            vec!["libaxolotl.so"]
+        );
+    }
+
+    #[test]
+    fn pkgver() {
+        let pkgver = |v: &str| aur_pkgver(&semver::Version::parse(v).unwrap());
+        assert_eq!(pkgver("1.2.3"), "1.2.3");
+        assert_eq!(pkgver("1.2.3-alpha.1"), "1.2.3_alpha.1");
+        assert_eq!(pkgver("1.2.3+build-5"), "1.2.3_build_5");
+    }
+}
@@ -0,0 +1,477 @@
+//! Code for generating Debian packages
+//!
+//! A .deb is just an `ar` archive of a couple tarballs, so we assemble it
+//! ourselves instead of requiring dpkg-deb on the machine doing the build.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Read, Write},
+};
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{DistManifest, Linkage, PackageManager, TargetTripleRef};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::Serialize;
+use tracing::info;
+
+use super::source_date_epoch;
+use crate::DistResult;
+
+/// Info about a Debian package
+#[derive(Debug, Clone, Serialize)]
+pub struct DebInstallerInfo {
+    /// The id of the artifact (used to look up linkage in the manifest)
+    pub artifact_id: String,
+    /// Final file path of the deb
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries get copied to before packaging
+    pub package_dir: Utf8PathBuf,
+    /// Name of the package
+    pub package_name: String,
+    /// Version of the package, in Debian's format
+    pub version: String,
+    /// Debian name of the architecture
+    pub architecture: String,
+    /// Who maintains the package
+    pub maintainer: String,
+    /// A brief description of the application
+    pub description: Option<String>,
+    /// The URL to the application's homepage
+    pub homepage: Option<String>,
+    /// Executables to install to /usr/bin
+    pub executables: Vec<String>,
+    /// Dynamic libraries to install to /usr/lib
+    pub cdylibs: Vec<String>,
+    /// Executable aliases
+    pub bin_aliases: BTreeMap<String, Vec<String>>,
+    /// READMEs and LICENSEs to install to /usr/share/doc
+    pub docs: Vec<Utf8PathBuf>,
+}
+
+/// Something to put in the data tarball of a package
+enum DebEntry {
+    File { mode: u32, contents: Vec<u8> },
+    Symlink { target: String },
+}
+
+impl DebInstallerInfo {
+    /// Build the deb
+    pub fn build(&self, manifest: &DistManifest) -> DistResult<()> {
+        info!("building a deb: {}", self.package_name);
+
+        let mut entries = BTreeMap::new();
+        for exe in &self.executables {
+            let contents = LocalAsset::load_bytes(self.package_dir.join(exe))?;
+            entries.insert(
+                format!("usr/bin/{exe}"),
+                DebEntry::File {
+                    mode: 0o755,
+                    contents,
+                },
+            );
+        }
+        for (bin, aliases) in &self.bin_aliases {
+            for alias in aliases {
+                entries.insert(
+                    format!("usr/bin/{alias}"),
+                    DebEntry::Symlink {
+                        target: bin.clone(),
+                    },
+                );
+            }
+        }
+        for lib in &self.cdylibs {
+            let contents = LocalAsset::load_bytes(self.package_dir.join(lib))?;
+            entries.insert(
+                format!("usr/lib/{lib}"),
+                DebEntry::File {
+                    mode: 0o644,
+                    contents,
+                },
+            );
+        }
+        for doc in &self.docs {
+            let contents = LocalAsset::load_bytes(doc)?;
+            let name = doc.file_name().expect("doc had no file name!?");
+            entries.insert(
+                format!("usr/share/doc/{}/{name}", self.package_name),
+                DebEntry::File {
+                    mode: 0o644,
+                    contents,
+                },
+            );
+        }
+
+        let mtime = source_date_epoch();
+        let depends = apt_dependencies(&manifest.linkage_for_artifact(&self.artifact_id));
+        let control = self.control_file(installed_size(&entries), &depends);
+        let control_tarball = tarball(
+            &BTreeMap::from([(
+                "control".to_owned(),
+                DebEntry::File {
+                    mode: 0o644,
+                    contents: control.into_bytes(),
+                },
+            )]),
+            mtime,
+        )?;
+        let data_tarball = tarball(&entries, mtime)?;
+
+        let mut deb = vec![];
+        write_ar(
+            &mut deb,
+            &[
+                ("debian-binary", b"2.0\n"),
+                ("control.tar.gz", &control_tarball),
+                ("data.tar.gz", &data_tarball),
+            ],
+            mtime,
+        )?;
+        std::fs::write(&self.file_path, deb)?;
+
+        Ok(())
+    }
+
+    /// Render the `control` file
+    fn control_file(&self, installed_size: u64, depends: &[String]) -> String {
+        let mut control = String::new();
+        let mut field = |name: &str, value: &str| {
+            control.push_str(&format!("{name}: {value}\n"));
+        };
+        field("Package", &self.package_name);
+        field("Version", &self.version);
+        field("Architecture", &self.architecture);
+        field("Maintainer", &self.maintainer);
+        field("Installed-Size", &installed_size.to_string());
+        if !depends.is_empty() {
+            field("Depends", &depends.join(", "));
+        }
+        field("Section", "utils");
+        field("Priority", "optional");
+        if let Some(homepage) = &self.homepage {
+            field("Homepage", homepage);
+        }
+        // The first line of the description is the synopsis, the rest is
+        // indented by a space with blank lines written as " ."
+        let description = self
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|desc| !desc.is_empty())
+            .unwrap_or(&self.package_name);
+        let mut lines = description.lines();
+        field("Description", lines.next().unwrap_or_default().trim());
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                control.push_str(" .\n");
+            } else {
+                control.push_str(&format!(" {line}\n"));
+            }
+        }
+        control
+    }
+}
+
+/// Get the apt packages providing the libraries something links against
+fn apt_dependencies(linkage: &Linkage) -> Vec<String> {
+    let Linkage {
+        system,
+        homebrew,
+        public_unmanaged,
+        other,
+        frameworks,
+    } = linkage;
+    system
+        .iter()
+        .chain(homebrew)
+        .chain(public_unmanaged)
+        .chain(other)
+        .chain(frameworks)
+        .filter(|lib| lib.package_manager == Some(PackageManager::Apt))
+        .filter_map(|lib| lib.source.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// The size of the installed package in KiB, as dpkg counts it
+fn installed_size(entries: &BTreeMap<String, DebEntry>) -> u64 {
+    entries
+        .values()
+        .map(|entry| match entry {
+            DebEntry::File { contents, .. } => (contents.len() as u64).div_ceil(1024),
+            DebEntry::Symlink { .. } => 1,
+        })
+        .sum()
+}
+
+/// Make a .tar.gz of the given entries (and all the directories they need)
+fn tarball(entries: &BTreeMap<String, DebEntry>, mtime: u64) -> DistResult<Vec<u8>> {
+    let mut dirs = BTreeSet::new();
+    for path in entries.keys() {
+        let mut parent = path.as_str();
+        while let Some((dir, _)) = parent.rsplit_once('/') {
+            dirs.insert(format!("{dir}/"));
+            parent = dir;
+        }
+    }
+
+    let mut builder = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+    let header = |entry_type, mode, size| {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_mode(mode);
+        header.set_size(size);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_mtime(mtime);
+        header
+    };
+    for dir in std::iter::once("./".to_owned()).chain(dirs) {
+        let mut header = header(tar::EntryType::Directory, 0o755, 0);
+        builder.append_data(&mut header, dir, std::io::empty())?;
+    }
+    for (path, entry) in entries {
+        match entry {
+            DebEntry::File { mode, contents } => {
+                let mut header = header(tar::EntryType::Regular, *mode, contents.len() as u64);
+                builder.append_data(&mut header, path, &contents[..])?;
+            }
+            DebEntry::Symlink { target } => {
+                let mut header = header(tar::EntryType::Symlink, 0o777, 0);
+                builder.append_link(&mut header, path, target)?;
+            }
+        }
+    }
+    Ok(builder.into_inner()?.finish()?)
+}
+
+/// Write an `ar` archive of the given files, in the flavour dpkg expects
+fn write_ar(out: &mut impl Write, files: &[(&str, &[u8])], mtime: u64) -> std::io::Result<()> {
+    out.write_all(b"!<arch>\n")?;
+    for (name, contents) in files {
+        let size = contents.len();
+        writeln!(
+            out,
+            "{name:<16}{mtime:<12}{:<6}{:<6}{:<8}{size:<10}`",
+            0, 0, "100644"
+        )?;
+        out.write_all(contents)?;
+        // Entries are 2-byte aligned
+        if size % 2 == 1 {
+            out.write_all(b"\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Read the `control` file back out of a .deb, if it's one we know how to read
+///
+/// Only gzipped (or uncompressed) control tarballs are supported, which covers
+/// everything we build ourselves.
+pub(crate) fn read_control_file(deb: &[u8]) -> Option<String> {
+    let mut rest = deb.strip_prefix(b"!<arch>\n")?;
+    while rest.len() >= 60 {
+        let (header, body) = rest.split_at(60);
+        let name = std::str::from_utf8(&header[..16]).ok()?.trim_end();
+        let size = std::str::from_utf8(&header[48..58])
+            .ok()?
+            .trim_end()
+            .parse::<usize>()
+            .ok()?;
+        let contents = body.get(..size)?;
+        let tarball: Box<dyn Read + '_> = match name.trim_end_matches('/') {
+            "control.tar.gz" => Box::new(GzDecoder::new(contents)),
+            "control.tar" => Box::new(contents),
+            _ => {
+                rest = body.get(size + size % 2..).unwrap_or_default();
+                continue;
+            }
+        };
+        let mut archive = tar::Archive::new(tarball);
+        for entry in archive.entries().ok()? {
+            let mut entry = entry.ok()?;
+            let path = entry.path().ok()?;
+            if path.as_os_str() == "./control" || path.as_os_str() == "control" {
+                let mut control = String::new();
+                entry.read_to_string(&mut control).ok()?;
+                return Some(control);
+            }
+        }
+        return None;
+    }
+    None
+}
+
+/// Get the Debian name for the architecture of a linux-gnu target, if it has one
+pub fn deb_architecture(target: &TargetTripleRef) -> Option<&'static str> {
+    if !target.is_linux() || target.is_musl() {
+        return None;
+    }
+    let arch = target.as_str().split('-').next().unwrap_or_default();
+    let deb_arch = match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "i686" | "i586" => "i386",
+        "armv7" | "thumbv7neon" if target.as_str().ends_with("gnueabihf") => "armhf",
+        "arm" if target.as_str().ends_with("gnueabihf") => "armhf",
+        "arm" | "armv5te" => "armel",
+        "powerpc64le" => "ppc64el",
+        "s390x" => "s390x",
+        "riscv64gc" => "riscv64",
+        "loongarch64" => "loong64",
+        _ => return None,
+    };
+    Some(deb_arch)
+}
+
+/// Turn a package name into something Debian accepts
+///
+/// Debian package names are lowercase and can only contain alphanumerics and `+-.`
+pub fn deb_package_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '+' | '-' | '.' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect()
+}
+
+/// Turn a semver version into a Debian version
+///
+/// Prereleases sort *before* their release in semver, which Debian spells with `~`.
+pub fn deb_version(version: &semver::Version) -> String {
+    let mut deb_version = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        deb_version.push('~');
+        deb_version.push_str(version.pre.as_str());
+    }
+    if !version.build.is_empty() {
+        deb_version.push('+');
+        deb_version.push_str(version.build.as_str());
+    }
+    deb_version
+}
+
+#[cfg(test)]
+mod tests {
+    use cargo_dist_schema::{Library, Linkage, PackageManager, TargetTripleRef};
+
+    use super::*;
+
+    fn info() -> DebInstallerInfo {
+        DebInstallerInfo {
+            artifact_id: "axolotlsay-x86_64-unknown-linux-gnu.deb".to_owned(),
+            file_path: "axolotlsay-x86_64-unknown-linux-gnu.deb".into(),
+            package_dir: "axolotlsay-x86_64-unknown-linux-gnu_deb".into(),
+            package_name: "axolotlsay".to_owned(),
+            version: "0.1.0~alpha.1".to_owned(),
+            architecture: "amd64".to_owned(),
+            maintainer: "axodotdev <hello@axo.dev>".to_owned(),
+            description: Some("say things\n\nlike an axolotl".to_owned()),
+            homepage: None,
+            executables: vec!["axolotlsay".to_owned()],
+            cdylibs: vec![],
+            bin_aliases: BTreeMap::new(),
+            docs: vec![],
+        }
+    }
+
+    #[test]
+    fn control_file() {
+        let control = info().control_file(3, &["libc6".to_owned(), "libssl3".to_owned()]);
+        assert_eq!(
+            control,
+            "Package: axolotlsay
+Version: 0.1.0~alpha.1
+Architecture: amd64
+Maintainer: axodotdev <hello@axo.dev>
+Installed-Size: 3
+Depends: libc6, libssl3
+Section: utils
+Priority: optional
+Description: say things
+ .
+ like an axolotl
+"
+        );
+    }
+
+    #[test]
+    fn depends_on_apt_packages() {
+        let lib = |path: &str, source: Option<&str>, package_manager| Library {
+            path: path.to_owned(),
+            source: source.map(str::to_owned),
+            package_manager,
+        };
+        let mut linkage = Linkage::default();
+        linkage.system.insert(lib(
+            "/lib/x86_64-linux-gnu/libc.so.6",
+            Some("libc6"),
+            Some(PackageManager::Apt),
+        ));
+        linkage.system.insert(lib(
+            "/lib/x86_64-linux-gnu/libm.so.6",
+            Some("libc6"),
+            Some(PackageManager::Apt),
+        ));
+        linkage.other.insert(lib(
+            "/usr/lib/x86_64-linux-gnu/libssl.so.3",
+            Some("libssl3"),
+            Some(PackageManager::Apt),
+        ));
+        linkage.other.insert(lib("/opt/libfoo.so", None, None));
+        assert_eq!(apt_dependencies(&linkage), ["libc6", "libssl3"]);
+    }
+
+    #[test]
+    fn ar_layout() {
+        let mut out = vec![];
+        write_ar(&mut out, &[("debian-binary", b"2.0\n"), ("odd", b"abc")], 7).unwrap();
+        let expected = "!<arch>\n\
+            debian-binary   7           0     0     100644  4         `\n2.0\n\
+            odd             7           0     0     100644  3         `\nabc\n";
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn reads_back_control_file() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let mut info = info();
+        info.package_dir = dir.clone();
+        info.file_path = dir.join("app.deb");
+        for exe in &info.executables {
+            std::fs::write(dir.join(exe), b"not really elf").unwrap();
+        }
+        info.build(&DistManifest::new(vec![], BTreeMap::new()))
+            .unwrap();
+
+        let deb = std::fs::read(&info.file_path).unwrap();
+        let control = read_control_file(&deb).unwrap();
+        assert!(control.starts_with("Package: axolotlsay\n"));
+        assert!(read_control_file(b"!<arch>\n").is_none());
+    }
+
+    #[test]
+    fn architectures() {
+        let arch = |triple: &str| deb_architecture(TargetTripleRef::from_str(triple));
+        assert_eq!(arch("x86_64-unknown-linux-gnu"), Some("amd64"));
+        assert_eq!(arch("aarch64-unknown-linux-gnu"), Some("arm64"));
+        assert_eq!(arch("armv7-unknown-linux-gnueabihf"), Some("armhf"));
+        assert_eq!(arch("x86_64-unknown-linux-musl"), None);
+        assert_eq!(arch("x86_64-pc-windows-msvc"), None);
+    }
+
+    #[test]
+    fn versions_and_names() {
+        let version = |v: &str| deb_version(&semver::Version::parse(v).unwrap());
+        assert_eq!(version("1.2.3"), "1.2.3");
+        assert_eq!(version("1.2.3-prerelease.2"), "1.2.3~prerelease.2");
+        assert_eq!(version("1.2.3+build.5"), "1.2.3+build.5");
+        assert_eq!(deb_package_name("My_App"), "my-app");
+    }
+}
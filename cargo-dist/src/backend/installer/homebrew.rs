@@ -1,16 +1,18 @@
-//! Code for generating formula.rb
+//! Code for generating formula.rb (and cask.rb)
 
 use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
 use cargo_dist_schema::DistManifest;
 use serde::Serialize;
 use spdx::{
     expression::{ExprNode, Operator},
     Expression, ParseError,
 };
+use tracing::warn;
 
-use super::InstallerInfo;
+use super::{recorded_or_local_sha256, InstallerInfo};
 use crate::{
-    backend::templates::TEMPLATE_INSTALLER_RB,
+    backend::templates::{TEMPLATE_INSTALLER_CASK_RB, TEMPLATE_INSTALLER_RB},
     config::{ChecksumStyle, LibraryStyle},
     errors::DistResult,
     installer::ExecutableZipFragment,
@@ -84,22 +86,90 @@ pub(crate) fn write_homebrew_formula(
     Ok(())
 }
 
+/// Info about a Homebrew cask wrapping the pkg installer
+#[derive(Debug, Clone, Serialize)]
+pub struct HomebrewCaskInstallerInfo {
+    /// The path to generate the cask at
+    pub dest_path: Utf8PathBuf,
+    /// The cask's token (the name you `brew install --cask`)
+    pub token: String,
+    /// The application's name
+    pub name: String,
+    /// The application's version
+    pub version: String,
+    /// The URL to the application's homepage
+    pub homepage: Option<String>,
+    /// A brief description of the application
+    pub desc: Option<String>,
+    /// URL of the directory where the pkgs can be fetched from
+    pub base_url: String,
+    /// The pkg's bundle identifier, used to uninstall it
+    pub identifier: String,
+    /// macOS AMD64 pkg
+    pub x86_64_macos: Option<String>,
+    /// sha256 of macOS AMD64 pkg
+    pub x86_64_macos_sha256: Option<String>,
+    /// macOS ARM64 pkg
+    pub arm64_macos: Option<String>,
+    /// sha256 of macOS ARM64 pkg
+    pub arm64_macos_sha256: Option<String>,
+    /// Install hint
+    pub hint: String,
+}
+
+pub(crate) fn write_homebrew_cask(
+    dist: &DistGraph,
+    source_info: &HomebrewCaskInstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let mut info = source_info.clone();
+
+    // Grab checksums
+    let pkg_sha256 = |id: &String| {
+        let checksum = recorded_or_local_sha256(dist, manifest, id);
+        if checksum.is_none() {
+            warn!(
+                "no sha256 checksum for {id}, so the Homebrew cask for {} installs it without verifying it",
+                source_info.name
+            );
+        }
+        checksum
+    };
+    if let Some(id) = &info.arm64_macos {
+        info.arm64_macos_sha256 = pkg_sha256(id);
+    }
+    if let Some(id) = &info.x86_64_macos {
+        info.x86_64_macos_sha256 = pkg_sha256(id);
+    }
+
+    let script = dist
+        .templates
+        .render_file_to_clean_string(TEMPLATE_INSTALLER_CASK_RB, &info)?;
+    LocalAsset::write_new(&script, &info.dest_path)?;
+    Ok(())
+}
+
 /// Grab the sha256 checksum for this artifact from the manifest
 fn use_sha256_checksum(
     manifest: &DistManifest,
     fragment: &Option<ExecutableZipFragment>,
     checksum: &mut Option<String>,
 ) {
-    let checksum_key = ChecksumStyle::Sha256.ext();
     if let Some(frag) = &fragment {
-        *checksum = manifest
-            .artifacts
-            .get(&frag.id)
-            .and_then(|a| a.checksums.get(checksum_key))
-            .cloned()
+        *checksum = sha256_checksum(manifest, &frag.id);
     }
 }
 
+/// Grab the sha256 checksum for the artifact with this id from the manifest
+fn sha256_checksum(manifest: &DistManifest, id: &str) -> Option<String> {
+    let checksum_key = ChecksumStyle::Sha256.ext();
+    manifest
+        .artifacts
+        .get(id)
+        .and_then(|a| a.checksums.get(checksum_key))
+        .cloned()
+}
+
 // Grab the linkage for this artifact from the manifest
 fn use_linkage(
     manifest: &DistManifest,
@@ -241,7 +311,57 @@ pub fn to_homebrew_license_format(app_license: &str) -> Result<String, ParseErro
 mod tests {
     use spdx::ParseError;
 
-    use super::{to_class_case, to_homebrew_license_format};
+    use super::{to_class_case, to_homebrew_license_format, HomebrewCaskInstallerInfo};
+    use crate::backend::templates::{Templates, TEMPLATE_INSTALLER_CASK_RB};
+
+    #[test]
+    fn cask_with_checksums() {
+        let info = HomebrewCaskInstallerInfo {
+            dest_path: "axolotlsay.cask.rb".into(),
+            token: "axolotlsay".to_owned(),
+            name: "axolotlsay".to_owned(),
+            version: "0.2.2".to_owned(),
+            homepage: Some("https://github.com/axodotdev/axolotlsay".to_owned()),
+            desc: None,
+            base_url: "https://example.com/v0.2.2".to_owned(),
+            identifier: "dev.axo.axolotlsay".to_owned(),
+            x86_64_macos: Some("axolotlsay-x86_64-apple-darwin.pkg".to_owned()),
+            x86_64_macos_sha256: Some("ab".repeat(32)),
+            arm64_macos: Some("axolotlsay-aarch64-apple-darwin.pkg".to_owned()),
+            arm64_macos_sha256: Some("cd".repeat(32)),
+            hint: String::new(),
+        };
+        let cask = Templates::new()
+            .unwrap()
+            .render_file_to_clean_string(TEMPLATE_INSTALLER_CASK_RB, &info)
+            .unwrap();
+        assert_eq!(
+            cask,
+            format!(
+                r#"cask "axolotlsay" do
+  version "0.2.2"
+  on_arm do
+    sha256 "{arm}"
+    url "https://example.com/v0.2.2/axolotlsay-aarch64-apple-darwin.pkg"
+    pkg "axolotlsay-aarch64-apple-darwin.pkg"
+  end
+  on_intel do
+    sha256 "{intel}"
+    url "https://example.com/v0.2.2/axolotlsay-x86_64-apple-darwin.pkg"
+    pkg "axolotlsay-x86_64-apple-darwin.pkg"
+  end
+
+  name "axolotlsay"
+  homepage "https://github.com/axodotdev/axolotlsay"
+
+  uninstall pkgutil: "dev.axo.axolotlsay"
+end
+"#,
+                arm = "cd".repeat(32),
+                intel = "ab".repeat(32)
+            )
+        );
+    }
 
     fn run_comparison(in_str: &str, expected: &str) {
         let out_str = to_class_case(in_str);
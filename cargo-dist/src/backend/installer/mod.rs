@@ -5,26 +5,41 @@
 use std::collections::BTreeMap;
 
 use camino::Utf8PathBuf;
-use cargo_dist_schema::TargetTriple;
+use cargo_dist_schema::{DistManifest, TargetTriple, VERSION_PLACEHOLDER};
 use macpkg::PkgInstallerInfo;
 use serde::Serialize;
 
 use crate::{
-    config::{JinjaInstallPathStrategy, LibraryStyle, ZipStyle},
+    config::{ChecksumStyle, JinjaInstallPathStrategy, LibraryStyle, ZipStyle},
+    generate_checksum,
     platform::{PlatformSupport, RuntimeConditions},
-    InstallReceipt, ReleaseIdx,
+    DistGraph, InstallReceipt, ReleaseIdx,
 };
 
-use self::homebrew::HomebrewInstallerInfo;
+use self::aur::AurInstallerInfo;
+use self::deb::DebInstallerInfo;
+use self::homebrew::{HomebrewCaskInstallerInfo, HomebrewInstallerInfo};
 use self::msi::MsiInstallerInfo;
-use self::npm::NpmInstallerInfo;
+use self::nix::NixInstallerInfo;
+use self::npm::{NpmInstallerInfo, NpmPlatformInstallerInfo};
+use self::pypi::PypiInstallerInfo;
+use self::rpm::RpmInstallerInfo;
+use self::scoop::ScoopInstallerInfo;
+use self::winget::WingetInstallerInfo;
 
+pub mod aur;
+pub mod deb;
 pub mod homebrew;
 pub mod macpkg;
 pub mod msi;
+pub mod nix;
 pub mod npm;
 pub mod powershell;
+pub mod pypi;
+pub mod rpm;
+pub mod scoop;
 pub mod shell;
+pub mod winget;
 
 /// A kind of an installer
 #[derive(Debug, Clone)]
@@ -32,16 +47,40 @@ pub mod shell;
 pub enum InstallerImpl {
     /// shell installer script
     Shell(InstallerInfo),
+    /// shell installer script with the archives appended to it
+    ShellOffline(InstallerInfo),
     /// powershell installer script
     Powershell(InstallerInfo),
+    /// shell uninstaller script
+    ShellUninstaller(UninstallerInfo),
+    /// powershell uninstaller script
+    PowershellUninstaller(UninstallerInfo),
     /// npm installer package
     Npm(NpmInstallerInfo),
+    /// npm package with the binaries for one platform
+    NpmPlatform(NpmPlatformInstallerInfo),
     /// Homebrew formula
     Homebrew(HomebrewInstallerInfo),
+    /// Homebrew cask for the pkg installer
+    HomebrewCask(HomebrewCaskInstallerInfo),
     /// Windows msi installer
     Msi(MsiInstallerInfo),
     /// Mac pkg installer
     Pkg(PkgInstallerInfo),
+    /// Scoop manifest
+    Scoop(ScoopInstallerInfo),
+    /// WinGet manifests
+    Winget(WingetInstallerInfo),
+    /// Debian package
+    Deb(DebInstallerInfo),
+    /// RPM package
+    Rpm(RpmInstallerInfo),
+    /// Arch User Repository package
+    Aur(AurInstallerInfo),
+    /// Nix flake
+    Nix(NixInstallerInfo),
+    /// Python wheel
+    Pypi(PypiInstallerInfo),
 }
 
 /// Generic info about an installer
@@ -88,6 +127,16 @@ pub struct InstallerInfo {
     pub no_modify_path_env_var: String,
 }
 
+/// Info about an uninstaller script, which undoes what an installer did
+/// by reading the receipt it left behind
+#[derive(Debug, Clone, Serialize)]
+pub struct UninstallerInfo {
+    /// The path to generate the uninstaller at
+    pub dest_path: Utf8PathBuf,
+    /// App name to use (also where the receipt lives)
+    pub app_name: String,
+}
+
 /// A fake fragment of an ExecutableZip artifact for installers
 #[derive(Debug, Clone, Serialize)]
 pub struct ExecutableZipFragment {
@@ -117,3 +166,166 @@ pub struct UpdaterFragment {
     /// The binary the artifact contains (name, assumed at root)
     pub binary: String,
 }
+
+/// A checksum a script installer can check a download against
+#[derive(Debug, Clone, Serialize)]
+pub struct ExpectedChecksum {
+    /// The algorithm, as named in the manifest (e.g. "sha256")
+    pub style: String,
+    /// The expected digest, in hex
+    pub value: String,
+}
+
+/// How a script installer can find versions other than the one it was generated for,
+/// so it can support `--version` and `--channel`
+#[derive(Debug, Clone, Serialize)]
+pub struct OtherReleases {
+    /// The base URL that a version's artifacts can be downloaded from,
+    /// with `{version}` standing in for the version
+    pub artifact_download_url: String,
+    /// What comes before the version in the tags of releases
+    pub tag_prefix: String,
+    /// What comes after the version in the tags of releases
+    pub tag_suffix: String,
+    /// The base URL that always has the latest stable release's artifacts, if any
+    pub latest_artifact_download_url: Option<String>,
+    /// An API endpoint that lists the releases newest first, if any
+    pub releases_api_url: Option<String>,
+    /// The style of the checksum files published next to each archive,
+    /// if it's one the script installers know how to check
+    pub checksum_style: Option<String>,
+    /// Like `artifact_download_url`, but for each of the fallback download URLs
+    /// that has the version in it
+    pub fallback_download_urls: Vec<String>,
+}
+
+/// Work out how the script installers for the given release can find its other versions
+///
+/// This is None if the release's host doesn't lay releases out predictably,
+/// in which case the installers can only install the version they were generated for.
+pub(crate) fn other_releases(
+    dist: &DistGraph,
+    release: ReleaseIdx,
+    manifest: &DistManifest,
+) -> Option<OtherReleases> {
+    let release = dist.release(release);
+    let tag = manifest.announcement_tag.as_deref()?;
+    let manifest_release = manifest.release_by_name(&release.app_name)?;
+    let scheme = manifest_release.release_url_scheme(tag)?;
+    let (tag_prefix, tag_suffix) = scheme.tag.split_once(VERSION_PLACEHOLDER)?;
+    let checksum_style = match dist.config.artifacts.checksum {
+        style @ (ChecksumStyle::Sha256 | ChecksumStyle::Sha512) => Some(style.ext().to_owned()),
+        _ => None,
+    };
+    // With download-urls, other versions are wherever those URLs would have them
+    let mut download_urls = manifest_release
+        .hosting
+        .download_urls
+        .iter()
+        .filter_map(|url| {
+            let (before, after) = url.rsplit_once(&release.version.to_string())?;
+            Some(format!("{before}{VERSION_PLACEHOLDER}{after}"))
+        })
+        .collect::<Vec<_>>();
+    let artifact_download_url = if download_urls.is_empty() {
+        scheme.artifact_download_url
+    } else {
+        download_urls.remove(0)
+    };
+    Some(OtherReleases {
+        artifact_download_url,
+        tag_prefix: tag_prefix.to_owned(),
+        tag_suffix: tag_suffix.to_owned(),
+        latest_artifact_download_url: scheme.latest_artifact_download_url,
+        releases_api_url: scheme.releases_api_url,
+        checksum_style,
+        fallback_download_urls: download_urls,
+    })
+}
+
+/// Point an installer at the download-urls of its release, if any are configured
+///
+/// The first URL becomes the installer's `base_url`, and the rest are returned as the
+/// fallbacks to try in order. The receipt gets a placeholder for the installer to
+/// record whichever URL it actually downloaded from.
+pub(crate) fn apply_download_urls(
+    info: &mut InstallerInfo,
+    manifest: &DistManifest,
+) -> Vec<String> {
+    if let Some(receipt) = &mut info.receipt {
+        receipt.download_url = Some("AXO_DOWNLOAD_URL".to_owned());
+    }
+    let Some(release) = manifest.release_by_name(&info.app_name) else {
+        return vec![];
+    };
+    let Some((first, rest)) = release.hosting.download_urls.split_first() else {
+        return vec![];
+    };
+    info.base_url.clone_from(first);
+    rest.to_vec()
+}
+
+/// Get the checksums dist has already computed for the given artifacts,
+/// in an algorithm the script installers know how to check
+pub(crate) fn expected_checksums<'a>(
+    manifest: &DistManifest,
+    ids: impl IntoIterator<Item = &'a str>,
+) -> BTreeMap<String, ExpectedChecksum> {
+    let supported = [ChecksumStyle::Sha256.ext(), ChecksumStyle::Sha512.ext()];
+    ids.into_iter()
+        .filter_map(|id| {
+            let checksums = &manifest.artifacts.get(id)?.checksums;
+            let (style, value) = supported
+                .iter()
+                .find_map(|style| Some((*style, checksums.get(*style)?)))?;
+            Some((
+                id.to_owned(),
+                ExpectedChecksum {
+                    style: style.to_owned(),
+                    value: value.clone(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Get the sha256 of the artifact with this id from the manifest,
+/// or hash it ourselves if it's lying around in the dist dir
+///
+/// Global installers that have to embed the checksums of local artifacts use this,
+/// so that they still work when checksums are disabled.
+pub(crate) fn recorded_or_local_sha256(
+    dist: &DistGraph,
+    manifest: &DistManifest,
+    artifact_id: &str,
+) -> Option<String> {
+    let recorded = manifest
+        .artifacts
+        .get(artifact_id)
+        .and_then(|a| a.checksums.get(ChecksumStyle::Sha256.ext()))
+        .cloned();
+    if recorded.is_some() {
+        return recorded;
+    }
+    let path = dist.dist_dir.join(artifact_id);
+    if !path.exists() {
+        return None;
+    }
+    generate_checksum(&ChecksumStyle::Sha256, &path).ok()
+}
+
+/// The mtime to give everything in a bundling installer (.deb, .rpm...)
+///
+/// Respects SOURCE_DATE_EPOCH so that builds can be reproducible.
+pub(crate) fn source_date_epoch() -> u64 {
+    if let Some(epoch) = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+    {
+        return epoch;
+    }
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,423 @@
+//! Code for generating Nix expressions
+//!
+//! We write a `default.nix` that fetches the prebuilt archives and installs
+//! the binaries out of them, plus a `flake.nix` with a `packages.<system>`
+//! output for each platform, so the archive of the two is installable with
+//! `nix profile install <url>`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use axoasset::LocalAsset;
+use base64::Engine;
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{DistManifest, Linkage, TargetTripleRef};
+use serde::Serialize;
+use tracing::warn;
+
+use super::{ExecutableZipFragment, InstallerInfo};
+use crate::{
+    backend::templates::TEMPLATE_INSTALLER_NIX,
+    config::{ChecksumStyle, LibraryStyle, ZipStyle},
+    errors::DistResult,
+    tasks::DistGraph,
+};
+
+/// Info about a Nix expression
+#[derive(Debug, Clone, Serialize)]
+pub struct NixInstallerInfo {
+    /// The name of the package
+    pub pname: String,
+    /// A brief description of the application
+    pub desc: Option<String>,
+    /// The URL to the application's homepage
+    pub homepage: Option<String>,
+    /// The application's license, in SPDX format
+    pub license: Option<String>,
+    /// The archive to fetch for each Nix system
+    pub sources: Vec<NixSource>,
+    /// Binaries to install, and the aliases to install them as
+    pub bins: BTreeMap<String, Vec<String>>,
+    /// Dir the expressions get written to before archiving
+    pub package_dir: Utf8PathBuf,
+    /// Generic installer info
+    pub inner: InstallerInfo,
+}
+
+/// An archive a Nix expression fetches for one system
+#[derive(Debug, Clone, Serialize)]
+pub struct NixSource {
+    /// Nix's name for the platform (`x86_64-linux`...)
+    pub system: String,
+    /// The archive
+    pub fragment: ExecutableZipFragment,
+}
+
+/// The names of the files in a Nix archive
+pub const NIX_FILES: [&str; 2] = ["default.nix", "flake.nix"];
+
+/// Everything the templates need, already quoted as Nix strings
+#[derive(Debug, Clone, Serialize)]
+struct NixExpression {
+    app_name: String,
+    app_version: String,
+    pname: String,
+    attr_name: String,
+    version: String,
+    description: Option<String>,
+    homepage: Option<String>,
+    licenses: Vec<String>,
+    main_program: Option<String>,
+    sources: Vec<NixSourceExpression>,
+    has_zip: bool,
+    build_inputs: Vec<String>,
+    aliases: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct NixSourceExpression {
+    system: String,
+    url: String,
+    hash: String,
+    source_root: String,
+    bins: Vec<String>,
+    libs: Vec<String>,
+    patchelf: bool,
+}
+
+pub(crate) fn write_nix_expressions(
+    dist: &DistGraph,
+    info: &NixInstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let expression = info.expression(manifest);
+    let files = dist
+        .templates
+        .render_dir_to_clean_strings(TEMPLATE_INSTALLER_NIX, &expression)?;
+
+    // Don't let the files of old versions sneak into the archive
+    if info.package_dir.exists() {
+        LocalAsset::remove_dir_all(&info.package_dir)?;
+    }
+    for (relpath, rendered) in files {
+        LocalAsset::write_new_all(&rendered, info.package_dir.join(relpath))?;
+    }
+    Ok(())
+}
+
+impl NixInstallerInfo {
+    fn expression(&self, manifest: &DistManifest) -> NixExpression {
+        let mut build_inputs = BTreeSet::new();
+        let mut unknown = BTreeSet::new();
+        let mut sources = vec![];
+        for source in &self.sources {
+            let fragment = &source.fragment;
+            let linkage = manifest.linkage_for_artifact(&fragment.id);
+            let (inputs, missing) = nix_dependencies(&linkage);
+            // gnu builds always need their interpreter patched, even if we
+            // didn't get to record what else they link against
+            let patchelf = fragment.target_triple.is_linux()
+                && (!fragment.target_triple.is_musl() || !inputs.is_empty() || !missing.is_empty());
+            if patchelf {
+                build_inputs.extend(inputs);
+                unknown.extend(missing);
+            }
+
+            let hash = match sha256_checksum(manifest, fragment) {
+                Some(checksum) => nix_string(&checksum),
+                None => {
+                    warn!(
+                        "no sha256 checksum for {}, so the Nix expression for {} can't be used until its hash is filled in",
+                        fragment.id, self.inner.app_name
+                    );
+                    "lib.fakeHash".to_owned()
+                }
+            };
+            // Tarballs have everything in a dir named after the archive, zips are flat
+            let source_root = if let ZipStyle::Tar(_) = fragment.zip_style {
+                fragment
+                    .id
+                    .strip_suffix(fragment.zip_style.ext())
+                    .unwrap_or(&fragment.id)
+            } else {
+                "."
+            };
+            let mut libs = vec![];
+            if self
+                .inner
+                .install_libraries
+                .contains(&LibraryStyle::CDynamic)
+            {
+                libs.extend(fragment.cdylibs.iter().map(|lib| nix_string(lib)));
+            }
+            if self
+                .inner
+                .install_libraries
+                .contains(&LibraryStyle::CStatic)
+            {
+                libs.extend(fragment.cstaticlibs.iter().map(|lib| nix_string(lib)));
+            }
+            sources.push(NixSourceExpression {
+                system: nix_string(&source.system),
+                url: nix_string(&format!("{}/{}", self.inner.base_url, fragment.id)),
+                hash,
+                source_root: nix_string(source_root),
+                bins: fragment.executables.iter().map(|b| nix_string(b)).collect(),
+                libs,
+                patchelf,
+            });
+        }
+        if !unknown.is_empty() {
+            warn!(
+                "couldn't tell which Nix packages provide these libraries, so the Nix expression for {} doesn't depend on them:\n  {}",
+                self.inner.app_name,
+                unknown.into_iter().collect::<Vec<_>>().join("\n  ")
+            );
+        }
+
+        let aliases = self
+            .bins
+            .iter()
+            .flat_map(|(bin, aliases)| {
+                aliases
+                    .iter()
+                    .map(move |alias| (nix_string(bin), nix_string(alias)))
+            })
+            .collect();
+        // `nix run` needs to know which binary to run if there's more than one
+        let main_program = self
+            .bins
+            .keys()
+            .find(|bin| **bin == self.inner.app_name)
+            .or_else(|| self.bins.keys().next())
+            .map(|bin| nix_string(bin));
+        let licenses = self
+            .license
+            .as_deref()
+            .and_then(|license| spdx::Expression::parse(license).ok())
+            .map(|expr| {
+                expr.requirements()
+                    .map(|req| nix_string(&req.req.license.to_string()))
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let description = self
+            .desc
+            .as_deref()
+            .and_then(|desc| desc.lines().map(str::trim).find(|line| !line.is_empty()))
+            .map(nix_string);
+
+        NixExpression {
+            app_name: self.inner.app_name.clone(),
+            app_version: self.inner.app_version.clone(),
+            pname: nix_string(&self.pname),
+            attr_name: nix_string(&self.pname),
+            version: nix_string(&self.inner.app_version),
+            description,
+            homepage: self.homepage.as_deref().map(nix_string),
+            licenses,
+            main_program,
+            has_zip: self
+                .sources
+                .iter()
+                .any(|source| source.fragment.zip_style == ZipStyle::Zip),
+            sources,
+            build_inputs: build_inputs.into_iter().map(str::to_owned).collect(),
+            aliases,
+        }
+    }
+}
+
+/// Grab the sha256 checksum for this artifact from the manifest, as an SRI hash
+fn sha256_checksum(manifest: &DistManifest, fragment: &ExecutableZipFragment) -> Option<String> {
+    let checksum = manifest
+        .artifacts
+        .get(&fragment.id)
+        .and_then(|a| a.checksums.get(ChecksumStyle::Sha256.ext()))?;
+    sri_hash(checksum)
+}
+
+/// Turn a hex sha256 into the `sha256-<base64>` form Nix wants
+fn sri_hash(hex: &str) -> Option<String> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    Some(format!("sha256-{encoded}"))
+}
+
+/// Work out which nixpkgs attributes provide the libraries a build links against
+///
+/// Returns the attributes, and the libraries we don't know an attribute for.
+/// Libraries that come from glibc are left out, since autoPatchelf finds those itself.
+fn nix_dependencies(linkage: &Linkage) -> (BTreeSet<&'static str>, BTreeSet<String>) {
+    let Linkage {
+        system,
+        homebrew: _,
+        public_unmanaged,
+        other,
+        frameworks: _,
+    } = linkage;
+    let mut inputs = BTreeSet::new();
+    let mut unknown = BTreeSet::new();
+    for lib in system.iter().chain(public_unmanaged).chain(other) {
+        let file_name = lib.path.rsplit('/').next().unwrap_or(&lib.path);
+        let soname = file_name.split(".so").next().unwrap_or(file_name);
+        match nix_package_for_library(soname) {
+            Some(Some(input)) => {
+                inputs.insert(input);
+            }
+            Some(None) => {}
+            None => {
+                unknown.insert(file_name.to_owned());
+            }
+        }
+    }
+    (inputs, unknown)
+}
+
+/// The nixpkgs attribute that provides a library (given without its `.so` suffix)
+///
+/// `Some(None)` means the library is part of libc.
+fn nix_package_for_library(name: &str) -> Option<Option<&'static str>> {
+    let input = match name {
+        "libc" | "libm" | "libdl" | "libpthread" | "librt" | "libresolv" | "libutil" | "libanl" => {
+            return Some(None)
+        }
+        _ if name.starts_with("ld-linux") => return Some(None),
+        "libgcc_s" | "libstdc++" | "libatomic" | "libgomp" => "stdenv.cc.cc.lib",
+        "libssl" | "libcrypto" => "openssl",
+        "libz" => "zlib",
+        "liblzma" => "xz",
+        "libzstd" => "zstd",
+        "libbz2" => "bzip2",
+        "libcurl" => "curl",
+        "libsqlite3" => "sqlite",
+        "libssh2" => "libssh2",
+        "libgit2" => "libgit2",
+        "libxml2" => "libxml2",
+        "libpcre2-8" => "pcre2",
+        "libffi" => "libffi",
+        "libgmp" => "gmp",
+        "libdbus-1" => "dbus",
+        "libudev" | "libsystemd" => "systemd",
+        "libasound" => "alsa-lib",
+        "libfontconfig" => "fontconfig",
+        "libfreetype" => "freetype",
+        "libX11" => "xorg.libX11",
+        "libxcb" => "xorg.libxcb",
+        "libwayland-client" => "wayland",
+        _ => return None,
+    };
+    Some(Some(input))
+}
+
+/// Get Nix's name for the platform of a target, if it has one
+pub fn nix_system(target: &TargetTripleRef) -> Option<&'static str> {
+    let arch = target.as_str().split('-').next().unwrap_or_default();
+    let system = if target.is_linux() {
+        match arch {
+            "x86_64" => "x86_64-linux",
+            "aarch64" => "aarch64-linux",
+            "i686" => "i686-linux",
+            "armv7" if target.as_str().ends_with("gnueabihf") => "armv7l-linux",
+            "riscv64gc" => "riscv64-linux",
+            _ => return None,
+        }
+    } else if target.is_darwin() {
+        match arch {
+            "x86_64" => "x86_64-darwin",
+            "aarch64" => "aarch64-darwin",
+            _ => return None,
+        }
+    } else {
+        return None;
+    };
+    Some(system)
+}
+
+/// Quote something as a Nix string
+fn nix_string(val: &str) -> String {
+    let mut out = String::with_capacity(val.len() + 2);
+    out.push('"');
+    let mut chars = val.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            // `${` would start an interpolation
+            '$' if chars.peek() == Some(&'{') => out.push_str("\\$"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use cargo_dist_schema::{Library, PackageManager};
+
+    use super::*;
+
+    #[test]
+    fn quotes_nix_strings() {
+        assert_eq!(nix_string("say \"hi\""), r#""say \"hi\"""#);
+        assert_eq!(nix_string("${HOME} $HOME"), r#""\${HOME} $HOME""#);
+        assert_eq!(nix_string("a\\b\nc"), r#""a\\b\nc""#);
+    }
+
+    #[test]
+    fn sri_hashes() {
+        assert_eq!(
+            sri_hash("7ef10c0b87daf0a92ecfd8a6726705ea60a1f530f3bac54cdc5230a32512b2be").unwrap(),
+            "sha256-fvEMC4fa8Kkuz9imcmcF6mCh9TDzusVM3FIwoyUSsr4="
+        );
+        assert_eq!(sri_hash("not a hash"), None);
+    }
+
+    #[test]
+    fn depends_on_nix_packages() {
+        let lib = |path: &str| Library {
+            path: path.to_owned(),
+            source: Some("libc6".to_owned()),
+            package_manager: Some(PackageManager::Apt),
+        };
+        let mut linkage = Linkage::default();
+        for path in [
+            "/lib/x86_64-linux-gnu/libc.so.6",
+            "/lib64/ld-linux-x86-64.so.2",
+            "/lib/x86_64-linux-gnu/libgcc_s.so.1",
+            "/lib/x86_64-linux-gnu/libssl.so.3",
+            "/usr/lib/libaxolotl.so",
+        ] {
+            linkage.system.insert(lib(path));
+        }
+        let (inputs, unknown) = nix_dependencies(&linkage);
+        assert_eq!(
+            inputs.into_iter().collect::<Vec<_>>(),
+            vec!["openssl", "stdenv.cc.cc.lib"]
+        );
+        assert_eq!(
+            unknown.into_iter().collect::<Vec<_>>(),
+            vec!["libaxolotl.so"]
+        );
+    }
+
+    #[test]
+    fn systems() {
+        let system = |t: &str| nix_system(TargetTripleRef::from_str(t));
+        assert_eq!(system("x86_64-unknown-linux-gnu"), Some("x86_64-linux"));
+        assert_eq!(system("aarch64-unknown-linux-musl"), Some("aarch64-linux"));
+        assert_eq!(system("aarch64-apple-darwin"), Some("aarch64-darwin"));
+        assert_eq!(system("x86_64-pc-windows-msvc"), None);
+    }
+}
@@ -2,12 +2,15 @@
 
 use axoasset::{LocalAsset, SourceFile};
 use camino::{Utf8Path, Utf8PathBuf};
-use cargo_dist_schema::{GlibcVersion, TargetTriple};
+use cargo_dist_schema::{DistManifest, GlibcVersion, TargetTriple, TargetTripleRef};
 use serde::Serialize;
 
-use super::InstallerInfo;
+use super::{apply_download_urls, InstallerInfo};
 use crate::{
-    backend::templates::{Templates, TEMPLATE_INSTALLER_NPM, TEMPLATE_INSTALLER_NPM_RUN_JS},
+    backend::templates::{
+        TemplateId, Templates, TEMPLATE_INSTALLER_NPM, TEMPLATE_INSTALLER_NPM_PLATFORMS,
+        TEMPLATE_INSTALLER_NPM_PLATFORMS_RUN_JS, TEMPLATE_INSTALLER_NPM_RUN_JS,
+    },
     errors::DistResult,
     platform::LibcVersion,
     DistGraph, SortedMap, SortedSet,
@@ -34,10 +37,49 @@ pub struct NpmInstallerInfo {
     pub npm_package_keywords: Option<Vec<String>>,
     /// Dir to build the package in
     pub package_dir: Utf8PathBuf,
+    /// Packages with the binaries for each platform, which this package
+    /// depends on instead of downloading the binaries when it's installed
+    pub platform_packages: Option<Vec<NpmPlatformPackage>>,
     /// Generic installer info
     pub inner: InstallerInfo,
 }
 
+/// An npm package with the binaries for one platform
+#[derive(Debug, Clone, Serialize)]
+pub struct NpmPlatformPackage {
+    /// The target the binaries were built for
+    pub target: TargetTriple,
+    /// The name of the npm package
+    pub npm_package_name: String,
+    /// The value of `process.platform` the package can be installed on
+    pub os: String,
+    /// The value of `process.arch` the package can be installed on
+    pub cpu: String,
+    /// The libc the package can be installed with, for linux
+    pub libc: Option<String>,
+    /// The executables in the package (name, assumed at root)
+    pub executables: Vec<String>,
+}
+
+/// Info about an npm package with the binaries for one platform
+#[derive(Debug, Clone)]
+pub struct NpmPlatformInstallerInfo {
+    /// The platform the package is for
+    pub package: NpmPlatformPackage,
+    /// The version of the npm package
+    pub npm_package_version: String,
+    /// Short description of the package
+    pub npm_package_desc: Option<String>,
+    /// URL to repository
+    pub npm_package_repository_url: Option<String>,
+    /// URL to homepage
+    pub npm_package_homepage_url: Option<String>,
+    /// License of the package
+    pub npm_package_license: Option<String>,
+    /// Dir to build the package in
+    pub package_dir: Utf8PathBuf,
+}
+
 const RUN_JS: &str = "run.js";
 const PACKAGE_JSON: &str = "package.json";
 const PACKAGE_LOCK: &str = "npm-shrinkwrap.json";
@@ -63,14 +105,66 @@ struct RunInfo {
     bin: String,
 }
 
-pub(crate) fn write_npm_project(dist: &DistGraph, info: &NpmInstallerInfo) -> DistResult<()> {
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlatformPackageJson<'a> {
+    name: &'a str,
+    version: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repository: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<&'a str>,
+    os: Vec<&'a str>,
+    cpu: Vec<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    libc: Option<Vec<&'a str>>,
+    // Yarn PnP can't run binaries out of a zip archive
+    prefer_unplugged: bool,
+}
+
+impl NpmInstallerInfo {
+    /// The template dir for this package, and the run.js template in it
+    pub fn template_dir(&self) -> (TemplateId, TemplateId) {
+        if self.platform_packages.is_some() {
+            (
+                TEMPLATE_INSTALLER_NPM_PLATFORMS,
+                TEMPLATE_INSTALLER_NPM_PLATFORMS_RUN_JS,
+            )
+        } else {
+            (TEMPLATE_INSTALLER_NPM, TEMPLATE_INSTALLER_NPM_RUN_JS)
+        }
+    }
+}
+
+pub(crate) fn write_npm_project(
+    dist: &DistGraph,
+    info: &NpmInstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    // Packages that download the binaries might have several places to try
+    let mut info = info.clone();
+    let fallback_urls = if info.platform_packages.is_none() {
+        apply_download_urls(&mut info.inner, manifest)
+    } else {
+        vec![]
+    };
+    let info = &info;
+
     // First render the dir
     let templates = &dist.templates;
-    let mut files = templates.render_dir_to_clean_strings(TEMPLATE_INSTALLER_NPM, info)?;
+    let (template_dir, run_js_template) = info.template_dir();
+    let mut files = templates.render_dir_to_clean_strings(template_dir, info)?;
     let platforms = platforms(info);
-    mangle_run_js(templates, &platforms, &mut files)?;
-    mangle_package_json(info, &platforms, &mut files)?;
-    mangle_package_lock(info, &platforms, &mut files)?;
+    mangle_run_js(templates, run_js_template, &platforms, &mut files)?;
+    mangle_package_json(info, &platforms, &fallback_urls, &mut files)?;
+    // Packages that download the binaries have dependencies to lock
+    if info.platform_packages.is_none() {
+        mangle_package_lock(info, &platforms, &mut files)?;
+    }
 
     // Finally, write the results
     let zip_dir = &info.package_dir;
@@ -81,8 +175,32 @@ pub(crate) fn write_npm_project(dist: &DistGraph, info: &NpmInstallerInfo) -> Di
     Ok(())
 }
 
+/// Write the package.json of an npm package with the binaries for one platform
+///
+/// The binaries themselves get copied next to it by the build.
+pub(crate) fn write_npm_platform_package(info: &NpmPlatformInstallerInfo) -> DistResult<()> {
+    let package = &info.package;
+    let package_json = PlatformPackageJson {
+        name: &package.npm_package_name,
+        version: &info.npm_package_version,
+        description: info.npm_package_desc.as_deref(),
+        repository: info.npm_package_repository_url.as_deref(),
+        homepage: info.npm_package_homepage_url.as_deref(),
+        license: info.npm_package_license.as_deref(),
+        os: vec![&package.os],
+        cpu: vec![&package.cpu],
+        libc: package.libc.as_deref().map(|libc| vec![libc]),
+        prefer_unplugged: true,
+    };
+    let contents = serde_json::to_string_pretty(&package_json).expect("serde_json failed");
+    LocalAsset::write_new_all(&contents, info.package_dir.join(PACKAGE_JSON))?;
+
+    Ok(())
+}
+
 fn mangle_run_js(
     templates: &Templates,
+    run_js_template: TemplateId,
     platforms: &PlatformSummary,
     files: &mut SortedMap<Utf8PathBuf, String>,
 ) -> DistResult<()> {
@@ -97,8 +215,7 @@ fn mangle_run_js(
         let info = RunInfo {
             bin: bin.to_owned(),
         };
-        let contents =
-            templates.render_file_to_clean_string(TEMPLATE_INSTALLER_NPM_RUN_JS, &info)?;
+        let contents = templates.render_file_to_clean_string(run_js_template, &info)?;
         files.insert(Utf8PathBuf::from(filename), contents);
     }
 
@@ -142,6 +259,7 @@ fn mangle_package_lock(
 fn mangle_package_json(
     info: &NpmInstallerInfo,
     platforms: &PlatformSummary,
+    fallback_urls: &[String],
     files: &mut SortedMap<Utf8PathBuf, String>,
 ) -> DistResult<()> {
     let package_json_path = Utf8Path::new(PACKAGE_JSON);
@@ -177,12 +295,30 @@ fn mangle_package_json(
     }
     // installer-specific fields
     package_json["bin"] = platforms.bins_json();
-    // These two fields are "non-standard" in the package.json format, but the
+    // These fields are "non-standard" in the package.json format, but the
     // installer expects to find them when it reads its own package.json (with `require`).
     // It's fairly normal to add random stuff to a package.json like this,
     // as it's a format that's infamously ill-defined with minimal validation.
-    package_json["artifactDownloadUrl"] = info.inner.base_url.clone().into();
-    package_json["supportedPlatforms"] = platforms.platform_support_json();
+    if let Some(packages) = &info.platform_packages {
+        // npm only installs the optional dependencies whose os/cpu/libc match
+        // the machine, so run.js can just look for whichever one it got.
+        let mut deps = SortedMap::<&str, &str>::new();
+        let mut supported = SortedMap::<&TargetTriple, serde_json::Value>::new();
+        for package in packages {
+            deps.insert(&package.npm_package_name, &info.npm_package_version);
+            supported.insert(&package.target, platform_package_json(package));
+        }
+        package_json["optionalDependencies"] =
+            serde_json::to_value(&deps).expect("serde_json failed");
+        package_json["supportedPlatforms"] =
+            serde_json::to_value(&supported).expect("serde_json failed");
+    } else {
+        package_json["artifactDownloadUrl"] = info.inner.base_url.clone().into();
+        if !fallback_urls.is_empty() {
+            package_json["fallbackDownloadUrls"] = fallback_urls.into();
+        }
+        package_json["supportedPlatforms"] = platforms.platform_support_json();
+    }
 
     match info.inner.runtime_conditions.min_glibc_version {
         Some(LibcVersion { major, series }) => {
@@ -201,6 +337,32 @@ fn mangle_package_json(
     Ok(())
 }
 
+fn platform_package_json(package: &NpmPlatformPackage) -> serde_json::Value {
+    let bins = package
+        .executables
+        .iter()
+        .map(|bin| {
+            let raw_name = bin.strip_suffix(".exe").unwrap_or(bin);
+            (raw_name.to_owned(), bin.to_owned())
+        })
+        .collect::<SortedMap<_, _>>();
+    let mut map = serde_json::Map::new();
+    map.insert(
+        "packageName".to_owned(),
+        package.npm_package_name.clone().into(),
+    );
+    map.insert("os".to_owned(), package.os.clone().into());
+    map.insert("cpu".to_owned(), package.cpu.clone().into());
+    if let Some(libc) = &package.libc {
+        map.insert("libc".to_owned(), libc.clone().into());
+    }
+    map.insert(
+        "bins".to_owned(),
+        serde_json::to_value(bins).expect("serde_json failed"),
+    );
+    map.into()
+}
+
 fn glibc_json(major: u64, series: u64) -> serde_json::Value {
     let mut map = SortedMap::<&str, u64>::new();
     map.insert("major", major);
@@ -269,3 +431,116 @@ impl PlatformSummary {
         format!("run-{bin}.js")
     }
 }
+
+/// Get the `os`, `cpu`, and `libc` an npm package for this target can be installed on
+///
+/// These use the names node uses for `process.platform`, `process.arch`, and libc families.
+pub fn npm_platform(
+    target: &TargetTripleRef,
+) -> Option<(&'static str, &'static str, Option<&'static str>)> {
+    let triple = target.as_str();
+    let arch = triple.split('-').next().unwrap_or_default();
+    let cpu = match arch {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "i686" | "i586" => "ia32",
+        "armv7" | "arm" => "arm",
+        "powerpc64le" => "ppc64",
+        "s390x" => "s390x",
+        "riscv64gc" => "riscv64",
+        "loongarch64" => "loong64",
+        _ => return None,
+    };
+    let (os, libc) = if target.is_linux() {
+        let libc = if target.is_musl() { "musl" } else { "glibc" };
+        ("linux", Some(libc))
+    } else if target.is_darwin() {
+        ("darwin", None)
+    } else if target.is_windows() {
+        ("win32", None)
+    } else if triple.contains("-freebsd") {
+        ("freebsd", None)
+    } else {
+        return None;
+    };
+    Some((os, cpu, libc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platforms() {
+        let cases = [
+            (
+                "x86_64-unknown-linux-gnu",
+                Some(("linux", "x64", Some("glibc"))),
+            ),
+            (
+                "aarch64-unknown-linux-musl",
+                Some(("linux", "arm64", Some("musl"))),
+            ),
+            (
+                "armv7-unknown-linux-gnueabihf",
+                Some(("linux", "arm", Some("glibc"))),
+            ),
+            ("aarch64-apple-darwin", Some(("darwin", "arm64", None))),
+            ("i686-pc-windows-msvc", Some(("win32", "ia32", None))),
+            ("x86_64-pc-windows-gnu", Some(("win32", "x64", None))),
+            ("x86_64-unknown-freebsd", Some(("freebsd", "x64", None))),
+            ("wasm32-unknown-unknown", None),
+        ];
+        for (target, expected) in cases {
+            assert_eq!(
+                npm_platform(TargetTripleRef::from_str(target)),
+                expected,
+                "{target}"
+            );
+        }
+    }
+
+    #[test]
+    fn platform_package() {
+        let dir = temp_dir::TempDir::new().unwrap();
+        let package_dir = Utf8PathBuf::from_path_buf(dir.path().to_owned()).unwrap();
+        let info = NpmPlatformInstallerInfo {
+            package: NpmPlatformPackage {
+                target: TargetTriple::new("x86_64-unknown-linux-musl".to_owned()),
+                npm_package_name: "@axodotdev/axolotlsay-x86_64-unknown-linux-musl".to_owned(),
+                os: "linux".to_owned(),
+                cpu: "x64".to_owned(),
+                libc: Some("musl".to_owned()),
+                executables: vec!["axolotlsay".to_owned()],
+            },
+            npm_package_version: "1.0.0-prerelease.1".to_owned(),
+            npm_package_desc: Some("says hi".to_owned()),
+            npm_package_repository_url: None,
+            npm_package_homepage_url: None,
+            npm_package_license: Some("MIT OR Apache-2.0".to_owned()),
+            package_dir: package_dir.clone(),
+        };
+        write_npm_platform_package(&info).unwrap();
+
+        let contents = std::fs::read_to_string(package_dir.join(PACKAGE_JSON)).unwrap();
+        assert_eq!(
+            contents,
+            r#"{
+  "name": "@axodotdev/axolotlsay-x86_64-unknown-linux-musl",
+  "version": "1.0.0-prerelease.1",
+  "description": "says hi",
+  "license": "MIT OR Apache-2.0",
+  "os": [
+    "linux"
+  ],
+  "cpu": [
+    "x64"
+  ],
+  "libc": [
+    "musl"
+  ],
+  "preferUnplugged": true
+}"#
+        );
+    }
+}
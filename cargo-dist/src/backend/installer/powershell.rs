@@ -1,15 +1,76 @@
-//! Code for generating installer.ps1
+//! Code for generating installer.ps1 (and uninstaller.ps1)
+
+use std::collections::BTreeMap;
 
 use axoasset::LocalAsset;
+use cargo_dist_schema::DistManifest;
+use serde::Serialize;
+
+use crate::{
+    backend::templates::{TEMPLATE_INSTALLER_PS1, TEMPLATE_UNINSTALLER_PS1},
+    errors::DistResult,
+    DistGraph,
+};
+
+use super::{
+    apply_download_urls, expected_checksums, other_releases, ExpectedChecksum, InstallerInfo,
+    OtherReleases, UninstallerInfo,
+};
+
+/// What installer.ps1 gets rendered with
+#[derive(Serialize)]
+struct PowershellInstallerContext<'a> {
+    #[serde(flatten)]
+    info: &'a InstallerInfo,
+    /// Checksums to verify each archive against, by archive id
+    checksums: BTreeMap<String, ExpectedChecksum>,
+    /// Key to verify each archive's detached signature against
+    minisign_public_key: Option<String>,
+    /// Where to find other versions, for `-Version` and `-Channel`
+    other_releases: Option<OtherReleases>,
+    /// Base URLs to try in order if `base_url` doesn't work
+    fallback_urls: Vec<String>,
+}
 
-use crate::{backend::templates::TEMPLATE_INSTALLER_PS1, errors::DistResult, DistGraph};
+pub(crate) fn write_install_ps_script(
+    dist: &DistGraph,
+    info: &InstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let checksums = expected_checksums(manifest, info.artifacts.iter().map(|a| a.id.as_str()));
+    let minisign_public_key = dist
+        .release(info.release)
+        .config
+        .installers
+        .powershell
+        .as_ref()
+        .and_then(|powershell| powershell.minisign_public_key.clone());
+    let other_releases = other_releases(dist, info.release, manifest);
+    let mut info = info.clone();
+    let fallback_urls = apply_download_urls(&mut info, manifest);
 
-use super::InstallerInfo;
+    let script = dist.templates.render_file_to_clean_string(
+        TEMPLATE_INSTALLER_PS1,
+        &PowershellInstallerContext {
+            info: &info,
+            checksums,
+            minisign_public_key,
+            other_releases,
+            fallback_urls,
+        },
+    )?;
+    LocalAsset::write_new(&script, &info.dest_path)?;
+    dist.signer.sign(&info.dest_path)?;
+    Ok(())
+}
 
-pub(crate) fn write_install_ps_script(dist: &DistGraph, info: &InstallerInfo) -> DistResult<()> {
+pub(crate) fn write_uninstall_ps_script(
+    dist: &DistGraph,
+    info: &UninstallerInfo,
+) -> DistResult<()> {
     let script = dist
         .templates
-        .render_file_to_clean_string(TEMPLATE_INSTALLER_PS1, info)?;
+        .render_file_to_clean_string(TEMPLATE_UNINSTALLER_PS1, info)?;
     LocalAsset::write_new(&script, &info.dest_path)?;
     dist.signer.sign(&info.dest_path)?;
     Ok(())
@@ -0,0 +1,524 @@
+//! Code for generating Python wheels
+//!
+//! A wheel is just a zip with some metadata files in a `.dist-info` dir, so we
+//! write it ourselves instead of requiring Python (or maturin) on the machine doing the build.
+//! The binaries go in the wheel's `.data/scripts` dir, which pip installs to the
+//! virtualenv's `bin` (or `Scripts`) dir.
+
+use std::io::Write;
+
+use axoasset::LocalAsset;
+use base64::Engine;
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{BuildEnvironment, GlibcVersion, TargetTriple, TargetTripleRef};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use super::source_date_epoch;
+use crate::{DistError, DistResult};
+
+/// Info about a Python wheel
+#[derive(Debug, Clone, Serialize)]
+pub struct PypiInstallerInfo {
+    /// Final file path of the wheel
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries get copied to before packaging
+    pub package_dir: Utf8PathBuf,
+    /// Name of the package on PyPI
+    pub package_name: String,
+    /// Version of the package, in PEP 440 format
+    pub version: String,
+    /// The target the wheel is for
+    pub target: TargetTriple,
+    /// The wheel's platform tag
+    pub platform_tag: String,
+    /// The glibc version in the platform tag, for manylinux wheels
+    pub manylinux: Option<GlibcVersion>,
+    /// A brief description of the application
+    pub description: Option<String>,
+    /// The URL to the application's homepage
+    pub homepage: Option<String>,
+    /// The URL to the application's source code
+    pub repository: Option<String>,
+    /// The application's license
+    pub license: Option<String>,
+    /// The README to use as the package's long description
+    pub readme: Option<Utf8PathBuf>,
+    /// LICENSE files to include
+    pub licenses: Vec<Utf8PathBuf>,
+    /// Executables to install as scripts
+    pub executables: Vec<String>,
+}
+
+impl PypiInstallerInfo {
+    /// Build the wheel
+    ///
+    /// `build_environment` is the environment of the machine that built the
+    /// binaries, which is checked against the glibc in the manylinux tag.
+    pub fn build(&self, build_environment: &BuildEnvironment) -> DistResult<()> {
+        info!("building a wheel: {}", self.file_path);
+        self.check_manylinux(build_environment)?;
+
+        let data_dir = format!("{}.data", self.dist_info_prefix());
+        let dist_info_dir = format!("{}.dist-info", self.dist_info_prefix());
+
+        // Everything but RECORD, which lists the hashes of all of these
+        let mut files = vec![];
+        for exe in &self.executables {
+            let contents = LocalAsset::load_bytes(self.package_dir.join(exe))?;
+            files.push(WheelFile {
+                path: format!("{data_dir}/scripts/{exe}"),
+                mode: 0o755,
+                contents,
+            });
+        }
+        let mut metadata_file = |name: String, contents: Vec<u8>| {
+            files.push(WheelFile {
+                path: format!("{dist_info_dir}/{name}"),
+                mode: 0o644,
+                contents,
+            });
+        };
+        let readme = self
+            .readme
+            .as_ref()
+            .map(LocalAsset::load_string)
+            .transpose()?;
+        metadata_file(
+            "METADATA".to_owned(),
+            self.metadata(readme.as_deref()).into_bytes(),
+        );
+        metadata_file("WHEEL".to_owned(), self.wheel_file().into_bytes());
+        for license in &self.licenses {
+            let name = license.file_name().expect("license had no file name!?");
+            metadata_file(format!("licenses/{name}"), LocalAsset::load_bytes(license)?);
+        }
+        let record_path = format!("{dist_info_dir}/RECORD");
+        files.push(WheelFile {
+            contents: record(&files, &record_path).into_bytes(),
+            path: record_path,
+            mode: 0o644,
+        });
+
+        let wheel = zip_files(&files, source_date_epoch())?;
+        std::fs::write(&self.file_path, wheel)?;
+
+        Ok(())
+    }
+
+    /// The start of the name of the `.dist-info` and `.data` dirs
+    /// Refuse to tag a wheel as running on an older glibc than its binaries were built against
+    fn check_manylinux(&self, build_environment: &BuildEnvironment) -> DistResult<()> {
+        let Some(manylinux) = &self.manylinux else {
+            return Ok(());
+        };
+        if let BuildEnvironment::Linux {
+            glibc_version: Some(built_with),
+        } = build_environment
+        {
+            if built_with > manylinux {
+                return Err(DistError::PypiGlibcTooNew {
+                    target: self.target.clone(),
+                    manylinux: format!("{}.{}", manylinux.major, manylinux.series),
+                    built_with: format!("{}.{}", built_with.major, built_with.series),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn dist_info_prefix(&self) -> String {
+        format!(
+            "{}-{}",
+            wheel_distribution_name(&self.package_name),
+            self.version
+        )
+    }
+
+    /// Render the `METADATA` file
+    fn metadata(&self, readme: Option<&str>) -> String {
+        let mut metadata = String::new();
+        let mut field = |name: &str, value: &str| {
+            metadata.push_str(&format!("{name}: {value}\n"));
+        };
+        field("Metadata-Version", "2.1");
+        field("Name", &self.package_name);
+        field("Version", &self.version);
+        if let Some(summary) = self
+            .description
+            .as_deref()
+            .and_then(|desc| desc.lines().next())
+        {
+            field("Summary", summary.trim());
+        }
+        if let Some(homepage) = &self.homepage {
+            field("Home-page", homepage);
+        }
+        if let Some(license) = &self.license {
+            field("License", license);
+        }
+        if let Some(repository) = &self.repository {
+            field("Project-URL", &format!("Repository, {repository}"));
+        }
+        if let Some(readme) = readme {
+            field("Description-Content-Type", "text/markdown");
+            metadata.push('\n');
+            metadata.push_str(readme);
+        }
+        metadata
+    }
+
+    /// Render the `WHEEL` file
+    fn wheel_file(&self) -> String {
+        format!(
+            "Wheel-Version: 1.0\nGenerator: dist ({})\nRoot-Is-Purelib: false\nTag: py3-none-{}\n",
+            env!("CARGO_PKG_VERSION"),
+            self.platform_tag
+        )
+    }
+}
+
+/// A file to put in a wheel
+struct WheelFile {
+    path: String,
+    mode: u32,
+    contents: Vec<u8>,
+}
+
+/// Render the `RECORD` file, which lists the hash of every other file in the wheel
+fn record(files: &[WheelFile], record_path: &str) -> String {
+    let mut record = String::new();
+    for file in files {
+        let digest =
+            base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(&file.contents));
+        record.push_str(&format!(
+            "{},sha256={digest},{}\n",
+            file.path,
+            file.contents.len()
+        ));
+    }
+    record.push_str(&format!("{record_path},,\n"));
+    record
+}
+
+/// Zip up the given files
+fn zip_files(files: &[WheelFile], mtime: u64) -> DistResult<Vec<u8>> {
+    // Zip timestamps can't go before 1980
+    let mtime = chrono::DateTime::from_timestamp(mtime as i64, 0)
+        .and_then(|mtime| {
+            use chrono::{Datelike, Timelike};
+            zip::DateTime::from_date_and_time(
+                mtime.year().try_into().ok()?,
+                mtime.month() as u8,
+                mtime.day() as u8,
+                mtime.hour() as u8,
+                mtime.minute() as u8,
+                mtime.second() as u8,
+            )
+            .ok()
+        })
+        .unwrap_or_default();
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+    for file in files {
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(mtime)
+            .unix_permissions(file.mode);
+        zip.start_file(file.path.as_str(), options)
+            .map_err(std::io::Error::from)?;
+        zip.write_all(&file.contents)?;
+    }
+    Ok(zip.finish().map_err(std::io::Error::from)?.into_inner())
+}
+
+/// Normalize a package name the way wheel filenames want it
+///
+/// Runs of `-`, `_`, and `.` become a single `_`, and everything is lowercase.
+pub fn wheel_distribution_name(name: &str) -> String {
+    let mut out = String::new();
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !out.ends_with('_') {
+                out.push('_');
+            }
+        } else {
+            out.push(c.to_ascii_lowercase());
+        }
+    }
+    out
+}
+
+/// Turn a semver version into a PEP 440 version
+///
+/// Prereleases are mapped onto alpha/beta/rc/dev releases based on their first
+/// identifier. Build metadata is dropped, since PyPI refuses local versions.
+pub fn pep440_version(version: &semver::Version) -> String {
+    let mut out = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if version.pre.is_empty() {
+        return out;
+    }
+    let mut parts = version.pre.as_str().split(['.', '-']);
+    let kind = parts.next().unwrap_or_default().to_ascii_lowercase();
+    // The first number in the prerelease is the prerelease number (`alpha.2`, `rc3`...)
+    let (kind, inline_number) = kind.split_at(
+        kind.find(|c: char| c.is_ascii_digit())
+            .unwrap_or(kind.len()),
+    );
+    let number = Some(inline_number)
+        .filter(|num| !num.is_empty())
+        .or_else(|| parts.find(|part| part.parse::<u64>().is_ok()))
+        .and_then(|num| num.parse::<u64>().ok())
+        .unwrap_or(0);
+    let kind = match kind {
+        "a" | "alpha" => "a",
+        "b" | "beta" => "b",
+        "c" | "rc" | "pre" | "preview" => "rc",
+        _ => ".dev",
+    };
+    out.push_str(&format!("{kind}{number}"));
+    out
+}
+
+/// Parse a glibc version like "2.17"
+pub fn parse_glibc_version(version: &str) -> Option<GlibcVersion> {
+    let (major, series) = version.trim().split_once('.')?;
+    Some(GlibcVersion {
+        major: major.parse().ok()?,
+        series: series.parse().ok()?,
+    })
+}
+
+/// Get the wheel platform tag for a target, if it's one wheels can be made for
+///
+/// linux-gnu wheels are tagged with the given glibc version, which should be
+/// the oldest glibc the binaries run on. It has to come from config rather than
+/// the machine we're running on, so that the wheel gets the same name when
+/// it's planned as when it's built; [`PypiInstallerInfo::build`][] checks it
+/// against the builder's glibc instead.
+pub fn wheel_platform_tag(target: &TargetTripleRef, glibc: &GlibcVersion) -> Option<String> {
+    let triple = target.as_str();
+    let arch = triple.split('-').next().unwrap_or_default();
+    if target.is_linux() {
+        let arch = match arch {
+            "x86_64" => "x86_64",
+            "aarch64" => "aarch64",
+            // manylinux has no tag for i586, and an i686 tag would lie about
+            // which CPUs the binaries need
+            "i686" => "i686",
+            "armv7" if triple.ends_with("eabihf") => "armv7l",
+            "powerpc64le" => "ppc64le",
+            "s390x" => "s390x",
+            "riscv64gc" => "riscv64",
+            "loongarch64" => "loongarch64",
+            _ => return None,
+        };
+        if target.is_musl() {
+            // Rust's musl targets have shipped musl 1.2 since Rust 1.71
+            return Some(format!("musllinux_1_2_{arch}"));
+        }
+        Some(format!("manylinux_{}_{}_{arch}", glibc.major, glibc.series))
+    } else if target.is_darwin() {
+        // The oldest macOS versions Rust supports on each architecture
+        match arch {
+            "x86_64" => Some("macosx_10_12_x86_64".to_owned()),
+            "aarch64" => Some("macosx_11_0_arm64".to_owned()),
+            _ => None,
+        }
+    } else if target.is_windows() {
+        match arch {
+            "x86_64" => Some("win_amd64".to_owned()),
+            "i686" => Some("win32".to_owned()),
+            "aarch64" => Some("win_arm64".to_owned()),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Get the file name of a wheel
+pub fn wheel_file_name(package_name: &str, version: &str, platform_tag: &str) -> String {
+    format!(
+        "{}-{version}-py3-none-{platform_tag}.whl",
+        wheel_distribution_name(package_name)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    /// The files in a wheel, by path
+    fn read_wheel(wheel: &[u8]) -> BTreeMap<String, (u32, Vec<u8>)> {
+        use std::io::Read;
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(wheel)).unwrap();
+        let mut files = BTreeMap::new();
+        for idx in 0..archive.len() {
+            let mut file = archive.by_index(idx).unwrap();
+            let mut contents = vec![];
+            file.read_to_end(&mut contents).unwrap();
+            files.insert(
+                file.name().to_owned(),
+                (file.unix_mode().unwrap_or_default(), contents),
+            );
+        }
+        files
+    }
+
+    fn info() -> PypiInstallerInfo {
+        PypiInstallerInfo {
+            file_path: "axolotlsay-0.1.0-py3-none-manylinux_2_31_x86_64.whl".into(),
+            package_dir: "axolotlsay-x86_64-unknown-linux-gnu_pypi".into(),
+            package_name: "axolotl-say".to_owned(),
+            version: "0.1.0".to_owned(),
+            target: TargetTriple::new("x86_64-unknown-linux-gnu".to_owned()),
+            platform_tag: "manylinux_2_31_x86_64".to_owned(),
+            manylinux: Some(GlibcVersion {
+                major: 2,
+                series: 31,
+            }),
+            description: Some("say things\n\nlike an axolotl".to_owned()),
+            homepage: Some("https://axo.dev".to_owned()),
+            repository: Some("https://github.com/axodotdev/axolotlsay".to_owned()),
+            license: Some("MIT OR Apache-2.0".to_owned()),
+            readme: None,
+            licenses: vec![],
+            executables: vec!["axolotlsay".to_owned()],
+        }
+    }
+
+    #[test]
+    fn metadata_file() {
+        assert_eq!(
+            info().metadata(Some("# axolotlsay\n")),
+            "Metadata-Version: 2.1
+Name: axolotl-say
+Version: 0.1.0
+Summary: say things
+Home-page: https://axo.dev
+License: MIT OR Apache-2.0
+Project-URL: Repository, https://github.com/axodotdev/axolotlsay
+Description-Content-Type: text/markdown
+
+# axolotlsay
+"
+        );
+    }
+
+    #[test]
+    fn wheel_layout() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let mut info = info();
+        info.package_dir = dir.clone();
+        info.file_path = dir.join("axolotl_say-0.1.0-py3-none-manylinux_2_31_x86_64.whl");
+        std::fs::write(dir.join("axolotlsay"), b"not really elf").unwrap();
+        info.build(&BuildEnvironment::Linux {
+            glibc_version: Some(GlibcVersion {
+                major: 2,
+                series: 31,
+            }),
+        })
+        .unwrap();
+
+        let files = read_wheel(&std::fs::read(&info.file_path).unwrap());
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            [
+                "axolotl_say-0.1.0.data/scripts/axolotlsay",
+                "axolotl_say-0.1.0.dist-info/METADATA",
+                "axolotl_say-0.1.0.dist-info/RECORD",
+                "axolotl_say-0.1.0.dist-info/WHEEL",
+            ]
+        );
+        let (mode, _) = &files["axolotl_say-0.1.0.data/scripts/axolotlsay"];
+        assert_eq!(mode & 0o777, 0o755);
+        let (_, wheel) = &files["axolotl_say-0.1.0.dist-info/WHEEL"];
+        assert!(String::from_utf8_lossy(wheel).contains("Tag: py3-none-manylinux_2_31_x86_64\n"));
+        let (_, record) = &files["axolotl_say-0.1.0.dist-info/RECORD"];
+        let record = String::from_utf8(record.clone()).unwrap();
+        assert!(record.starts_with(
+            "axolotl_say-0.1.0.data/scripts/axolotlsay,sha256=VocuAsrgGqH6qabRra2GUbN0GqNCxASi6SK-JVcIh7U,14\n"
+        ));
+        assert!(record.ends_with("axolotl_say-0.1.0.dist-info/RECORD,,\n"));
+    }
+
+    #[test]
+    fn manylinux_checked_against_builder() {
+        let info = info();
+        let built_with = |series| BuildEnvironment::Linux {
+            glibc_version: Some(GlibcVersion { major: 2, series }),
+        };
+        assert!(info.check_manylinux(&built_with(28)).is_ok());
+        assert!(info.check_manylinux(&built_with(31)).is_ok());
+        assert!(info
+            .check_manylinux(&BuildEnvironment::Indeterminate)
+            .is_ok());
+        let err = info.check_manylinux(&built_with(35)).unwrap_err();
+        assert!(matches!(
+            err,
+            DistError::PypiGlibcTooNew { ref manylinux, ref built_with, .. }
+                if manylinux == "2.31" && built_with == "2.35"
+        ));
+    }
+
+    #[test]
+    fn glibc_versions() {
+        assert_eq!(
+            parse_glibc_version("2.17"),
+            Some(GlibcVersion {
+                major: 2,
+                series: 17
+            })
+        );
+        assert_eq!(parse_glibc_version("2"), None);
+        assert_eq!(parse_glibc_version("manylinux2014"), None);
+    }
+
+    #[test]
+    fn versions() {
+        let version = |v: &str| pep440_version(&semver::Version::parse(v).unwrap());
+        assert_eq!(version("1.2.3"), "1.2.3");
+        assert_eq!(version("1.2.3+build.5"), "1.2.3");
+        assert_eq!(version("1.2.3-alpha.2"), "1.2.3a2");
+        assert_eq!(version("1.2.3-beta"), "1.2.3b0");
+        assert_eq!(version("1.2.3-rc3"), "1.2.3rc3");
+        assert_eq!(version("0.24.0-prerelease.2"), "0.24.0.dev2");
+    }
+
+    #[test]
+    fn platform_tags() {
+        let glibc = GlibcVersion {
+            major: 2,
+            series: 35,
+        };
+        let tag = |triple: &str| wheel_platform_tag(TargetTripleRef::from_str(triple), &glibc);
+        assert_eq!(
+            tag("x86_64-unknown-linux-gnu").as_deref(),
+            Some("manylinux_2_35_x86_64")
+        );
+        assert_eq!(
+            tag("aarch64-unknown-linux-musl").as_deref(),
+            Some("musllinux_1_2_aarch64")
+        );
+        assert_eq!(
+            tag("aarch64-apple-darwin").as_deref(),
+            Some("macosx_11_0_arm64")
+        );
+        assert_eq!(tag("x86_64-pc-windows-msvc").as_deref(), Some("win_amd64"));
+        assert_eq!(
+            tag("i686-unknown-linux-gnu").as_deref(),
+            Some("manylinux_2_35_i686")
+        );
+        assert_eq!(tag("i586-unknown-linux-gnu"), None);
+        assert_eq!(tag("wasm32-wasi"), None);
+        assert_eq!(
+            wheel_file_name("Axolotl.Say", "0.1.0", "win_amd64"),
+            "axolotl_say-0.1.0-py3-none-win_amd64.whl"
+        );
+    }
+}
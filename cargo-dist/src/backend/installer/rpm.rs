@@ -0,0 +1,965 @@
+//! Code for generating RPM packages
+//!
+//! An .rpm is a fixed "lead", two binary headers and a compressed cpio archive,
+//! so we write it ourselves instead of requiring rpmbuild on the machine doing
+//! the build.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write,
+};
+
+use axoasset::LocalAsset;
+use camino::Utf8PathBuf;
+use cargo_dist_schema::TargetTripleRef;
+use flate2::{write::GzEncoder, Compression};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use super::source_date_epoch;
+use crate::DistResult;
+
+/// Info about an RPM package
+#[derive(Debug, Clone, Serialize)]
+pub struct RpmInstallerInfo {
+    /// Final file path of the rpm
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries get copied to before packaging
+    pub package_dir: Utf8PathBuf,
+    /// Name of the package
+    pub package_name: String,
+    /// Version of the package, in RPM's format
+    pub version: String,
+    /// Release of the package (the packaging revision of this version)
+    pub release: String,
+    /// RPM name of the architecture
+    pub architecture: String,
+    /// A brief description of the application
+    pub description: Option<String>,
+    /// The application's license
+    pub license: Option<String>,
+    /// The URL to the application's homepage
+    pub url: Option<String>,
+    /// Executables to install to /usr/bin
+    pub executables: Vec<String>,
+    /// Dynamic libraries to install to /usr/lib
+    pub cdylibs: Vec<String>,
+    /// Executable aliases
+    pub bin_aliases: BTreeMap<String, Vec<String>>,
+    /// READMEs and CHANGELOGs to install to /usr/share/doc
+    pub docs: Vec<Utf8PathBuf>,
+    /// LICENSEs to install to /usr/share/licenses
+    pub licenses: Vec<Utf8PathBuf>,
+}
+
+// Header tags, see rpmtag.h
+const TAG_HEADERSIGNATURES: u32 = 62;
+const TAG_HEADERIMMUTABLE: u32 = 63;
+const TAG_HEADERI18NTABLE: u32 = 100;
+const SIGTAG_SHA256: u32 = 273;
+const SIGTAG_SIZE: u32 = 1000;
+const SIGTAG_PAYLOADSIZE: u32 = 1007;
+const TAG_NAME: u32 = 1000;
+const TAG_VERSION: u32 = 1001;
+const TAG_RELEASE: u32 = 1002;
+const TAG_SUMMARY: u32 = 1004;
+const TAG_DESCRIPTION: u32 = 1005;
+const TAG_BUILDTIME: u32 = 1006;
+const TAG_SIZE: u32 = 1009;
+const TAG_LICENSE: u32 = 1014;
+const TAG_GROUP: u32 = 1016;
+const TAG_URL: u32 = 1020;
+const TAG_OS: u32 = 1021;
+const TAG_ARCH: u32 = 1022;
+const TAG_FILESIZES: u32 = 1028;
+const TAG_FILEMODES: u32 = 1030;
+const TAG_FILERDEVS: u32 = 1033;
+const TAG_FILEMTIMES: u32 = 1034;
+const TAG_FILEDIGESTS: u32 = 1035;
+const TAG_FILELINKTOS: u32 = 1036;
+const TAG_FILEFLAGS: u32 = 1037;
+const TAG_FILEUSERNAME: u32 = 1039;
+const TAG_FILEGROUPNAME: u32 = 1040;
+const TAG_SOURCERPM: u32 = 1044;
+const TAG_PROVIDENAME: u32 = 1047;
+const TAG_REQUIREFLAGS: u32 = 1048;
+const TAG_REQUIRENAME: u32 = 1049;
+const TAG_REQUIREVERSION: u32 = 1050;
+const TAG_FILEDEVICES: u32 = 1095;
+const TAG_FILEINODES: u32 = 1096;
+const TAG_FILELANGS: u32 = 1097;
+const TAG_PROVIDEFLAGS: u32 = 1112;
+const TAG_PROVIDEVERSION: u32 = 1113;
+const TAG_DIRINDEXES: u32 = 1116;
+const TAG_BASENAMES: u32 = 1117;
+const TAG_DIRNAMES: u32 = 1118;
+const TAG_PAYLOADFORMAT: u32 = 1124;
+const TAG_PAYLOADCOMPRESSOR: u32 = 1125;
+const TAG_PAYLOADFLAGS: u32 = 1126;
+const TAG_FILEDIGESTALGO: u32 = 5011;
+const TAG_PAYLOADDIGEST: u32 = 5092;
+const TAG_PAYLOADDIGESTALGO: u32 = 5093;
+
+// Dependency flags, see rpmds.h
+const SENSE_LESS: u32 = 1 << 1;
+const SENSE_GREATER: u32 = 1 << 2;
+const SENSE_EQUAL: u32 = 1 << 3;
+const SENSE_FIND_REQUIRES: u32 = 1 << 14;
+const SENSE_RPMLIB: u32 = 1 << 24;
+
+// File flags, see rpmfiles.h
+const FILE_DOC: u32 = 1 << 1;
+const FILE_LICENSE: u32 = 1 << 7;
+
+/// PGPHASHALGO_SHA256
+const HASH_SHA256: u32 = 8;
+
+/// Features of rpm itself that our packages rely on
+const RPMLIB_REQUIRES: &[(&str, &str)] = &[
+    ("rpmlib(CompressedFileNames)", "3.0.4-1"),
+    ("rpmlib(FileDigests)", "4.6.0-1"),
+    ("rpmlib(PayloadFilesHavePrefix)", "4.0-1"),
+];
+
+/// Something to put in the payload of a package
+#[derive(Debug)]
+enum RpmEntry {
+    Dir,
+    File {
+        mode: u32,
+        flags: u32,
+        contents: Vec<u8>,
+    },
+    Symlink {
+        target: String,
+    },
+}
+
+impl RpmEntry {
+    fn mode(&self) -> u32 {
+        match self {
+            RpmEntry::Dir => 0o040755,
+            RpmEntry::File { mode, .. } => 0o100000 | mode,
+            RpmEntry::Symlink { .. } => 0o120777,
+        }
+    }
+
+    fn contents(&self) -> &[u8] {
+        match self {
+            RpmEntry::Dir => &[],
+            RpmEntry::File { contents, .. } => contents,
+            RpmEntry::Symlink { target } => target.as_bytes(),
+        }
+    }
+}
+
+impl RpmInstallerInfo {
+    /// Build the rpm
+    pub fn build(&self) -> DistResult<()> {
+        info!("building an rpm: {}", self.package_name);
+
+        let mut entries = BTreeMap::new();
+        for exe in &self.executables {
+            let contents = LocalAsset::load_bytes(self.package_dir.join(exe))?;
+            entries.insert(
+                format!("/usr/bin/{exe}"),
+                RpmEntry::File {
+                    mode: 0o755,
+                    flags: 0,
+                    contents,
+                },
+            );
+        }
+        for (bin, aliases) in &self.bin_aliases {
+            for alias in aliases {
+                entries.insert(
+                    format!("/usr/bin/{alias}"),
+                    RpmEntry::Symlink {
+                        target: bin.clone(),
+                    },
+                );
+            }
+        }
+        for lib in &self.cdylibs {
+            let contents = LocalAsset::load_bytes(self.package_dir.join(lib))?;
+            entries.insert(
+                format!("/usr/lib/{lib}"),
+                RpmEntry::File {
+                    mode: 0o755,
+                    flags: 0,
+                    contents,
+                },
+            );
+        }
+        // We own the directories our docs go in, but not any of the standard ones
+        for (dir, files, flags) in [
+            ("doc", &self.docs, FILE_DOC),
+            ("licenses", &self.licenses, FILE_LICENSE),
+        ] {
+            if files.is_empty() {
+                continue;
+            }
+            let dir = format!("/usr/share/{dir}/{}", self.package_name);
+            for file in files {
+                let contents = LocalAsset::load_bytes(file)?;
+                let name = file.file_name().expect("doc had no file name!?");
+                entries.insert(
+                    format!("{dir}/{name}"),
+                    RpmEntry::File {
+                        mode: 0o644,
+                        flags,
+                        contents,
+                    },
+                );
+            }
+            entries.insert(dir, RpmEntry::Dir);
+        }
+
+        let mtime = source_date_epoch();
+        let cpio = cpio_archive(&entries, mtime)?;
+        let mut payload = GzEncoder::new(vec![], Compression::best());
+        payload.write_all(&cpio)?;
+        let payload = payload.finish()?;
+
+        let header = self
+            .header(&entries, mtime, &payload)
+            .to_bytes(TAG_HEADERIMMUTABLE);
+
+        let mut signature = Header::default();
+        signature.int32(SIGTAG_SIZE, &[(header.len() + payload.len()) as u32]);
+        signature.string(SIGTAG_SHA256, &format!("{:x}", Sha256::digest(&header)));
+        signature.int32(SIGTAG_PAYLOADSIZE, &[cpio.len() as u32]);
+        let mut signature = signature.to_bytes(TAG_HEADERSIGNATURES);
+        // The main header has to start 8-byte aligned
+        signature.resize(signature.len().next_multiple_of(8), 0);
+
+        let mut rpm = self.lead();
+        rpm.extend(signature);
+        rpm.extend(header);
+        rpm.extend(payload);
+        std::fs::write(&self.file_path, rpm)?;
+
+        Ok(())
+    }
+
+    /// The lead, a mostly-vestigial fixed-size preamble
+    fn lead(&self) -> Vec<u8> {
+        let mut lead = vec![0xed, 0xab, 0xee, 0xdb, 3, 0];
+        // binary package, arch number (unused)
+        lead.extend([0, 0, 0, 0]);
+        let mut name =
+            format!("{}-{}-{}", self.package_name, self.version, self.release).into_bytes();
+        name.resize(66, 0);
+        // The name has to be nul-terminated
+        name[65] = 0;
+        lead.extend(name);
+        // linux, header-style signature
+        lead.extend([0, 1, 0, 5]);
+        lead.extend([0; 16]);
+        lead
+    }
+
+    /// The main header, with all the metadata and the file list
+    fn header(&self, entries: &BTreeMap<String, RpmEntry>, mtime: u64, payload: &[u8]) -> Header {
+        let summary = self
+            .description
+            .as_deref()
+            .and_then(|desc| desc.lines().map(str::trim).find(|line| !line.is_empty()))
+            .unwrap_or(&self.package_name);
+        let description = self
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|desc| !desc.is_empty())
+            .unwrap_or(summary);
+        let full_version = format!("{}-{}", self.version, self.release);
+
+        let mut header = Header::default();
+        header.string_array(TAG_HEADERI18NTABLE, &["C"]);
+        header.string(TAG_NAME, &self.package_name);
+        header.string(TAG_VERSION, &self.version);
+        header.string(TAG_RELEASE, &self.release);
+        header.i18n_string(TAG_SUMMARY, summary);
+        header.i18n_string(TAG_DESCRIPTION, description);
+        header.int32(TAG_BUILDTIME, &[mtime as u32]);
+        let size = entries
+            .values()
+            .map(|entry| entry.contents().len() as u32)
+            .sum::<u32>();
+        header.int32(TAG_SIZE, &[size]);
+        if let Some(license) = &self.license {
+            header.string(TAG_LICENSE, license);
+        }
+        header.i18n_string(TAG_GROUP, "Unspecified");
+        if let Some(url) = &self.url {
+            header.string(TAG_URL, url);
+        }
+        header.string(TAG_OS, "linux");
+        header.string(TAG_ARCH, &self.architecture);
+        // Being built from a source rpm is what makes this a binary rpm
+        header.string(
+            TAG_SOURCERPM,
+            &format!("{}-{full_version}.src.rpm", self.package_name),
+        );
+
+        // The file list, as a bunch of parallel arrays
+        let mut dirnames = Vec::<String>::new();
+        let mut dirindexes = vec![];
+        let mut basenames = vec![];
+        for path in entries.keys() {
+            let (dir, base) = path.rsplit_once('/').expect("rpm paths are absolute");
+            let dir = format!("{dir}/");
+            let idx = match dirnames.iter().position(|d| d == &dir) {
+                Some(idx) => idx,
+                None => {
+                    dirnames.push(dir);
+                    dirnames.len() - 1
+                }
+            };
+            dirindexes.push(idx as u32);
+            basenames.push(base);
+        }
+        let count = entries.len();
+        header.int32(
+            TAG_FILESIZES,
+            &entries
+                .values()
+                .map(|entry| entry.contents().len() as u32)
+                .collect::<Vec<_>>(),
+        );
+        header.int16(
+            TAG_FILEMODES,
+            &entries
+                .values()
+                .map(|entry| entry.mode() as u16)
+                .collect::<Vec<_>>(),
+        );
+        header.int16(TAG_FILERDEVS, &vec![0; count]);
+        header.int32(TAG_FILEMTIMES, &vec![mtime as u32; count]);
+        header.string_array(
+            TAG_FILEDIGESTS,
+            &entries
+                .values()
+                .map(|entry| match entry {
+                    RpmEntry::File { contents, .. } => format!("{:x}", Sha256::digest(contents)),
+                    _ => String::new(),
+                })
+                .collect::<Vec<_>>(),
+        );
+        header.string_array(
+            TAG_FILELINKTOS,
+            &entries
+                .values()
+                .map(|entry| match entry {
+                    RpmEntry::Symlink { target } => target.as_str(),
+                    _ => "",
+                })
+                .collect::<Vec<_>>(),
+        );
+        header.int32(
+            TAG_FILEFLAGS,
+            &entries
+                .values()
+                .map(|entry| match entry {
+                    RpmEntry::File { flags, .. } => *flags,
+                    _ => 0,
+                })
+                .collect::<Vec<_>>(),
+        );
+        header.string_array(TAG_FILEUSERNAME, &vec!["root"; count]);
+        header.string_array(TAG_FILEGROUPNAME, &vec!["root"; count]);
+        header.int32(TAG_FILEDEVICES, &vec![1; count]);
+        header.int32(TAG_FILEINODES, &(1..=count as u32).collect::<Vec<_>>());
+        header.string_array(TAG_FILELANGS, &vec![""; count]);
+        header.int32(TAG_DIRINDEXES, &dirindexes);
+        header.string_array(TAG_BASENAMES, &basenames);
+        header.string_array(TAG_DIRNAMES, &dirnames);
+        header.int32(TAG_FILEDIGESTALGO, &[HASH_SHA256]);
+
+        // What we provide, and what we need from the system
+        let (provides, requires) = elf_dependencies(entries);
+        let mut provide_names = vec![self.package_name.clone()];
+        let mut provide_flags = vec![SENSE_EQUAL];
+        let mut provide_versions = vec![full_version];
+        for provide in &provides {
+            provide_names.push(provide.clone());
+            provide_flags.push(0);
+            provide_versions.push(String::new());
+        }
+        header.string_array(TAG_PROVIDENAME, &provide_names);
+        header.int32(TAG_PROVIDEFLAGS, &provide_flags);
+        header.string_array(TAG_PROVIDEVERSION, &provide_versions);
+
+        let mut require_names = vec![];
+        let mut require_flags = vec![];
+        let mut require_versions = vec![];
+        for (name, version) in RPMLIB_REQUIRES {
+            require_names.push(*name);
+            require_flags.push(SENSE_RPMLIB | SENSE_LESS | SENSE_EQUAL);
+            require_versions.push(*version);
+        }
+        for require in requires.difference(&provides) {
+            require_names.push(require.as_str());
+            require_flags.push(SENSE_FIND_REQUIRES);
+            require_versions.push("");
+        }
+        header.string_array(TAG_REQUIRENAME, &require_names);
+        header.int32(TAG_REQUIREFLAGS, &require_flags);
+        header.string_array(TAG_REQUIREVERSION, &require_versions);
+
+        header.string(TAG_PAYLOADFORMAT, "cpio");
+        header.string(TAG_PAYLOADCOMPRESSOR, "gzip");
+        header.string(TAG_PAYLOADFLAGS, "9");
+        header.string_array(
+            TAG_PAYLOADDIGEST,
+            &[format!("{:x}", Sha256::digest(payload))],
+        );
+        header.int32(TAG_PAYLOADDIGESTALGO, &[HASH_SHA256]);
+
+        header
+    }
+}
+
+/// Get the sonames the packaged ELF files provide and require, the same way rpm's elfdeps does
+///
+/// Anything that isn't an ELF file (like the fake binaries of our tests) is ignored.
+fn elf_dependencies(entries: &BTreeMap<String, RpmEntry>) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut provides = BTreeSet::new();
+    let mut requires = BTreeSet::new();
+    for entry in entries.values() {
+        let RpmEntry::File { contents, .. } = entry else {
+            continue;
+        };
+        let Ok(elf) = goblin::elf::Elf::parse(contents) else {
+            continue;
+        };
+        let marker = if elf.is_64 { "(64bit)" } else { "" };
+        let soname = |name: &str| {
+            if elf.is_64 {
+                format!("{name}(){marker}")
+            } else {
+                name.to_owned()
+            }
+        };
+
+        if let Some(name) = elf.soname {
+            provides.insert(soname(name));
+        }
+        for library in &elf.libraries {
+            requires.insert(soname(library));
+        }
+        if let Some(verneed) = &elf.verneed {
+            for need in verneed.iter() {
+                let Some(library) = elf.dynstrtab.get_at(need.vn_file) else {
+                    continue;
+                };
+                for aux in need.iter() {
+                    // VER_FLG_WEAK
+                    if aux.vna_flags & 0x2 != 0 {
+                        continue;
+                    }
+                    if let Some(version) = elf.dynstrtab.get_at(aux.vna_name) {
+                        requires.insert(format!("{library}({version}){marker}"));
+                    }
+                }
+            }
+        }
+    }
+    (provides, requires)
+}
+
+/// Make a "newc" cpio archive of the given entries
+fn cpio_archive(entries: &BTreeMap<String, RpmEntry>, mtime: u64) -> std::io::Result<Vec<u8>> {
+    let mut out = vec![];
+    let append = |out: &mut Vec<u8>, ino: usize, name: &str, mode: u32, contents: &[u8]| {
+        let nlink = if mode & 0o040000 != 0 { 2 } else { 1 };
+        let fields = [
+            ino as u32,
+            mode,
+            0,
+            0,
+            nlink,
+            mtime as u32,
+            contents.len() as u32,
+            0,
+            0,
+            0,
+            0,
+            name.len() as u32 + 1,
+            0,
+        ];
+        write!(out, "070701")?;
+        for field in fields {
+            write!(out, "{field:08x}")?;
+        }
+        out.extend(name.as_bytes());
+        out.push(0);
+        out.resize(out.len().next_multiple_of(4), 0);
+        out.extend(contents);
+        out.resize(out.len().next_multiple_of(4), 0);
+        std::io::Result::Ok(())
+    };
+    // inode numbers line up with FILEINODES
+    for (idx, (path, entry)) in entries.iter().enumerate() {
+        append(
+            &mut out,
+            idx + 1,
+            &format!(".{path}"),
+            entry.mode(),
+            entry.contents(),
+        )?;
+    }
+    append(&mut out, 0, "TRAILER!!!", 0, &[])?;
+    Ok(out)
+}
+
+/// An rpm header being built up
+#[derive(Default)]
+struct Header {
+    /// tag => (type, count, data)
+    entries: BTreeMap<u32, (u32, u32, Vec<u8>)>,
+}
+
+impl Header {
+    fn string(&mut self, tag: u32, value: &str) {
+        self.entries.insert(tag, (6, 1, nul_terminated(value)));
+    }
+
+    fn i18n_string(&mut self, tag: u32, value: &str) {
+        self.entries.insert(tag, (9, 1, nul_terminated(value)));
+    }
+
+    fn string_array(&mut self, tag: u32, values: &[impl AsRef<str>]) {
+        let data = values
+            .iter()
+            .flat_map(|value| nul_terminated(value.as_ref()))
+            .collect();
+        self.entries.insert(tag, (8, values.len() as u32, data));
+    }
+
+    fn int16(&mut self, tag: u32, values: &[u16]) {
+        let data = values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect();
+        self.entries.insert(tag, (3, values.len() as u32, data));
+    }
+
+    fn int32(&mut self, tag: u32, values: &[u32]) {
+        let data = values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect();
+        self.entries.insert(tag, (4, values.len() as u32, data));
+    }
+
+    /// Serialize the header, wrapped in a region with the given tag
+    fn to_bytes(&self, region_tag: u32) -> Vec<u8> {
+        // The region's own entry comes first, and its data (a trailer pointing
+        // back at the start of the index) comes last
+        let nindex = self.entries.len() as u32 + 1;
+        let mut index = vec![];
+        let mut store = vec![];
+        for (tag, (kind, count, data)) in &self.entries {
+            let align = match kind {
+                3 => 2,
+                4 => 4,
+                _ => 1,
+            };
+            store.resize(store.len().next_multiple_of(align), 0);
+            index.push([*tag, *kind, store.len() as u32, *count]);
+            store.extend(data);
+        }
+        let region_offset = store.len() as u32;
+        for field in [region_tag, 7, (nindex * 16).wrapping_neg(), 16] {
+            store.extend(field.to_be_bytes());
+        }
+        index.insert(0, [region_tag, 7, region_offset, 16]);
+
+        let mut out = vec![0x8e, 0xad, 0xe8, 0x01, 0, 0, 0, 0];
+        out.extend(nindex.to_be_bytes());
+        out.extend((store.len() as u32).to_be_bytes());
+        for entry in index {
+            for field in entry {
+                out.extend(field.to_be_bytes());
+            }
+        }
+        out.extend(store);
+        out
+    }
+}
+
+impl Header {
+    /// Parse a header from the start of `bytes`, also returning how long it was
+    fn parse(bytes: &[u8]) -> Option<(Self, usize)> {
+        let be32 = |bytes: &[u8], offset: usize| {
+            let field = bytes.get(offset..offset + 4)?;
+            Some(u32::from_be_bytes(field.try_into().ok()?) as usize)
+        };
+        if bytes.get(..4)? != [0x8e, 0xad, 0xe8, 0x01] {
+            return None;
+        }
+        let nindex = be32(bytes, 8)?;
+        let store_len = be32(bytes, 12)?;
+        let store_start = 16 + nindex * 16;
+        let store = bytes.get(store_start..store_start + store_len)?;
+
+        let mut header = Header::default();
+        for entry in bytes[16..store_start].chunks(16) {
+            let [tag, kind, offset, count] = [0, 4, 8, 12].map(|i| be32(entry, i).unwrap_or(0));
+            let data = store.get(offset..)?;
+            let len = match kind {
+                3 => count * 2,
+                4 => count * 4,
+                // strings are nul-terminated, so we need to go find the end
+                6 | 8 | 9 => {
+                    let strings = if kind == 6 { 1 } else { count };
+                    data.split_inclusive(|b| *b == 0)
+                        .take(strings)
+                        .map(|s| s.len())
+                        .sum()
+                }
+                _ => continue,
+            };
+            header.entries.insert(
+                tag as u32,
+                (kind as u32, count as u32, data.get(..len)?.to_vec()),
+            );
+        }
+        Some((header, store_start + store_len))
+    }
+
+    fn get_strings(&self, tag: u32) -> Vec<String> {
+        match self.entries.get(&tag) {
+            Some((6 | 8 | 9, _, data)) => data
+                .split_inclusive(|b| *b == 0)
+                .map(|s| String::from_utf8_lossy(s.strip_suffix(&[0]).unwrap_or(s)).into_owned())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn get_string(&self, tag: u32) -> Option<String> {
+        self.get_strings(tag).into_iter().next()
+    }
+
+    fn get_ints(&self, tag: u32) -> Vec<u32> {
+        match self.entries.get(&tag) {
+            Some((3, _, data)) => data
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as u32)
+                .collect(),
+            Some((4, _, data)) => data
+                .chunks_exact(4)
+                .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
+
+/// The parts of an rpm's headers that a package repository needs to list it
+#[derive(Debug, Clone)]
+pub(crate) struct RpmMetadata {
+    /// Name of the package
+    pub name: String,
+    /// Version of the package
+    pub version: String,
+    /// Release of the package
+    pub release: String,
+    /// Architecture of the package
+    pub arch: String,
+    /// One-line summary
+    pub summary: String,
+    /// Longer description
+    pub description: String,
+    /// License of the package
+    pub license: Option<String>,
+    /// Homepage of the package
+    pub url: Option<String>,
+    /// Group of the package
+    pub group: Option<String>,
+    /// The source rpm the package claims to be built from
+    pub source_rpm: Option<String>,
+    /// When the package was built
+    pub build_time: u32,
+    /// Total size of the installed files
+    pub installed_size: u32,
+    /// Size of the uncompressed payload
+    pub archive_size: u32,
+    /// Where the main header starts in the file
+    pub header_start: usize,
+    /// Where the main header ends (and the payload starts) in the file
+    pub header_end: usize,
+    /// Capabilities the package provides
+    pub provides: Vec<RpmDependency>,
+    /// Capabilities the package requires
+    pub requires: Vec<RpmDependency>,
+    /// Every path in the package, and whether it's a directory
+    pub files: Vec<(String, bool)>,
+}
+
+/// A capability an rpm provides or requires
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RpmDependency {
+    /// Name of the capability
+    pub name: String,
+    /// `RPMSENSE_*` flags
+    pub flags: u32,
+    /// The version the flags compare against, if any
+    pub version: String,
+}
+
+impl RpmDependency {
+    /// How createrepo spells the comparison in these flags, if there is one
+    pub fn comparison(&self) -> Option<&'static str> {
+        let less = self.flags & SENSE_LESS != 0;
+        let greater = self.flags & SENSE_GREATER != 0;
+        let equal = self.flags & SENSE_EQUAL != 0;
+        match (less, greater, equal) {
+            (true, false, false) => Some("LT"),
+            (false, true, false) => Some("GT"),
+            (false, false, true) => Some("EQ"),
+            (true, false, true) => Some("LE"),
+            (false, true, true) => Some("GE"),
+            _ => None,
+        }
+    }
+
+    /// Whether this is one of the `rpmlib(...)` features rpm itself provides
+    pub fn is_rpmlib(&self) -> bool {
+        self.flags & SENSE_RPMLIB != 0 || self.name.starts_with("rpmlib(")
+    }
+}
+
+/// Read the metadata out of an rpm's headers
+pub(crate) fn read_rpm_metadata(rpm: &[u8]) -> Option<RpmMetadata> {
+    if rpm.get(..4)? != [0xed, 0xab, 0xee, 0xdb] {
+        return None;
+    }
+    let (signature, signature_len) = Header::parse(rpm.get(96..)?)?;
+    let header_start = (96 + signature_len).next_multiple_of(8);
+    let (header, header_len) = Header::parse(rpm.get(header_start..)?)?;
+
+    let dependencies = |names: u32, flags: u32, versions: u32| {
+        let flags = header.get_ints(flags);
+        let versions = header.get_strings(versions);
+        header
+            .get_strings(names)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, name)| RpmDependency {
+                name,
+                flags: flags.get(idx).copied().unwrap_or(0),
+                version: versions.get(idx).cloned().unwrap_or_default(),
+            })
+            .collect()
+    };
+
+    let dirnames = header.get_strings(TAG_DIRNAMES);
+    let modes = header.get_ints(TAG_FILEMODES);
+    let files = header
+        .get_strings(TAG_BASENAMES)
+        .into_iter()
+        .zip(header.get_ints(TAG_DIRINDEXES))
+        .enumerate()
+        .map(|(idx, (base, dir))| {
+            let dir = dirnames.get(dir as usize).map(String::as_str);
+            let is_dir = modes
+                .get(idx)
+                .is_some_and(|mode| mode & 0o170000 == 0o040000);
+            (format!("{}{base}", dir.unwrap_or_default()), is_dir)
+        })
+        .collect();
+
+    let summary = header.get_string(TAG_SUMMARY).unwrap_or_default();
+    Some(RpmMetadata {
+        name: header.get_string(TAG_NAME)?,
+        version: header.get_string(TAG_VERSION)?,
+        release: header.get_string(TAG_RELEASE)?,
+        arch: header.get_string(TAG_ARCH)?,
+        description: header
+            .get_string(TAG_DESCRIPTION)
+            .unwrap_or(summary.clone()),
+        summary,
+        license: header.get_string(TAG_LICENSE),
+        url: header.get_string(TAG_URL),
+        group: header.get_string(TAG_GROUP),
+        source_rpm: header.get_string(TAG_SOURCERPM),
+        build_time: header.get_ints(TAG_BUILDTIME).first().copied().unwrap_or(0),
+        installed_size: header.get_ints(TAG_SIZE).first().copied().unwrap_or(0),
+        archive_size: signature
+            .get_ints(SIGTAG_PAYLOADSIZE)
+            .first()
+            .copied()
+            .unwrap_or(0),
+        header_start,
+        header_end: header_start + header_len,
+        provides: dependencies(TAG_PROVIDENAME, TAG_PROVIDEFLAGS, TAG_PROVIDEVERSION),
+        requires: dependencies(TAG_REQUIRENAME, TAG_REQUIREFLAGS, TAG_REQUIREVERSION),
+        files,
+    })
+}
+
+fn nul_terminated(value: &str) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.push(0);
+    bytes
+}
+
+/// Get the RPM name for the architecture of a linux target, if it has one
+pub fn rpm_architecture(target: &TargetTripleRef) -> Option<&'static str> {
+    if !target.is_linux() {
+        return None;
+    }
+    let arch = target.as_str().split('-').next().unwrap_or_default();
+    let rpm_arch = match arch {
+        "x86_64" => "x86_64",
+        "aarch64" => "aarch64",
+        "i686" => "i686",
+        "i586" => "i586",
+        "armv7" if target.as_str().ends_with("gnueabihf") => "armv7hl",
+        "powerpc64le" => "ppc64le",
+        "s390x" => "s390x",
+        "riscv64gc" => "riscv64",
+        _ => return None,
+    };
+    Some(rpm_arch)
+}
+
+/// Turn a semver version into an RPM version
+///
+/// Prereleases sort *before* their release in semver, which RPM spells with `~`,
+/// and RPM versions can't contain `-`.
+pub fn rpm_version(version: &semver::Version) -> String {
+    let mut rpm_version = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        rpm_version.push('~');
+        rpm_version.push_str(&version.pre.replace('-', "_"));
+    }
+    if !version.build.is_empty() {
+        rpm_version.push('+');
+        rpm_version.push_str(&version.build.replace('-', "_"));
+    }
+    rpm_version
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_layout() {
+        let mut header = Header::default();
+        header.string(TAG_NAME, "axolotlsay");
+        header.int16(TAG_FILEMODES, &[0o100755]);
+        header.int32(TAG_FILESIZES, &[7]);
+        let bytes = header.to_bytes(TAG_HEADERIMMUTABLE);
+
+        let be32 = |at: usize| u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap());
+        assert_eq!(&bytes[..4], &[0x8e, 0xad, 0xe8, 0x01]);
+        // region entry + 3 entries
+        assert_eq!(be32(8), 4);
+        let store_len = be32(12) as usize;
+        let store = 16 + 4 * 16;
+        assert_eq!(bytes.len(), store + store_len);
+
+        // The region entry points at the trailer at the end of the store
+        assert_eq!(be32(16), TAG_HEADERIMMUTABLE);
+        assert_eq!(be32(16 + 8) as usize, store_len - 16);
+        let trailer = store + store_len - 16;
+        assert_eq!(be32(trailer), TAG_HEADERIMMUTABLE);
+        assert_eq!(be32(trailer + 8) as i32, -(4 * 16));
+
+        // Entries are sorted by tag and ints are aligned
+        assert_eq!(be32(32), TAG_NAME);
+        assert_eq!(be32(32 + 8), 0);
+        assert_eq!(be32(48), TAG_FILESIZES);
+        assert_eq!(be32(48 + 8), 12);
+        assert_eq!(be32(64), TAG_FILEMODES);
+        assert_eq!(be32(64 + 8), 16);
+        assert_eq!(&bytes[store..store + 11], b"axolotlsay\0");
+    }
+
+    #[test]
+    fn reads_back_metadata() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        std::fs::write(dir.join("axolotlsay"), b"not really elf").unwrap();
+        let info = RpmInstallerInfo {
+            file_path: dir.join("axolotlsay.rpm"),
+            package_dir: dir.clone(),
+            package_name: "axolotlsay".to_owned(),
+            version: "0.1.0".to_owned(),
+            release: "1".to_owned(),
+            architecture: "x86_64".to_owned(),
+            description: Some("say things".to_owned()),
+            license: Some("MIT".to_owned()),
+            url: None,
+            executables: vec!["axolotlsay".to_owned()],
+            cdylibs: vec![],
+            bin_aliases: BTreeMap::new(),
+            docs: vec![],
+            licenses: vec![],
+        };
+        info.build().unwrap();
+
+        let rpm = std::fs::read(&info.file_path).unwrap();
+        let metadata = read_rpm_metadata(&rpm).unwrap();
+        assert_eq!(metadata.name, "axolotlsay");
+        assert_eq!(metadata.version, "0.1.0");
+        assert_eq!(metadata.arch, "x86_64");
+        assert_eq!(metadata.summary, "say things");
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+        assert_eq!(metadata.header_start % 8, 0);
+        assert!(metadata.header_end < rpm.len());
+        assert_eq!(metadata.files, [("/usr/bin/axolotlsay".to_owned(), false)]);
+        assert_eq!(metadata.provides[0].comparison(), Some("EQ"));
+        assert!(metadata.requires.iter().all(|r| r.is_rpmlib()));
+        assert!(read_rpm_metadata(b"not an rpm").is_none());
+    }
+
+    #[test]
+    fn cpio_layout() {
+        let entries = BTreeMap::from([(
+            "/usr/bin/say".to_owned(),
+            RpmEntry::File {
+                mode: 0o755,
+                flags: 0,
+                contents: b"hi".to_vec(),
+            },
+        )]);
+        let cpio = cpio_archive(&entries, 0).unwrap();
+        let header = String::from_utf8(cpio[..110].to_vec()).unwrap();
+        assert_eq!(
+            header,
+            concat!(
+                "070701", "00000001", "000081ed", "00000000", "00000000", "00000001", "00000000",
+                "00000002", "00000000", "00000000", "00000000", "00000000", "0000000e", "00000000",
+            )
+        );
+        // name and contents are each padded to 4 bytes
+        assert_eq!(&cpio[110..124], b"./usr/bin/say\0");
+        assert_eq!(&cpio[124..128], b"hi\0\0");
+        assert_eq!(&cpio[128..134], b"070701");
+        assert!(cpio.len() % 4 == 0);
+    }
+
+    #[test]
+    fn architectures() {
+        let arch = |triple: &str| rpm_architecture(TargetTripleRef::from_str(triple));
+        assert_eq!(arch("x86_64-unknown-linux-gnu"), Some("x86_64"));
+        assert_eq!(arch("x86_64-unknown-linux-musl"), Some("x86_64"));
+        assert_eq!(arch("armv7-unknown-linux-gnueabihf"), Some("armv7hl"));
+        assert_eq!(arch("aarch64-apple-darwin"), None);
+    }
+
+    #[test]
+    fn versions() {
+        let version = |v: &str| rpm_version(&semver::Version::parse(v).unwrap());
+        assert_eq!(version("1.2.3"), "1.2.3");
+        assert_eq!(version("1.2.3-pre-release.2"), "1.2.3~pre_release.2");
+        assert_eq!(version("1.2.3+build-5"), "1.2.3+build_5");
+    }
+}
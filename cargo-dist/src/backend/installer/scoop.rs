@@ -0,0 +1,295 @@
+//! Code for generating Scoop manifests
+
+use std::collections::BTreeMap;
+
+use axoasset::LocalAsset;
+use cargo_dist_schema::DistManifest;
+use serde::Serialize;
+
+use super::InstallerInfo;
+use crate::{
+    config::ChecksumStyle, errors::DistResult, installer::ExecutableZipFragment, tasks::DistGraph,
+};
+
+/// Info about a Scoop manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoopInstallerInfo {
+    /// The name the app has in the bucket
+    pub name: String,
+    /// A brief description of the application
+    pub desc: Option<String>,
+    /// The URL to the application's homepage
+    pub homepage: Option<String>,
+    /// The application's license, in SPDX format
+    pub license: Option<String>,
+    /// A GitHub repository to write the manifest to, in owner/name format
+    pub bucket: Option<String>,
+    /// Windows AMD64 artifact
+    pub x86_64_windows: Option<ExecutableZipFragment>,
+    /// Windows x86 artifact
+    pub x86_windows: Option<ExecutableZipFragment>,
+    /// Windows ARM64 artifact
+    pub arm64_windows: Option<ExecutableZipFragment>,
+    /// Binaries to make shims for, and the aliases to make shims for them under
+    pub bins: BTreeMap<String, Vec<String>>,
+    /// How Scoop should check for new versions, if we know
+    pub checkver: Option<ScoopCheckver>,
+    /// URL of the directory artifacts of any version can be fetched from,
+    /// with the version replaced by `$version`
+    pub autoupdate_base_url: Option<String>,
+    /// Generic installer info
+    pub inner: InstallerInfo,
+}
+
+/// The `checkver` block of a Scoop manifest
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum ScoopCheckver {
+    /// Check the latest release of a GitHub repository
+    Github {
+        /// URL of the repository
+        github: String,
+    },
+    /// Scrape a page for the newest version
+    Url {
+        /// URL of the page
+        url: String,
+        /// Regex whose first group is the version
+        regex: String,
+    },
+}
+
+impl ScoopCheckver {
+    /// Check a GitHub repository for releases tagged like the given tag
+    pub fn for_github_release(owner: &str, repo: &str, tag: &str, version: &str) -> Self {
+        let repo_url = format!("https://github.com/{owner}/{repo}");
+        let prefix = tag.strip_suffix(version).unwrap_or_default();
+        // Scoop understands `v1.0.0` and `1.0.0` tags itself, anything else
+        // (like `myapp-v1.0.0`) needs to be scraped out of the releases page
+        if prefix.is_empty() || prefix == "v" {
+            ScoopCheckver::Github { github: repo_url }
+        } else {
+            ScoopCheckver::Url {
+                url: format!("{repo_url}/releases"),
+                regex: format!("/releases/tag/{}([\\w.+-]+)", regex_escape(prefix)),
+            }
+        }
+    }
+}
+
+/// Turn a release's download URL into a template Scoop can fill in with a new version
+///
+/// Only the version in the tag (the last path segment) is replaced, so a version
+/// that also happens to appear in the owner or repo name is left alone.
+pub fn autoupdate_base_url(download_url: &str, version: &str) -> Option<String> {
+    let (base, tag) = download_url.rsplit_once('/')?;
+    let prefix = tag.strip_suffix(version)?;
+    Some(format!("{base}/{prefix}$version"))
+}
+
+/// Escape everything in `text` a regex might treat specially
+fn regex_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if !c.is_ascii_alphanumeric() && c != '_' && c != '-' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScoopManifest {
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    homepage: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<String>,
+    architecture: BTreeMap<&'static str, ScoopArchitecture>,
+    bin: Vec<ScoopBin>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checkver: Option<ScoopCheckver>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    autoupdate: Option<ScoopAutoupdate>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScoopArchitecture {
+    url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<String>,
+}
+
+/// Either a plain binary or `[binary, alias]`
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+enum ScoopBin {
+    Plain(String),
+    Alias([String; 2]),
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScoopAutoupdate {
+    architecture: BTreeMap<&'static str, ScoopArchitecture>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hash: Option<ScoopHashSource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScoopHashSource {
+    url: String,
+}
+
+pub(crate) fn write_scoop_manifest(
+    _dist: &DistGraph,
+    info: &ScoopInstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let contents = scoop_manifest_json(info, manifest)?;
+    LocalAsset::write_new(&contents, &info.inner.dest_path)?;
+    Ok(())
+}
+
+fn scoop_manifest_json(info: &ScoopInstallerInfo, manifest: &DistManifest) -> DistResult<String> {
+    let arches = [
+        ("64bit", &info.x86_64_windows),
+        ("32bit", &info.x86_windows),
+        ("arm64", &info.arm64_windows),
+    ];
+    let arches = arches
+        .into_iter()
+        .filter_map(|(arch, fragment)| Some((arch, fragment.as_ref()?)))
+        .collect::<Vec<_>>();
+
+    let architecture = arches
+        .iter()
+        .map(|(arch, fragment)| {
+            let entry = ScoopArchitecture {
+                url: format!("{}/{}", info.inner.base_url, fragment.id),
+                hash: sha256_checksum(manifest, fragment),
+            };
+            (*arch, entry)
+        })
+        .collect();
+
+    let mut bin = vec![];
+    for (binary, aliases) in &info.bins {
+        let exe = format!("{binary}.exe");
+        bin.push(ScoopBin::Plain(exe.clone()));
+        for alias in aliases {
+            bin.push(ScoopBin::Alias([exe.clone(), alias.clone()]));
+        }
+    }
+
+    // Can't autoupdate if we can't tell when there's an update
+    let autoupdate = info
+        .autoupdate_base_url
+        .as_ref()
+        .filter(|_| info.checkver.is_some())
+        .map(|base_url| {
+            let architecture = arches
+                .iter()
+                .map(|(arch, fragment)| {
+                    let entry = ScoopArchitecture {
+                        url: format!("{base_url}/{}", fragment.id),
+                        hash: None,
+                    };
+                    (*arch, entry)
+                })
+                .collect();
+            // If every archive has a .sha256 next to it, Scoop can just read those,
+            // otherwise it'll download the archives and hash them itself
+            let has_checksum_files = arches
+                .iter()
+                .all(|(_, fragment)| has_sha256_file(manifest, fragment));
+            let hash = has_checksum_files.then(|| ScoopHashSource {
+                url: "$url.sha256".to_owned(),
+            });
+            ScoopAutoupdate { architecture, hash }
+        });
+
+    let scoop_manifest = ScoopManifest {
+        version: info.inner.app_version.clone(),
+        description: info.desc.clone(),
+        homepage: info.homepage.clone(),
+        license: info.license.clone(),
+        architecture,
+        bin,
+        checkver: info.checkver.clone(),
+        autoupdate,
+    };
+    let mut contents = serde_json::to_string_pretty(&scoop_manifest)
+        .expect("scoop manifest can always be serialized");
+    contents.push('\n');
+    Ok(contents)
+}
+
+/// Grab the sha256 checksum for this artifact from the manifest
+fn sha256_checksum(manifest: &DistManifest, fragment: &ExecutableZipFragment) -> Option<String> {
+    manifest
+        .artifacts
+        .get(&fragment.id)
+        .and_then(|a| a.checksums.get(ChecksumStyle::Sha256.ext()))
+        .cloned()
+}
+
+/// Whether this artifact gets a `.sha256` file uploaded next to it
+fn has_sha256_file(manifest: &DistManifest, fragment: &ExecutableZipFragment) -> bool {
+    let expected = format!("{}.{}", fragment.id, ChecksumStyle::Sha256.ext());
+    manifest
+        .artifacts
+        .get(&fragment.id)
+        .and_then(|a| a.checksum.as_ref())
+        .is_some_and(|checksum| checksum == &expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{autoupdate_base_url, regex_escape, ScoopCheckver};
+
+    #[test]
+    fn checkver_plain_tags() {
+        for tag in ["v1.2.3", "1.2.3"] {
+            let checkver = ScoopCheckver::for_github_release("axo", "say", tag, "1.2.3");
+            assert!(matches!(
+                checkver,
+                ScoopCheckver::Github { github } if github == "https://github.com/axo/say"
+            ));
+        }
+    }
+
+    #[test]
+    fn checkver_prefixed_tags() {
+        let checkver = ScoopCheckver::for_github_release("axo", "say", "say-v1.2.3", "1.2.3");
+        let ScoopCheckver::Url { url, regex } = checkver else {
+            panic!("prefixed tags need to be scraped");
+        };
+        assert_eq!(url, "https://github.com/axo/say/releases");
+        assert_eq!(regex, "/releases/tag/say-v([\\w.+-]+)");
+    }
+
+    #[test]
+    fn autoupdate_only_templates_the_tag() {
+        assert_eq!(
+            autoupdate_base_url(
+                "https://github.com/axo/say-1.2.3/releases/download/say-v1.2.3",
+                "1.2.3"
+            )
+            .as_deref(),
+            Some("https://github.com/axo/say-1.2.3/releases/download/say-v$version")
+        );
+        assert_eq!(
+            autoupdate_base_url("https://example.com/releases/latest", "1.2.3"),
+            None
+        );
+    }
+
+    #[test]
+    fn escapes_regex_syntax() {
+        assert_eq!(regex_escape("my_app-v"), "my_app-v");
+        assert_eq!(regex_escape("app.v+"), "app\\.v\\+");
+    }
+}
@@ -1,18 +1,346 @@
-//! Code for generating installer.sh
+//! Code for generating installer.sh (and uninstaller.sh)
+
+use std::collections::BTreeMap;
 
 use axoasset::LocalAsset;
+use camino::Utf8Path;
+use cargo_dist_schema::DistManifest;
+use serde::Serialize;
+
+use crate::{
+    backend::templates::{TEMPLATE_INSTALLER_SH, TEMPLATE_UNINSTALLER_SH},
+    errors::{DistError, DistResult},
+    platform::PlatformSupport,
+    DistGraph,
+};
+
+use super::{
+    apply_download_urls, expected_checksums, other_releases, ExpectedChecksum, InstallerInfo,
+    OtherReleases, UninstallerInfo,
+};
+
+/// The line separating an offline installer's script from the archives appended to it
+const PAYLOAD_MARKER: &str = "__CARGO_DIST_PAYLOAD_BELOW__";
+
+/// What installer.sh gets rendered with
+#[derive(Serialize)]
+struct ShellInstallerContext<'a> {
+    #[serde(flatten)]
+    info: &'a InstallerInfo,
+    /// The archives appended to the script, for offline installers
+    payload: Option<Payload>,
+    /// Checksums to verify each archive against, by archive id
+    checksums: BTreeMap<String, ExpectedChecksum>,
+    /// Key to verify each archive's detached signature against
+    minisign_public_key: Option<String>,
+    /// Where to find other versions, for `--version` and `--channel`
+    other_releases: Option<OtherReleases>,
+    /// Base URLs to try in order if `base_url` doesn't work
+    fallback_urls: Vec<String>,
+}
+
+/// The archives an offline installer carries after its [`PAYLOAD_MARKER`][]
+#[derive(Serialize)]
+struct Payload {
+    marker: &'static str,
+    archives: Vec<PayloadArchive>,
+}
+
+/// Where an archive is in the payload
+#[derive(Serialize)]
+struct PayloadArchive {
+    id: String,
+    /// Byte offset from the start of the payload
+    offset: usize,
+    /// Size in bytes
+    size: usize,
+}
+
+pub(crate) fn write_install_sh_script(
+    dist: &DistGraph,
+    info: &InstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let platform_support = dist.release(info.release).platform_support.clone();
+    let script = render_install_sh_script(dist, info, manifest, platform_support, None)?;
+    LocalAsset::write_new(&script, &info.dest_path)?;
+    Ok(())
+}
+
+/// Like [`write_install_sh_script`][], but appends the archives to the script,
+/// so it can install without fetching anything
+pub(crate) fn write_offline_install_sh_script(
+    dist: &DistGraph,
+    info: &InstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let platform_support = offline_platform_support(&dist.release(info.release).platform_support);
+    let (payload, payload_bytes) = load_payload(&dist.dist_dir, &platform_support)?;
+    let script = render_install_sh_script(dist, info, manifest, platform_support, Some(payload))?;
+    std::fs::write(&info.dest_path, append_payload(script, payload_bytes))?;
+    Ok(())
+}
 
-use crate::{backend::templates::TEMPLATE_INSTALLER_SH, errors::DistResult, DistGraph};
+/// Narrow a release's platforms to the ones an offline installer should carry archives for
+///
+/// Windows users get installer.ps1, so their archives would only bloat the script.
+fn offline_platform_support(platform_support: &PlatformSupport) -> PlatformSupport {
+    let mut platform_support = platform_support.clone();
+    platform_support
+        .platforms
+        .retain(|target, _| !target.is_windows());
+    platform_support
+}
 
-use super::InstallerInfo;
+/// Load every archive the installer might pick (including fallbacks like musl builds),
+/// laid out back to back
+fn load_payload(
+    dist_dir: &Utf8Path,
+    platform_support: &PlatformSupport,
+) -> DistResult<(Payload, Vec<u8>)> {
+    let mut archives = vec![];
+    let mut bytes = vec![];
+    for (archive_idx, archive) in platform_support.archives.iter().enumerate() {
+        let pickable = platform_support
+            .platforms
+            .values()
+            .flatten()
+            .any(|option| option.archive_idx == archive_idx);
+        if !pickable {
+            continue;
+        }
+        let path = dist_dir.join(&archive.id);
+        if !path.exists() {
+            return Err(DistError::OfflineInstallerMissingArchive {
+                archive: archive.id.clone(),
+                dist_dir: dist_dir.to_owned(),
+            });
+        }
+        let contents = LocalAsset::load_bytes(&path)?;
+        archives.push(PayloadArchive {
+            id: archive.id.clone(),
+            offset: bytes.len(),
+            size: contents.len(),
+        });
+        bytes.extend(contents);
+    }
+    let payload = Payload {
+        marker: PAYLOAD_MARKER,
+        archives,
+    };
+    Ok((payload, bytes))
+}
 
-pub(crate) fn write_install_sh_script(dist: &DistGraph, info: &InstallerInfo) -> DistResult<()> {
+fn append_payload(script: String, payload_bytes: Vec<u8>) -> Vec<u8> {
+    let mut output = script.into_bytes();
+    output.extend(format!("{PAYLOAD_MARKER}\n").as_bytes());
+    output.extend(payload_bytes);
+    output
+}
+
+fn render_install_sh_script(
+    dist: &DistGraph,
+    info: &InstallerInfo,
+    manifest: &DistManifest,
+    platform_support: PlatformSupport,
+    payload: Option<Payload>,
+) -> DistResult<String> {
+    let release = dist.release(info.release);
     let mut info = info.clone();
-    info.platform_support = Some(dist.release(info.release).platform_support.clone());
 
+    let checksums = expected_checksums(
+        manifest,
+        platform_support
+            .archives
+            .iter()
+            .map(|archive| archive.id.as_str()),
+    );
+    info.platform_support = Some(platform_support);
+    let minisign_public_key = release
+        .config
+        .installers
+        .shell
+        .as_ref()
+        .and_then(|shell| shell.minisign_public_key.clone());
+    // Offline installers can only install the archives they carry
+    let (other_releases, fallback_urls) = if payload.is_none() {
+        (
+            other_releases(dist, info.release, manifest),
+            apply_download_urls(&mut info, manifest),
+        )
+    } else {
+        (None, vec![])
+    };
+
+    dist.templates.render_file_to_clean_string(
+        TEMPLATE_INSTALLER_SH,
+        &ShellInstallerContext {
+            info: &info,
+            payload,
+            checksums,
+            minisign_public_key,
+            other_releases,
+            fallback_urls,
+        },
+    )
+}
+
+pub(crate) fn write_uninstall_sh_script(
+    dist: &DistGraph,
+    info: &UninstallerInfo,
+) -> DistResult<()> {
     let script = dist
         .templates
-        .render_file_to_clean_string(TEMPLATE_INSTALLER_SH, &info)?;
+        .render_file_to_clean_string(TEMPLATE_UNINSTALLER_SH, info)?;
     LocalAsset::write_new(&script, &info.dest_path)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use cargo_dist_schema::TargetTriple;
+
+    use super::*;
+    use crate::{
+        backend::templates::Templates,
+        config::{CompressionImpl, ZipStyle},
+        platform::{FetchableArchive, PlatformEntry, RuntimeConditions, SupportQuality},
+        ReleaseIdx, SortedMap,
+    };
+
+    fn archive(target: &str, ext: &str) -> FetchableArchive {
+        let target_triple = TargetTriple::new(target.to_owned());
+        FetchableArchive {
+            id: format!("axolotlsay-{target}{ext}"),
+            native_runtime_conditions: RuntimeConditions::default(),
+            target_triple: target_triple.clone(),
+            target_triples: vec![target_triple],
+            sha256sum: None,
+            executables: vec!["axolotlsay".to_owned()],
+            cdylibs: vec![],
+            cstaticlibs: vec![],
+            zip_style: if ext == ".zip" {
+                ZipStyle::Zip
+            } else {
+                ZipStyle::Tar(CompressionImpl::Gzip)
+            },
+            updater: None,
+        }
+    }
+
+    fn platform_support() -> PlatformSupport {
+        let archives = vec![
+            archive("x86_64-unknown-linux-gnu", ".tar.gz"),
+            archive("x86_64-apple-darwin", ".tar.gz"),
+            archive("x86_64-pc-windows-msvc", ".zip"),
+        ];
+        let platforms = archives
+            .iter()
+            .enumerate()
+            .map(|(archive_idx, archive)| {
+                let entry = PlatformEntry {
+                    quality: SupportQuality::HostNative,
+                    runtime_conditions: RuntimeConditions::default(),
+                    archive_idx,
+                };
+                (archive.target_triple.clone(), vec![entry])
+            })
+            .collect::<SortedMap<_, _>>();
+        PlatformSupport {
+            archives,
+            updaters: vec![],
+            platforms,
+        }
+    }
+
+    fn info(platform_support: PlatformSupport) -> InstallerInfo {
+        let artifacts = platform_support.fragments();
+        let bin_aliases = artifacts
+            .iter()
+            .map(|artifact| (artifact.target_triple.clone(), BTreeMap::new()))
+            .collect();
+        InstallerInfo {
+            release: ReleaseIdx(0),
+            dest_path: "axolotlsay-installer.sh".into(),
+            app_name: "axolotlsay".to_owned(),
+            app_version: "0.2.2".to_owned(),
+            base_url: "https://example.com/v0.2.2".to_owned(),
+            artifacts,
+            desc: String::new(),
+            hint: String::new(),
+            install_paths: vec![],
+            install_success_msg: String::new(),
+            receipt: None,
+            bin_aliases,
+            install_libraries: vec![],
+            runtime_conditions: RuntimeConditions::default(),
+            platform_support: Some(platform_support),
+            install_dir_env_var: String::new(),
+            unmanaged_dir_env_var: String::new(),
+            disable_update_env_var: String::new(),
+            no_modify_path_env_var: String::new(),
+        }
+    }
+
+    #[test]
+    fn offline_payload_extracts() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dist_dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let platform_support = offline_platform_support(&platform_support());
+        for archive in &platform_support.archives {
+            LocalAsset::write_new(
+                &format!("contents of {}", archive.id),
+                dist_dir.join(&archive.id),
+            )
+            .unwrap();
+        }
+
+        let (payload, payload_bytes) = load_payload(&dist_dir, &platform_support).unwrap();
+        let ids = payload
+            .archives
+            .iter()
+            .map(|archive| archive.id.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                "axolotlsay-x86_64-unknown-linux-gnu.tar.gz",
+                "axolotlsay-x86_64-apple-darwin.tar.gz",
+            ]
+        );
+
+        let script = Templates::new()
+            .unwrap()
+            .render_file_to_clean_string(
+                TEMPLATE_INSTALLER_SH,
+                &ShellInstallerContext {
+                    info: &info(platform_support),
+                    payload: Some(payload),
+                    checksums: BTreeMap::new(),
+                    minisign_public_key: None,
+                    other_releases: None,
+                    fallback_urls: vec![],
+                },
+            )
+            .unwrap();
+        assert!(!script.contains("\"x86_64-pc-windows-msvc\")"));
+        let output = append_payload(script, payload_bytes);
+
+        // Extract each archive the way the script does: from the line after the marker,
+        // skip to the offset its extract_payload case has, and read its size
+        let output = String::from_utf8(output).unwrap();
+        let (script, payload) = output.split_once(&format!("\n{PAYLOAD_MARKER}\n")).unwrap();
+        let (_, extract_payload) = script.split_once("extract_payload() {").unwrap();
+        let rendered_value = |case: &str, var: &str| -> usize {
+            let (_, rest) = case.split_once(&format!("{var}=\"")).unwrap();
+            rest.split_once('"').unwrap().0.parse().unwrap()
+        };
+        for id in ids {
+            let (_, case) = extract_payload.split_once(&format!("\"{id}\")")).unwrap();
+            let offset = rendered_value(case, "_offset");
+            let size = rendered_value(case, "_size");
+            assert_eq!(&payload[offset..offset + size], format!("contents of {id}"));
+        }
+    }
+}
@@ -0,0 +1,649 @@
+//! Code for generating WinGet manifests
+//!
+//! WinGet wants a "multi-file" manifest for every version of a package: a version
+//! manifest, a locale manifest with all the human-readable metadata, and an
+//! installer manifest listing what to download for each architecture. These are
+//! written in the same directory layout as the winget-pkgs repository, so they
+//! can be copied straight into a checkout of it.
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_dist_schema::{DistManifest, TargetTripleRef};
+use serde::Serialize;
+use tracing::warn;
+
+use super::{recorded_or_local_sha256, InstallerImpl, InstallerInfo};
+use crate::{
+    backend::diff_files,
+    config::{Config, WingetArgs},
+    errors::{DistError, DistResult},
+    gather_work,
+    tasks::DistGraph,
+    ArtifactKind,
+};
+
+/// The version of the manifest schema we generate
+pub const WINGET_MANIFEST_VERSION: &str = "1.6.0";
+/// The locale all our metadata is written in
+const DEFAULT_LOCALE: &str = "en-US";
+
+/// Info about a set of WinGet manifests
+#[derive(Debug, Clone, Serialize)]
+pub struct WingetInstallerInfo {
+    /// The PackageIdentifier, in Publisher.Package format
+    pub package_identifier: String,
+    /// The name of the publisher
+    pub publisher: String,
+    /// The URL of the publisher
+    pub publisher_url: Option<String>,
+    /// The author of the package
+    pub author: Option<String>,
+    /// The URL to the package's homepage
+    pub package_url: Option<String>,
+    /// The package's license, in SPDX format
+    pub license: Option<String>,
+    /// A brief description of the package
+    pub short_description: Option<String>,
+    /// Keywords for the package
+    pub tags: Vec<String>,
+    /// Things to download for each architecture
+    pub installers: Vec<WingetInstaller>,
+    /// Dir the manifests are written to, before being archived
+    pub package_dir: Utf8PathBuf,
+    /// Generic installer info
+    pub inner: InstallerInfo,
+}
+
+/// One entry of a WinGet installer manifest
+#[derive(Debug, Clone, Serialize)]
+pub struct WingetInstaller {
+    /// The architecture, in WinGet's terms (x64, x86, arm64)
+    pub architecture: &'static str,
+    /// The artifact to download
+    pub artifact_id: String,
+    /// What kind of artifact it is
+    pub kind: WingetInstallerKind,
+}
+
+/// The kinds of artifacts WinGet can install from
+#[derive(Debug, Clone, Serialize)]
+pub enum WingetInstallerKind {
+    /// An msi installer
+    Msi,
+    /// A zip of portable executables
+    Zip {
+        /// The executables in the zip (names, assumed at root)
+        executables: Vec<String>,
+    },
+}
+
+/// The three files of a multi-file WinGet manifest
+#[derive(Debug, Clone)]
+pub struct WingetManifests {
+    /// The version manifest
+    version: VersionManifest,
+    /// The default locale manifest
+    locale: LocaleManifest,
+    /// The installer manifest
+    installer: InstallerManifest,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct VersionManifest {
+    package_identifier: String,
+    package_version: String,
+    default_locale: String,
+    manifest_type: &'static str,
+    manifest_version: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct LocaleManifest {
+    package_identifier: String,
+    package_version: String,
+    package_locale: String,
+    publisher: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    package_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    package_url: Option<String>,
+    license: String,
+    short_description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    manifest_type: &'static str,
+    manifest_version: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct InstallerManifest {
+    package_identifier: String,
+    package_version: String,
+    installers: Vec<InstallerEntry>,
+    manifest_type: &'static str,
+    manifest_version: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct InstallerEntry {
+    architecture: &'static str,
+    installer_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nested_installer_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    nested_installer_files: Vec<NestedInstallerFile>,
+    installer_url: String,
+    installer_sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct NestedInstallerFile {
+    relative_file_path: String,
+    portable_command_alias: String,
+}
+
+impl WingetInstallerInfo {
+    /// Render the manifests, using the checksums recorded in the dist manifest
+    /// (or the artifacts in the dist dir, if they were built by something else)
+    ///
+    /// Errors if an installer's checksum isn't known, rather than writing out
+    /// a manifest WinGet would reject.
+    pub fn manifests(
+        &self,
+        dist: &DistGraph,
+        manifest: &DistManifest,
+    ) -> DistResult<WingetManifests> {
+        let package_identifier = self.package_identifier.clone();
+        let package_version = self.inner.app_version.clone();
+
+        let installers = self
+            .installers
+            .iter()
+            .map(|installer| {
+                let (installer_type, nested_installer_type, nested_installer_files) =
+                    match &installer.kind {
+                        WingetInstallerKind::Msi => ("msi", None, vec![]),
+                        WingetInstallerKind::Zip { executables } => {
+                            let files = executables
+                                .iter()
+                                .map(|exe| NestedInstallerFile {
+                                    relative_file_path: exe.clone(),
+                                    portable_command_alias: exe
+                                        .strip_suffix(".exe")
+                                        .unwrap_or(exe)
+                                        .to_owned(),
+                                })
+                                .collect();
+                            ("zip", Some("portable"), files)
+                        }
+                    };
+                let sha256 = recorded_or_local_sha256(dist, manifest, &installer.artifact_id)
+                    .ok_or_else(|| DistError::WingetMissingChecksum {
+                        artifact_id: installer.artifact_id.clone(),
+                    })?;
+                Ok(InstallerEntry {
+                    architecture: installer.architecture,
+                    installer_type,
+                    nested_installer_type,
+                    nested_installer_files,
+                    installer_url: format!("{}/{}", self.inner.base_url, installer.artifact_id),
+                    installer_sha256: sha256.to_ascii_uppercase(),
+                })
+            })
+            .collect::<DistResult<_>>()?;
+
+        Ok(WingetManifests {
+            version: VersionManifest {
+                package_identifier: package_identifier.clone(),
+                package_version: package_version.clone(),
+                default_locale: DEFAULT_LOCALE.to_owned(),
+                manifest_type: "version",
+                manifest_version: WINGET_MANIFEST_VERSION,
+            },
+            locale: LocaleManifest {
+                package_identifier: package_identifier.clone(),
+                package_version: package_version.clone(),
+                package_locale: DEFAULT_LOCALE.to_owned(),
+                publisher: self.publisher.clone(),
+                publisher_url: self.publisher_url.clone(),
+                author: self.author.clone(),
+                package_name: self.inner.app_name.clone(),
+                package_url: self.package_url.clone(),
+                license: self.license.clone().unwrap_or_default(),
+                short_description: self.short_description.clone().unwrap_or_default(),
+                tags: self.tags.clone(),
+                manifest_type: "defaultLocale",
+                manifest_version: WINGET_MANIFEST_VERSION,
+            },
+            installer: InstallerManifest {
+                package_identifier,
+                package_version,
+                installers,
+                manifest_type: "installer",
+                manifest_version: WINGET_MANIFEST_VERSION,
+            },
+        })
+    }
+
+    /// The directory the manifests go in, relative to the root of winget-pkgs' `manifests` dir
+    ///
+    /// e.g. `a/Axodotdev/Axolotlsay/1.0.0`
+    pub fn relative_dir(&self) -> Utf8PathBuf {
+        let first = self
+            .package_identifier
+            .chars()
+            .next()
+            .unwrap_or('_')
+            .to_ascii_lowercase();
+        let mut dir = Utf8PathBuf::from(first.to_string());
+        for part in self.package_identifier.split('.') {
+            dir.push(part);
+        }
+        dir.push(&self.inner.app_version);
+        dir
+    }
+}
+
+impl WingetManifests {
+    /// The file names and contents of the manifests
+    pub fn files(&self) -> DistResult<Vec<(String, String)>> {
+        let [version_name, locale_name, installer_name] =
+            file_names(&self.version.package_identifier);
+        let files = [
+            (version_name, "version", to_yaml(&self.version)?),
+            (locale_name, "defaultLocale", to_yaml(&self.locale)?),
+            (installer_name, "installer", to_yaml(&self.installer)?),
+        ];
+        Ok(files
+            .into_iter()
+            .map(|(name, kind, yaml)| {
+                let contents = format!(
+                    "# yaml-language-server: $schema=https://aka.ms/winget-manifest.{kind}.{WINGET_MANIFEST_VERSION}.schema.json\n\n{yaml}"
+                );
+                (name, contents)
+            })
+            .collect())
+    }
+
+    /// Write the manifests into the given dir
+    fn write_to_dir(&self, dir: &Utf8Path) -> DistResult<()> {
+        for (name, contents) in self.files()? {
+            LocalAsset::write_new_all(&contents, dir.join(name))?;
+        }
+        Ok(())
+    }
+
+    /// Check whether the manifests in the given dir differ from these ones
+    fn check_dir(&self, dir: &Utf8Path) -> DistResult<()> {
+        for (name, contents) in self.files()? {
+            diff_files(&dir.join(name), &contents)?;
+        }
+        Ok(())
+    }
+
+    /// Lint the manifests for values the WinGet schema would reject, returning every problem found
+    ///
+    /// This isn't a full schema validation, it only covers the fields we fill in
+    /// from user config (identifiers, lengths, URLs, hashes).
+    pub fn lint(&self) -> Vec<String> {
+        let mut problems = vec![];
+        let mut check = |ok: bool, problem: String| {
+            if !ok {
+                problems.push(problem);
+            }
+        };
+
+        let id = &self.version.package_identifier;
+        check(
+            is_package_identifier(id),
+            format!("PackageIdentifier {id:?} must be 2 to 8 dot-separated parts, each 1 to 32 characters without whitespace or any of \\/:*?\"<>|"),
+        );
+        let version = &self.version.package_version;
+        check(
+            !version.is_empty()
+                && version.len() <= 128
+                && !version.chars().any(is_forbidden_path_char),
+            format!(
+                "PackageVersion {version:?} must be 1 to 128 characters without any of \\/:*?\"<>|"
+            ),
+        );
+
+        let locale = &self.locale;
+        check_len(&mut check, "Publisher", &locale.publisher, 2, 256);
+        check_len(&mut check, "PackageName", &locale.package_name, 2, 256);
+        check_len(&mut check, "License", &locale.license, 3, 512);
+        check_len(
+            &mut check,
+            "ShortDescription",
+            &locale.short_description,
+            3,
+            256,
+        );
+        if let Some(author) = &locale.author {
+            check_len(&mut check, "Author", author, 2, 256);
+        }
+        for (field, url) in [
+            ("PublisherUrl", &locale.publisher_url),
+            ("PackageUrl", &locale.package_url),
+        ] {
+            if let Some(url) = url {
+                check(
+                    is_url(url),
+                    format!("{field} {url:?} must be an http(s) URL"),
+                );
+            }
+        }
+        check(
+            locale.tags.len() <= 16,
+            format!(
+                "Tags can have at most 16 entries, not {}",
+                locale.tags.len()
+            ),
+        );
+        for tag in &locale.tags {
+            check_len(&mut check, "Tag", tag, 1, 40);
+        }
+
+        let installers = &self.installer.installers;
+        check(
+            !installers.is_empty(),
+            "Installers must have at least one entry".to_owned(),
+        );
+        for installer in installers {
+            let arch = installer.architecture;
+            check(
+                is_url(&installer.installer_url),
+                format!(
+                    "InstallerUrl {:?} ({arch}) must be an http(s) URL",
+                    installer.installer_url
+                ),
+            );
+            let sha256 = &installer.installer_sha256;
+            check(
+                sha256.len() == 64 && sha256.chars().all(|c| c.is_ascii_hexdigit()),
+                format!(
+                    "InstallerSha256 of {} ({arch}) must be a sha256 hash, not {sha256:?}",
+                    installer.installer_url
+                ),
+            );
+            for file in &installer.nested_installer_files {
+                check_len(
+                    &mut check,
+                    "PortableCommandAlias",
+                    &file.portable_command_alias,
+                    1,
+                    40,
+                );
+            }
+        }
+        problems
+    }
+}
+
+pub(crate) fn write_winget_manifests(
+    dist: &DistGraph,
+    info: &WingetInstallerInfo,
+    manifest: &DistManifest,
+) -> DistResult<()> {
+    let manifests = info.manifests(dist, manifest)?;
+    let problems = manifests.lint();
+    if !problems.is_empty() {
+        warn!(
+            "the WinGet manifests for {} have lint problems:\n  {}\n  (run `dist winget` again once all the Windows artifacts are built)",
+            info.inner.app_name,
+            problems.join("\n  ")
+        );
+    }
+    // Don't let the manifests of old versions sneak into the archive
+    if info.package_dir.exists() {
+        LocalAsset::remove_dir_all(&info.package_dir)?;
+    }
+    manifests.write_to_dir(&info.package_dir.join(info.relative_dir()))
+}
+
+/// Lint the WinGet manifests of the releases being built, and write them out in the
+/// layout of winget-pkgs' `manifests` dir (or with `--check`, check the ones already
+/// there are up to date)
+pub fn do_winget(cfg: &Config, args: &WingetArgs) -> DistResult<()> {
+    let (dist, manifest) = gather_work(cfg)?;
+    let output_dir = args
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| dist.dist_dir.join("winget-manifests"));
+
+    let mut found_any = false;
+    for artifact in &dist.artifacts {
+        let ArtifactKind::Installer(InstallerImpl::Winget(info)) = &artifact.kind else {
+            continue;
+        };
+        found_any = true;
+        let manifests = info.manifests(&dist, &manifest)?;
+        let problems = manifests.lint();
+        if !problems.is_empty() {
+            return Err(DistError::WingetManifestLint {
+                package_identifier: info.package_identifier.clone(),
+                problems: problems.join("\n"),
+            });
+        }
+        let dir = output_dir.join(info.relative_dir());
+        if args.check {
+            manifests.check_dir(&dir)?;
+            eprintln!("WinGet manifests in {dir} are up to date");
+            continue;
+        }
+        manifests.write_to_dir(&dir)?;
+        eprintln!("generated WinGet manifests to {dir}");
+    }
+    if !found_any {
+        return Err(DistError::NoWingetInstallers);
+    }
+    Ok(())
+}
+
+/// The names of the version, locale and installer manifests of a package
+pub fn file_names(package_identifier: &str) -> [String; 3] {
+    [
+        format!("{package_identifier}.yaml"),
+        format!("{package_identifier}.locale.{DEFAULT_LOCALE}.yaml"),
+        format!("{package_identifier}.installer.yaml"),
+    ]
+}
+
+fn to_yaml(value: &impl Serialize) -> DistResult<String> {
+    Ok(serde_yml::to_string(value)?)
+}
+
+fn check_len(
+    check: &mut impl FnMut(bool, String),
+    field: &str,
+    value: &str,
+    min: usize,
+    max: usize,
+) {
+    let len = value.chars().count();
+    check(
+        (min..=max).contains(&len),
+        format!("{field} {value:?} must be {min} to {max} characters"),
+    );
+}
+
+fn is_forbidden_path_char(c: char) -> bool {
+    matches!(c, '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|') || c.is_control()
+}
+
+fn is_package_identifier(id: &str) -> bool {
+    let parts = id.split('.').collect::<Vec<_>>();
+    id.len() <= 128
+        && (2..=8).contains(&parts.len())
+        && parts.iter().all(|part| {
+            (1..=32).contains(&part.chars().count())
+                && !part
+                    .chars()
+                    .any(|c| c.is_whitespace() || is_forbidden_path_char(c))
+        })
+}
+
+fn is_url(url: &str) -> bool {
+    let lower = url.to_ascii_lowercase();
+    url.len() <= 2048
+        && (lower.starts_with("https://") || lower.starts_with("http://"))
+        && url
+            .split_once("://")
+            .is_some_and(|(_, rest)| !rest.is_empty())
+}
+
+/// Get WinGet's name for the architecture of a Windows target
+pub fn winget_architecture(target: &TargetTripleRef) -> Option<&'static str> {
+    if !target.is_windows() {
+        None
+    } else if target.is_x86_64() {
+        Some("x64")
+    } else if target.is_aarch64() {
+        Some("arm64")
+    } else if target.as_str().starts_with("i686-") || target.as_str().starts_with("i586-") {
+        Some("x86")
+    } else {
+        None
+    }
+}
+
+/// Turn some name into something usable as part of a PackageIdentifier
+pub fn to_identifier_part(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifests() -> WingetManifests {
+        let id = "axodotdev.axolotlsay".to_owned();
+        let version = "1.0.0".to_owned();
+        WingetManifests {
+            version: VersionManifest {
+                package_identifier: id.clone(),
+                package_version: version.clone(),
+                default_locale: DEFAULT_LOCALE.to_owned(),
+                manifest_type: "version",
+                manifest_version: WINGET_MANIFEST_VERSION,
+            },
+            locale: LocaleManifest {
+                package_identifier: id.clone(),
+                package_version: version.clone(),
+                package_locale: DEFAULT_LOCALE.to_owned(),
+                publisher: "axodotdev".to_owned(),
+                publisher_url: Some("https://github.com/axodotdev".to_owned()),
+                author: None,
+                package_name: "axolotlsay".to_owned(),
+                package_url: None,
+                license: "MIT".to_owned(),
+                short_description: "a cute axolotl".to_owned(),
+                tags: vec![],
+                manifest_type: "defaultLocale",
+                manifest_version: WINGET_MANIFEST_VERSION,
+            },
+            installer: InstallerManifest {
+                package_identifier: id,
+                package_version: version,
+                installers: vec![InstallerEntry {
+                    architecture: "x64",
+                    installer_type: "zip",
+                    nested_installer_type: Some("portable"),
+                    nested_installer_files: vec![NestedInstallerFile {
+                        relative_file_path: "axolotlsay.exe".to_owned(),
+                        portable_command_alias: "axolotlsay".to_owned(),
+                    }],
+                    installer_url: "https://example.com/axolotlsay.zip".to_owned(),
+                    installer_sha256: "AB".repeat(32),
+                }],
+                manifest_type: "installer",
+                manifest_version: WINGET_MANIFEST_VERSION,
+            },
+        }
+    }
+
+    #[test]
+    fn clean_lint() {
+        assert_eq!(manifests().lint(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn lint_problems() {
+        let mut manifests = manifests();
+        "axolotlsay".clone_into(&mut manifests.version.package_identifier);
+        manifests.locale.license.clear();
+        manifests.installer.installers[0].installer_sha256.clear();
+        let problems = manifests.lint();
+        assert_eq!(problems.len(), 3, "{problems:#?}");
+        assert!(problems[0].starts_with("PackageIdentifier"));
+        assert!(problems[1].starts_with("License"));
+        assert!(problems[2].starts_with("InstallerSha256"));
+    }
+
+    #[test]
+    fn check_detects_drift() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let mut manifests = manifests();
+        manifests.write_to_dir(&dir).unwrap();
+        manifests.check_dir(&dir).unwrap();
+
+        "Apache-2.0".clone_into(&mut manifests.locale.license);
+        assert!(matches!(
+            manifests.check_dir(&dir),
+            Err(DistError::CheckFileMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn package_identifiers() {
+        assert!(is_package_identifier("axodotdev.axolotlsay"));
+        assert!(is_package_identifier("Microsoft.VisualStudio.Code"));
+        assert!(!is_package_identifier("axolotlsay"));
+        assert!(!is_package_identifier("axo dev.axolotlsay"));
+        assert!(!is_package_identifier("axodotdev..axolotlsay"));
+        assert_eq!(to_identifier_part("Axo Developer Co."), "AxoDeveloperCo");
+    }
+
+    #[test]
+    fn architectures() {
+        let arch = |target: &str| winget_architecture(TargetTripleRef::from_str(target));
+        assert_eq!(arch("x86_64-pc-windows-msvc"), Some("x64"));
+        assert_eq!(arch("x86_64-pc-windows-gnu"), Some("x64"));
+        assert_eq!(arch("i686-pc-windows-msvc"), Some("x86"));
+        assert_eq!(arch("aarch64-pc-windows-msvc"), Some("arm64"));
+        assert_eq!(arch("x86_64-unknown-linux-gnu"), None);
+    }
+
+    #[test]
+    fn files_have_schema_headers() {
+        let files = manifests().files().unwrap();
+        let names = files
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "axodotdev.axolotlsay.yaml",
+                "axodotdev.axolotlsay.locale.en-US.yaml",
+                "axodotdev.axolotlsay.installer.yaml"
+            ]
+        );
+        let (_, installer) = &files[2];
+        assert!(installer.starts_with(
+            "# yaml-language-server: $schema=https://aka.ms/winget-manifest.installer.1.6.0.schema.json\n"
+        ));
+        assert!(installer.contains("ManifestType: installer\n"));
+    }
+}
@@ -10,6 +10,7 @@ use crate::errors::{DistError, DistResult};
 
 pub mod ci;
 pub mod installer;
+pub mod oci;
 pub mod templates;
 
 /// Check if the given file has the same contents we generated
@@ -0,0 +1,655 @@
+//! Code for building OCI (container) images
+//!
+//! An OCI image layout is just a directory of content-addressed blobs with an
+//! `index.json` pointing into them, so we write the blobs ourselves and tar them
+//! up, instead of requiring a Docker daemon (or buildah...) on the machine doing the build.
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_dist_schema::TargetTripleRef;
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::{backend::installer::source_date_epoch, errors::DistResult, DistError, DistGraph};
+
+pub mod registry;
+
+use registry::BaseImage;
+
+/// Media type of an image index
+pub const MEDIA_TYPE_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+/// Media type of an image manifest
+pub const MEDIA_TYPE_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+/// Media type of an image config
+pub const MEDIA_TYPE_CONFIG: &str = "application/vnd.oci.image.config.v1+json";
+/// Media type of a gzipped layer
+pub const MEDIA_TYPE_LAYER: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+
+/// Annotation for the tag of an image in a layout
+const ANNOTATION_REF_NAME: &str = "org.opencontainers.image.ref.name";
+/// Annotation for the image an image was built on
+const ANNOTATION_BASE_NAME: &str = "org.opencontainers.image.base.name";
+/// Annotation for the digest of the image an image was built on
+const ANNOTATION_BASE_DIGEST: &str = "org.opencontainers.image.base.digest";
+/// Annotation for when an image was built
+const ANNOTATION_CREATED: &str = "org.opencontainers.image.created";
+
+/// Where binaries are installed in images
+const BIN_DIR: &str = "usr/local/bin";
+/// PATH for images that don't inherit one from their base image
+const DEFAULT_PATH: &str = "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// A kind of OCI image artifact
+#[derive(Debug, Clone)]
+pub enum OciImageImpl {
+    /// An image for a single platform
+    Image(OciImageInfo),
+    /// A multi-arch image made out of the images for each platform
+    Index(OciIndexInfo),
+}
+
+/// Info about an image for a single platform
+#[derive(Debug, Clone)]
+pub struct OciImageInfo {
+    /// Final file path of the image layout tarball
+    pub file_path: Utf8PathBuf,
+    /// Dir the binaries get copied to before building the image
+    pub package_dir: Utf8PathBuf,
+    /// The platform the image runs on
+    pub platform: OciPlatform,
+    /// Executables to put in the image
+    pub executables: Vec<String>,
+    /// The executable to run when the image is run, if there's an obvious one
+    pub entrypoint: Option<String>,
+    /// The image to build on top of (pinned by digest), or None for `scratch`
+    pub base_image: Option<String>,
+    /// The tag the image gets in the layout
+    pub tag: String,
+    /// Annotations (and labels) describing the image
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// Info about a multi-arch image
+#[derive(Debug, Clone)]
+pub struct OciIndexInfo {
+    /// Final file path of the image layout tarball
+    pub file_path: Utf8PathBuf,
+    /// The single-platform image layout tarballs to combine
+    pub images: Vec<Utf8PathBuf>,
+    /// The tag the image gets in the layout
+    pub tag: String,
+    /// Annotations describing the image
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// A platform, as OCI describes it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OciPlatform {
+    /// CPU architecture (GOARCH values, like `amd64`)
+    pub architecture: String,
+    /// Operating system (GOOS values, like `linux`)
+    pub os: String,
+    /// Variant of the CPU architecture (`v7`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+}
+
+impl std::fmt::Display for OciPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.os, self.architecture)?;
+        if let Some(variant) = &self.variant {
+            write!(f, "/{variant}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A reference to a blob
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Descriptor {
+    /// What the blob is
+    pub media_type: String,
+    /// `sha256:<hex>` of the blob
+    pub digest: String,
+    /// Size of the blob in bytes
+    pub size: u64,
+    /// The platform the blob is for (for manifests in an index)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<OciPlatform>,
+    /// Extra info about the blob
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// An image manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageManifest {
+    /// Always 2
+    pub schema_version: u32,
+    /// Always [`MEDIA_TYPE_MANIFEST`][] for manifests we write
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// The image's config
+    pub config: Descriptor,
+    /// The image's layers, from the bottom up
+    pub layers: Vec<Descriptor>,
+    /// Extra info about the image
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+/// An image index (a list of manifests, usually one per platform)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageIndex {
+    /// Always 2
+    pub schema_version: u32,
+    /// Always [`MEDIA_TYPE_INDEX`][] for indexes we write
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    /// The manifests
+    pub manifests: Vec<Descriptor>,
+    /// Extra info about the image
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+}
+
+impl ImageIndex {
+    fn new(manifests: Vec<Descriptor>, annotations: BTreeMap<String, String>) -> Self {
+        Self {
+            schema_version: 2,
+            media_type: Some(MEDIA_TYPE_INDEX.to_owned()),
+            manifests,
+            annotations,
+        }
+    }
+}
+
+/// The blobs of an image layout, keyed by digest
+#[derive(Debug, Default)]
+struct Blobs(BTreeMap<String, Vec<u8>>);
+
+impl Blobs {
+    /// Add a blob, returning a descriptor for it
+    fn add(&mut self, media_type: &str, contents: Vec<u8>) -> Descriptor {
+        let digest = sha256_digest(&contents);
+        let size = contents.len() as u64;
+        self.0.insert(digest.clone(), contents);
+        Descriptor {
+            media_type: media_type.to_owned(),
+            digest,
+            size,
+            platform: None,
+            annotations: BTreeMap::new(),
+        }
+    }
+
+    /// Add some json as a blob, returning a descriptor for it
+    fn add_json(&mut self, media_type: &str, value: &impl Serialize) -> Descriptor {
+        let contents = serde_json::to_vec(value).expect("failed to serialize OCI json");
+        self.add(media_type, contents)
+    }
+}
+
+/// Get the `sha256:<hex>` digest of some bytes
+pub(crate) fn sha256_digest(contents: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(contents))
+}
+
+/// Build an OCI image layout tarball for one platform
+pub(crate) fn build_oci_image(dist: &DistGraph, info: &OciImageInfo) -> DistResult<()> {
+    info!("building an OCI image: {}", info.file_path);
+    let base = info
+        .base_image
+        .as_deref()
+        .map(|reference| registry::fetch_base_image(dist, reference, &info.platform))
+        .transpose()?;
+    write_oci_image(info, base.as_ref(), source_date_epoch())
+}
+
+/// Write out an image with our layer on top of the (already fetched) base image
+fn write_oci_image(info: &OciImageInfo, base: Option<&BaseImage>, mtime: u64) -> DistResult<()> {
+    let created = rfc3339(mtime);
+
+    let mut files = BTreeMap::new();
+    for exe in &info.executables {
+        let contents = LocalAsset::load_bytes(info.package_dir.join(exe))?;
+        files.insert(format!("{BIN_DIR}/{exe}"), contents);
+    }
+    let layer = layer_tarball(&files, mtime)?;
+    let diff_id = sha256_digest(&layer);
+    let layer = gzip(&layer)?;
+
+    let mut blobs = Blobs::default();
+    let mut layers = vec![];
+    let mut annotations = info.annotations.clone();
+    annotations.insert(ANNOTATION_CREATED.to_owned(), created.clone());
+    let mut config = match base {
+        Some(base) => {
+            for (descriptor, contents) in &base.layers {
+                let media_type = registry::oci_layer_media_type(&descriptor.media_type);
+                layers.push(blobs.add(media_type, contents.clone()));
+            }
+            annotations.insert(ANNOTATION_BASE_NAME.to_owned(), base.reference.clone());
+            annotations.insert(
+                ANNOTATION_BASE_DIGEST.to_owned(),
+                base.manifest_digest.clone(),
+            );
+            base.config.clone()
+        }
+        None => json!({}),
+    };
+    layers.push(blobs.add(MEDIA_TYPE_LAYER, layer));
+    image_config(&mut config, info, &annotations, &diff_id, &created);
+
+    let config = blobs.add_json(MEDIA_TYPE_CONFIG, &config);
+    let manifest = ImageManifest {
+        schema_version: 2,
+        media_type: Some(MEDIA_TYPE_MANIFEST.to_owned()),
+        config,
+        layers,
+        annotations,
+    };
+    let mut manifest = blobs.add_json(MEDIA_TYPE_MANIFEST, &manifest);
+    manifest.platform = Some(info.platform.clone());
+    manifest
+        .annotations
+        .insert(ANNOTATION_REF_NAME.to_owned(), info.tag.clone());
+
+    let index = ImageIndex::new(vec![manifest], BTreeMap::new());
+    write_layout(&info.file_path, &blobs, &index, mtime)
+}
+
+/// Build a multi-arch OCI image layout tarball out of the images for each platform
+pub(crate) fn build_oci_index(info: &OciIndexInfo) -> DistResult<()> {
+    info!("building a multi-arch OCI image: {}", info.file_path);
+    write_oci_index(info, source_date_epoch())
+}
+
+fn write_oci_index(info: &OciIndexInfo, mtime: u64) -> DistResult<()> {
+    let mut blobs = Blobs::default();
+    let mut manifests = vec![];
+    for path in &info.images {
+        if !path.exists() {
+            warn!(
+                "{path} hasn't been built, so it won't be part of {}",
+                info.file_path
+            );
+            continue;
+        }
+        let (index, image_blobs) = read_layout(path)?;
+        blobs.0.extend(image_blobs.0);
+        for mut manifest in index.manifests {
+            manifest.annotations.remove(ANNOTATION_REF_NAME);
+            manifests.push(manifest);
+        }
+    }
+    if manifests.is_empty() {
+        return Err(DistError::OciImageUnreadable {
+            path: info.images.first().cloned().unwrap_or_default(),
+        });
+    }
+
+    let mut annotations = info.annotations.clone();
+    annotations.insert(ANNOTATION_CREATED.to_owned(), rfc3339(mtime));
+    let mut image = blobs.add_json(MEDIA_TYPE_INDEX, &ImageIndex::new(manifests, annotations));
+    image
+        .annotations
+        .insert(ANNOTATION_REF_NAME.to_owned(), info.tag.clone());
+
+    let index = ImageIndex::new(vec![image], BTreeMap::new());
+    write_layout(&info.file_path, &blobs, &index, mtime)
+}
+
+/// Fill in the image config with everything about our layer
+///
+/// `config` is either empty or the config of the base image, in which case
+/// anything we don't need to change (like its Env) is kept.
+fn image_config(
+    config: &mut Value,
+    info: &OciImageInfo,
+    labels: &BTreeMap<String, String>,
+    diff_id: &str,
+    created: &str,
+) {
+    if !config.is_object() {
+        *config = json!({});
+    }
+    config["created"] = json!(created);
+    config["architecture"] = json!(info.platform.architecture);
+    config["os"] = json!(info.platform.os);
+    match &info.platform.variant {
+        Some(variant) => config["variant"] = json!(variant),
+        None => {
+            if let Some(config) = config.as_object_mut() {
+                config.remove("variant");
+            }
+        }
+    }
+
+    let runtime = &mut config["config"];
+    if !runtime.is_object() {
+        *runtime = json!({});
+    }
+    let has_path = runtime["Env"].as_array().is_some_and(|env| {
+        env.iter()
+            .any(|var| var.as_str().is_some_and(|var| var.starts_with("PATH=")))
+    });
+    if !has_path {
+        match runtime["Env"].as_array_mut() {
+            Some(env) => env.push(json!(DEFAULT_PATH)),
+            None => runtime["Env"] = json!([DEFAULT_PATH]),
+        }
+    }
+    if let Some(entrypoint) = &info.entrypoint {
+        runtime["Entrypoint"] = json!([format!("/{BIN_DIR}/{entrypoint}")]);
+        // The base image's Cmd would otherwise be passed to our binary as arguments
+        if let Some(runtime) = runtime.as_object_mut() {
+            runtime.remove("Cmd");
+        }
+    }
+    if !runtime["Labels"].is_object() {
+        runtime["Labels"] = json!({});
+    }
+    for (key, val) in labels {
+        runtime["Labels"][key] = json!(val);
+    }
+
+    let rootfs = &mut config["rootfs"];
+    if !rootfs["diff_ids"].is_array() {
+        *rootfs = json!({ "type": "layers", "diff_ids": [] });
+    }
+    if let Some(diff_ids) = rootfs["diff_ids"].as_array_mut() {
+        diff_ids.push(json!(diff_id));
+    }
+
+    let history = json!({
+        "created": created,
+        "created_by": "dist",
+        "comment": format!("{} {}", info.executables.join(" "), info.tag),
+    });
+    match config["history"].as_array_mut() {
+        Some(entries) => entries.push(history),
+        None => config["history"] = json!([history]),
+    }
+}
+
+/// Make an (uncompressed) layer tarball of the given files, and the directories they need
+fn layer_tarball(files: &BTreeMap<String, Vec<u8>>, mtime: u64) -> DistResult<Vec<u8>> {
+    let mut builder = tar::Builder::new(vec![]);
+    let mut dirs = vec![];
+    let mut dir = String::new();
+    for part in BIN_DIR.split('/') {
+        dir.push_str(part);
+        dir.push('/');
+        dirs.push(dir.clone());
+    }
+    for dir in dirs {
+        let mut header = tar_header(tar::EntryType::Directory, 0o755, 0, mtime);
+        builder.append_data(&mut header, dir, std::io::empty())?;
+    }
+    for (path, contents) in files {
+        let mut header = tar_header(tar::EntryType::Regular, 0o755, contents.len() as u64, mtime);
+        builder.append_data(&mut header, path, &contents[..])?;
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn gzip(contents: &[u8]) -> DistResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(contents)?;
+    Ok(encoder.finish()?)
+}
+
+fn tar_header(entry_type: tar::EntryType, mode: u32, size: u64, mtime: u64) -> tar::Header {
+    let mut header = tar::Header::new_ustar();
+    header.set_entry_type(entry_type);
+    header.set_mode(mode);
+    header.set_size(size);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(mtime);
+    header
+}
+
+/// Write an image layout tarball
+fn write_layout(path: &Utf8Path, blobs: &Blobs, index: &ImageIndex, mtime: u64) -> DistResult<()> {
+    let mut builder = tar::Builder::new(vec![]);
+    let file = |builder: &mut tar::Builder<Vec<u8>>, name: &str, contents: &[u8]| {
+        let mut header = tar_header(tar::EntryType::Regular, 0o644, contents.len() as u64, mtime);
+        builder.append_data(&mut header, name, contents)
+    };
+    file(
+        &mut builder,
+        "oci-layout",
+        br#"{"imageLayoutVersion":"1.0.0"}"#,
+    )?;
+    file(
+        &mut builder,
+        "index.json",
+        &serde_json::to_vec(index).expect("failed to serialize OCI json"),
+    )?;
+    for dir in ["blobs/", "blobs/sha256/"] {
+        let mut header = tar_header(tar::EntryType::Directory, 0o755, 0, mtime);
+        builder.append_data(&mut header, dir, std::io::empty())?;
+    }
+    for (digest, contents) in &blobs.0 {
+        let name = format!("blobs/{}", digest.replacen(':', "/", 1));
+        file(&mut builder, &name, contents)?;
+    }
+    std::fs::write(path, builder.into_inner()?)?;
+    Ok(())
+}
+
+/// Read back an image layout tarball we wrote
+fn read_layout(path: &Utf8Path) -> DistResult<(ImageIndex, Blobs)> {
+    let unreadable = || DistError::OciImageUnreadable {
+        path: path.to_owned(),
+    };
+    let mut archive = tar::Archive::new(std::fs::File::open(path)?);
+    let mut index = None;
+    let mut blobs = Blobs::default();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type() != tar::EntryType::Regular {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut contents = vec![];
+        entry.read_to_end(&mut contents)?;
+        if name == "index.json" {
+            index =
+                Some(serde_json::from_slice::<ImageIndex>(&contents).map_err(|_| unreadable())?);
+        } else if let Some(hex) = name.strip_prefix("blobs/sha256/") {
+            let digest = sha256_digest(&contents);
+            if digest != format!("sha256:{hex}") {
+                return Err(unreadable());
+            }
+            blobs.0.insert(digest, contents);
+        }
+    }
+    let index = index.ok_or_else(unreadable)?;
+    Ok((index, blobs))
+}
+
+/// Format a unix timestamp like OCI wants
+fn rfc3339(secs: u64) -> String {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Get the OCI platform of a target, if it's one we can make images for
+pub fn oci_platform(target: &TargetTripleRef) -> Option<OciPlatform> {
+    if !target.is_linux() {
+        return None;
+    }
+    let target = target.as_str();
+    let arch = target.split('-').next().unwrap_or_default();
+    let (architecture, variant) = match arch {
+        "x86_64" => ("amd64", None),
+        "aarch64" => ("arm64", None),
+        "i686" | "i586" => ("386", None),
+        "armv7" => ("arm", Some("v7")),
+        "arm" => ("arm", Some("v6")),
+        "riscv64gc" => ("riscv64", None),
+        "powerpc64le" => ("ppc64le", None),
+        "s390x" => ("s390x", None),
+        _ => return None,
+    };
+    Some(OciPlatform {
+        architecture: architecture.to_owned(),
+        os: "linux".to_owned(),
+        variant: variant.map(str::to_owned),
+    })
+}
+
+/// Make a version usable as an image tag (which can't contain `+`)
+pub fn oci_tag(version: &semver::Version) -> String {
+    version.to_string().replace('+', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(dir: &Utf8Path, arch: &str, exe: &str) -> OciImageInfo {
+        std::fs::write(dir.join(exe), format!("{exe} for {arch}")).unwrap();
+        OciImageInfo {
+            file_path: dir.join(format!("{arch}-oci.tar")),
+            package_dir: dir.to_owned(),
+            platform: oci_platform(TargetTripleRef::from_str(&format!(
+                "{arch}-unknown-linux-musl"
+            )))
+            .unwrap(),
+            executables: vec![exe.to_owned()],
+            entrypoint: Some(exe.to_owned()),
+            base_image: None,
+            tag: "1.0.0".to_owned(),
+            annotations: BTreeMap::from([(
+                "org.opencontainers.image.title".to_owned(),
+                "axolotlsay".to_owned(),
+            )]),
+        }
+    }
+
+    fn blob<T: serde::de::DeserializeOwned>(blobs: &Blobs, descriptor: &Descriptor) -> T {
+        let contents = &blobs.0[&descriptor.digest];
+        assert_eq!(contents.len() as u64, descriptor.size);
+        serde_json::from_slice(contents).unwrap()
+    }
+
+    #[test]
+    fn scratch_image_layout() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let info = image(&dir, "x86_64", "axolotlsay");
+        write_oci_image(&info, None, 0).unwrap();
+
+        let (index, blobs) = read_layout(&info.file_path).unwrap();
+        assert_eq!(index.manifests.len(), 1);
+        let descriptor = &index.manifests[0];
+        assert_eq!(descriptor.media_type, MEDIA_TYPE_MANIFEST);
+        assert_eq!(descriptor.annotations[ANNOTATION_REF_NAME], "1.0.0");
+        assert_eq!(
+            descriptor.platform.as_ref().unwrap().to_string(),
+            "linux/amd64"
+        );
+
+        let manifest: ImageManifest = blob(&blobs, descriptor);
+        assert_eq!(manifest.layers.len(), 1);
+        assert_eq!(manifest.layers[0].media_type, MEDIA_TYPE_LAYER);
+        assert!(blobs.0.contains_key(&manifest.layers[0].digest));
+
+        let config: Value = blob(&blobs, &manifest.config);
+        assert_eq!(config["architecture"], "amd64");
+        assert_eq!(config["created"], "1970-01-01T00:00:00Z");
+        assert_eq!(
+            config["config"]["Entrypoint"],
+            json!(["/usr/local/bin/axolotlsay"])
+        );
+        assert_eq!(
+            config["config"]["Labels"]["org.opencontainers.image.title"],
+            "axolotlsay"
+        );
+        assert_eq!(config["rootfs"]["diff_ids"].as_array().unwrap().len(), 1);
+
+        // Builds are reproducible
+        let first = std::fs::read(&info.file_path).unwrap();
+        write_oci_image(&info, None, 0).unwrap();
+        assert_eq!(first, std::fs::read(&info.file_path).unwrap());
+    }
+
+    #[test]
+    fn multi_arch_index() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let images = ["x86_64", "aarch64"].map(|arch| image(&dir, arch, "axolotlsay"));
+        for info in &images {
+            write_oci_image(info, None, 0).unwrap();
+        }
+        let info = OciIndexInfo {
+            file_path: dir.join("axolotlsay-oci.tar"),
+            images: images
+                .iter()
+                .map(|info| info.file_path.clone())
+                .chain([dir.join("missing-oci.tar")])
+                .collect(),
+            tag: "1.0.0".to_owned(),
+            annotations: BTreeMap::new(),
+        };
+        write_oci_index(&info, 0).unwrap();
+
+        let (index, blobs) = read_layout(&info.file_path).unwrap();
+        assert_eq!(index.manifests.len(), 1);
+        assert_eq!(index.manifests[0].media_type, MEDIA_TYPE_INDEX);
+        assert_eq!(index.manifests[0].annotations[ANNOTATION_REF_NAME], "1.0.0");
+
+        let inner: ImageIndex = blob(&blobs, &index.manifests[0]);
+        let platforms = inner
+            .manifests
+            .iter()
+            .map(|manifest| manifest.platform.as_ref().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(platforms, ["linux/amd64", "linux/arm64"]);
+        for manifest in &inner.manifests {
+            assert!(!manifest.annotations.contains_key(ANNOTATION_REF_NAME));
+            let manifest: ImageManifest = blob(&blobs, manifest);
+            assert!(blobs.0.contains_key(&manifest.layers[0].digest));
+        }
+    }
+
+    #[test]
+    fn platforms() {
+        let platform = |triple: &str| {
+            oci_platform(TargetTripleRef::from_str(triple)).map(|platform| platform.to_string())
+        };
+        assert_eq!(
+            platform("x86_64-unknown-linux-gnu").as_deref(),
+            Some("linux/amd64")
+        );
+        assert_eq!(
+            platform("aarch64-unknown-linux-musl").as_deref(),
+            Some("linux/arm64")
+        );
+        assert_eq!(
+            platform("armv7-unknown-linux-gnueabihf").as_deref(),
+            Some("linux/arm/v7")
+        );
+        assert_eq!(platform("x86_64-apple-darwin"), None);
+        assert_eq!(
+            oci_tag(&semver::Version::parse("1.2.3+build.5").unwrap()),
+            "1.2.3_build.5"
+        );
+    }
+}
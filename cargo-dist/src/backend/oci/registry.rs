@@ -0,0 +1,505 @@
+//! Fetching base images from container registries
+//!
+//! Base images are pinned by digest, so everything we fetch is content-addressed.
+//! That lets us keep it all in the tool cache forever (and use it in `--offline` builds),
+//! as long as it still hashes to the digest we asked for.
+
+use std::sync::Mutex;
+
+use axoasset::{reqwest, LocalAsset};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{sha256_digest, Descriptor, ImageIndex, ImageManifest, OciPlatform, MEDIA_TYPE_LAYER};
+use crate::{
+    errors::DistResult,
+    net::{create_registry_client, with_retries},
+    DistError, DistGraph,
+};
+
+/// Subdirectory of the tool cache blobs are kept in
+const CACHE_SUBDIR: &str = "oci/blobs/sha256";
+
+/// What we'll accept when asking for a manifest
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// A reference to an image, pinned by digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImageReference {
+    /// The host of the registry's API
+    pub registry: String,
+    /// The repository in the registry (`library/debian`)
+    pub repository: String,
+    /// `sha256:<hex>` of the manifest (or index)
+    pub digest: String,
+}
+
+impl ImageReference {
+    /// Parse a reference like `gcr.io/distroless/cc-debian12@sha256:...`
+    ///
+    /// Docker Hub references can be abbreviated like they can for `docker pull`,
+    /// and any tag (`debian:12@sha256:...`) is ignored in favour of the digest.
+    pub fn parse(reference: &str) -> DistResult<Self> {
+        let invalid = || DistError::OciBaseImageInvalid {
+            reference: reference.to_owned(),
+        };
+        let (name, digest) = reference.split_once('@').ok_or_else(invalid)?;
+        let hex = digest.strip_prefix("sha256:").ok_or_else(invalid)?;
+        if hex.len() != 64 || !hex.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+            return Err(invalid());
+        }
+        // Drop the tag, being careful not to mistake a registry's port for one
+        let name = match name.rsplit_once(':') {
+            Some((rest, tag)) if !tag.contains('/') => rest,
+            _ => name,
+        };
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, rest)) if host.contains(['.', ':']) || host == "localhost" => {
+                (host.to_owned(), rest.to_owned())
+            }
+            Some(_) => ("docker.io".to_owned(), name.to_owned()),
+            None => ("docker.io".to_owned(), format!("library/{name}")),
+        };
+        if repository.is_empty() {
+            return Err(invalid());
+        }
+        // Docker Hub's API isn't served from the name everyone uses for it
+        let registry = if registry == "docker.io" {
+            "registry-1.docker.io".to_owned()
+        } else {
+            registry
+        };
+        Ok(Self {
+            registry,
+            repository,
+            digest: digest.to_owned(),
+        })
+    }
+
+    fn url(&self, kind: BlobKind, digest: &str) -> String {
+        let kind = match kind {
+            BlobKind::Manifest => "manifests",
+            BlobKind::Blob => "blobs",
+        };
+        format!(
+            "https://{}/v2/{}/{kind}/{digest}",
+            self.registry, self.repository
+        )
+    }
+}
+
+/// The parts of a base image we need to build on top of it
+#[derive(Debug, Clone)]
+pub(crate) struct BaseImage {
+    /// The reference the base image was configured with
+    pub reference: String,
+    /// The digest of the manifest for our platform
+    pub manifest_digest: String,
+    /// The image's config
+    pub config: Value,
+    /// The image's layers, from the bottom up
+    pub layers: Vec<(Descriptor, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum BlobKind {
+    Manifest,
+    Blob,
+}
+
+/// Get everything in a base image for the given platform, from the tool cache or its registry
+pub(crate) fn fetch_base_image(
+    dist: &DistGraph,
+    reference: &str,
+    platform: &OciPlatform,
+) -> DistResult<BaseImage> {
+    let image = ImageReference::parse(reference)?;
+    let mut fetcher = Fetcher {
+        dist,
+        reference,
+        image: &image,
+        client: None,
+    };
+
+    let mut manifest_digest = image.digest.clone();
+    let mut contents = fetcher.fetch(BlobKind::Manifest, &manifest_digest)?;
+    let is_index = serde_json::from_slice::<Value>(&contents)
+        .ok()
+        .is_some_and(|manifest| manifest.get("manifests").is_some());
+    if is_index {
+        let index: ImageIndex =
+            parse_json(&image.url(BlobKind::Manifest, &manifest_digest), &contents)?;
+        manifest_digest = index
+            .manifests
+            .iter()
+            .find(|manifest| {
+                manifest
+                    .platform
+                    .as_ref()
+                    .is_some_and(|candidate| platform_matches(candidate, platform))
+            })
+            .map(|manifest| manifest.digest.clone())
+            .ok_or_else(|| DistError::OciBaseImageNoPlatform {
+                reference: reference.to_owned(),
+                platform: platform.to_string(),
+            })?;
+        contents = fetcher.fetch(BlobKind::Manifest, &manifest_digest)?;
+    }
+    let manifest: ImageManifest =
+        parse_json(&image.url(BlobKind::Manifest, &manifest_digest), &contents)?;
+
+    let config_url = image.url(BlobKind::Blob, &manifest.config.digest);
+    let config = parse_json(
+        &config_url,
+        &fetcher.fetch(BlobKind::Blob, &manifest.config.digest)?,
+    )?;
+    let mut layers = vec![];
+    for layer in manifest.layers {
+        let contents = fetcher.fetch(BlobKind::Blob, &layer.digest)?;
+        layers.push((layer, contents));
+    }
+
+    Ok(BaseImage {
+        reference: reference.to_owned(),
+        manifest_digest,
+        config,
+        layers,
+    })
+}
+
+/// Whether an image in an index is for the platform we want
+///
+/// A missing variant on our side matches any variant (arm64 images are often `v8`).
+fn platform_matches(candidate: &OciPlatform, wanted: &OciPlatform) -> bool {
+    candidate.os == wanted.os
+        && candidate.architecture == wanted.architecture
+        && (wanted.variant.is_none() || candidate.variant == wanted.variant)
+}
+
+/// Get the OCI media type for a layer, which may be a Docker one if the base image is old
+pub(crate) fn oci_layer_media_type(media_type: &str) -> &str {
+    match media_type {
+        "application/vnd.docker.image.rootfs.diff.tar.gzip" => MEDIA_TYPE_LAYER,
+        other => other,
+    }
+}
+
+fn parse_json<T: for<'de> Deserialize<'de>>(url: &str, contents: &[u8]) -> DistResult<T> {
+    serde_json::from_slice(contents).map_err(|e| DistError::OciRegistryRequestFailed {
+        url: url.to_owned(),
+        details: e.to_string(),
+    })
+}
+
+/// Fetches the blobs of one image, going through the tool cache
+struct Fetcher<'a> {
+    dist: &'a DistGraph,
+    reference: &'a str,
+    image: &'a ImageReference,
+    /// Only created once we actually need to touch the network
+    client: Option<RegistryClient>,
+}
+
+impl Fetcher<'_> {
+    fn fetch(&mut self, kind: BlobKind, digest: &str) -> DistResult<Vec<u8>> {
+        let url = self.image.url(kind, digest);
+        let hex =
+            digest
+                .strip_prefix("sha256:")
+                .ok_or_else(|| DistError::OciRegistryRequestFailed {
+                    url: url.clone(),
+                    details: format!("unsupported digest algorithm: {digest}"),
+                })?;
+        let cache_dir = self.dist.cache_dir.join(CACHE_SUBDIR);
+        let cached = cache_dir.join(hex);
+        if cached.exists() {
+            let contents = LocalAsset::load_bytes(&cached)?;
+            if sha256_digest(&contents) == digest {
+                return Ok(contents);
+            }
+        }
+        if self.dist.client_settings.offline {
+            return Err(DistError::OfflineCacheMiss {
+                tool: format!("base image {}", self.reference),
+                path: cached,
+            });
+        }
+
+        let client = match &mut self.client {
+            Some(client) => client,
+            None => self
+                .client
+                .insert(create_registry_client(&self.dist.client_settings)?),
+        };
+        let accept = match kind {
+            BlobKind::Manifest => MANIFEST_ACCEPT,
+            BlobKind::Blob => "*/*",
+        };
+        let contents = tokio::runtime::Handle::current().block_on(with_retries(
+            &self.dist.client_settings,
+            &format!("fetching {url}"),
+            || client.get(&url, accept),
+        ))?;
+        let actual = sha256_digest(&contents);
+        if actual != digest {
+            return Err(DistError::OciDigestMismatch {
+                url,
+                expected: digest.to_owned(),
+                actual,
+            });
+        }
+
+        // Write next to the final path so a failed write can't leave a broken blob behind
+        LocalAsset::create_dir_all(&cache_dir)?;
+        let partial = cached.with_extension("part");
+        std::fs::write(&partial, &contents)?;
+        std::fs::rename(&partial, &cached)?;
+        Ok(contents)
+    }
+}
+
+/// A client for pulling from container registries (anonymously)
+pub(crate) struct RegistryClient {
+    client: reqwest::Client,
+    /// Bearer token from the registry's auth service, once we've needed one
+    token: Mutex<Option<String>>,
+}
+
+/// A response from a registry's auth service
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+impl RegistryClient {
+    /// Wrap an http client
+    pub fn new(client: reqwest::Client) -> Self {
+        Self {
+            client,
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Get a manifest or blob, authenticating if the registry asks us to
+    async fn get(&self, url: &str, accept: &str) -> DistResult<Vec<u8>> {
+        let mut response = self.send(url, accept).await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let challenge = response
+                .headers()
+                .get(reqwest::header::WWW_AUTHENTICATE)
+                .and_then(|challenge| challenge.to_str().ok())
+                .unwrap_or_default()
+                .to_owned();
+            let token = self.fetch_token(url, &challenge).await?;
+            *self.token.lock().unwrap() = Some(token);
+            response = self.send(url, accept).await?;
+        }
+        let status = response.status();
+        if !status.is_success() {
+            return Err(DistError::OciRegistryRequestFailed {
+                url: url.to_owned(),
+                details: status.to_string(),
+            });
+        }
+        let contents = response
+            .bytes()
+            .await
+            .map_err(|e| DistError::OciRegistryRequestFailed {
+                url: url.to_owned(),
+                details: e.to_string(),
+            })?;
+        Ok(contents.to_vec())
+    }
+
+    async fn send(&self, url: &str, accept: &str) -> DistResult<reqwest::Response> {
+        let mut request = self.client.get(url).header(reqwest::header::ACCEPT, accept);
+        let token = self.token.lock().unwrap().clone();
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request
+            .send()
+            .await
+            .map_err(|e| DistError::OciRegistryRequestFailed {
+                url: url.to_owned(),
+                details: e.to_string(),
+            })
+    }
+
+    /// Get an anonymous pull token, as described by a `WWW-Authenticate: Bearer ...` challenge
+    async fn fetch_token(&self, url: &str, challenge: &str) -> DistResult<String> {
+        let failed = |details: String| DistError::OciRegistryRequestFailed {
+            url: url.to_owned(),
+            details,
+        };
+        let params = parse_bearer_challenge(challenge).ok_or_else(|| {
+            failed(format!(
+                "the registry wants credentials dist doesn't have (only anonymous pulls are supported): {challenge}"
+            ))
+        })?;
+        let realm = params
+            .iter()
+            .find(|(key, _)| key == "realm")
+            .map(|(_, val)| val.clone())
+            .ok_or_else(|| failed(format!("no realm to get a token from: {challenge}")))?;
+        let query = params
+            .iter()
+            .filter(|(key, _)| key == "service" || key == "scope")
+            .collect::<Vec<_>>();
+        let response = self
+            .client
+            .get(&realm)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| failed(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(failed(format!(
+                "couldn't get a token: {}",
+                response.status()
+            )));
+        }
+        let token: TokenResponse = response.json().await.map_err(|e| failed(e.to_string()))?;
+        token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| failed("the registry didn't give us a token".to_owned()))
+    }
+}
+
+/// Parse the parameters out of a `Bearer realm="...",service="..."` challenge
+fn parse_bearer_challenge(challenge: &str) -> Option<Vec<(String, String)>> {
+    let (scheme, rest) = challenge.trim().split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    let mut params = vec![];
+    let mut chars = rest.trim().chars().peekable();
+    loop {
+        let key = chars
+            .by_ref()
+            .take_while(|c| *c != '=')
+            .collect::<String>()
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_owned();
+        if key.is_empty() {
+            break;
+        }
+        let mut val = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => val.extend(chars.next()),
+                    c => val.push(c),
+                }
+            }
+            // Skip to the next parameter
+            for c in chars.by_ref() {
+                if c == ',' {
+                    break;
+                }
+            }
+        } else {
+            val = chars.by_ref().take_while(|c| *c != ',').collect::<String>();
+        }
+        params.push((key, val.trim().to_owned()));
+    }
+    Some(params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIGEST: &str = "sha256:2e1c9a0e7a4a8f8a5d0d5c8a0f6c4f9a8c6e0b1f6d1f0c5c3a2c0a5e3f2b1c0d";
+
+    #[test]
+    fn parses_references() {
+        let parse = |name: &str| ImageReference::parse(&format!("{name}@{DIGEST}")).unwrap();
+        let image = |registry: &str, repository: &str| ImageReference {
+            registry: registry.to_owned(),
+            repository: repository.to_owned(),
+            digest: DIGEST.to_owned(),
+        };
+        assert_eq!(
+            parse("debian"),
+            image("registry-1.docker.io", "library/debian")
+        );
+        assert_eq!(
+            parse("debian:12-slim"),
+            image("registry-1.docker.io", "library/debian")
+        );
+        assert_eq!(
+            parse("axodotdev/base"),
+            image("registry-1.docker.io", "axodotdev/base")
+        );
+        assert_eq!(
+            parse("gcr.io/distroless/cc-debian12:nonroot"),
+            image("gcr.io", "distroless/cc-debian12")
+        );
+        assert_eq!(
+            parse("localhost:5000/base"),
+            image("localhost:5000", "base")
+        );
+
+        for bad in ["debian", "debian:12", "debian@sha256:abc", "debian@md5:abc"] {
+            assert!(ImageReference::parse(bad).is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn parses_challenges() {
+        let params = parse_bearer_challenge(
+            r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:library/debian:pull,push""#,
+        )
+        .unwrap();
+        assert_eq!(
+            params,
+            vec![
+                (
+                    "realm".to_owned(),
+                    "https://auth.docker.io/token".to_owned()
+                ),
+                ("service".to_owned(), "registry.docker.io".to_owned()),
+                (
+                    "scope".to_owned(),
+                    "repository:library/debian:pull,push".to_owned()
+                ),
+            ]
+        );
+        assert_eq!(parse_bearer_challenge(r#"Basic realm="registry""#), None);
+    }
+
+    #[test]
+    fn matches_platforms() {
+        let platform = |architecture: &str, variant: Option<&str>| OciPlatform {
+            architecture: architecture.to_owned(),
+            os: "linux".to_owned(),
+            variant: variant.map(str::to_owned),
+        };
+        assert!(platform_matches(
+            &platform("arm64", Some("v8")),
+            &platform("arm64", None)
+        ));
+        assert!(platform_matches(
+            &platform("arm", Some("v7")),
+            &platform("arm", Some("v7"))
+        ));
+        assert!(!platform_matches(
+            &platform("arm", Some("v6")),
+            &platform("arm", Some("v7"))
+        ));
+        assert!(!platform_matches(
+            &platform("amd64", None),
+            &platform("arm64", None)
+        ));
+    }
+}
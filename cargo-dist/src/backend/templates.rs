@@ -15,14 +15,28 @@ pub type TemplateId = &'static str;
 pub const TEMPLATE_INSTALLER_PS1: TemplateId = "installer/installer.ps1";
 /// Template key for installer.sh
 pub const TEMPLATE_INSTALLER_SH: TemplateId = "installer/installer.sh";
+/// Template key for uninstaller.ps1
+pub const TEMPLATE_UNINSTALLER_PS1: TemplateId = "installer/uninstaller.ps1";
+/// Template key for uninstaller.sh
+pub const TEMPLATE_UNINSTALLER_SH: TemplateId = "installer/uninstaller.sh";
 /// Template key for Homebrew formula
 pub const TEMPLATE_INSTALLER_RB: TemplateId = "installer/homebrew.rb";
+/// Template key for Homebrew cask
+pub const TEMPLATE_INSTALLER_CASK_RB: TemplateId = "installer/homebrew-cask.rb";
 /// Template key for the npm installer dir
 pub const TEMPLATE_INSTALLER_NPM: TemplateId = "installer/npm";
 /// Template key for the npm installer dir
 pub const TEMPLATE_INSTALLER_NPM_RUN_JS: TemplateId = "installer/npm/run.js";
+/// Template key for the npm installer dir that depends on per-platform packages
+pub const TEMPLATE_INSTALLER_NPM_PLATFORMS: TemplateId = "installer/npm-platforms";
+/// Template key for the run.js of the npm installer that depends on per-platform packages
+pub const TEMPLATE_INSTALLER_NPM_PLATFORMS_RUN_JS: TemplateId = "installer/npm-platforms/run.js";
+/// Template key for the Nix installer dir
+pub const TEMPLATE_INSTALLER_NIX: TemplateId = "installer/nix";
 /// Template key for the github ci.yml
 pub const TEMPLATE_CI_GITHUB: TemplateId = "ci/github/release.yml";
+/// Template key for the .gitlab-ci.yml
+pub const TEMPLATE_CI_GITLAB: TemplateId = "ci/gitlab/gitlab-ci.yml";
 
 /// ID used to look up an environment in [`Templates::envs`][]
 type EnvId = &'static str;
@@ -379,12 +393,29 @@ mod test {
 
         templates.get_template_file(TEMPLATE_INSTALLER_SH).unwrap();
         templates.get_template_file(TEMPLATE_INSTALLER_RB).unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_CASK_RB)
+            .unwrap();
         templates.get_template_file(TEMPLATE_INSTALLER_PS1).unwrap();
+        templates
+            .get_template_file(TEMPLATE_UNINSTALLER_SH)
+            .unwrap();
+        templates
+            .get_template_file(TEMPLATE_UNINSTALLER_PS1)
+            .unwrap();
         templates.get_template_dir(TEMPLATE_INSTALLER_NPM).unwrap();
         templates
             .get_template_file(TEMPLATE_INSTALLER_NPM_RUN_JS)
             .unwrap();
+        templates
+            .get_template_dir(TEMPLATE_INSTALLER_NPM_PLATFORMS)
+            .unwrap();
+        templates
+            .get_template_file(TEMPLATE_INSTALLER_NPM_PLATFORMS_RUN_JS)
+            .unwrap();
+        templates.get_template_dir(TEMPLATE_INSTALLER_NIX).unwrap();
 
         templates.get_template_file(TEMPLATE_CI_GITHUB).unwrap();
+        templates.get_template_file(TEMPLATE_CI_GITLAB).unwrap();
     }
 }
@@ -0,0 +1,203 @@
+//! The tool cache
+//!
+//! Tools that dist downloads while building (axoupdater, CodeSignTool, base images...) are kept
+//! here, so that builds can run in `--offline` mode. `dist cache fill` pre-downloads
+//! everything the workspace's builds will need.
+
+use axoasset::LocalAsset;
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_dist_schema::TargetTripleRef;
+
+use crate::{
+    backend::oci::{registry::fetch_base_image, OciImageImpl},
+    config::Config,
+    errors::DistResult,
+    gather_work,
+    net::with_retries,
+    sign, BuildStep, DistError, DistGraph, AXOUPDATER_ASSET_ROOT,
+};
+
+/// Environment variable to put the tool cache somewhere else
+pub const CACHE_DIR_VAR: &str = "DIST_CACHE_DIR";
+
+/// Subdirectory of the cache prebuilt axoupdaters are kept in
+const UPDATER_SUBDIR: &str = "axoupdater";
+
+/// Get the directory of the tool cache
+pub fn cache_dir(target_dir: &Utf8Path) -> Utf8PathBuf {
+    match std::env::var(CACHE_DIR_VAR) {
+        Ok(dir) if !dir.is_empty() => Utf8PathBuf::from(dir),
+        _ => target_dir.join("dist-cache"),
+    }
+}
+
+/// Get the archive of a prebuilt axoupdater for the given target, downloading it
+/// into the cache if needed (and allowed)
+///
+/// Returns None if there's no prebuilt axoupdater for the target.
+pub(crate) fn fetch_updater_archive(
+    dist: &DistGraph,
+    target: &TargetTripleRef,
+) -> DistResult<Option<Utf8PathBuf>> {
+    let ext = if target.is_windows() {
+        ".zip"
+    } else {
+        ".tar.xz"
+    };
+    let archive_name = format!("axoupdater-cli-{target}{ext}");
+    let cached = dist.cache_dir.join(UPDATER_SUBDIR).join(&archive_name);
+
+    if dist.client_settings.offline {
+        if cached.exists() {
+            return Ok(Some(cached));
+        }
+        return Err(DistError::OfflineCacheMiss {
+            tool: format!("axoupdater for {target}"),
+            path: cached,
+        });
+    }
+
+    let expected_url = format!("{AXOUPDATER_ASSET_ROOT}/{archive_name}");
+    let handle = tokio::runtime::Handle::current();
+    let resp = handle
+        .block_on(with_retries(
+            &dist.client_settings,
+            "checking for a prebuilt axoupdater",
+            || dist.axoclient.head(&expected_url),
+        ))
+        .map_err(|_| DistError::AxoupdaterReleaseCheckFailed {})?;
+
+    // If we got a 404, there's no asset, so it will have to be built from source
+    if resp.status() == axoasset::reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    // Some unexpected result that wasn't 200 or 404
+    if !resp.status().is_success() {
+        return Err(DistError::AxoupdaterReleaseCheckFailed {});
+    }
+
+    // Always refresh the cache when we're online, the url always points at the latest release.
+    // Download next to the final path so a failed download can't leave a broken archive behind.
+    let partial = cached.with_extension("part");
+    LocalAsset::create_dir_all(dist.cache_dir.join(UPDATER_SUBDIR))?;
+    handle.block_on(with_retries(
+        &dist.client_settings,
+        "fetching axoupdater",
+        || {
+            dist.axoclient
+                .load_and_write_to_file(&expected_url, &partial)
+        },
+    ))?;
+    std::fs::rename(&partial, &cached)?;
+
+    Ok(Some(cached))
+}
+
+/// Download every tool the workspace's builds will need into the tool cache
+pub fn do_cache_fill(cfg: &Config) -> DistResult<()> {
+    let (dist, _manifest) = gather_work(cfg)?;
+    dist.client_settings.check_online("dist cache fill")?;
+
+    let updaters = dist
+        .local_build_steps
+        .iter()
+        .chain(&dist.global_build_steps)
+        .filter_map(|step| match step {
+            BuildStep::Updater(updater) => Some(&updater.target_triple),
+            _ => None,
+        });
+    for target in updaters {
+        if fetch_updater_archive(&dist, target)?.is_some() {
+            eprintln!("cached axoupdater for {target}");
+        } else {
+            eprintln!("there's no prebuilt axoupdater for {target}, it will be built from source (which needs network access)");
+        }
+    }
+
+    let base_images = dist
+        .local_build_steps
+        .iter()
+        .chain(&dist.global_build_steps)
+        .filter_map(|step| match step {
+            BuildStep::GenerateOciImage(OciImageImpl::Image(info)) => info
+                .base_image
+                .as_ref()
+                .map(|base_image| (base_image, &info.platform)),
+            _ => None,
+        });
+    for (base_image, platform) in base_images {
+        fetch_base_image(&dist, base_image, platform)?;
+        eprintln!("cached base image {base_image} for {platform}");
+    }
+
+    let signs_windows = dist.config.builds.ssldotcom_windows_sign.is_some()
+        && dist
+            .artifacts
+            .iter()
+            .flat_map(|artifact| &artifact.target_triples)
+            .any(|target| target.is_windows());
+    if signs_windows {
+        sign::fetch_code_sign_tool(&dist.axoclient, &dist.client_settings, &dist.cache_dir)?;
+        eprintln!("cached CodeSignTool");
+    }
+
+    eprintln!("tool cache filled: {}", dist.cache_dir);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::ArtifactMode, tests::mock::*, DistGraphBuilder};
+
+    fn offline_graph(cache_dir: &Utf8Path) -> DistGraph {
+        let mut workspaces = workspace_just_axo();
+        let mut graph = DistGraphBuilder::new(
+            "a".to_owned(),
+            mock_tools(),
+            &mut workspaces,
+            ArtifactMode::All,
+            true,
+            true,
+            false,
+        )
+        .unwrap();
+        graph.inner.cache_dir = cache_dir.to_owned();
+        graph.inner.client_settings.offline = true;
+        graph.inner
+    }
+
+    #[test]
+    fn offline_updater_cache_hit() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let cache_dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let dist = offline_graph(&cache_dir);
+        let target = TargetTripleRef::from_str("x86_64-unknown-linux-gnu");
+        let cached = cache_dir
+            .join(UPDATER_SUBDIR)
+            .join("axoupdater-cli-x86_64-unknown-linux-gnu.tar.xz");
+        LocalAsset::write_new_all("not really an archive", &cached).unwrap();
+
+        assert_eq!(fetch_updater_archive(&dist, target).unwrap(), Some(cached));
+    }
+
+    #[test]
+    fn offline_updater_cache_miss() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let cache_dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let dist = offline_graph(&cache_dir);
+        let target = TargetTripleRef::from_str("x86_64-pc-windows-msvc");
+
+        let err = fetch_updater_archive(&dist, target).unwrap_err();
+        let DistError::OfflineCacheMiss { tool, path } = err else {
+            panic!("expected a cache miss, got {err:?}");
+        };
+        assert_eq!(tool, "axoupdater for x86_64-pc-windows-msvc");
+        assert_eq!(
+            path,
+            cache_dir
+                .join(UPDATER_SUBDIR)
+                .join("axoupdater-cli-x86_64-pc-windows-msvc.zip")
+        );
+    }
+}
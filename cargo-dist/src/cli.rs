@@ -104,6 +104,13 @@ pub struct Cli {
     #[clap(long)]
     #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
     pub allow_dirty: bool,
+    /// Forbid all network access
+    ///
+    /// Any tools dist would download (like axoupdater or CodeSignTool) must already
+    /// be in the tool cache, see `dist cache fill`. Setting DIST_OFFLINE=1 does the same thing.
+    #[clap(long)]
+    #[clap(help_heading = "GLOBAL OPTIONS", global = true)]
+    pub offline: bool,
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -172,6 +179,38 @@ pub enum Commands {
     #[clap(disable_version_flag = true)]
     Host(HostArgs),
 
+    /// Download a published release and point its installers somewhere else
+    ///
+    /// Every file listed in the dist-manifest.json is downloaded and checked
+    /// against the checksums the manifest records. The shell and powershell
+    /// installers are then re-rendered to fetch from --base-url, so the whole
+    /// directory can be served from an internal server or airgapped network.
+    ///
+    /// The installers are rendered with the dist config of the current project,
+    /// so this has to be run in a checkout of the project that made the release.
+    #[clap(disable_version_flag = true)]
+    Mirror(MirrorArgs),
+
+    /// Manage the cache of tools dist downloads
+    ///
+    /// The cache lives in target/dist-cache, unless DIST_CACHE_DIR says otherwise.
+    #[clap(disable_version_flag = true)]
+    #[clap(subcommand)]
+    Cache(CacheCommands),
+
+    /// Publish the released packages to package registries
+    #[clap(disable_version_flag = true)]
+    #[clap(subcommand)]
+    Publish(PublishCommands),
+
+    /// Generate WinGet manifests for the release
+    ///
+    /// The manifests are linted for values WinGet would reject, and written out in the
+    /// same layout as the manifests dir of winget-pkgs. Checksums are taken from the
+    /// Windows artifacts in target/distrib, so build those first.
+    #[clap(disable_version_flag = true)]
+    Winget(WingetArgs),
+
     /// Performs a self-update, if a new version is available, and then 'init'
     #[clap(disable_version_flag = true)]
     Selfupdate(UpdateArgs),
@@ -376,6 +415,10 @@ pub struct UpdateArgs {
 pub enum CiStyle {
     /// Generate github CI that uploads to github releases
     Github,
+    /// Generate a GitLab CI pipeline
+    Gitlab,
+    /// Generate Forgejo Actions that upload to Gitea releases
+    Forgejo,
 }
 
 impl CiStyle {
@@ -383,6 +426,8 @@ impl CiStyle {
     pub fn to_lib(self) -> cargo_dist::config::CiStyle {
         match self {
             CiStyle::Github => cargo_dist::config::CiStyle::Github,
+            CiStyle::Gitlab => cargo_dist::config::CiStyle::Gitlab,
+            CiStyle::Forgejo => cargo_dist::config::CiStyle::Forgejo,
         }
     }
 }
@@ -398,8 +443,22 @@ pub enum InstallerStyle {
     Npm,
     /// Generates a Homebrew formula
     Homebrew,
+    /// Generates a Scoop manifest
+    Scoop,
+    /// Generates WinGet manifests
+    Winget,
     /// Generates an msi for each windows platform
     Msi,
+    /// Generates a .deb for each linux-gnu platform
+    Deb,
+    /// Generates an .rpm for each linux platform
+    Rpm,
+    /// Generates an Arch Linux PKGBUILD
+    Aur,
+    /// Generates a Nix flake
+    Nix,
+    /// Generates a Python wheel for each platform
+    Pypi,
 }
 
 impl InstallerStyle {
@@ -410,7 +469,14 @@ impl InstallerStyle {
             InstallerStyle::Powershell => cargo_dist::config::InstallerStyle::Powershell,
             InstallerStyle::Npm => cargo_dist::config::InstallerStyle::Npm,
             InstallerStyle::Homebrew => cargo_dist::config::InstallerStyle::Homebrew,
+            InstallerStyle::Scoop => cargo_dist::config::InstallerStyle::Scoop,
+            InstallerStyle::Winget => cargo_dist::config::InstallerStyle::Winget,
             InstallerStyle::Msi => cargo_dist::config::InstallerStyle::Msi,
+            InstallerStyle::Deb => cargo_dist::config::InstallerStyle::Deb,
+            InstallerStyle::Rpm => cargo_dist::config::InstallerStyle::Rpm,
+            InstallerStyle::Aur => cargo_dist::config::InstallerStyle::Aur,
+            InstallerStyle::Nix => cargo_dist::config::InstallerStyle::Nix,
+            InstallerStyle::Pypi => cargo_dist::config::InstallerStyle::Pypi,
         }
     }
 }
@@ -431,6 +497,59 @@ pub enum OutputFormat {
     Json,
 }
 
+#[derive(Subcommand, Clone, Debug)]
+pub enum CacheCommands {
+    /// Download every tool your builds will need into the tool cache
+    ///
+    /// Run this on a machine with network access, then copy the cache to
+    /// machines that need to build with --offline.
+    #[clap(disable_version_flag = true)]
+    Fill(CacheFillArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct CacheFillArgs {}
+
+#[derive(Subcommand, Clone, Debug)]
+pub enum PublishCommands {
+    /// Run `cargo publish` for the released packages
+    ///
+    /// The announcement's packages are published along with any workspace crates
+    /// they depend on, dependencies first. Versions the registry already has are
+    /// skipped, as are crates with `publish = false`. The registry token is taken
+    /// from CARGO_REGISTRY_TOKEN like cargo normally would.
+    #[clap(disable_version_flag = true)]
+    Crates(PublishCratesArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct PublishCratesArgs {
+    /// The cargo registry to publish to (defaults to crates.io)
+    #[clap(long)]
+    pub registry: Option<String>,
+    /// The sparse index of the registry, to check for already-published versions
+    ///
+    /// Defaults to crates.io's index, or CARGO_REGISTRIES_<NAME>_INDEX with --registry.
+    #[clap(long)]
+    pub index: Option<String>,
+    /// Only report what would be published
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct WingetArgs {
+    /// Check that the manifests in the output dir are up to date, without writing them
+    #[clap(long)]
+    #[clap(default_value_t = false)]
+    pub check: bool,
+    /// Where to write the manifests (defaults to target/distrib/winget-manifests)
+    ///
+    /// Pass the manifests dir of a winget-pkgs checkout to add the new version there.
+    #[clap(long)]
+    pub output_dir: Option<Utf8PathBuf>,
+}
+
 #[derive(Args, Clone, Debug)]
 pub struct ManifestSchemaArgs {
     /// Write the manifest schema to the named file instead of stdout
@@ -445,6 +564,18 @@ pub struct HostArgs {
     pub steps: Vec<HostStyle>,
 }
 
+#[derive(Args, Clone, Debug)]
+pub struct MirrorArgs {
+    /// The dist-manifest.json of the release to mirror (a URL or a local path)
+    pub manifest: String,
+    /// The URL the mirrored files will be served from
+    #[clap(long)]
+    pub base_url: String,
+    /// The directory to download the release into
+    #[clap(long, default_value = "mirror")]
+    pub output_dir: Utf8PathBuf,
+}
+
 impl HostStyle {
     /// Convert the application version of this enum to the library version
     pub fn to_lib(self) -> cargo_dist::config::HostStyle {
@@ -479,6 +610,9 @@ impl HostingStyle {
         match self {
             HostingStyle::Github => cargo_dist::config::HostingStyle::Github,
             HostingStyle::Axodotdev => cargo_dist::config::HostingStyle::Axodotdev,
+            HostingStyle::Gitea => cargo_dist::config::HostingStyle::Gitea,
+            HostingStyle::S3 => cargo_dist::config::HostingStyle::S3,
+            HostingStyle::Mirror => cargo_dist::config::HostingStyle::Mirror,
         }
     }
 }
@@ -490,6 +624,12 @@ pub enum HostingStyle {
     Github,
     /// Host on Axo Releases ("Abyss")
     Axodotdev,
+    /// Host on Gitea (or Forgejo) Releases
+    Gitea,
+    /// Host in an S3-compatible bucket
+    S3,
+    /// Publish into a static directory tree (e.g. for an internal mirror)
+    Mirror,
 }
 
 impl std::fmt::Display for HostingStyle {
@@ -497,6 +637,9 @@ impl std::fmt::Display for HostingStyle {
         let string = match self {
             HostingStyle::Github => "github",
             HostingStyle::Axodotdev => "axodotdev",
+            HostingStyle::Gitea => "gitea",
+            HostingStyle::S3 => "s3",
+            HostingStyle::Mirror => "mirror",
         };
         string.fmt(f)
     }
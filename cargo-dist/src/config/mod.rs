@@ -53,6 +53,8 @@ pub struct Config {
     pub no_local_paths: bool,
     /// If true, override allow-dirty in the config and ignore all dirtyness
     pub allow_all_dirty: bool,
+    /// If true, forbid all network access
+    pub offline: bool,
     /// Target triples we want to build for
     pub targets: Vec<TargetTriple>,
     /// CI kinds we want to support
@@ -97,12 +99,19 @@ impl std::fmt::Display for ArtifactMode {
 pub enum CiStyle {
     /// Generate Github CI
     Github,
+    /// Generate GitLab CI
+    Gitlab,
+    /// Generate Forgejo (or Gitea) Actions
+    Forgejo,
 }
 impl CiStyle {
     /// If the CI provider provides a native release hosting system, get it
     pub(crate) fn native_hosting(&self) -> Option<HostingStyle> {
         match self {
             CiStyle::Github => Some(HostingStyle::Github),
+            // GitLab CI just runs the pipeline, hosting must be picked explicitly
+            CiStyle::Gitlab => None,
+            CiStyle::Forgejo => Some(HostingStyle::Gitea),
         }
     }
 }
@@ -111,6 +120,8 @@ impl std::fmt::Display for CiStyle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match self {
             CiStyle::Github => "github",
+            CiStyle::Gitlab => "gitlab",
+            CiStyle::Forgejo => "forgejo",
         };
         string.fmt(f)
     }
@@ -121,6 +132,8 @@ impl std::str::FromStr for CiStyle {
     fn from_str(val: &str) -> DistResult<Self> {
         let res = match val {
             "github" => CiStyle::Github,
+            "gitlab" => CiStyle::Gitlab,
+            "forgejo" => CiStyle::Forgejo,
             s => {
                 return Err(DistError::UnrecognizedCiStyle {
                     style: s.to_string(),
@@ -184,6 +197,20 @@ pub enum InstallerStyle {
     Msi,
     /// Generate an Apple pkg installer that embeds the binary
     Pkg,
+    /// Generate a Scoop manifest that fetches from [`cargo_dist_schema::Release::artifact_download_url`][]
+    Scoop,
+    /// Generate WinGet manifests that fetch from [`cargo_dist_schema::Release::artifact_download_url`][]
+    Winget,
+    /// Generate a Debian package that embeds the binary
+    Deb,
+    /// Generate an RPM package that embeds the binary
+    Rpm,
+    /// Generate an Arch Linux PKGBUILD that fetches from [`cargo_dist_schema::Release::artifact_download_url`][]
+    Aur,
+    /// Generate a Nix flake that fetches from [`cargo_dist_schema::Release::artifact_download_url`][]
+    Nix,
+    /// Generate a Python wheel that embeds the binary
+    Pypi,
 }
 
 impl std::fmt::Display for InstallerStyle {
@@ -195,6 +222,13 @@ impl std::fmt::Display for InstallerStyle {
             InstallerStyle::Homebrew => "homebrew",
             InstallerStyle::Msi => "msi",
             InstallerStyle::Pkg => "pkg",
+            InstallerStyle::Scoop => "scoop",
+            InstallerStyle::Winget => "winget",
+            InstallerStyle::Deb => "deb",
+            InstallerStyle::Rpm => "rpm",
+            InstallerStyle::Aur => "aur",
+            InstallerStyle::Nix => "nix",
+            InstallerStyle::Pypi => "pypi",
         };
         string.fmt(f)
     }
@@ -232,6 +266,12 @@ pub enum HostingStyle {
     Github,
     /// Host on Axo Releases ("Abyss")
     Axodotdev,
+    /// Host on Gitea (or Forgejo) Releases
+    Gitea,
+    /// Host in an S3-compatible bucket
+    S3,
+    /// Publish into a static directory tree (e.g. for an internal mirror)
+    Mirror,
 }
 
 impl std::fmt::Display for HostingStyle {
@@ -239,6 +279,9 @@ impl std::fmt::Display for HostingStyle {
         let string = match self {
             HostingStyle::Github => "github",
             HostingStyle::Axodotdev => "axodotdev",
+            HostingStyle::Gitea => "gitea",
+            HostingStyle::S3 => "s3",
+            HostingStyle::Mirror => "mirror",
         };
         string.fmt(f)
     }
@@ -250,6 +293,9 @@ impl std::str::FromStr for HostingStyle {
         let res = match val {
             "github" => HostingStyle::Github,
             "axodotdev" => HostingStyle::Axodotdev,
+            "gitea" => HostingStyle::Gitea,
+            "s3" => HostingStyle::S3,
+            "mirror" => HostingStyle::Mirror,
             s => {
                 return Err(DistError::UnrecognizedHostingStyle {
                     style: s.to_string(),
@@ -268,6 +314,14 @@ pub enum PublishStyle {
     Homebrew,
     /// Publish an npm pkg to the global npm registry
     Npm,
+    /// Publish the released crates to crates.io with `cargo publish`
+    Crates,
+    /// Publish a Scoop manifest to a bucket repository
+    Scoop,
+    /// Publish a PKGBUILD to the Arch User Repository
+    Aur,
+    /// Publish wheels to PyPI
+    Pypi,
     /// User-supplied value
     User(String),
 }
@@ -281,6 +335,14 @@ impl std::str::FromStr for PublishStyle {
             Ok(Self::Homebrew)
         } else if s == "npm" {
             Ok(Self::Npm)
+        } else if s == "crates" {
+            Ok(Self::Crates)
+        } else if s == "scoop" {
+            Ok(Self::Scoop)
+        } else if s == "aur" {
+            Ok(Self::Aur)
+        } else if s == "pypi" {
+            Ok(Self::Pypi)
         } else {
             Err(DistError::UnrecognizedJobStyle {
                 style: s.to_owned(),
@@ -306,6 +368,10 @@ impl std::fmt::Display for PublishStyle {
         match self {
             PublishStyle::Homebrew => write!(f, "homebrew"),
             PublishStyle::Npm => write!(f, "npm"),
+            PublishStyle::Crates => write!(f, "crates"),
+            PublishStyle::Scoop => write!(f, "scoop"),
+            PublishStyle::Aur => write!(f, "aur"),
+            PublishStyle::Pypi => write!(f, "pypi"),
             PublishStyle::User(s) => write!(f, "./{s}"),
         }
     }
@@ -730,6 +796,37 @@ impl std::fmt::Display for HostStyle {
     }
 }
 
+/// Arguments to `dist mirror`
+#[derive(Clone, Debug)]
+pub struct MirrorArgs {
+    /// The dist-manifest.json of the release to mirror (a URL or a local path)
+    pub manifest: String,
+    /// The URL the mirrored files will be served from
+    pub base_url: String,
+    /// The directory to download the release into
+    pub output_dir: Utf8PathBuf,
+}
+
+/// Arguments to `dist winget`
+#[derive(Clone, Debug)]
+pub struct WingetArgs {
+    /// Check the manifests already in the output dir are up to date, without writing them
+    pub check: bool,
+    /// The directory to write the manifests to
+    pub output_dir: Option<Utf8PathBuf>,
+}
+
+/// Arguments to `dist publish crates`
+#[derive(Clone, Debug)]
+pub struct PublishCratesArgs {
+    /// The cargo registry to publish to (crates.io if None)
+    pub registry: Option<String>,
+    /// The sparse index of the registry, for checking what's already published
+    pub index: Option<String>,
+    /// Only report what would be published
+    pub dry_run: bool,
+}
+
 /// Configuration for Mac .pkg installers
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
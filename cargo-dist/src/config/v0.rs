@@ -94,10 +94,25 @@ pub struct DistMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub install_success_msg: Option<String>,
 
+    /// A minisign public key that the shell and powershell installers should
+    /// check each archive's detached signature (`<archive>.minisig`) against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minisign_public_key: Option<String>,
+
     /// A Homebrew tap to push the Homebrew formula to, if built
     pub tap: Option<String>,
     /// Customize the name of the Homebrew formula
     pub formula: Option<String>,
+    /// A Scoop bucket (GitHub repo) to push the Scoop manifest to, if built
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scoop_bucket: Option<String>,
+    /// Customize the WinGet PackageIdentifier (Publisher.Package)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winget_package_identifier: Option<String>,
+    /// The oldest glibc each linux-gnu target's binaries run on, like "2.17",
+    /// which picks the manylinux tag of its Python wheel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pypi_manylinux: Option<SortedMap<TargetTriple, String>>,
 
     /// A set of packages to install before building
     #[serde(rename = "dependencies")]
@@ -158,6 +173,16 @@ pub struct DistMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub npm_scope: Option<String>,
 
+    /// Whether to publish an npm package for each platform with the binaries inside
+    /// (defaults false, which downloads the binaries when the package is installed)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub npm_platform_packages: Option<bool>,
+
+    /// Whether to also generate a shell installer that carries the archives with it,
+    /// so it works without network access
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shell_offline: Option<bool>,
+
     /// Which checksum algorithm to use, from: sha256, sha512, sha3-256,
     /// sha3-512, blake2s, blake2b, or false (to disable checksums)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -330,6 +355,18 @@ pub struct DistMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub publish_prereleases: Option<bool>,
 
+    /// The npm registry to publish npm packages to
+    ///
+    /// (defaults to https://registry.npmjs.org)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub npm_registry: Option<String>,
+
+    /// The name of the secret (or CI variable) with the token for publishing to npm
+    ///
+    /// (defaults to NPM_TOKEN)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub npm_token_env_var: Option<String>,
+
     /// Always regard releases as stable
     ///
     /// (defaults to false)
@@ -385,10 +422,92 @@ pub struct DistMetadata {
     #[serde(default, with = "opt_string_or_vec")]
     pub hosting: Option<Vec<HostingStyle>>,
 
+    /// The bucket to upload to when using S3 hosting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_bucket: Option<String>,
+
+    /// A key prefix to put releases under when using S3 hosting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_prefix: Option<String>,
+
+    /// The S3 API endpoint, for S3-compatible services that aren't AWS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_endpoint: Option<String>,
+
+    /// The region of the S3 bucket (default "us-east-1")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_region: Option<String>,
+
+    /// The URL installers should download S3-hosted files from, if not the bucket itself
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_public_url: Option<String>,
+
+    /// Whether to also copy stable releases to a `latest/` prefix of the S3 bucket
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3_latest_alias: Option<bool>,
+
+    /// The directory to publish a static mirror of releases into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror_root: Option<Utf8PathBuf>,
+
+    /// The URL the static mirror of releases will be served from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror_url: Option<String>,
+
+    /// The base URLs the shell, powershell and npm installers should try to download
+    /// artifacts from, in order (either the name of a host, or a URL)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_urls: Option<Vec<String>>,
+
+    /// Whether to publish an apt repository of the .deb installers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apt_repo: Option<bool>,
+
+    /// Whether to publish a yum repository of the .rpm installers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yum_repo: Option<bool>,
+
+    /// The GPG key to sign apt/yum repository metadata with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_repo_gpg_key: Option<String>,
+
+    /// A proxy to send all of dist's http requests through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_proxy: Option<String>,
+
+    /// Extra CA certificates (PEM files) to trust when making http requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_ca_certs: Option<Vec<Utf8PathBuf>>,
+
+    /// How long to wait to connect to a server, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_connect_timeout: Option<u64>,
+
+    /// How long to wait for a server to send more data, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_read_timeout: Option<u64>,
+
+    /// How many times to retry a failed fetch (default 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_retries: Option<u32>,
+
+    /// How long to wait before the first retry of a failed fetch, in seconds (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_retry_backoff: Option<u64>,
+
     /// Any extra artifacts and their buildscripts
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra_artifacts: Option<Vec<ExtraArtifact>>,
 
+    /// Whether to build an OCI (container) image of the binaries for each linux target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oci_image: Option<bool>,
+
+    /// The image linux-gnu OCI images are built on top of, pinned by digest
+    /// (`registry/repo@sha256:...`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oci_base_image: Option<String>,
+
     /// Custom GitHub runners, mapped by triple target
     #[serde(skip_serializing_if = "Option::is_none")]
     pub github_custom_runners: Option<SortedMap<TargetTriple, GithubRunner>>,
@@ -410,6 +529,10 @@ pub struct DistMetadata {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub install_updater: Option<bool>,
 
+    /// Whether to generate uninstallers next to the shell/powershell installers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uninstallers: Option<bool>,
+
     /// Whether artifacts/installers for this app should be displayed in release bodies
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display: Option<bool>,
@@ -432,6 +555,18 @@ pub struct DistMetadata {
     #[serde(default)]
     pub github_build_setup: Option<String>,
 
+    /// Custom GitLab runner tags, mapped by triple target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gitlab_custom_runners: Option<SortedMap<TargetTriple, String>>,
+
+    /// Docker image to run GitLab CI jobs in on linux runners
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gitlab_image: Option<String>,
+
+    /// Custom Forgejo runner labels, mapped by triple target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forgejo_custom_runners: Option<SortedMap<TargetTriple, String>>,
+
     /// Configuration specific to Mac .pkg installers
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
@@ -445,15 +580,22 @@ impl DistMetadata {
         let DistMetadata {
             include,
             extra_artifacts,
+            http_ca_certs,
             // The rest of these don't include relative paths
+            oci_image: _,
+            oci_base_image: _,
             cargo_dist_version: _,
             rust_toolchain_version: _,
             dist: _,
             ci: _,
             installers: _,
             install_success_msg: _,
+            minisign_public_key: _,
             tap: _,
             formula: _,
+            scoop_bucket: _,
+            winget_package_identifier: _,
+            pypi_manylinux: _,
             system_dependencies: _,
             targets: _,
             auto_includes: _,
@@ -461,6 +603,8 @@ impl DistMetadata {
             unix_archive: _,
             npm_package: _,
             npm_scope: _,
+            npm_platform_packages: _,
+            shell_offline: _,
             checksum: _,
             precise_builds: _,
             fail_fast: _,
@@ -481,6 +625,8 @@ impl DistMetadata {
             publish_jobs: _,
             post_announce_jobs: _,
             publish_prereleases: _,
+            npm_registry: _,
+            npm_token_env_var: _,
             force_latest: _,
             create_release: _,
             pr_run_mode: _,
@@ -491,11 +637,29 @@ impl DistMetadata {
             github_attestations: _,
             msvc_crt_static: _,
             hosting: _,
+            s3_bucket: _,
+            s3_prefix: _,
+            s3_endpoint: _,
+            s3_region: _,
+            s3_public_url: _,
+            s3_latest_alias: _,
+            mirror_root: _,
+            mirror_url: _,
+            download_urls: _,
+            apt_repo: _,
+            yum_repo: _,
+            package_repo_gpg_key: _,
+            http_proxy: _,
+            http_connect_timeout: _,
+            http_read_timeout: _,
+            http_retries: _,
+            http_retry_backoff: _,
             github_custom_runners: _,
             github_custom_job_permissions: _,
             bin_aliases: _,
             tag_namespace: _,
             install_updater: _,
+            uninstallers: _,
             github_releases_repo: _,
             github_releases_submodule_path: _,
             display: _,
@@ -503,6 +667,9 @@ impl DistMetadata {
             package_libraries: _,
             install_libraries: _,
             github_build_setup: _,
+            gitlab_custom_runners: _,
+            gitlab_image: _,
+            forgejo_custom_runners: _,
             mac_pkg_config: _,
         } = self;
         if let Some(include) = include {
@@ -510,6 +677,11 @@ impl DistMetadata {
                 *include = base_path.join(&*include);
             }
         }
+        if let Some(http_ca_certs) = http_ca_certs {
+            for ca_cert in http_ca_certs {
+                *ca_cert = base_path.join(&*ca_cert);
+            }
+        }
         if let Some(extra_artifacts) = extra_artifacts {
             for extra in extra_artifacts {
                 // We update the working_dir to be relative to this file
@@ -546,8 +718,12 @@ impl DistMetadata {
             ci,
             installers,
             install_success_msg,
+            minisign_public_key,
             tap,
             formula,
+            scoop_bucket,
+            winget_package_identifier,
+            pypi_manylinux,
             system_dependencies,
             targets,
             include,
@@ -556,6 +732,8 @@ impl DistMetadata {
             unix_archive,
             npm_package,
             npm_scope,
+            npm_platform_packages,
+            shell_offline,
             checksum,
             precise_builds,
             merge_tasks,
@@ -576,6 +754,8 @@ impl DistMetadata {
             publish_jobs,
             post_announce_jobs,
             publish_prereleases,
+            npm_registry,
+            npm_token_env_var,
             force_latest,
             create_release,
             pr_run_mode,
@@ -586,12 +766,33 @@ impl DistMetadata {
             github_attestations,
             msvc_crt_static,
             hosting,
+            s3_bucket,
+            s3_prefix,
+            s3_endpoint,
+            s3_region,
+            s3_public_url,
+            s3_latest_alias,
+            mirror_root,
+            mirror_url,
+            download_urls,
+            apt_repo,
+            yum_repo,
+            package_repo_gpg_key,
+            http_proxy,
+            http_ca_certs,
+            http_connect_timeout,
+            http_read_timeout,
+            http_retries,
+            http_retry_backoff,
             extra_artifacts,
+            oci_image,
+            oci_base_image,
             github_custom_runners,
             github_custom_job_permissions,
             bin_aliases,
             tag_namespace,
             install_updater,
+            uninstallers,
             github_releases_repo,
             github_releases_submodule_path,
             display,
@@ -599,6 +800,9 @@ impl DistMetadata {
             package_libraries,
             install_libraries,
             github_build_setup,
+            gitlab_custom_runners,
+            gitlab_image,
+            forgejo_custom_runners,
             mac_pkg_config,
         } = self;
 
@@ -650,6 +854,12 @@ impl DistMetadata {
         if publish_prereleases.is_some() {
             warn!("package.metadata.dist.publish-prereleases is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
+        if npm_registry.is_some() {
+            warn!("package.metadata.dist.npm-registry is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if npm_token_env_var.is_some() {
+            warn!("package.metadata.dist.npm-token-env-var is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
         if force_latest.is_some() {
             warn!("package.metadata.dist.force-stable is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
@@ -671,6 +881,60 @@ impl DistMetadata {
         if hosting.is_some() {
             warn!("package.metadata.dist.hosting is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
+        if s3_bucket.is_some() {
+            warn!("package.metadata.dist.s3-bucket is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if s3_prefix.is_some() {
+            warn!("package.metadata.dist.s3-prefix is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if s3_endpoint.is_some() {
+            warn!("package.metadata.dist.s3-endpoint is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if s3_region.is_some() {
+            warn!("package.metadata.dist.s3-region is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if s3_public_url.is_some() {
+            warn!("package.metadata.dist.s3-public-url is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if s3_latest_alias.is_some() {
+            warn!("package.metadata.dist.s3-latest-alias is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if mirror_root.is_some() {
+            warn!("package.metadata.dist.mirror-root is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if mirror_url.is_some() {
+            warn!("package.metadata.dist.mirror-url is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if download_urls.is_some() {
+            warn!("package.metadata.dist.download-urls is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if apt_repo.is_some() {
+            warn!("package.metadata.dist.apt-repo is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if yum_repo.is_some() {
+            warn!("package.metadata.dist.yum-repo is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if package_repo_gpg_key.is_some() {
+            warn!("package.metadata.dist.package-repo-gpg-key is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if http_proxy.is_some() {
+            warn!("package.metadata.dist.http-proxy is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if http_ca_certs.is_some() {
+            warn!("package.metadata.dist.http-ca-certs is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if http_connect_timeout.is_some() {
+            warn!("package.metadata.dist.http-connect-timeout is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if http_read_timeout.is_some() {
+            warn!("package.metadata.dist.http-read-timeout is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if http_retries.is_some() {
+            warn!("package.metadata.dist.http-retries is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if http_retry_backoff.is_some() {
+            warn!("package.metadata.dist.http-retry-backoff is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
         if plan_jobs.is_some() {
             warn!("package.metadata.dist.plan-jobs is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
@@ -707,6 +971,15 @@ impl DistMetadata {
         if github_build_setup.is_some() {
             warn!("package.metadata.dist.github-build-setup is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
         }
+        if gitlab_custom_runners.is_some() {
+            warn!("package.metadata.dist.gitlab-custom-runners is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if gitlab_image.is_some() {
+            warn!("package.metadata.dist.gitlab-image is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
+        if forgejo_custom_runners.is_some() {
+            warn!("package.metadata.dist.forgejo-custom-runners is set, but this is only accepted in workspace.metadata (value is being ignored): {}", package_manifest_path);
+        }
 
         // Merge non-global settings
         if installers.is_none() {
@@ -733,6 +1006,12 @@ impl DistMetadata {
         if npm_scope.is_none() {
             npm_scope.clone_from(&workspace_config.npm_scope);
         }
+        if npm_platform_packages.is_none() {
+            *npm_platform_packages = workspace_config.npm_platform_packages;
+        }
+        if shell_offline.is_none() {
+            *shell_offline = workspace_config.shell_offline;
+        }
         if checksum.is_none() {
             *checksum = workspace_config.checksum;
         }
@@ -742,6 +1021,9 @@ impl DistMetadata {
         if install_success_msg.is_none() {
             install_success_msg.clone_from(&workspace_config.install_success_msg);
         }
+        if minisign_public_key.is_none() {
+            minisign_public_key.clone_from(&workspace_config.minisign_public_key);
+        }
         if features.is_none() {
             features.clone_from(&workspace_config.features);
         }
@@ -757,18 +1039,36 @@ impl DistMetadata {
         if formula.is_none() {
             formula.clone_from(&workspace_config.formula);
         }
+        if scoop_bucket.is_none() {
+            scoop_bucket.clone_from(&workspace_config.scoop_bucket);
+        }
+        if winget_package_identifier.is_none() {
+            winget_package_identifier.clone_from(&workspace_config.winget_package_identifier);
+        }
+        if pypi_manylinux.is_none() {
+            pypi_manylinux.clone_from(&workspace_config.pypi_manylinux);
+        }
         if system_dependencies.is_none() {
             system_dependencies.clone_from(&workspace_config.system_dependencies);
         }
         if extra_artifacts.is_none() {
             extra_artifacts.clone_from(&workspace_config.extra_artifacts);
         }
+        if oci_image.is_none() {
+            *oci_image = workspace_config.oci_image;
+        }
+        if oci_base_image.is_none() {
+            oci_base_image.clone_from(&workspace_config.oci_base_image);
+        }
         if bin_aliases.is_none() {
             bin_aliases.clone_from(&workspace_config.bin_aliases);
         }
         if install_updater.is_none() {
             *install_updater = workspace_config.install_updater;
         }
+        if uninstallers.is_none() {
+            *uninstallers = workspace_config.uninstallers;
+        }
         if display.is_none() {
             *display = workspace_config.display;
         }
@@ -1,18 +1,30 @@
 //! conversion layer from v0 config to v1 config
 
 use artifacts::archives::ArchiveLayer;
+use artifacts::oci::OciImageLayer;
 use artifacts::ArtifactLayer;
 use builds::cargo::CargoBuildLayer;
 use builds::{BuildLayer, CommonBuildLayer};
+use ci::forgejo::ForgejoCiLayer;
 use ci::github::GithubCiLayer;
+use ci::gitlab::GitlabCiLayer;
 use ci::{CiLayer, CommonCiLayer};
 use hosts::github::GithubHostLayer;
+use hosts::mirror::MirrorHostLayer;
+use hosts::package_repo::PackageRepoLayer;
+use hosts::s3::S3HostLayer;
 use hosts::{CommonHostLayer, HostLayer};
 use installers::homebrew::HomebrewInstallerLayer;
 use installers::npm::NpmInstallerLayer;
 use installers::pkg::PkgInstallerLayer;
+use installers::pypi::PypiInstallerLayer;
+use installers::scoop::ScoopInstallerLayer;
+use installers::shell::ShellInstallerLayer;
+use installers::winget::WingetInstallerLayer;
 use installers::{CommonInstallerLayer, InstallerLayer};
 use layer::BoolOr;
+use net::NetLayer;
+use publishers::npm::NpmPublisherLayer;
 use publishers::{CommonPublisherLayer, PublisherLayer};
 
 use super::v0::DistMetadata;
@@ -30,8 +42,12 @@ impl DistMetadata {
             allow_dirty,
             installers,
             install_success_msg,
+            minisign_public_key,
             tap,
             formula,
+            scoop_bucket,
+            winget_package_identifier,
+            pypi_manylinux,
             system_dependencies,
             targets,
             include,
@@ -41,6 +57,8 @@ impl DistMetadata {
             unix_archive,
             npm_package,
             npm_scope,
+            npm_platform_packages,
+            shell_offline,
             checksum,
             precise_builds,
             merge_tasks,
@@ -61,6 +79,8 @@ impl DistMetadata {
             publish_jobs,
             post_announce_jobs,
             publish_prereleases,
+            npm_registry,
+            npm_token_env_var,
             force_latest,
             create_release,
             github_releases_repo,
@@ -71,17 +91,41 @@ impl DistMetadata {
             mac_pkg_config,
             github_attestations,
             hosting,
+            s3_bucket,
+            s3_prefix,
+            s3_endpoint,
+            s3_region,
+            s3_public_url,
+            s3_latest_alias,
+            mirror_root,
+            mirror_url,
+            download_urls,
+            apt_repo,
+            yum_repo,
+            package_repo_gpg_key,
+            http_proxy,
+            http_ca_certs,
+            http_connect_timeout,
+            http_read_timeout,
+            http_retries,
+            http_retry_backoff,
             extra_artifacts,
+            oci_image,
+            oci_base_image,
             github_custom_runners,
             github_custom_job_permissions,
             bin_aliases,
             tag_namespace,
             install_updater,
+            uninstallers,
             display,
             display_name,
             package_libraries,
             install_libraries,
             github_build_setup,
+            gitlab_custom_runners,
+            gitlab_image,
+            forgejo_custom_runners,
         } = self.clone();
 
         // Archives
@@ -97,14 +141,21 @@ impl DistMetadata {
             unix_archive,
             package_libraries,
         });
+        let oci_layer = match (oci_image, oci_base_image) {
+            (Some(false), _) => Some(BoolOr::Bool(false)),
+            (Some(true), base_image) => Some(BoolOr::Val(OciImageLayer { base_image })),
+            (None, _) => None,
+        };
         let needs_artifacts = archive_layer.is_some()
             || source_tarball.is_some()
             || extra_artifacts.is_some()
+            || oci_layer.is_some()
             || checksum.is_some();
         let artifacts_layer = needs_artifacts.then_some(ArtifactLayer {
             archives: archive_layer,
             source_tarball,
             extra: extra_artifacts,
+            oci: oci_layer,
             checksum,
         });
 
@@ -153,6 +204,28 @@ impl DistMetadata {
             }
         });
         let has_github_ci = github_ci_layer.is_some();
+        let gitlab_ci_layer = list_to_bool_layer(is_global, &ci, CiStyle::Gitlab, || {
+            if gitlab_custom_runners.is_some() || gitlab_image.is_some() {
+                Some(GitlabCiLayer {
+                    common: CommonCiLayer::default(),
+                    runners: gitlab_custom_runners,
+                    image: gitlab_image,
+                })
+            } else {
+                None
+            }
+        });
+        let forgejo_ci_layer = list_to_bool_layer(is_global, &ci, CiStyle::Forgejo, || {
+            if forgejo_custom_runners.is_some() {
+                Some(ForgejoCiLayer {
+                    common: CommonCiLayer::default(),
+                    runners: forgejo_custom_runners,
+                })
+            } else {
+                None
+            }
+        });
+        let has_forgejo_ci = forgejo_ci_layer.is_some();
         let custom_publish_jobs = publish_jobs.as_ref().map(|jobs| {
             jobs.iter()
                 .filter_map(|p| {
@@ -174,6 +247,8 @@ impl DistMetadata {
             custom_publish_jobs
         };
         let needs_ci_layer = github_ci_layer.is_some()
+            || gitlab_ci_layer.is_some()
+            || forgejo_ci_layer.is_some()
             || merge_tasks.is_some()
             || fail_fast.is_some()
             || cache_builds.is_some()
@@ -205,6 +280,8 @@ impl DistMetadata {
                 post_announce_jobs,
             },
             github: github_ci_layer,
+            gitlab: gitlab_ci_layer,
+            forgejo: forgejo_ci_layer,
         });
 
         // hosts
@@ -230,20 +307,84 @@ impl DistMetadata {
             });
         let axodotdev_host_layer =
             list_to_bool_layer(is_global, &hosting, HostingStyle::Axodotdev, || None);
-        if github_host_layer.is_none() && axodotdev_host_layer.is_none() && has_github_ci {
+        let mut gitea_host_layer =
+            list_to_bool_layer(is_global, &hosting, HostingStyle::Gitea, || None);
+        let s3_host_layer = list_to_bool_layer(is_global, &hosting, HostingStyle::S3, || {
+            if s3_bucket.is_some()
+                || s3_prefix.is_some()
+                || s3_endpoint.is_some()
+                || s3_region.is_some()
+                || s3_public_url.is_some()
+                || s3_latest_alias.is_some()
+            {
+                Some(S3HostLayer {
+                    common: CommonHostLayer::default(),
+                    bucket: s3_bucket,
+                    prefix: s3_prefix,
+                    endpoint: s3_endpoint,
+                    region: s3_region,
+                    public_url: s3_public_url,
+                    latest_alias: s3_latest_alias,
+                })
+            } else {
+                None
+            }
+        });
+        let mirror_host_layer =
+            list_to_bool_layer(is_global, &hosting, HostingStyle::Mirror, || {
+                if mirror_root.is_some() || mirror_url.is_some() {
+                    Some(MirrorHostLayer {
+                        common: CommonHostLayer::default(),
+                        root: mirror_root,
+                        url: mirror_url,
+                    })
+                } else {
+                    None
+                }
+            });
+        let package_repo_layer =
+            if apt_repo.is_some() || yum_repo.is_some() || package_repo_gpg_key.is_some() {
+                Some(BoolOr::Val(PackageRepoLayer {
+                    apt: Some(apt_repo.unwrap_or(false)),
+                    yum: Some(yum_repo.unwrap_or(false)),
+                    gpg_key: package_repo_gpg_key,
+                }))
+            } else {
+                None
+            };
+        // If no hosting was picked, use the native hosting of the CI
+        let no_explicit_hosting = github_host_layer.is_none()
+            && axodotdev_host_layer.is_none()
+            && gitea_host_layer.is_none()
+            && s3_host_layer.is_none()
+            && mirror_host_layer.is_none();
+        if no_explicit_hosting && has_github_ci {
             github_host_layer = Some(BoolOr::Bool(true));
         }
+        if no_explicit_hosting && has_forgejo_ci {
+            gitea_host_layer = Some(BoolOr::Bool(true));
+        }
 
         let needs_host_layer = github_host_layer.is_some()
             || axodotdev_host_layer.is_some()
+            || gitea_host_layer.is_some()
+            || s3_host_layer.is_some()
+            || mirror_host_layer.is_some()
+            || package_repo_layer.is_some()
             || force_latest.is_some()
+            || download_urls.is_some()
             || display.is_some()
             || display_name.is_some();
         let host_layer = needs_host_layer.then_some(HostLayer {
             common: CommonHostLayer {},
             github: github_host_layer,
             axodotdev: axodotdev_host_layer,
+            gitea: gitea_host_layer,
+            s3: s3_host_layer,
+            mirror: mirror_host_layer,
+            package_repo: package_repo_layer,
             force_latest,
+            download_urls,
             display,
             display_name,
         });
@@ -263,11 +404,12 @@ impl DistMetadata {
             });
         let npm_installer_layer =
             list_to_bool_layer(is_global, &installers, InstallerStyle::Npm, || {
-                if npm_package.is_some() || npm_scope.is_some() {
+                if npm_package.is_some() || npm_scope.is_some() || npm_platform_packages.is_some() {
                     Some(NpmInstallerLayer {
                         common: CommonInstallerLayer::default(),
                         package: npm_package,
                         scope: npm_scope,
+                        platform_packages: npm_platform_packages,
                     })
                 } else {
                     None
@@ -290,24 +432,68 @@ impl DistMetadata {
         let powershell_installer_layer =
             list_to_bool_layer(is_global, &installers, InstallerStyle::Powershell, || None);
         let shell_installer_layer =
-            list_to_bool_layer(is_global, &installers, InstallerStyle::Shell, || None);
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Shell, || {
+                shell_offline.map(|offline| ShellInstallerLayer {
+                    common: CommonInstallerLayer::default(),
+                    offline: Some(offline),
+                })
+            });
+        let scoop_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Scoop, || {
+                scoop_bucket.map(|bucket| ScoopInstallerLayer {
+                    common: CommonInstallerLayer::default(),
+                    bucket: Some(bucket),
+                })
+            });
+        let winget_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Winget, || {
+                winget_package_identifier.map(|package_identifier| WingetInstallerLayer {
+                    common: CommonInstallerLayer::default(),
+                    package_identifier: Some(package_identifier),
+                })
+            });
+        let deb_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Deb, || None);
+        let rpm_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Rpm, || None);
+        let aur_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Aur, || None);
+        let nix_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Nix, || None);
+        let pypi_installer_layer =
+            list_to_bool_layer(is_global, &installers, InstallerStyle::Pypi, || {
+                pypi_manylinux.map(|manylinux| PypiInstallerLayer {
+                    common: CommonInstallerLayer::default(),
+                    manylinux: Some(manylinux),
+                })
+            });
         let needs_installer_layer = homebrew_installer_layer.is_some()
+            || scoop_installer_layer.is_some()
+            || winget_installer_layer.is_some()
             || npm_installer_layer.is_some()
             || msi_installer_layer.is_some()
             || powershell_installer_layer.is_some()
             || shell_installer_layer.is_some()
             || pkg_installer_layer.is_some()
+            || deb_installer_layer.is_some()
+            || rpm_installer_layer.is_some()
+            || aur_installer_layer.is_some()
+            || nix_installer_layer.is_some()
+            || pypi_installer_layer.is_some()
             || install_path.is_some()
             || install_success_msg.is_some()
+            || minisign_public_key.is_some()
             || install_libraries.is_some()
             || bin_aliases.is_some()
-            || install_updater.is_some();
+            || install_updater.is_some()
+            || uninstallers.is_some();
         let installer_layer = needs_installer_layer.then_some(InstallerLayer {
             common: CommonInstallerLayer {
                 install_path,
                 install_success_msg,
                 install_libraries,
                 bin_aliases,
+                minisign_public_key,
             },
             homebrew: homebrew_installer_layer,
             msi: msi_installer_layer,
@@ -315,16 +501,46 @@ impl DistMetadata {
             powershell: powershell_installer_layer,
             shell: shell_installer_layer,
             pkg: pkg_installer_layer,
+            scoop: scoop_installer_layer,
+            winget: winget_installer_layer,
+            deb: deb_installer_layer,
+            rpm: rpm_installer_layer,
+            aur: aur_installer_layer,
+            nix: nix_installer_layer,
+            pypi: pypi_installer_layer,
             updater: install_updater,
+            uninstallers,
         });
 
         // publish
         let homebrew_publisher_layer =
             list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Homebrew, || None);
         let npm_publisher_layer =
-            list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Npm, || None);
+            list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Npm, || {
+                if npm_registry.is_some() || npm_token_env_var.is_some() {
+                    Some(NpmPublisherLayer {
+                        common: CommonPublisherLayer::default(),
+                        registry: npm_registry,
+                        token_env_var: npm_token_env_var,
+                    })
+                } else {
+                    None
+                }
+            });
+        let crates_publisher_layer =
+            list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Crates, || None);
+        let scoop_publisher_layer =
+            list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Scoop, || None);
+        let aur_publisher_layer =
+            list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Aur, || None);
+        let pypi_publisher_layer =
+            list_to_bool_layer(is_global, &publish_jobs, PublishStyle::Pypi, || None);
         let needs_publisher_layer = homebrew_publisher_layer.is_some()
             || npm_publisher_layer.is_some()
+            || crates_publisher_layer.is_some()
+            || scoop_publisher_layer.is_some()
+            || aur_publisher_layer.is_some()
+            || pypi_publisher_layer.is_some()
             || publish_prereleases.is_some();
         let publisher_layer = needs_publisher_layer.then_some(PublisherLayer {
             common: CommonPublisherLayer {
@@ -332,6 +548,26 @@ impl DistMetadata {
             },
             homebrew: homebrew_publisher_layer,
             npm: npm_publisher_layer,
+            crates: crates_publisher_layer,
+            scoop: scoop_publisher_layer,
+            aur: aur_publisher_layer,
+            pypi: pypi_publisher_layer,
+        });
+
+        // net
+        let needs_net_layer = http_proxy.is_some()
+            || http_ca_certs.is_some()
+            || http_connect_timeout.is_some()
+            || http_read_timeout.is_some()
+            || http_retries.is_some()
+            || http_retry_backoff.is_some();
+        let net_layer = needs_net_layer.then_some(NetLayer {
+            proxy: http_proxy,
+            ca_certs: http_ca_certs,
+            connect_timeout: http_connect_timeout,
+            read_timeout: http_read_timeout,
+            retries: http_retries,
+            retry_backoff: http_retry_backoff,
         });
 
         // done!
@@ -347,6 +583,7 @@ impl DistMetadata {
             hosts: host_layer,
             installers: installer_layer,
             publishers: publisher_layer,
+            net: net_layer,
         }
     }
 }
@@ -1,9 +1,11 @@
 //! artifact config
 
 pub mod archives;
+pub mod oci;
 
 use super::*;
 use archives::*;
+use oci::*;
 
 /// app-specific artifact config (final)
 #[derive(Debug, Clone)]
@@ -12,6 +14,8 @@ pub struct AppArtifactConfig {
     pub archives: ArchiveConfig,
     /// Any extra artifacts and their buildscripts
     pub extra: Vec<ExtraArtifact>,
+    /// oci image config
+    pub oci: Option<OciImageConfig>,
 }
 
 /// workspace artifact config (final)
@@ -40,6 +44,10 @@ pub struct ArtifactLayer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra: Option<Vec<ExtraArtifact>>,
 
+    /// Whether to build an OCI image for each linux target (and how)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oci: Option<BoolOr<OciImageLayer>>,
+
     /// How to checksum
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checksum: Option<ChecksumStyle>,
@@ -50,6 +58,7 @@ impl AppArtifactConfig {
         Self {
             archives: ArchiveConfig::defaults_for_package(workspaces, pkg_idx),
             extra: vec![],
+            oci: None,
         }
     }
 }
@@ -71,6 +80,7 @@ impl ApplyLayer for AppArtifactConfig {
         Self::Layer {
             archives,
             extra,
+            oci,
             // these are all workspace-only
             source_tarball: _,
             checksum: _,
@@ -78,6 +88,7 @@ impl ApplyLayer for AppArtifactConfig {
     ) {
         self.archives.apply_val_layer(archives);
         self.extra.apply_val(extra);
+        self.oci.apply_bool_layer(oci);
     }
 }
 
@@ -91,6 +102,7 @@ impl ApplyLayer for WorkspaceArtifactConfig {
             // these are all app-only
             archives: _,
             extra: _,
+            oci: _,
         }: Self::Layer,
     ) {
         self.source_tarball.apply_val(source_tarball);
@@ -0,0 +1,35 @@
+//! oci image config
+
+use super::*;
+
+/// oci image config (final)
+#[derive(Debug, Default, Clone)]
+pub struct OciImageConfig {
+    /// The image linux-gnu images are built on top of, pinned by digest
+    ///
+    /// linux-musl images are always built on an empty (`scratch`) image.
+    pub base_image: Option<String>,
+}
+
+/// oci image config (raw from config file)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct OciImageLayer {
+    /// The image linux-gnu images are built on top of, pinned by digest
+    /// (`registry/repo@sha256:...`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_image: Option<String>,
+}
+
+impl ApplyLayer for OciImageConfig {
+    type Layer = OciImageLayer;
+    fn apply_layer(&mut self, Self::Layer { base_image }: Self::Layer) {
+        self.base_image.apply_opt(base_image);
+    }
+}
+impl ApplyLayer for OciImageLayer {
+    type Layer = OciImageLayer;
+    fn apply_layer(&mut self, Self::Layer { base_image }: Self::Layer) {
+        self.base_image.apply_opt(base_image);
+    }
+}
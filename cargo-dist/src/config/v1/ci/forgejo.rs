@@ -0,0 +1,58 @@
+//! forgejo ci config
+
+use cargo_dist_schema::TargetTriple;
+
+use super::*;
+
+/// forgejo ci config (raw from file)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ForgejoCiLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonCiLayer,
+
+    /// Custom Forgejo runner labels, mapped by triple target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runners: Option<SortedMap<TargetTriple, String>>,
+}
+/// forgejo ci config (final)
+#[derive(Debug, Default, Clone)]
+pub struct ForgejoCiConfig {
+    /// Common options
+    pub common: CommonCiConfig,
+    /// Custom Forgejo runner labels, mapped by triple target
+    pub runners: SortedMap<TargetTriple, String>,
+}
+
+impl ForgejoCiConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph, common: &CommonCiConfig) -> Self {
+        Self {
+            common: common.clone(),
+            runners: Default::default(),
+        }
+    }
+}
+
+impl ApplyLayer for ForgejoCiConfig {
+    type Layer = ForgejoCiLayer;
+    fn apply_layer(&mut self, Self::Layer { common, runners }: Self::Layer) {
+        self.common.apply_layer(common);
+        self.runners.apply_val(runners);
+    }
+}
+impl ApplyLayer for ForgejoCiLayer {
+    type Layer = ForgejoCiLayer;
+    fn apply_layer(&mut self, Self::Layer { common, runners }: Self::Layer) {
+        self.common.apply_layer(common);
+        self.runners.apply_opt(runners);
+    }
+}
+
+impl std::ops::Deref for ForgejoCiConfig {
+    type Target = CommonCiConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
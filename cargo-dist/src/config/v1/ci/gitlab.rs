@@ -0,0 +1,81 @@
+//! gitlab ci config
+
+use cargo_dist_schema::TargetTriple;
+
+use super::*;
+
+/// gitlab ci config (raw from file)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GitlabCiLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonCiLayer,
+
+    /// Custom GitLab runner tags, mapped by triple target
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runners: Option<SortedMap<TargetTriple, String>>,
+
+    /// Docker image to use for jobs running on linux runners
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+/// gitlab ci config (final)
+#[derive(Debug, Default, Clone)]
+pub struct GitlabCiConfig {
+    /// Common options
+    pub common: CommonCiConfig,
+    /// Custom GitLab runner tags, mapped by triple target
+    pub runners: SortedMap<TargetTriple, String>,
+    /// Docker image to use for jobs running on linux runners
+    pub image: Option<String>,
+}
+
+impl GitlabCiConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph, common: &CommonCiConfig) -> Self {
+        Self {
+            common: common.clone(),
+            runners: Default::default(),
+            image: None,
+        }
+    }
+}
+
+impl ApplyLayer for GitlabCiConfig {
+    type Layer = GitlabCiLayer;
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            common,
+            runners,
+            image,
+        }: Self::Layer,
+    ) {
+        self.common.apply_layer(common);
+        self.runners.apply_val(runners);
+        self.image.apply_opt(image);
+    }
+}
+impl ApplyLayer for GitlabCiLayer {
+    type Layer = GitlabCiLayer;
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            common,
+            runners,
+            image,
+        }: Self::Layer,
+    ) {
+        self.common.apply_layer(common);
+        self.runners.apply_opt(runners);
+        self.image.apply_opt(image);
+    }
+}
+
+impl std::ops::Deref for GitlabCiConfig {
+    type Target = CommonCiConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
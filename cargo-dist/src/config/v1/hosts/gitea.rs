@@ -0,0 +1,46 @@
+//! gitea host config
+
+use super::*;
+
+/// gitea host (raw)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GiteaHostLayer {
+    /// Common options
+    pub common: CommonHostLayer,
+}
+/// gitea host (final)
+#[derive(Debug, Default, Clone)]
+pub struct GiteaHostConfig {
+    /// Common options
+    pub common: CommonHostConfig,
+}
+
+impl GiteaHostConfig {
+    /// Get defaults for the given workspace
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph, common: &CommonHostConfig) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for GiteaHostConfig {
+    type Layer = GiteaHostLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for GiteaHostLayer {
+    type Layer = GiteaHostLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for GiteaHostConfig {
+    type Target = CommonHostConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -0,0 +1,66 @@
+//! mirror host config
+
+use super::*;
+
+/// mirror host (raw)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct MirrorHostLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonHostLayer,
+
+    /// The directory to publish the mirror's tree into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<Utf8PathBuf>,
+
+    /// The URL the mirror's root will be served from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+/// mirror host (final)
+#[derive(Debug, Default, Clone)]
+pub struct MirrorHostConfig {
+    /// Common options
+    pub common: CommonHostConfig,
+    /// The directory to publish the mirror's tree into
+    /// (default is a `mirror` directory in the target dir)
+    pub root: Option<Utf8PathBuf>,
+    /// The URL the mirror's root will be served from
+    pub url: Option<String>,
+}
+
+impl MirrorHostConfig {
+    /// Get defaults for the given workspace
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph, common: &CommonHostConfig) -> Self {
+        Self {
+            common: common.clone(),
+            root: None,
+            url: None,
+        }
+    }
+}
+
+impl ApplyLayer for MirrorHostConfig {
+    type Layer = MirrorHostLayer;
+    fn apply_layer(&mut self, Self::Layer { common, root, url }: Self::Layer) {
+        self.common.apply_layer(common);
+        self.root.apply_opt(root);
+        self.url.apply_opt(url);
+    }
+}
+impl ApplyLayer for MirrorHostLayer {
+    type Layer = MirrorHostLayer;
+    fn apply_layer(&mut self, Self::Layer { common, root, url }: Self::Layer) {
+        self.common.apply_layer(common);
+        self.root.apply_opt(root);
+        self.url.apply_opt(url);
+    }
+}
+
+impl std::ops::Deref for MirrorHostConfig {
+    type Target = CommonHostConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -1,12 +1,20 @@
 //! host config
 
 pub mod axodotdev;
+pub mod gitea;
 pub mod github;
+pub mod mirror;
+pub mod package_repo;
+pub mod s3;
 
 use super::*;
 
 use axodotdev::*;
+use gitea::*;
 use github::*;
+use mirror::*;
+use package_repo::*;
+use s3::*;
 
 #[derive(Debug, Clone)]
 /// package-specific host config (final)
@@ -22,10 +30,20 @@ pub struct AppHostConfig {
 pub struct WorkspaceHostConfig {
     /// Always regard releases as stable
     pub force_latest: bool,
+    /// The base URLs installers should try to download artifacts from, in order
+    pub download_urls: Vec<String>,
     /// github host config (github releases)
     pub github: Option<GithubHostConfig>,
     /// axodotdev host config (axo releases)
     pub axodotdev: Option<AxodotdevHostConfig>,
+    /// gitea host config (gitea releases)
+    pub gitea: Option<GiteaHostConfig>,
+    /// s3 host config (s3-compatible buckets)
+    pub s3: Option<S3HostConfig>,
+    /// mirror host config (static directory trees)
+    pub mirror: Option<MirrorHostConfig>,
+    /// apt/yum repositories to publish alongside the hosted files
+    pub package_repo: Option<PackageRepoConfig>,
 }
 /// host config (inheritance not folded in yet)
 #[derive(Debug, Clone)]
@@ -34,6 +52,8 @@ pub struct HostConfigInheritable {
     pub common: CommonHostConfig,
     /// Always regard releases as stable
     pub force_latest: Option<bool>,
+    /// The base URLs installers should try to download artifacts from, in order
+    pub download_urls: Option<Vec<String>>,
     /// Whether artifacts/installers for this app should be displayed in release bodies
     pub display: Option<bool>,
     /// How to refer to the app in release bodies
@@ -42,6 +62,14 @@ pub struct HostConfigInheritable {
     pub github: Option<GithubHostLayer>,
     /// axodotdev hosting
     pub axodotdev: Option<AxodotdevHostLayer>,
+    /// gitea hosting
+    pub gitea: Option<GiteaHostLayer>,
+    /// s3 hosting
+    pub s3: Option<S3HostLayer>,
+    /// mirror hosting
+    pub mirror: Option<MirrorHostLayer>,
+    /// apt/yum repositories
+    pub package_repo: Option<PackageRepoLayer>,
 }
 
 /// host config (raw from file)
@@ -64,6 +92,17 @@ pub struct HostLayer {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub force_latest: Option<bool>,
 
+    /// The base URLs the shell, powershell and npm installers should try to
+    /// download artifacts from, in order
+    ///
+    /// (defaults to just the URL of the preferred host)
+    ///
+    /// Each entry is either the name of a host that's enabled
+    /// (like "github" or "mirror"), or a URL that can use
+    /// `{app}`, `{version}` and `{tag}` placeholders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_urls: Option<Vec<String>>,
+
     /// Whether artifacts/installers for this app should be displayed in release bodies
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display: Option<bool>,
@@ -78,6 +117,18 @@ pub struct HostLayer {
     /// axodotdev hosting
     #[serde(skip_serializing_if = "Option::is_none")]
     pub axodotdev: Option<BoolOr<AxodotdevHostLayer>>,
+    /// gitea hosting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gitea: Option<BoolOr<GiteaHostLayer>>,
+    /// s3 hosting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub s3: Option<BoolOr<S3HostLayer>>,
+    /// mirror hosting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<BoolOr<MirrorHostLayer>>,
+    /// apt/yum repositories to publish alongside the hosted files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_repo: Option<BoolOr<PackageRepoLayer>>,
 }
 impl HostConfigInheritable {
     /// get defaults for a package
@@ -86,7 +137,12 @@ impl HostConfigInheritable {
             common: CommonHostConfig::defaults_for_package(workspaces, pkg_idx),
             github: None,
             axodotdev: None,
+            gitea: None,
+            s3: None,
+            mirror: None,
+            package_repo: None,
             force_latest: None,
+            download_urls: None,
             display: None,
             display_name: None,
         }
@@ -97,7 +153,12 @@ impl HostConfigInheritable {
             common: CommonHostConfig::defaults_for_workspace(workspaces),
             github: None,
             axodotdev: None,
+            gitea: None,
+            s3: None,
+            mirror: None,
+            package_repo: None,
             force_latest: None,
+            download_urls: None,
             display: None,
             display_name: None,
         }
@@ -112,7 +173,12 @@ impl HostConfigInheritable {
             common: _,
             github: _,
             axodotdev: _,
+            gitea: _,
+            s3: _,
+            mirror: _,
+            package_repo: _,
             force_latest: _,
+            download_urls: _,
             display,
             display_name,
         } = self;
@@ -132,7 +198,12 @@ impl HostConfigInheritable {
             common,
             github,
             axodotdev,
+            gitea,
+            s3,
+            mirror,
+            package_repo,
             force_latest,
+            download_urls,
             display: _,
             display_name: _,
         } = self;
@@ -146,10 +217,35 @@ impl HostConfigInheritable {
             default.apply_layer(axodotdev);
             default
         });
+        let gitea = gitea.map(|gitea| {
+            let mut default = GiteaHostConfig::defaults_for_workspace(workspaces, &common);
+            default.apply_layer(gitea);
+            default
+        });
+        let s3 = s3.map(|s3| {
+            let mut default = S3HostConfig::defaults_for_workspace(workspaces, &common);
+            default.apply_layer(s3);
+            default
+        });
+        let mirror = mirror.map(|mirror| {
+            let mut default = MirrorHostConfig::defaults_for_workspace(workspaces, &common);
+            default.apply_layer(mirror);
+            default
+        });
+        let package_repo = package_repo.map(|package_repo| {
+            let mut default = PackageRepoConfig::defaults_for_workspace(workspaces);
+            default.apply_layer(package_repo);
+            default
+        });
         WorkspaceHostConfig {
             github,
             axodotdev,
+            gitea,
+            s3,
+            mirror,
+            package_repo,
             force_latest: force_latest.unwrap_or(false),
+            download_urls: download_urls.unwrap_or_default(),
         }
     }
 }
@@ -162,7 +258,12 @@ impl ApplyLayer for HostConfigInheritable {
             common,
             github,
             axodotdev,
+            gitea,
+            s3,
+            mirror,
+            package_repo,
             force_latest,
+            download_urls,
             display,
             display_name,
         }: Self::Layer,
@@ -170,7 +271,12 @@ impl ApplyLayer for HostConfigInheritable {
         self.common.apply_layer(common);
         self.github.apply_bool_layer(github);
         self.axodotdev.apply_bool_layer(axodotdev);
+        self.gitea.apply_bool_layer(gitea);
+        self.s3.apply_bool_layer(s3);
+        self.mirror.apply_bool_layer(mirror);
+        self.package_repo.apply_bool_layer(package_repo);
         self.force_latest.apply_opt(force_latest);
+        self.download_urls.apply_opt(download_urls);
         self.display.apply_opt(display);
         self.display_name.apply_opt(display_name);
     }
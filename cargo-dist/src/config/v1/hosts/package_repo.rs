@@ -0,0 +1,59 @@
+//! package repository config
+
+use super::*;
+
+/// package repository (raw)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackageRepoLayer {
+    /// Whether to generate an apt repository for .deb packages (default true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apt: Option<bool>,
+
+    /// Whether to generate a yum repository for .rpm packages (default true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yum: Option<bool>,
+
+    /// The GPG key to sign the repository metadata with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpg_key: Option<String>,
+}
+/// package repository (final)
+#[derive(Debug, Default, Clone)]
+pub struct PackageRepoConfig {
+    /// Whether to generate an apt repository for .deb packages
+    pub apt: bool,
+    /// Whether to generate a yum repository for .rpm packages
+    pub yum: bool,
+    /// The GPG key to sign the repository metadata with
+    /// (unsigned if not set)
+    pub gpg_key: Option<String>,
+}
+
+impl PackageRepoConfig {
+    /// Get defaults for the given workspace
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph) -> Self {
+        Self {
+            apt: true,
+            yum: true,
+            gpg_key: None,
+        }
+    }
+}
+
+impl ApplyLayer for PackageRepoConfig {
+    type Layer = PackageRepoLayer;
+    fn apply_layer(&mut self, Self::Layer { apt, yum, gpg_key }: Self::Layer) {
+        self.apt.apply_val(apt);
+        self.yum.apply_val(yum);
+        self.gpg_key.apply_opt(gpg_key);
+    }
+}
+impl ApplyLayer for PackageRepoLayer {
+    type Layer = PackageRepoLayer;
+    fn apply_layer(&mut self, Self::Layer { apt, yum, gpg_key }: Self::Layer) {
+        self.apt.apply_opt(apt);
+        self.yum.apply_opt(yum);
+        self.gpg_key.apply_opt(gpg_key);
+    }
+}
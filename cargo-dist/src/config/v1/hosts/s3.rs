@@ -0,0 +1,123 @@
+//! s3 host config
+
+use super::*;
+
+/// s3 host (raw)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct S3HostLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonHostLayer,
+
+    /// The bucket to upload to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+
+    /// A key prefix to put releases under (e.g. "releases/myapp")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// The S3 API endpoint, for S3-compatible services that aren't AWS
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+
+    /// The region of the bucket (default "us-east-1")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+
+    /// The URL installers should download from, if not the bucket itself (e.g. a CDN)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<String>,
+
+    /// Whether to also copy stable releases to a `latest/` prefix (default false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_alias: Option<bool>,
+}
+/// s3 host (final)
+#[derive(Debug, Default, Clone)]
+pub struct S3HostConfig {
+    /// Common options
+    pub common: CommonHostConfig,
+    /// The bucket to upload to
+    pub bucket: Option<String>,
+    /// A key prefix to put releases under
+    pub prefix: Option<String>,
+    /// The S3 API endpoint, if not AWS
+    pub endpoint: Option<String>,
+    /// The region of the bucket
+    pub region: String,
+    /// The URL installers should download from, if not the bucket itself
+    pub public_url: Option<String>,
+    /// Whether to also copy stable releases to a `latest/` prefix
+    pub latest_alias: bool,
+}
+
+impl S3HostConfig {
+    /// Get defaults for the given workspace
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph, common: &CommonHostConfig) -> Self {
+        Self {
+            common: common.clone(),
+            bucket: None,
+            prefix: None,
+            endpoint: None,
+            region: "us-east-1".to_owned(),
+            public_url: None,
+            latest_alias: false,
+        }
+    }
+}
+
+impl ApplyLayer for S3HostConfig {
+    type Layer = S3HostLayer;
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            common,
+            bucket,
+            prefix,
+            endpoint,
+            region,
+            public_url,
+            latest_alias,
+        }: Self::Layer,
+    ) {
+        self.common.apply_layer(common);
+        self.bucket.apply_opt(bucket);
+        self.prefix.apply_opt(prefix);
+        self.endpoint.apply_opt(endpoint);
+        self.region.apply_val(region);
+        self.public_url.apply_opt(public_url);
+        self.latest_alias.apply_val(latest_alias);
+    }
+}
+impl ApplyLayer for S3HostLayer {
+    type Layer = S3HostLayer;
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            common,
+            bucket,
+            prefix,
+            endpoint,
+            region,
+            public_url,
+            latest_alias,
+        }: Self::Layer,
+    ) {
+        self.common.apply_layer(common);
+        self.bucket.apply_opt(bucket);
+        self.prefix.apply_opt(prefix);
+        self.endpoint.apply_opt(endpoint);
+        self.region.apply_opt(region);
+        self.public_url.apply_opt(public_url);
+        self.latest_alias.apply_opt(latest_alias);
+    }
+}
+
+impl std::ops::Deref for S3HostConfig {
+    type Target = CommonHostConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -0,0 +1,51 @@
+//! aur installer config
+
+use super::*;
+
+/// Options for aur installer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct AurInstallerLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonInstallerLayer,
+}
+/// Options for aur installer
+#[derive(Debug, Default, Clone)]
+pub struct AurInstallerConfig {
+    /// Common options
+    pub common: CommonInstallerConfig,
+}
+
+impl AurInstallerConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonInstallerConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for AurInstallerConfig {
+    type Layer = AurInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for AurInstallerLayer {
+    type Layer = AurInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for AurInstallerConfig {
+    type Target = CommonInstallerConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -0,0 +1,51 @@
+//! deb installer config
+
+use super::*;
+
+/// Options for deb installer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct DebInstallerLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonInstallerLayer,
+}
+/// Options for deb installer
+#[derive(Debug, Default, Clone)]
+pub struct DebInstallerConfig {
+    /// Common options
+    pub common: CommonInstallerConfig,
+}
+
+impl DebInstallerConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonInstallerConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for DebInstallerConfig {
+    type Layer = DebInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for DebInstallerLayer {
+    type Layer = DebInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for DebInstallerConfig {
+    type Target = CommonInstallerConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
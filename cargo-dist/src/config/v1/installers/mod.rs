@@ -1,26 +1,42 @@
 //! installer config
 
+pub mod aur;
+pub mod deb;
 pub mod homebrew;
 pub mod msi;
+pub mod nix;
 pub mod npm;
 pub mod pkg;
 pub mod powershell;
+pub mod pypi;
+pub mod rpm;
+pub mod scoop;
 pub mod shell;
+pub mod winget;
 
 use super::*;
 
+use aur::*;
+use deb::*;
 use homebrew::*;
 use msi::*;
+use nix::*;
 use npm::*;
 use pkg::*;
 use powershell::*;
+use pypi::*;
+use rpm::*;
+use scoop::*;
 use shell::*;
+use winget::*;
 
 /// workspace installer config (final)
 #[derive(Debug, Default, Clone)]
 pub struct WorkspaceInstallerConfig {
     /// Whether to install an updater program alongside the software
     pub updater: bool,
+    /// Whether to generate uninstallers next to the shell/powershell installers
+    pub uninstallers: bool,
 }
 /// package installer config (final)
 #[derive(Debug, Default, Clone)]
@@ -37,6 +53,20 @@ pub struct AppInstallerConfig {
     pub shell: Option<ShellInstallerConfig>,
     /// shell installer
     pub pkg: Option<PkgInstallerConfig>,
+    /// scoop installer
+    pub scoop: Option<ScoopInstallerConfig>,
+    /// winget installer
+    pub winget: Option<WingetInstallerConfig>,
+    /// deb installer
+    pub deb: Option<DebInstallerConfig>,
+    /// rpm installer
+    pub rpm: Option<RpmInstallerConfig>,
+    /// aur installer
+    pub aur: Option<AurInstallerConfig>,
+    /// nix installer
+    pub nix: Option<NixInstallerConfig>,
+    /// pypi installer
+    pub pypi: Option<PypiInstallerConfig>,
 }
 
 /// installer config (inheritance not yet applied)
@@ -56,8 +86,24 @@ pub struct InstallerConfigInheritable {
     pub shell: Option<ShellInstallerLayer>,
     /// pkg installer
     pub pkg: Option<PkgInstallerLayer>,
+    /// scoop installer
+    pub scoop: Option<ScoopInstallerLayer>,
+    /// winget installer
+    pub winget: Option<WingetInstallerLayer>,
+    /// deb installer
+    pub deb: Option<DebInstallerLayer>,
+    /// rpm installer
+    pub rpm: Option<RpmInstallerLayer>,
+    /// aur installer
+    pub aur: Option<AurInstallerLayer>,
+    /// nix installer
+    pub nix: Option<NixInstallerLayer>,
+    /// pypi installer
+    pub pypi: Option<PypiInstallerLayer>,
     /// Whether to install an updater program alongside the software
     pub updater: bool,
+    /// Whether to generate uninstallers next to the shell/powershell installers
+    pub uninstallers: bool,
 }
 
 /// installer config (raw from file)
@@ -79,9 +125,26 @@ pub struct InstallerLayer {
     pub shell: Option<BoolOr<ShellInstallerLayer>>,
     /// pkg installer
     pub pkg: Option<BoolOr<PkgInstallerLayer>>,
+    /// scoop installer
+    pub scoop: Option<BoolOr<ScoopInstallerLayer>>,
+    /// winget installer
+    pub winget: Option<BoolOr<WingetInstallerLayer>>,
+    /// deb installer
+    pub deb: Option<BoolOr<DebInstallerLayer>>,
+    /// rpm installer
+    pub rpm: Option<BoolOr<RpmInstallerLayer>>,
+    /// aur installer
+    pub aur: Option<BoolOr<AurInstallerLayer>>,
+    /// nix installer
+    pub nix: Option<BoolOr<NixInstallerLayer>>,
+    /// pypi installer
+    pub pypi: Option<BoolOr<PypiInstallerLayer>>,
     /// Whether to install an updater program alongside the software
     #[serde(skip_serializing_if = "Option::is_none")]
     pub updater: Option<bool>,
+    /// Whether to generate uninstallers next to the shell/powershell installers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uninstallers: Option<bool>,
 }
 impl InstallerConfigInheritable {
     /// defaults for a workspace
@@ -102,7 +165,15 @@ impl InstallerConfigInheritable {
             powershell: None,
             shell: None,
             pkg: None,
+            scoop: None,
+            winget: None,
+            deb: None,
+            rpm: None,
+            aur: None,
+            nix: None,
+            pypi: None,
             updater: false,
+            uninstallers: false,
         }
     }
     /// apply inheritance to and get final workspace config
@@ -113,6 +184,7 @@ impl InstallerConfigInheritable {
         let Self {
             // global
             updater,
+            uninstallers,
             // local-only
             common: _,
             homebrew: _,
@@ -121,9 +193,19 @@ impl InstallerConfigInheritable {
             powershell: _,
             shell: _,
             pkg: _,
+            scoop: _,
+            winget: _,
+            deb: _,
+            rpm: _,
+            aur: _,
+            nix: _,
+            pypi: _,
         } = self;
 
-        WorkspaceInstallerConfig { updater }
+        WorkspaceInstallerConfig {
+            updater,
+            uninstallers,
+        }
     }
     /// apply inheritance to get final package config
     pub fn apply_inheritance_for_package(
@@ -139,8 +221,16 @@ impl InstallerConfigInheritable {
             powershell,
             shell,
             pkg,
+            scoop,
+            winget,
+            deb,
+            rpm,
+            aur,
+            nix,
+            pypi,
             // global-only
             updater: _,
+            uninstallers: _,
         } = self;
         let homebrew = homebrew.map(|homebrew| {
             let mut default =
@@ -178,6 +268,48 @@ impl InstallerConfigInheritable {
             default.apply_layer(pkg);
             default
         });
+        let scoop = scoop.map(|scoop| {
+            let mut default =
+                ScoopInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(scoop);
+            default
+        });
+        let winget = winget.map(|winget| {
+            let mut default =
+                WingetInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(winget);
+            default
+        });
+        let deb = deb.map(|deb| {
+            let mut default =
+                DebInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(deb);
+            default
+        });
+        let rpm = rpm.map(|rpm| {
+            let mut default =
+                RpmInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(rpm);
+            default
+        });
+        let aur = aur.map(|aur| {
+            let mut default =
+                AurInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(aur);
+            default
+        });
+        let nix = nix.map(|nix| {
+            let mut default =
+                NixInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(nix);
+            default
+        });
+        let pypi = pypi.map(|pypi| {
+            let mut default =
+                PypiInstallerConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(pypi);
+            default
+        });
         AppInstallerConfig {
             homebrew,
             msi,
@@ -185,6 +317,13 @@ impl InstallerConfigInheritable {
             powershell,
             shell,
             pkg,
+            scoop,
+            winget,
+            deb,
+            rpm,
+            aur,
+            nix,
+            pypi,
         }
     }
 }
@@ -200,7 +339,15 @@ impl ApplyLayer for InstallerConfigInheritable {
             powershell,
             shell,
             pkg,
+            scoop,
+            winget,
+            deb,
+            rpm,
+            aur,
+            nix,
+            pypi,
             updater,
+            uninstallers,
         }: Self::Layer,
     ) {
         self.common.apply_layer(common);
@@ -210,7 +357,15 @@ impl ApplyLayer for InstallerConfigInheritable {
         self.powershell.apply_bool_layer(powershell);
         self.shell.apply_bool_layer(shell);
         self.pkg.apply_bool_layer(pkg);
+        self.scoop.apply_bool_layer(scoop);
+        self.winget.apply_bool_layer(winget);
+        self.deb.apply_bool_layer(deb);
+        self.rpm.apply_bool_layer(rpm);
+        self.aur.apply_bool_layer(aur);
+        self.nix.apply_bool_layer(nix);
+        self.pypi.apply_bool_layer(pypi);
         self.updater.apply_val(updater);
+        self.uninstallers.apply_val(uninstallers);
     }
 }
 
@@ -247,6 +402,11 @@ pub struct CommonInstallerLayer {
     /// Aliases to install binaries as
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bin_aliases: Option<SortedMap<String, Vec<String>>>,
+
+    /// A minisign public key that script installers should check the archives'
+    /// detached signatures (`<archive>.minisig`) against
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minisign_public_key: Option<String>,
 }
 /// inheritable installer fields (final)
 #[derive(Debug, Default, Clone)]
@@ -277,6 +437,10 @@ pub struct CommonInstallerConfig {
 
     /// Whether to install an updater program alongside the software
     pub install_updater: bool,
+
+    /// A minisign public key that script installers should check the archives'
+    /// detached signatures (`<archive>.minisig`) against
+    pub minisign_public_key: Option<String>,
 }
 impl CommonInstallerConfig {
     /// defaults
@@ -287,6 +451,7 @@ impl CommonInstallerConfig {
             install_libraries: Default::default(),
             bin_aliases: Default::default(),
             install_updater: false,
+            minisign_public_key: None,
         }
     }
 }
@@ -299,12 +464,14 @@ impl ApplyLayer for CommonInstallerConfig {
             install_success_msg,
             install_libraries,
             bin_aliases,
+            minisign_public_key,
         }: Self::Layer,
     ) {
         self.install_path.apply_val(install_path);
         self.install_success_msg.apply_val(install_success_msg);
         self.install_libraries.apply_val(install_libraries);
         self.bin_aliases.apply_val(bin_aliases);
+        self.minisign_public_key.apply_opt(minisign_public_key);
     }
 }
 impl ApplyLayer for CommonInstallerLayer {
@@ -316,11 +483,13 @@ impl ApplyLayer for CommonInstallerLayer {
             install_success_msg,
             install_libraries,
             bin_aliases,
+            minisign_public_key,
         }: Self::Layer,
     ) {
         self.install_path.apply_opt(install_path);
         self.install_success_msg.apply_opt(install_success_msg);
         self.install_libraries.apply_opt(install_libraries);
         self.bin_aliases.apply_opt(bin_aliases);
+        self.minisign_public_key.apply_opt(minisign_public_key);
     }
 }
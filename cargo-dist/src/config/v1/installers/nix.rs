@@ -0,0 +1,51 @@
+//! nix installer config
+
+use super::*;
+
+/// Options for nix installer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NixInstallerLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonInstallerLayer,
+}
+/// Options for nix installer
+#[derive(Debug, Default, Clone)]
+pub struct NixInstallerConfig {
+    /// Common options
+    pub common: CommonInstallerConfig,
+}
+
+impl NixInstallerConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonInstallerConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for NixInstallerConfig {
+    type Layer = NixInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for NixInstallerLayer {
+    type Layer = NixInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for NixInstallerConfig {
+    type Target = CommonInstallerConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -17,6 +17,11 @@ pub struct NpmInstallerLayer {
     /// A scope to prefix the npm package with (@ should be included).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scope: Option<String>,
+
+    /// Whether to publish an npm package per platform with the binaries inside,
+    /// instead of downloading the binaries when the package is installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform_packages: Option<bool>,
 }
 
 /// Options for npm installer (final)
@@ -30,6 +35,10 @@ pub struct NpmInstallerConfig {
 
     /// A scope to prefix the npm package with (@ should be included).
     pub scope: Option<String>,
+
+    /// Whether to publish an npm package per platform with the binaries inside,
+    /// instead of downloading the binaries when the package is installed
+    pub platform_packages: bool,
 }
 
 impl NpmInstallerConfig {
@@ -44,6 +53,7 @@ impl NpmInstallerConfig {
             common: common.clone(),
             package: pkg.name.clone(),
             scope: pkg.npm_scope.clone(),
+            platform_packages: false,
         }
     }
 }
@@ -56,11 +66,13 @@ impl ApplyLayer for NpmInstallerConfig {
             common,
             scope,
             package,
+            platform_packages,
         }: Self::Layer,
     ) {
         self.common.apply_layer(common);
         self.scope.apply_opt(scope);
         self.package.apply_val(package);
+        self.platform_packages.apply_val(platform_packages);
     }
 }
 impl ApplyLayer for NpmInstallerLayer {
@@ -71,11 +83,13 @@ impl ApplyLayer for NpmInstallerLayer {
             common,
             scope,
             package,
+            platform_packages,
         }: Self::Layer,
     ) {
         self.common.apply_layer(common);
         self.scope.apply_opt(scope);
         self.package.apply_opt(package);
+        self.platform_packages.apply_opt(platform_packages);
     }
 }
 
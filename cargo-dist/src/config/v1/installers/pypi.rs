@@ -0,0 +1,63 @@
+//! pypi installer config
+
+use super::*;
+
+/// Options for pypi installer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PypiInstallerLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonInstallerLayer,
+
+    /// The oldest glibc each linux-gnu target's binaries run on, like "2.17",
+    /// which picks the manylinux tag of its wheel
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manylinux: Option<SortedMap<TargetTriple, String>>,
+}
+/// Options for pypi installer
+#[derive(Debug, Default, Clone)]
+pub struct PypiInstallerConfig {
+    /// Common options
+    pub common: CommonInstallerConfig,
+
+    /// The oldest glibc each linux-gnu target's binaries run on, like "2.17",
+    /// which picks the manylinux tag of its wheel
+    pub manylinux: SortedMap<TargetTriple, String>,
+}
+
+impl PypiInstallerConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonInstallerConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+            manylinux: SortedMap::new(),
+        }
+    }
+}
+
+impl ApplyLayer for PypiInstallerConfig {
+    type Layer = PypiInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common, manylinux }: Self::Layer) {
+        self.common.apply_layer(common);
+        self.manylinux.apply_val(manylinux);
+    }
+}
+impl ApplyLayer for PypiInstallerLayer {
+    type Layer = PypiInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common, manylinux }: Self::Layer) {
+        self.common.apply_layer(common);
+        self.manylinux.apply_opt(manylinux);
+    }
+}
+
+impl std::ops::Deref for PypiInstallerConfig {
+    type Target = CommonInstallerConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
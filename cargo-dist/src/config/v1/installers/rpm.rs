@@ -0,0 +1,51 @@
+//! rpm installer config
+
+use super::*;
+
+/// Options for rpm installer
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct RpmInstallerLayer {
+    /// Common options
+    #[serde(flatten)]
+    pub common: CommonInstallerLayer,
+}
+/// Options for rpm installer
+#[derive(Debug, Default, Clone)]
+pub struct RpmInstallerConfig {
+    /// Common options
+    pub common: CommonInstallerConfig,
+}
+
+impl RpmInstallerConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonInstallerConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for RpmInstallerConfig {
+    type Layer = RpmInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for RpmInstallerLayer {
+    type Layer = RpmInstallerLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for RpmInstallerConfig {
+    type Target = CommonInstallerConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
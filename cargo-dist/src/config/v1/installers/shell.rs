@@ -9,12 +9,21 @@ pub struct ShellInstallerLayer {
     /// Common options
     #[serde(flatten)]
     pub common: CommonInstallerLayer,
+
+    /// Whether to also generate an offline installer that carries the archives
+    /// with it, instead of downloading them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline: Option<bool>,
 }
 /// Options for shell installer
 #[derive(Debug, Default, Clone)]
 pub struct ShellInstallerConfig {
     /// Common options
     pub common: CommonInstallerConfig,
+
+    /// Whether to also generate an offline installer that carries the archives
+    /// with it, instead of downloading them
+    pub offline: bool,
 }
 
 impl ShellInstallerConfig {
@@ -26,20 +35,23 @@ impl ShellInstallerConfig {
     ) -> Self {
         Self {
             common: common.clone(),
+            offline: false,
         }
     }
 }
 
 impl ApplyLayer for ShellInstallerConfig {
     type Layer = ShellInstallerLayer;
-    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+    fn apply_layer(&mut self, Self::Layer { common, offline }: Self::Layer) {
         self.common.apply_layer(common);
+        self.offline.apply_val(offline);
     }
 }
 impl ApplyLayer for ShellInstallerLayer {
     type Layer = ShellInstallerLayer;
-    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+    fn apply_layer(&mut self, Self::Layer { common, offline }: Self::Layer) {
         self.common.apply_layer(common);
+        self.offline.apply_opt(offline);
     }
 }
 
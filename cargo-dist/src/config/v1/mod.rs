@@ -110,6 +110,7 @@ pub mod builds;
 pub mod ci;
 pub mod hosts;
 pub mod installers;
+pub mod net;
 pub mod publishers;
 
 use axoproject::{PackageIdx, WorkspaceGraph};
@@ -123,6 +124,7 @@ use builds::*;
 use ci::*;
 use hosts::*;
 use installers::*;
+use net::*;
 use publishers::*;
 
 /// Compute the workspace-level config
@@ -173,6 +175,8 @@ pub struct WorkspaceConfig {
     pub builds: WorkspaceBuildConfig,
     /// installer config
     pub installers: WorkspaceInstallerConfig,
+    /// network config
+    pub net: NetConfig,
 }
 /// config that is global to the entire workspace
 ///
@@ -193,6 +197,8 @@ pub struct WorkspaceConfigInheritable {
     pub builds: BuildConfigInheritable,
     /// installer config
     pub installers: InstallerConfigInheritable,
+    /// network config
+    pub net: NetConfig,
 }
 impl WorkspaceConfigInheritable {
     /// Get the defaults for workspace-level config
@@ -203,6 +209,7 @@ impl WorkspaceConfigInheritable {
             hosts: HostConfigInheritable::defaults_for_workspace(workspaces),
             builds: BuildConfigInheritable::defaults_for_workspace(workspaces),
             installers: InstallerConfigInheritable::defaults_for_workspace(workspaces),
+            net: NetConfig::defaults_for_workspace(workspaces),
             dist_version: None,
             allow_dirty: vec![],
         }
@@ -215,6 +222,7 @@ impl WorkspaceConfigInheritable {
             hosts,
             builds,
             installers,
+            net,
             dist_version,
             allow_dirty,
         } = self;
@@ -224,6 +232,7 @@ impl WorkspaceConfigInheritable {
             hosts: hosts.apply_inheritance_for_workspace(workspaces),
             builds: builds.apply_inheritance_for_workspace(workspaces),
             installers: installers.apply_inheritance_for_workspace(workspaces),
+            net,
             dist_version,
             allow_dirty,
         }
@@ -239,6 +248,7 @@ impl ApplyLayer for WorkspaceConfigInheritable {
             hosts,
             installers,
             ci,
+            net,
             allow_dirty,
             dist_version,
             // app-scope only
@@ -252,6 +262,7 @@ impl ApplyLayer for WorkspaceConfigInheritable {
         self.hosts.apply_val_layer(hosts);
         self.installers.apply_val_layer(installers);
         self.ci.apply_val_layer(ci);
+        self.net.apply_val_layer(net);
         self.dist_version.apply_opt(dist_version);
         self.allow_dirty.apply_val(allow_dirty);
     }
@@ -348,6 +359,7 @@ impl ApplyLayer for AppConfigInheritable {
             targets,
             // workspace-scope only
             ci: _,
+            net: _,
             allow_dirty: _,
             dist_version: _,
         }: Self::Layer,
@@ -424,6 +436,9 @@ pub struct TomlLayer {
     /// publisher config
     #[serde(skip_serializing_if = "Option::is_none")]
     pub publishers: Option<PublisherLayer>,
+    /// network config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub net: Option<NetLayer>,
 }
 
 impl TomlLayer {
@@ -459,6 +474,13 @@ impl TomlLayer {
                 }
             }
         }
+        if let Some(net) = &mut self.net {
+            if let Some(ca_certs) = &mut net.ca_certs {
+                for path in ca_certs {
+                    make_path_relative_to(path, base_path);
+                }
+            }
+        }
     }
 }
 
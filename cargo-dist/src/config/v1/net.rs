@@ -0,0 +1,112 @@
+//! network config
+
+use super::*;
+
+/// network config (final)
+#[derive(Debug, Clone)]
+pub struct NetConfig {
+    /// A proxy to send all http requests through
+    pub proxy: Option<String>,
+    /// Extra CA certificates (PEM files) to trust
+    pub ca_certs: Vec<Utf8PathBuf>,
+    /// How long to wait to connect to a server, in seconds
+    pub connect_timeout: Option<u64>,
+    /// How long to wait for a server to send more data, in seconds
+    pub read_timeout: Option<u64>,
+    /// How many times to retry a failed fetch
+    pub retries: u32,
+    /// How long to wait before the first retry, in seconds (doubles each retry)
+    pub retry_backoff: u64,
+}
+
+/// network config (raw)
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct NetLayer {
+    /// A proxy to send all http requests through (e.g. "http://proxy.internal:3128")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// Extra CA certificates (PEM files) to trust, for TLS-intercepting proxies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_certs: Option<Vec<Utf8PathBuf>>,
+
+    /// How long to wait to connect to a server, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+
+    /// How long to wait for a server to send more data, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_timeout: Option<u64>,
+
+    /// How many times to retry a failed fetch (default 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+
+    /// How long to wait before the first retry, in seconds (default 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_backoff: Option<u64>,
+}
+
+impl NetConfig {
+    /// Get defaults for the given workspace
+    pub fn defaults_for_workspace(_workspaces: &WorkspaceGraph) -> Self {
+        Self::default()
+    }
+}
+
+impl Default for NetConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            ca_certs: vec![],
+            connect_timeout: None,
+            read_timeout: None,
+            retries: 3,
+            retry_backoff: 1,
+        }
+    }
+}
+
+impl ApplyLayer for NetConfig {
+    type Layer = NetLayer;
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            proxy,
+            ca_certs,
+            connect_timeout,
+            read_timeout,
+            retries,
+            retry_backoff,
+        }: Self::Layer,
+    ) {
+        self.proxy.apply_opt(proxy);
+        self.ca_certs.apply_val(ca_certs);
+        self.connect_timeout.apply_opt(connect_timeout);
+        self.read_timeout.apply_opt(read_timeout);
+        self.retries.apply_val(retries);
+        self.retry_backoff.apply_val(retry_backoff);
+    }
+}
+impl ApplyLayer for NetLayer {
+    type Layer = NetLayer;
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            proxy,
+            ca_certs,
+            connect_timeout,
+            read_timeout,
+            retries,
+            retry_backoff,
+        }: Self::Layer,
+    ) {
+        self.proxy.apply_opt(proxy);
+        self.ca_certs.apply_opt(ca_certs);
+        self.connect_timeout.apply_opt(connect_timeout);
+        self.read_timeout.apply_opt(read_timeout);
+        self.retries.apply_opt(retries);
+        self.retry_backoff.apply_opt(retry_backoff);
+    }
+}
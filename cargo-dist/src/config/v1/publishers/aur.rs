@@ -0,0 +1,49 @@
+//! aur publisher config
+
+use super::*;
+
+/// Options for aur publishes
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct AurPublisherLayer {
+    /// Common options
+    pub common: CommonPublisherLayer,
+}
+/// Options for aur publishes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AurPublisherConfig {
+    /// Common options
+    pub common: CommonPublisherConfig,
+}
+
+impl AurPublisherConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonPublisherConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for AurPublisherConfig {
+    type Layer = AurPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for AurPublisherLayer {
+    type Layer = AurPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for AurPublisherConfig {
+    type Target = CommonPublisherConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
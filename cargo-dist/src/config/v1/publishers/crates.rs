@@ -0,0 +1,49 @@
+//! crates.io publisher config
+
+use super::*;
+
+/// Options for crates.io publishes
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CratesPublisherLayer {
+    /// Common options
+    pub common: CommonPublisherLayer,
+}
+/// Options for crates.io publishes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CratesPublisherConfig {
+    /// Common options
+    pub common: CommonPublisherConfig,
+}
+
+impl CratesPublisherConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonPublisherConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for CratesPublisherConfig {
+    type Layer = CratesPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for CratesPublisherLayer {
+    type Layer = CratesPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for CratesPublisherConfig {
+    type Target = CommonPublisherConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -1,12 +1,20 @@
 //! publisher config
 
+pub mod aur;
+pub mod crates;
 pub mod homebrew;
 pub mod npm;
+pub mod pypi;
+pub mod scoop;
 
 use super::*;
 
+use aur::*;
+use crates::*;
 use homebrew::*;
 use npm::*;
+use pypi::*;
+use scoop::*;
 
 /// the final publisher config
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -15,6 +23,14 @@ pub struct PublisherConfig {
     pub homebrew: Option<HomebrewPublisherConfig>,
     /// npm publisher
     pub npm: Option<NpmPublisherConfig>,
+    /// crates.io publisher
+    pub crates: Option<CratesPublisherConfig>,
+    /// scoop publisher
+    pub scoop: Option<ScoopPublisherConfig>,
+    /// aur publisher
+    pub aur: Option<AurPublisherConfig>,
+    /// pypi publisher
+    pub pypi: Option<PypiPublisherConfig>,
 }
 
 /// the publisher config
@@ -28,6 +44,14 @@ pub struct PublisherConfigInheritable {
     pub homebrew: Option<HomebrewPublisherLayer>,
     /// npm publisher
     pub npm: Option<NpmPublisherLayer>,
+    /// crates.io publisher
+    pub crates: Option<CratesPublisherLayer>,
+    /// scoop publisher
+    pub scoop: Option<ScoopPublisherLayer>,
+    /// aur publisher
+    pub aur: Option<AurPublisherLayer>,
+    /// pypi publisher
+    pub pypi: Option<PypiPublisherLayer>,
 }
 
 /// "raw" publisher config from presum
@@ -40,6 +64,14 @@ pub struct PublisherLayer {
     pub homebrew: Option<BoolOr<HomebrewPublisherLayer>>,
     /// npm publisher
     pub npm: Option<BoolOr<NpmPublisherLayer>>,
+    /// crates.io publisher
+    pub crates: Option<BoolOr<CratesPublisherLayer>>,
+    /// scoop publisher
+    pub scoop: Option<BoolOr<ScoopPublisherLayer>>,
+    /// aur publisher
+    pub aur: Option<BoolOr<AurPublisherLayer>>,
+    /// pypi publisher
+    pub pypi: Option<BoolOr<PypiPublisherLayer>>,
 }
 impl PublisherConfigInheritable {
     /// get the defaults for a given package
@@ -48,6 +80,10 @@ impl PublisherConfigInheritable {
             common: CommonPublisherConfig::defaults_for_package(workspaces, pkg_idx),
             homebrew: None,
             npm: None,
+            crates: None,
+            scoop: None,
+            aur: None,
+            pypi: None,
         }
     }
     /// fold the inherited fields in to get the final publisher config
@@ -60,6 +96,10 @@ impl PublisherConfigInheritable {
             common,
             homebrew,
             npm,
+            crates,
+            scoop,
+            aur,
+            pypi,
         } = self;
         let homebrew = homebrew.map(|homebrew| {
             let mut default =
@@ -73,7 +113,38 @@ impl PublisherConfigInheritable {
             default.apply_layer(npm);
             default
         });
-        PublisherConfig { homebrew, npm }
+        let crates = crates.map(|crates| {
+            let mut default =
+                CratesPublisherConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(crates);
+            default
+        });
+        let scoop = scoop.map(|scoop| {
+            let mut default =
+                ScoopPublisherConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(scoop);
+            default
+        });
+        let aur = aur.map(|aur| {
+            let mut default =
+                AurPublisherConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(aur);
+            default
+        });
+        let pypi = pypi.map(|pypi| {
+            let mut default =
+                PypiPublisherConfig::defaults_for_package(workspaces, pkg_idx, &common);
+            default.apply_layer(pypi);
+            default
+        });
+        PublisherConfig {
+            homebrew,
+            npm,
+            crates,
+            scoop,
+            aur,
+            pypi,
+        }
     }
 }
 impl ApplyLayer for PublisherConfigInheritable {
@@ -84,11 +155,19 @@ impl ApplyLayer for PublisherConfigInheritable {
             common,
             homebrew,
             npm,
+            crates,
+            scoop,
+            aur,
+            pypi,
         }: Self::Layer,
     ) {
         self.common.apply_layer(common);
         self.homebrew.apply_bool_layer(homebrew);
         self.npm.apply_bool_layer(npm);
+        self.crates.apply_bool_layer(crates);
+        self.scoop.apply_bool_layer(scoop);
+        self.aur.apply_bool_layer(aur);
+        self.pypi.apply_bool_layer(pypi);
     }
 }
 
@@ -2,17 +2,35 @@
 
 use super::*;
 
+/// The registry npm packages are published to by default
+pub const NPM_DEFAULT_REGISTRY: &str = "https://registry.npmjs.org";
+
 /// Options for npm publishes
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub struct NpmPublisherLayer {
     /// Common options
     pub common: CommonPublisherLayer,
+
+    /// The registry to publish to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+
+    /// The name of the secret (or CI variable) with the token to publish with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_env_var: Option<String>,
 }
 /// Options for npm publishes
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct NpmPublisherConfig {
     /// Common options
     pub common: CommonPublisherConfig,
+
+    /// The registry to publish to
+    pub registry: String,
+
+    /// The name of the secret (or CI variable) with the token to publish with
+    pub token_env_var: String,
 }
 
 impl NpmPublisherConfig {
@@ -24,20 +42,40 @@ impl NpmPublisherConfig {
     ) -> Self {
         Self {
             common: common.clone(),
+            registry: NPM_DEFAULT_REGISTRY.to_owned(),
+            token_env_var: "NPM_TOKEN".to_owned(),
         }
     }
 }
 
 impl ApplyLayer for NpmPublisherConfig {
     type Layer = NpmPublisherLayer;
-    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            common,
+            registry,
+            token_env_var,
+        }: Self::Layer,
+    ) {
         self.common.apply_layer(common);
+        self.registry.apply_val(registry);
+        self.token_env_var.apply_val(token_env_var);
     }
 }
 impl ApplyLayer for NpmPublisherLayer {
     type Layer = NpmPublisherLayer;
-    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+    fn apply_layer(
+        &mut self,
+        Self::Layer {
+            common,
+            registry,
+            token_env_var,
+        }: Self::Layer,
+    ) {
         self.common.apply_layer(common);
+        self.registry.apply_opt(registry);
+        self.token_env_var.apply_opt(token_env_var);
     }
 }
 
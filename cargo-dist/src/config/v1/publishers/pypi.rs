@@ -0,0 +1,49 @@
+//! pypi publisher config
+
+use super::*;
+
+/// Options for PyPI publishes
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PypiPublisherLayer {
+    /// Common options
+    pub common: CommonPublisherLayer,
+}
+/// Options for PyPI publishes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PypiPublisherConfig {
+    /// Common options
+    pub common: CommonPublisherConfig,
+}
+
+impl PypiPublisherConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonPublisherConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for PypiPublisherConfig {
+    type Layer = PypiPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for PypiPublisherLayer {
+    type Layer = PypiPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for PypiPublisherConfig {
+    type Target = CommonPublisherConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
@@ -0,0 +1,49 @@
+//! scoop publisher config
+
+use super::*;
+
+/// Options for scoop publishes
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ScoopPublisherLayer {
+    /// Common options
+    pub common: CommonPublisherLayer,
+}
+/// Options for scoop publishes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScoopPublisherConfig {
+    /// Common options
+    pub common: CommonPublisherConfig,
+}
+
+impl ScoopPublisherConfig {
+    /// Get defaults for the given package
+    pub fn defaults_for_package(
+        _workspaces: &WorkspaceGraph,
+        _pkg_idx: PackageIdx,
+        common: &CommonPublisherConfig,
+    ) -> Self {
+        Self {
+            common: common.clone(),
+        }
+    }
+}
+
+impl ApplyLayer for ScoopPublisherConfig {
+    type Layer = ScoopPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+impl ApplyLayer for ScoopPublisherLayer {
+    type Layer = ScoopPublisherLayer;
+    fn apply_layer(&mut self, Self::Layer { common }: Self::Layer) {
+        self.common.apply_layer(common);
+    }
+}
+
+impl std::ops::Deref for ScoopPublisherConfig {
+    type Target = CommonPublisherConfig;
+    fn deref(&self) -> &Self::Target {
+        &self.common
+    }
+}
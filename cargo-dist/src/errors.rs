@@ -71,6 +71,10 @@ pub enum DistError {
     #[error(transparent)]
     ParseIntError(#[from] std::num::ParseIntError),
 
+    /// random cargo metadata error
+    #[error(transparent)]
+    CargoMetadata(#[from] cargo_metadata::Error),
+
     /// A problem with a jinja template, which is always a dist bug
     #[error("Failed to render template")]
     #[diagnostic(help("this is a bug in dist, let us know and we'll fix it: https://github.com/axodotdev/cargo-dist/issues/new"))]
@@ -155,6 +159,337 @@ pub enum DistError {
         inner: AxoprojectError,
     },
 
+    /// We got a repository URL but couldn't interpret it as a repo on a Gitea instance
+    #[error("Gitea hosting requires a repository URL like https://gitea.example.com/owner/repo")]
+    CantEnableGiteaUrlNotForge {
+        /// inner error that caught this
+        #[diagnostic_source]
+        inner: AxoprojectError,
+    },
+
+    /// Gitea hosting was used without a token to talk to the API with
+    #[error("Gitea hosting requires an API token, but {var} isn't set")]
+    #[diagnostic(help("Set {var} to a token with write access to the repository"))]
+    GiteaTokenMissing {
+        /// The environment variable that should be set
+        var: &'static str,
+    },
+
+    /// A request to the Gitea API failed
+    #[error("Request to the Gitea API failed: {url}")]
+    #[diagnostic(help("{details}"))]
+    GiteaRequestFailed {
+        /// The URL we requested
+        url: String,
+        /// What went wrong
+        details: String,
+    },
+
+    /// The Gitea Release we wanted to publish doesn't exist
+    #[error("Couldn't find a Gitea Release for {tag}")]
+    #[diagnostic(help("The release is created by `dist host --steps=upload`, did that run?"))]
+    GiteaReleaseMissing {
+        /// The tag of the release
+        tag: String,
+    },
+
+    /// S3 hosting was enabled without saying which bucket to use
+    #[error("S3 hosting is enabled, but no bucket was specified")]
+    #[diagnostic(help("Set s3-bucket in your dist config"))]
+    S3BucketMissing,
+
+    /// S3 hosting was used without credentials to sign requests with
+    #[error("S3 hosting requires credentials, but {var} isn't set")]
+    #[diagnostic(help("Set AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY to credentials with write access to the bucket"))]
+    S3CredentialsMissing {
+        /// The environment variable that should be set
+        var: &'static str,
+    },
+
+    /// A request to the S3 API failed
+    #[error("Request to S3 failed: {url}")]
+    #[diagnostic(help("{details}"))]
+    S3RequestFailed {
+        /// The URL we requested
+        url: String,
+        /// What went wrong
+        details: String,
+    },
+
+    /// The configured http proxy couldn't be used
+    #[error("couldn't use {proxy} as an http proxy")]
+    InvalidHttpProxy {
+        /// The proxy
+        proxy: String,
+        /// What went wrong
+        #[source]
+        details: axoasset::reqwest::Error,
+    },
+
+    /// A configured CA certificate couldn't be used
+    #[error("couldn't load CA certificates from {path}")]
+    #[diagnostic(help("http-ca-certs should be PEM files"))]
+    InvalidCaCert {
+        /// The file of certificates
+        path: Utf8PathBuf,
+        /// What went wrong
+        #[source]
+        details: axoasset::reqwest::Error,
+    },
+
+    /// Axodotdev hosting can't apply a network setting
+    #[error("axodotdev hosting doesn't support {setting}")]
+    #[diagnostic(help("axodotdev hosting makes its own http requests, which can only use the HTTPS_PROXY environment variable and the standard CA certificates; unset {setting} (and its DIST_HTTP_* or SSL_CERT_FILE environment variable) to use it"))]
+    AxodotdevHostingUnsupportedNetSetting {
+        /// The setting
+        setting: &'static str,
+    },
+
+    /// A DIST_HTTP_* environment variable wasn't a number
+    #[error("{var} should be a number, but it was set to {value}")]
+    InvalidHttpEnvVar {
+        /// The environment variable
+        var: &'static str,
+        /// Its value
+        value: String,
+    },
+
+    /// Something needed the network in --offline mode
+    #[error("{what} needs network access, but dist is running in offline mode")]
+    #[diagnostic(help("remove --offline (or unset DIST_OFFLINE) to allow network access"))]
+    Offline {
+        /// The thing that needed the network
+        what: String,
+    },
+
+    /// A tool wasn't in the tool cache in --offline mode
+    #[error("{tool} isn't in the tool cache, and dist is running in offline mode")]
+    #[diagnostic(help("run `dist cache fill` on a machine with network access and copy its tool cache here (looked for {path}, set DIST_CACHE_DIR to look elsewhere)"))]
+    OfflineCacheMiss {
+        /// The tool we needed
+        tool: String,
+        /// Where we looked for it
+        path: Utf8PathBuf,
+    },
+
+    /// We don't know where a cargo registry's index is
+    #[error("couldn't find the index of the {registry} registry")]
+    #[diagnostic(help("pass --index, or set CARGO_REGISTRIES_<NAME>_INDEX like cargo expects"))]
+    CratesUnknownIndex {
+        /// The registry
+        registry: String,
+    },
+
+    /// A cargo registry index we can't read
+    #[error("{index} isn't a sparse registry index")]
+    #[diagnostic(help(
+        "dist checks for already-published versions through the sparse protocol, pass a sparse+https:// index with --index"
+    ))]
+    CratesIndexNotSparse {
+        /// The index
+        index: String,
+    },
+
+    /// Checking a registry index didn't work out
+    #[error("failed to check {url} for already-published versions: {status}")]
+    CratesIndexCheckFailed {
+        /// The url of the index file
+        url: String,
+        /// What went wrong
+        status: String,
+    },
+
+    /// We couldn't come up with a WinGet PackageIdentifier
+    #[error("couldn't pick a WinGet package identifier for {app_name}")]
+    #[diagnostic(help(
+        "set winget-package-identifier (in Publisher.Package format), or add a GitHub repository or authors to Cargo.toml"
+    ))]
+    WingetNoPackageIdentifier {
+        /// The app
+        app_name: String,
+    },
+
+    /// The WinGet manifests wouldn't pass validation
+    #[error("the WinGet manifests for {package_identifier} failed linting")]
+    #[diagnostic(help("{problems}"))]
+    WingetManifestLint {
+        /// The package
+        package_identifier: String,
+        /// Everything that's wrong with them
+        problems: String,
+    },
+
+    /// A WinGet installer's checksum isn't known
+    #[error("couldn't find the sha256 of {artifact_id} for its WinGet manifest")]
+    #[diagnostic(help(
+        "WinGet manifests are built after the Windows artifacts, so put {artifact_id} in the dist dir or its checksum in the dist-manifest.json first"
+    ))]
+    WingetMissingChecksum {
+        /// The artifact with no checksum
+        artifact_id: String,
+    },
+
+    /// `dist winget` was run without any WinGet installers enabled
+    #[error("no WinGet manifests to generate")]
+    #[diagnostic(help("add \"winget\" to installers, and make sure you're building for Windows"))]
+    NoWingetInstallers,
+
+    /// A deb has nobody to name as its maintainer
+    #[error("can't make a deb for {app_name}, it has no authors to name as its Maintainer")]
+    #[diagnostic(help(
+        "add authors to Cargo.toml, the first one is used as the Maintainer (like \"Jane Doe <jane@example.com>\")"
+    ))]
+    DebNoMaintainer {
+        /// The app
+        app_name: String,
+    },
+
+    /// pypi-manylinux has a glibc version we can't make sense of
+    #[error("pypi-manylinux for {target} is {version:?}, which isn't a glibc version")]
+    #[diagnostic(help("use the oldest glibc your binaries run on, like \"2.17\""))]
+    PypiManylinuxInvalid {
+        /// The target it was set for
+        target: TargetTriple,
+        /// The bad value
+        version: String,
+    },
+
+    /// A manylinux wheel's binaries were built against a newer glibc than its tag claims
+    #[error("the pypi wheel for {target} is tagged for glibc {manylinux}, but its binaries were built against glibc {built_with}")]
+    #[diagnostic(help(
+        "set pypi-manylinux for {target} to \"{built_with}\", or build on a machine with an older glibc"
+    ))]
+    PypiGlibcTooNew {
+        /// The target the wheel is for
+        target: TargetTriple,
+        /// The glibc version in the wheel's tag
+        manylinux: String,
+        /// The glibc version the binaries were built against
+        built_with: String,
+    },
+
+    /// Mirror hosting was enabled without saying where the mirror is served from
+    #[error("mirror hosting is enabled, but no mirror-url was specified")]
+    #[diagnostic(help(
+        "Set mirror-url to the URL the mirror's root directory will be served from"
+    ))]
+    MirrorUrlMissing,
+
+    /// download-urls names a host that isn't enabled
+    #[error("download-urls includes {host}, but {host} hosting isn't enabled")]
+    #[diagnostic(help("Add {host} to hosting, or remove it from download-urls"))]
+    DownloadUrlsHostDisabled {
+        /// The host in question
+        host: String,
+    },
+
+    /// A release being mirrored doesn't say where its files are
+    #[error("can't mirror {app_name}, its dist-manifest.json doesn't say where its artifacts are hosted")]
+    MirrorNoDownloadUrl {
+        /// The app in question
+        app_name: String,
+    },
+
+    /// Trying to run dist mirror in a random dir
+    #[error("`dist mirror` needs to be run in a project")]
+    #[diagnostic(help(
+        "the installers are re-rendered with the project's dist config, so run this in a checkout of the project that made the release"
+    ))]
+    MirrorNotInWorkspace {
+        /// The report about the missing workspace
+        #[diagnostic_source]
+        cause: axoproject::errors::ProjectError,
+    },
+
+    /// A file downloaded for a mirror didn't match its checksum
+    #[error("{name} doesn't match the {algorithm} checksum in the dist-manifest.json")]
+    #[diagnostic(help("expected {expected}, got {actual}"))]
+    MirrorChecksumMismatch {
+        /// The file in question
+        name: String,
+        /// The checksum algorithm
+        algorithm: String,
+        /// The checksum the manifest says the file should have
+        expected: String,
+        /// The checksum the file actually has
+        actual: String,
+    },
+
+    /// An offline installer needs an archive that hasn't been built
+    #[error("the offline shell installer needs {archive}, but it isn't in {dist_dir}")]
+    #[diagnostic(help(
+        "build (or download) the local artifacts before building the global artifacts"
+    ))]
+    OfflineInstallerMissingArchive {
+        /// The archive in question
+        archive: String,
+        /// Where we looked for it
+        dist_dir: Utf8PathBuf,
+    },
+
+    /// apt/yum repositories were enabled without a host that can serve them
+    #[error("apt and yum repositories can only be published with s3 or mirror hosting")]
+    #[diagnostic(help("add \"s3\" or \"mirror\" to hosting, or turn off apt-repo and yum-repo"))]
+    PackageRepoNeedsTree,
+
+    /// A package couldn't be read to add it to the apt/yum repositories
+    #[error("couldn't read {name} to add it to the package repositories")]
+    #[diagnostic(help("only packages built by dist are supported"))]
+    PackageRepoBadPackage {
+        /// The package in question
+        name: String,
+    },
+
+    /// oci-base-image isn't a reference we can pull
+    #[error("oci-base-image = \"{reference}\" isn't pinned to a digest")]
+    #[diagnostic(help(
+        "use a reference like gcr.io/distroless/cc-debian12@sha256:..., so that every build gets the same base"
+    ))]
+    OciBaseImageInvalid {
+        /// The configured value
+        reference: String,
+    },
+
+    /// The base image of an OCI image doesn't support the image's platform
+    #[error("{reference} doesn't have an image for {platform}")]
+    #[diagnostic(help("pick an oci-base-image that supports all of your linux-gnu targets"))]
+    OciBaseImageNoPlatform {
+        /// The base image
+        reference: String,
+        /// The OCI platform we needed (`linux/arm64`)
+        platform: String,
+    },
+
+    /// A request to a container registry failed
+    #[error("request to a container registry failed: {url}")]
+    #[diagnostic(help("{details}"))]
+    OciRegistryRequestFailed {
+        /// The URL we requested
+        url: String,
+        /// What went wrong
+        details: String,
+    },
+
+    /// A blob we fetched (or cached) isn't the blob we asked for
+    #[error("{url} doesn't have the digest it should")]
+    #[diagnostic(help("expected {expected}, got {actual}"))]
+    OciDigestMismatch {
+        /// Where the blob came from
+        url: String,
+        /// The digest we asked for
+        expected: String,
+        /// The digest of what we got
+        actual: String,
+    },
+
+    /// An OCI image we built couldn't be read back to make the multi-arch image
+    #[error("couldn't read the OCI image {path}")]
+    #[diagnostic(help("only images built by dist are supported"))]
+    OciImageUnreadable {
+        /// The image in question
+        path: Utf8PathBuf,
+    },
+
     /// User supplied an illegal npm scope
     #[error("The npm-scope field must be an all-lowercase value; the supplied value was {scope}")]
     ScopeMustBeLowercase {
@@ -208,6 +543,14 @@ pub enum DistError {
         packages: Vec<camino::Utf8PathBuf>,
     },
 
+    /// packages disagreed on scoop buckets
+    #[error("different scoop buckets were set in your workspace, this is currently unsupported")]
+    #[diagnostic(help("these packages disagree:\n{packages:#?}"))]
+    MismatchedScoopBuckets {
+        /// paths of problem manifests
+        packages: Vec<camino::Utf8PathBuf>,
+    },
+
     /// packages disagreed on publishers
     #[error("different publisher setttings were in your workspace, this is currently unuspported")]
     #[diagnostic(help("these packages disagree:\n{packages:#?}"))]
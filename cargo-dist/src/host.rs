@@ -1,5 +1,10 @@
 //! Details for hosting artifacts
 
+pub(crate) mod gitea;
+pub(crate) mod mirror;
+pub(crate) mod pkgrepo;
+pub(crate) mod s3;
+
 use crate::{
     announce::{announcement_axodotdev, announcement_github, AnnouncementTag},
     check_integrity,
@@ -10,11 +15,11 @@ use crate::{
     errors::DistResult,
     gather_work,
     manifest::save_manifest,
-    net::create_gazenot_client,
+    net::{create_gazenot_client, with_retries},
     DistError, DistGraph, DistGraphBuilder, HostingInfo,
 };
 use axoproject::WorkspaceGraph;
-use cargo_dist_schema::{DistManifest, Hosting};
+use cargo_dist_schema::{DistManifest, GiteaHosting, Hosting};
 use gazenot::{AnnouncementKey, Gazenot};
 
 /// Do hosting
@@ -63,6 +68,37 @@ pub fn do_host(cfg: &Config, host_args: HostArgs) -> DistResult<DistManifest> {
                 HostingStyle::Github => {
                     // implemented in CI backend
                 }
+                HostingStyle::Gitea => {
+                    if host_args.steps.contains(&HostStyle::Upload) {
+                        save_manifest(&dist.dist_dir.join("dist-manifest.json"), &manifest)?;
+                        gitea::upload_to_gitea(&dist, &manifest)?;
+                    }
+                    if host_args.steps.contains(&HostStyle::Release) {
+                        gitea::release_gitea(&dist, &manifest)?;
+                    }
+                    // Like Github Releases, there's nothing left to do on Announce
+                }
+                HostingStyle::S3 => {
+                    if host_args.steps.contains(&HostStyle::Upload) {
+                        save_manifest(&dist.dist_dir.join("dist-manifest.json"), &manifest)?;
+                        s3::upload_to_s3(&dist, &manifest)?;
+                    }
+                    if host_args.steps.contains(&HostStyle::Release) {
+                        s3::release_s3(&dist, &manifest)?;
+                        pkgrepo::publish_to_s3(&dist, &manifest)?;
+                    }
+                    // Buckets don't have any notion of announcing
+                }
+                HostingStyle::Mirror => {
+                    if host_args.steps.contains(&HostStyle::Upload) {
+                        save_manifest(&dist.dist_dir.join("dist-manifest.json"), &manifest)?;
+                        mirror::copy_to_mirror(&dist, &manifest)?;
+                    }
+                    if host_args.steps.contains(&HostStyle::Release) {
+                        mirror::release_mirror(&dist, &manifest)?;
+                        pkgrepo::publish_to_mirror(&dist, &manifest)?;
+                    }
+                }
             }
         }
     }
@@ -81,10 +117,20 @@ impl<'a> DistGraphBuilder<'a> {
     ) -> DistResult<()> {
         let mut ci = vec![];
         {
-            let CiConfig { github } = &self.inner.config.ci;
+            let CiConfig {
+                github,
+                gitlab,
+                forgejo,
+            } = &self.inner.config.ci;
             if github.is_some() {
                 ci.push(CiStyle::Github);
             }
+            if gitlab.is_some() {
+                ci.push(CiStyle::Gitlab);
+            }
+            if forgejo.is_some() {
+                ci.push(CiStyle::Forgejo);
+            }
         }
 
         let mut hosting = vec![];
@@ -92,14 +138,32 @@ impl<'a> DistGraphBuilder<'a> {
             let WorkspaceHostConfig {
                 github,
                 axodotdev,
+                gitea,
+                s3,
+                mirror,
+                package_repo,
                 force_latest: _,
+                download_urls: _,
             } = &self.inner.config.hosts;
+            // The repositories live next to the releases, so the host needs to be a tree
+            if package_repo.is_some() && s3.is_none() && mirror.is_none() {
+                return Err(DistError::PackageRepoNeedsTree);
+            }
             if github.is_some() {
                 hosting.push(HostingStyle::Github);
             }
             if axodotdev.is_some() {
                 hosting.push(HostingStyle::Axodotdev);
             }
+            if gitea.is_some() {
+                hosting.push(HostingStyle::Gitea);
+            }
+            if s3.is_some() {
+                hosting.push(HostingStyle::S3);
+            }
+            if mirror.is_some() {
+                hosting.push(HostingStyle::Mirror);
+            }
         }
         let hosting = if hosting.is_empty() {
             None
@@ -163,8 +227,12 @@ impl<'a> DistGraphBuilder<'a> {
                             &hosting.source_host,
                             &hosting.owner,
                         )?;
-                        tokio::runtime::Handle::current()
-                            .block_on(abyss.create_artifact_sets(packages))?
+                        let packages = packages.collect::<Vec<_>>();
+                        tokio::runtime::Handle::current().block_on(with_retries(
+                            &self.inner.client_settings,
+                            "creating axodotdev artifact sets",
+                            || abyss.create_artifact_sets(packages.clone()),
+                        ))?
                     } else {
                         packages.map(gazenot::ArtifactSet::mock).collect()
                     };
@@ -197,6 +265,72 @@ impl<'a> DistGraphBuilder<'a> {
                         })
                     }
                 }
+                HostingStyle::Gitea => {
+                    // `dist host` creates the release later, for now we just need to know the URLs
+                    let repo_url = &hosting.repo_url;
+                    let api_url = format!("{}/api/v1", hosting.source_host_url);
+                    for (name, version) in &releases_without_hosting {
+                        let tag = &announcing.tag;
+                        self.manifest
+                            .ensure_release(name.clone(), version.clone())
+                            .hosting
+                            .gitea = Some(GiteaHosting {
+                            artifact_download_url: format!("{repo_url}/releases/download/{tag}"),
+                            api_url: api_url.clone(),
+                            owner: hosting.owner.clone(),
+                            repo: hosting.project.clone(),
+                        })
+                    }
+                }
+                HostingStyle::S3 => {
+                    // We know where everything will go before anything is uploaded
+                    let config = self
+                        .inner
+                        .config
+                        .hosts
+                        .s3
+                        .as_ref()
+                        .expect("s3 hosting enabled without s3 config");
+                    let s3_hosting = s3::s3_hosting(config, &announcing.tag)?;
+                    for (name, version) in &releases_without_hosting {
+                        self.manifest
+                            .ensure_release(name.clone(), version.clone())
+                            .hosting
+                            .s3 = Some(s3_hosting.clone());
+                    }
+                }
+                HostingStyle::Mirror => {
+                    // Every app gets its own directory in the mirror
+                    let config = self
+                        .inner
+                        .config
+                        .hosts
+                        .mirror
+                        .as_ref()
+                        .expect("mirror hosting enabled without mirror config");
+                    for (name, version) in &releases_without_hosting {
+                        let mirror_hosting = mirror::mirror_hosting(config, name, version)?;
+                        self.manifest
+                            .ensure_release(name.clone(), version.clone())
+                            .hosting
+                            .mirror = Some(mirror_hosting);
+                    }
+                }
+            }
+        }
+
+        // Now that every host knows its URLs, work out the order installers should try them in
+        let download_urls = &self.inner.config.hosts.download_urls;
+        if !download_urls.is_empty() {
+            for (name, version) in &releases_without_hosting {
+                let release = self.manifest.ensure_release(name.clone(), version.clone());
+                release.hosting.download_urls = resolve_download_urls(
+                    download_urls,
+                    &release.hosting,
+                    name,
+                    version,
+                    &announcing.tag,
+                )?;
             }
         }
 
@@ -204,6 +338,54 @@ impl<'a> DistGraphBuilder<'a> {
     }
 }
 
+/// Turn the download-urls config into the URLs of a release, in the same order
+///
+/// Entries are either the name of a host, meaning wherever that host put the
+/// release, or a URL with `{app}`, `{version}` and `{tag}` placeholders.
+fn resolve_download_urls(
+    download_urls: &[String],
+    hosting: &Hosting,
+    app_name: &str,
+    version: &str,
+    tag: &str,
+) -> DistResult<Vec<String>> {
+    download_urls
+        .iter()
+        .map(|entry| {
+            if entry.contains("://") {
+                let url = entry
+                    .replace("{app}", app_name)
+                    .replace("{version}", version)
+                    .replace("{tag}", tag);
+                return Ok(url.trim_end_matches('/').to_owned());
+            }
+            let host = entry.parse::<HostingStyle>()?;
+            let url = match host {
+                HostingStyle::Github => hosting
+                    .github
+                    .as_ref()
+                    .map(|h| h.artifact_download_url.clone()),
+                HostingStyle::Axodotdev => hosting
+                    .axodotdev
+                    .as_ref()
+                    .and_then(|h| h.set_download_url.clone()),
+                HostingStyle::Gitea => hosting
+                    .gitea
+                    .as_ref()
+                    .map(|h| h.artifact_download_url.clone()),
+                HostingStyle::S3 => hosting.s3.as_ref().map(|h| h.artifact_download_url.clone()),
+                HostingStyle::Mirror => hosting
+                    .mirror
+                    .as_ref()
+                    .map(|h| h.artifact_download_url.clone()),
+            };
+            url.ok_or_else(|| DistError::DownloadUrlsHostDisabled {
+                host: host.to_string(),
+            })
+        })
+        .collect()
+}
+
 fn check_hosting(_dist: &DistGraph, _manifest: &DistManifest, _abyss: &Gazenot) -> DistResult<()> {
     // FIXME: implement a ping/whoami API to check the Abyss client is working
 
@@ -217,6 +399,10 @@ fn upload_to_hosting(dist: &DistGraph, manifest: &DistManifest, abyss: &Gazenot)
         let Hosting {
             axodotdev,
             github: _,
+            gitea: _,
+            s3: _,
+            mirror: _,
+            download_urls: _,
         } = &release.hosting;
         if let Some(set) = axodotdev {
             // Upload all files associated with this Release, plus the dist-manifest.json
@@ -231,14 +417,19 @@ fn upload_to_hosting(dist: &DistGraph, manifest: &DistManifest, abyss: &Gazenot)
             None
         }
     });
+    let files = files.collect::<Vec<_>>();
 
-    tokio::runtime::Handle::current().block_on(abyss.upload_files(files))?;
+    tokio::runtime::Handle::current().block_on(with_retries(
+        &dist.client_settings,
+        "uploading to axodotdev hosting",
+        || abyss.upload_files(files.clone()),
+    ))?;
     eprintln!("all artifacts hosted!");
     Ok(())
 }
 
 fn release_hosting(
-    _dist: &DistGraph,
+    dist: &DistGraph,
     manifest: &mut DistManifest,
     abyss: &Gazenot,
 ) -> DistResult<()> {
@@ -248,6 +439,10 @@ fn release_hosting(
         let Hosting {
             axodotdev,
             github: _,
+            gitea: _,
+            s3: _,
+            mirror: _,
+            download_urls: _,
         } = &release.hosting;
         if let Some(set) = axodotdev {
             let release = gazenot::ReleaseKey {
@@ -260,10 +455,14 @@ fn release_hosting(
             None
         }
     });
+    let releases = releases.collect::<Vec<_>>();
 
     // Tell The Abyss To Release
-    let new_releases =
-        tokio::runtime::Handle::current().block_on(abyss.create_releases(releases))?;
+    let new_releases = tokio::runtime::Handle::current().block_on(with_retries(
+        &dist.client_settings,
+        "creating axodotdev releases",
+        || abyss.create_releases(releases.clone()),
+    ))?;
 
     // Update artifact download URLs with release results
     for new_release in new_releases {
@@ -279,7 +478,7 @@ fn release_hosting(
     Ok(())
 }
 
-fn announce_hosting(_dist: &DistGraph, manifest: &DistManifest, abyss: &Gazenot) -> DistResult<()> {
+fn announce_hosting(dist: &DistGraph, manifest: &DistManifest, abyss: &Gazenot) -> DistResult<()> {
     // Perform the announcement
     let releases = manifest
         .releases
@@ -289,6 +488,10 @@ fn announce_hosting(_dist: &DistGraph, manifest: &DistManifest, abyss: &Gazenot)
             let Hosting {
                 axodotdev,
                 github: _,
+                gitea: _,
+                s3: _,
+                mirror: _,
+                download_urls: _,
             } = &release.hosting;
             axodotdev
                 .as_ref()
@@ -299,8 +502,11 @@ fn announce_hosting(_dist: &DistGraph, manifest: &DistManifest, abyss: &Gazenot)
     let announcement = AnnouncementKey {
         body: announcement_axodotdev(manifest),
     };
-    tokio::runtime::Handle::current()
-        .block_on(abyss.create_announcements(&releases, announcement))?;
+    tokio::runtime::Handle::current().block_on(with_retries(
+        &dist.client_settings,
+        "announcing on axodotdev hosting",
+        || abyss.create_announcements(&releases, announcement.clone()),
+    ))?;
     eprintln!("release announced!");
     Ok(())
 }
@@ -346,7 +552,31 @@ pub(crate) fn select_hosting(
         }
     };
 
-    // Currently there's only one supported sourcehost provider
+    // Gitea can be self-hosted anywhere and S3/mirrors don't care where the source lives,
+    // everything else needs the repo to be on GitHub
+    let needs_github = hosting_providers
+        .iter()
+        .any(|h| matches!(h, HostingStyle::Github | HostingStyle::Axodotdev));
+    let uses_gitea = hosting_providers.contains(&HostingStyle::Gitea);
+    if !needs_github && (uses_gitea || raw_repository_url.github_repo().is_err()) {
+        let repo = raw_repository_url.forge_repo().map_err(|e| {
+            if uses_gitea {
+                DistError::CantEnableGiteaUrlNotForge { inner: e }
+            } else {
+                DistError::CantEnableGithubUrlNotGithub { inner: e }
+            }
+        })?;
+        let source_host = if uses_gitea { "gitea" } else { "git" };
+        return Ok(Some(HostingInfo {
+            hosts: hosting_providers,
+            repo_url: repo.web_url(),
+            source_host: source_host.to_owned(),
+            source_host_url: repo.base_url,
+            owner: repo.owner,
+            project: repo.name,
+        }));
+    }
+
     let repo = raw_repository_url
         .github_repo()
         .map_err(|e| DistError::CantEnableGithubUrlNotGithub { inner: e })?;
@@ -356,7 +586,65 @@ pub(crate) fn select_hosting(
         hosts: hosting_providers,
         repo_url: repo_url.clone(),
         source_host: "github".to_owned(),
+        source_host_url: "https://github.com".to_owned(),
         owner: repo.owner,
         project: repo.name,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cargo_dist_schema::{GithubHosting, MirrorHosting};
+
+    fn hosting() -> Hosting {
+        Hosting {
+            github: Some(GithubHosting {
+                artifact_download_url: "https://github.com/owner/app/releases/download/v1.0.0"
+                    .to_owned(),
+                owner: "owner".to_owned(),
+                repo: "app".to_owned(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn download_urls_keep_their_order() {
+        let download_urls = [
+            "https://cdn.example.com/{app}/{tag}/".to_owned(),
+            "github".to_owned(),
+            "https://mirror.example.com/{app}/{version}".to_owned(),
+        ];
+        let urls =
+            resolve_download_urls(&download_urls, &hosting(), "app", "1.0.0", "v1.0.0").unwrap();
+        assert_eq!(
+            urls,
+            [
+                "https://cdn.example.com/app/v1.0.0",
+                "https://github.com/owner/app/releases/download/v1.0.0",
+                "https://mirror.example.com/app/1.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn download_urls_need_their_hosts() {
+        let mut hosting = hosting();
+        let download_urls = ["mirror".to_owned()];
+        let err =
+            resolve_download_urls(&download_urls, &hosting, "app", "1.0.0", "v1.0.0").unwrap_err();
+        assert!(matches!(err, DistError::DownloadUrlsHostDisabled { .. }));
+
+        hosting.mirror = Some(MirrorHosting {
+            artifact_download_url: "https://mirror.example.com/app/1.0.0".to_owned(),
+            path: "app/1.0.0".to_owned(),
+        });
+        let urls =
+            resolve_download_urls(&download_urls, &hosting, "app", "1.0.0", "v1.0.0").unwrap();
+        assert_eq!(urls, ["https://mirror.example.com/app/1.0.0"]);
+
+        let download_urls = ["gitlab".to_owned()];
+        assert!(resolve_download_urls(&download_urls, &hosting, "app", "1.0.0", "v1.0.0").is_err());
+    }
+}
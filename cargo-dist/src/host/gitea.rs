@@ -0,0 +1,420 @@
+//! Gitea (and Forgejo) Releases
+//!
+//! Unlike GitHub Releases, which the CI backend drives with `gh`, these are
+//! created by `dist host` itself through the Gitea REST API.
+
+use axoasset::{reqwest, LocalAsset};
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{DistManifest, GiteaHosting};
+use serde::{Deserialize, Serialize};
+
+use crate::{errors::DistResult, net::create_gitea_client, DistError, DistGraph, SortedSet};
+
+/// Environment variable the API token is read from
+const GITEA_TOKEN_VAR: &str = "GITEA_TOKEN";
+/// Boundary for the multipart bodies of asset uploads
+const MULTIPART_BOUNDARY: &str = "dist-gitea-release-asset";
+
+/// A client for the Releases part of the Gitea API, scoped to one repo
+pub(crate) struct GiteaClient {
+    client: reqwest::Client,
+    /// e.g. `https://codeberg.org/api/v1/repos/owner/repo`
+    repo_api_url: String,
+    token: String,
+}
+
+/// A release, as the Gitea API reports it
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GiteaRelease {
+    /// ID to use to refer to the release in other requests
+    pub id: u64,
+    /// Files already attached to the release
+    #[serde(default)]
+    pub assets: Vec<GiteaAsset>,
+}
+
+/// A file attached to a release
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GiteaAsset {
+    /// ID to use to refer to the asset in other requests
+    pub id: u64,
+    /// Filename of the asset
+    pub name: String,
+}
+
+/// Fields for creating or editing a release
+#[derive(Debug, Clone, Default, Serialize)]
+pub(crate) struct GiteaReleaseOptions {
+    /// The tag the release is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_name: Option<String>,
+    /// The commit to create the tag on, if it doesn't exist yet
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_commitish: Option<String>,
+    /// Title of the release
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Release notes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Whether the release is a draft (hidden and not yet published)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub draft: Option<bool>,
+    /// Whether the release is marked as a prerelease
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prerelease: Option<bool>,
+}
+
+impl GiteaClient {
+    /// Create a client for the repo the given hosting refers to
+    ///
+    /// This will error out if GITEA_TOKEN isn't set, so it should only be
+    /// called when we know we HAVE to talk to Gitea.
+    pub fn new(client: reqwest::Client, hosting: &GiteaHosting) -> DistResult<Self> {
+        let token = std::env::var(GITEA_TOKEN_VAR)
+            .ok()
+            .filter(|t| !t.is_empty())
+            .ok_or(DistError::GiteaTokenMissing {
+                var: GITEA_TOKEN_VAR,
+            })?;
+        let repo_api_url = format!(
+            "{}/repos/{}/{}",
+            hosting.api_url.trim_end_matches('/'),
+            hosting.owner,
+            hosting.repo
+        );
+        Ok(Self {
+            client,
+            repo_api_url,
+            token,
+        })
+    }
+
+    /// Get the release for a tag, if it exists
+    pub async fn release_by_tag(&self, tag: &str) -> DistResult<Option<GiteaRelease>> {
+        let url = format!("{}/releases/tags/{tag}", self.repo_api_url);
+        let response = self.send(self.client.get(&url), &url).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(parse_response(response, &url).await?))
+    }
+
+    /// Create a new release
+    pub async fn create_release(&self, options: &GiteaReleaseOptions) -> DistResult<GiteaRelease> {
+        let url = format!("{}/releases", self.repo_api_url);
+        let response = self
+            .send(self.client.post(&url).json(options), &url)
+            .await?;
+        parse_response(response, &url).await
+    }
+
+    /// Update an existing release
+    pub async fn edit_release(
+        &self,
+        release_id: u64,
+        options: &GiteaReleaseOptions,
+    ) -> DistResult<GiteaRelease> {
+        let url = format!("{}/releases/{release_id}", self.repo_api_url);
+        let response = self
+            .send(self.client.patch(&url).json(options), &url)
+            .await?;
+        parse_response(response, &url).await
+    }
+
+    /// Attach a file to a release, replacing any previous file with the same name
+    pub async fn upload_asset(&self, release: &GiteaRelease, path: &Utf8PathBuf) -> DistResult<()> {
+        let name = path.file_name().expect("release asset had no filename");
+        // Reruns of a pipeline would otherwise fail on the duplicate
+        for asset in release.assets.iter().filter(|a| a.name == name) {
+            let url = format!(
+                "{}/releases/{}/assets/{}",
+                self.repo_api_url, release.id, asset.id
+            );
+            let response = self.send(self.client.delete(&url), &url).await?;
+            check_status(response, &url).await?;
+        }
+
+        let contents = LocalAsset::load_bytes(path)?;
+        let url = format!("{}/releases/{}/assets", self.repo_api_url, release.id);
+        let request = self
+            .client
+            .post(&url)
+            .query(&[("name", name)])
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={MULTIPART_BOUNDARY}"),
+            )
+            .body(multipart_body(name, contents));
+        let response = self.send(request, &url).await?;
+        check_status(response, &url).await?;
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        url: &str,
+    ) -> DistResult<reqwest::Response> {
+        request
+            .header(
+                reqwest::header::AUTHORIZATION,
+                format!("token {}", self.token),
+            )
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|e| DistError::GiteaRequestFailed {
+                url: url.to_owned(),
+                details: e.to_string(),
+            })
+    }
+}
+
+/// Make sure a (draft) Gitea Release exists for the announcement, and upload all files to it
+pub(crate) fn upload_to_gitea(dist: &DistGraph, manifest: &DistManifest) -> DistResult<()> {
+    let Some(hosting) = gitea_hosting(manifest) else {
+        return Ok(());
+    };
+    let tag = manifest
+        .announcement_tag
+        .clone()
+        .expect("gitea hosting requires a tag");
+
+    // Every release with this hosting shares the same Gitea Release
+    let files = manifest
+        .releases
+        .iter()
+        .filter(|release| release.hosting.gitea.is_some())
+        .flat_map(|release| {
+            manifest
+                .artifacts_for_release(release)
+                .filter_map(|(_id, artifact)| artifact.name.as_deref())
+        })
+        .chain(Some("dist-manifest.json"))
+        .map(|name| dist.dist_dir.join(name))
+        .collect::<SortedSet<_>>();
+
+    let client = create_gitea_client(&dist.client_settings, hosting)?;
+    tokio::runtime::Handle::current().block_on(async {
+        let release = match client.release_by_tag(&tag).await? {
+            Some(release) => release,
+            None => {
+                let options = GiteaReleaseOptions {
+                    tag_name: Some(tag.clone()),
+                    // Only matters if the tag doesn't exist yet (e.g. release-branch mode)
+                    target_commitish: std::env::var("GITHUB_SHA").ok(),
+                    name: manifest.announcement_title.clone(),
+                    body: manifest.announcement_github_body.clone(),
+                    draft: Some(true),
+                    prerelease: Some(manifest.announcement_is_prerelease),
+                };
+                client.create_release(&options).await?
+            }
+        };
+        for file in &files {
+            client.upload_asset(&release, file).await?;
+        }
+        DistResult::Ok(())
+    })?;
+    eprintln!("all artifacts uploaded to gitea!");
+    Ok(())
+}
+
+/// Publish the draft Gitea Release, making its download URLs live
+pub(crate) fn release_gitea(dist: &DistGraph, manifest: &DistManifest) -> DistResult<()> {
+    let Some(hosting) = gitea_hosting(manifest) else {
+        return Ok(());
+    };
+    let tag = manifest
+        .announcement_tag
+        .clone()
+        .expect("gitea hosting requires a tag");
+
+    let client = create_gitea_client(&dist.client_settings, hosting)?;
+    tokio::runtime::Handle::current().block_on(async {
+        let release = client
+            .release_by_tag(&tag)
+            .await?
+            .ok_or_else(|| DistError::GiteaReleaseMissing { tag: tag.clone() })?;
+        let options = GiteaReleaseOptions {
+            name: manifest.announcement_title.clone(),
+            body: manifest.announcement_github_body.clone(),
+            draft: Some(false),
+            prerelease: Some(manifest.announcement_is_prerelease),
+            ..Default::default()
+        };
+        client.edit_release(release.id, &options).await?;
+        DistResult::Ok(())
+    })?;
+    eprintln!("gitea release published!");
+    Ok(())
+}
+
+fn gitea_hosting(manifest: &DistManifest) -> Option<&GiteaHosting> {
+    manifest
+        .releases
+        .iter()
+        .find_map(|release| release.hosting.gitea.as_ref())
+}
+
+/// Encode a file as the `attachment` field of a multipart/form-data body
+fn multipart_body(name: &str, contents: Vec<u8>) -> Vec<u8> {
+    let mut body = format!(
+        "--{MULTIPART_BOUNDARY}\r\n\
+         Content-Disposition: form-data; name=\"attachment\"; filename=\"{name}\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend(contents);
+    body.extend(format!("\r\n--{MULTIPART_BOUNDARY}--\r\n").into_bytes());
+    body
+}
+
+async fn check_status(response: reqwest::Response, url: &str) -> DistResult<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    Err(DistError::GiteaRequestFailed {
+        url: url.to_owned(),
+        details: format!("{status}: {body}"),
+    })
+}
+
+async fn parse_response<T: for<'de> Deserialize<'de>>(
+    response: reqwest::Response,
+    url: &str,
+) -> DistResult<T> {
+    let response = check_status(response, url).await?;
+    response
+        .json()
+        .await
+        .map_err(|e| DistError::GiteaRequestFailed {
+            url: url.to_owned(),
+            details: e.to_string(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::http::{MockRequest, MockServer};
+
+    /// A tiny stand-in for a Gitea instance, just smart enough for the release endpoints
+    fn mock_gitea() -> (MockServer, GiteaClient) {
+        let server = MockServer::start(respond);
+        let client = GiteaClient {
+            client: reqwest::Client::new(),
+            repo_api_url: format!("{}/api/v1/repos/me/app", server.url),
+            token: "hunter2".to_owned(),
+        };
+        (server, client)
+    }
+
+    fn respond(request: &MockRequest) -> (&'static str, &'static str) {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/api/v1/repos/me/app/releases/tags/v1.0.0") => ("404 Not Found", "{}"),
+            ("POST", "/api/v1/repos/me/app/releases") => ("201 Created", r#"{"id":7,"assets":[]}"#),
+            ("POST", "/api/v1/repos/me/app/releases/7/assets?name=app.tar.gz") => {
+                ("201 Created", r#"{"id":3,"name":"app.tar.gz"}"#)
+            }
+            ("DELETE", "/api/v1/repos/me/app/releases/7/assets/3") => ("204 No Content", ""),
+            ("PATCH", "/api/v1/repos/me/app/releases/7") => ("200 OK", r#"{"id":7}"#),
+            _ => (
+                "500 Internal Server Error",
+                r#"{"message":"unexpected request"}"#,
+            ),
+        }
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn creates_uploads_and_publishes_release() {
+        let (server, client) = mock_gitea();
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let asset = Utf8PathBuf::from_path_buf(tmp.path().join("app.tar.gz")).unwrap();
+        LocalAsset::write_new_all("pretend this is a tarball", &asset).unwrap();
+
+        runtime().block_on(async {
+            assert!(client.release_by_tag("v1.0.0").await.unwrap().is_none());
+            let release = client
+                .create_release(&GiteaReleaseOptions {
+                    tag_name: Some("v1.0.0".to_owned()),
+                    draft: Some(true),
+                    ..Default::default()
+                })
+                .await
+                .unwrap();
+            assert_eq!(release.id, 7);
+            client.upload_asset(&release, &asset).await.unwrap();
+            let publish = GiteaReleaseOptions {
+                draft: Some(false),
+                ..Default::default()
+            };
+            client.edit_release(release.id, &publish).await.unwrap();
+        });
+
+        let requests = server.requests();
+        let calls = requests.iter().map(MockRequest::call).collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            [
+                "GET /api/v1/repos/me/app/releases/tags/v1.0.0",
+                "POST /api/v1/repos/me/app/releases",
+                "POST /api/v1/repos/me/app/releases/7/assets?name=app.tar.gz",
+                "PATCH /api/v1/repos/me/app/releases/7",
+            ]
+        );
+        assert!(requests
+            .iter()
+            .all(|r| r.header("authorization") == Some("token hunter2")));
+
+        let create: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+        assert_eq!(
+            create,
+            serde_json::json!({ "tag_name": "v1.0.0", "draft": true })
+        );
+        let upload = String::from_utf8_lossy(&requests[2].body);
+        assert!(upload.contains(r#"name="attachment"; filename="app.tar.gz""#));
+        assert!(upload.contains("pretend this is a tarball"));
+        let publish: serde_json::Value = serde_json::from_slice(&requests[3].body).unwrap();
+        assert_eq!(publish, serde_json::json!({ "draft": false }));
+    }
+
+    #[test]
+    fn reupload_replaces_asset() {
+        let (server, client) = mock_gitea();
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let asset = Utf8PathBuf::from_path_buf(tmp.path().join("app.tar.gz")).unwrap();
+        LocalAsset::write_new_all("a better tarball", &asset).unwrap();
+        let release = GiteaRelease {
+            id: 7,
+            assets: vec![GiteaAsset {
+                id: 3,
+                name: "app.tar.gz".to_owned(),
+            }],
+        };
+
+        runtime()
+            .block_on(client.upload_asset(&release, &asset))
+            .unwrap();
+
+        let requests = server.requests();
+        let calls = requests.iter().map(MockRequest::call).collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            [
+                "DELETE /api/v1/repos/me/app/releases/7/assets/3",
+                "POST /api/v1/repos/me/app/releases/7/assets?name=app.tar.gz",
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Publishing releases into a static directory tree
+//!
+//! The tree looks like `<root>/<app>/<version>/<files>`, with an `index.json`
+//! at the root listing every release that has been published into it, so it can be
+//! served by any static web server (or rsynced somewhere that can't reach the internet).
+
+use axoasset::{LocalAsset, SourceFile};
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_dist_schema::{DistManifest, MirrorHosting, Release};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::v1::hosts::mirror::MirrorHostConfig, errors::DistResult, DistError, DistGraph,
+};
+
+/// The name of the file listing every release in the mirror
+pub(crate) const MIRROR_INDEX: &str = "index.json";
+
+/// The index of every release published into a mirror
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct MirrorIndex {
+    /// Every release, oldest first
+    pub releases: Vec<MirrorRelease>,
+}
+
+/// A release in the mirror's index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct MirrorRelease {
+    /// The name of the app
+    pub app_name: String,
+    /// The version of the app
+    pub app_version: String,
+    /// The tag the release was announced under
+    pub tag: String,
+    /// Whether the release is a prerelease
+    pub prerelease: bool,
+    /// The directory of the release, relative to the root of the mirror
+    pub path: String,
+    /// The URL the release's files can be downloaded from
+    pub artifact_download_url: String,
+    /// The files in the release's directory
+    pub files: Vec<String>,
+}
+
+impl MirrorIndex {
+    /// Add a release to the index, replacing any previous entry for the same version
+    pub fn add_release(&mut self, release: MirrorRelease) {
+        self.releases.retain(|old| {
+            old.app_name != release.app_name || old.app_version != release.app_version
+        });
+        self.releases.push(release);
+    }
+}
+
+/// Work out where a release should be published in the configured mirror
+pub(crate) fn mirror_hosting(
+    config: &MirrorHostConfig,
+    app_name: &str,
+    app_version: &str,
+) -> DistResult<MirrorHosting> {
+    let Some(url) = &config.url else {
+        return Err(DistError::MirrorUrlMissing);
+    };
+    let path = format!("{app_name}/{app_version}");
+    Ok(MirrorHosting {
+        artifact_download_url: format!("{}/{path}", url.trim_end_matches('/')),
+        path,
+    })
+}
+
+/// Get the directory the mirror lives in
+pub(crate) fn mirror_root(dist: &DistGraph) -> Utf8PathBuf {
+    match dist
+        .config
+        .hosts
+        .mirror
+        .as_ref()
+        .and_then(|m| m.root.as_ref())
+    {
+        Some(root) => dist.workspace_dir.join(root),
+        None => dist.target_dir.join("mirror"),
+    }
+}
+
+/// Copy every file of each release (and the dist-manifest.json) into the mirror
+pub(crate) fn copy_to_mirror(dist: &DistGraph, manifest: &DistManifest) -> DistResult<()> {
+    let root = mirror_root(dist);
+    for (release, hosting) in mirror_releases(manifest) {
+        let dest = root.join(&hosting.path);
+        LocalAsset::create_dir_all(&dest)?;
+        for name in release_files(manifest, release) {
+            LocalAsset::copy_file_to_dir(dist.dist_dir.join(&name), &dest)?;
+        }
+    }
+    eprintln!("all artifacts copied to {root}");
+    Ok(())
+}
+
+/// Add the releases to the mirror's index, making them visible to anyone browsing it
+pub(crate) fn release_mirror(dist: &DistGraph, manifest: &DistManifest) -> DistResult<()> {
+    let root = mirror_root(dist);
+    let tag = manifest
+        .announcement_tag
+        .clone()
+        .expect("mirror hosting requires a tag");
+
+    let index_path = root.join(MIRROR_INDEX);
+    let mut index = load_index(&index_path)?;
+    for (release, hosting) in mirror_releases(manifest) {
+        index.add_release(MirrorRelease {
+            app_name: release.app_name.clone(),
+            app_version: release.app_version.clone(),
+            tag: tag.clone(),
+            prerelease: manifest.announcement_is_prerelease,
+            path: hosting.path.clone(),
+            artifact_download_url: hosting.artifact_download_url.clone(),
+            files: release_files(manifest, release),
+        });
+    }
+    let contents = serde_json::to_string_pretty(&index).expect("failed to serialize mirror index");
+    LocalAsset::write_new_all(&contents, &index_path)?;
+    eprintln!("updated {index_path}");
+    Ok(())
+}
+
+fn mirror_releases(manifest: &DistManifest) -> impl Iterator<Item = (&Release, &MirrorHosting)> {
+    manifest
+        .releases
+        .iter()
+        .filter_map(|release| Some((release, release.hosting.mirror.as_ref()?)))
+}
+
+fn release_files(manifest: &DistManifest, release: &Release) -> Vec<String> {
+    let mut files = manifest
+        .artifacts_for_release(release)
+        .filter_map(|(_id, artifact)| artifact.name.clone())
+        .chain(Some("dist-manifest.json".to_owned()))
+        .collect::<Vec<_>>();
+    files.sort();
+    files.dedup();
+    files
+}
+
+fn load_index(path: &Utf8Path) -> DistResult<MirrorIndex> {
+    if !path.exists() {
+        return Ok(MirrorIndex::default());
+    }
+    let file = SourceFile::load_local(path)?;
+    Ok(file.deserialize_json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str) -> MirrorRelease {
+        MirrorRelease {
+            app_name: "app".to_owned(),
+            app_version: version.to_owned(),
+            tag: format!("v{version}"),
+            prerelease: false,
+            path: format!("app/{version}"),
+            artifact_download_url: format!("https://mirror.example.com/app/{version}"),
+            files: vec!["dist-manifest.json".to_owned()],
+        }
+    }
+
+    #[test]
+    fn index_replaces_republished_versions() {
+        let mut index = MirrorIndex::default();
+        index.add_release(release("1.0.0"));
+        index.add_release(release("1.1.0"));
+        let mut republished = release("1.0.0");
+        republished.files.push("app.tar.gz".to_owned());
+        index.add_release(republished);
+
+        let versions = index
+            .releases
+            .iter()
+            .map(|r| r.app_version.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(versions, ["1.1.0", "1.0.0"]);
+        assert_eq!(index.releases[1].files.len(), 2);
+    }
+
+    #[test]
+    fn index_roundtrips() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let path = Utf8PathBuf::from_path_buf(tmp.path().join(MIRROR_INDEX)).unwrap();
+        assert!(load_index(&path).unwrap().releases.is_empty());
+
+        let mut index = MirrorIndex::default();
+        index.add_release(release("1.0.0"));
+        LocalAsset::write_new_all(&serde_json::to_string_pretty(&index).unwrap(), &path).unwrap();
+
+        let loaded = load_index(&path).unwrap();
+        assert_eq!(loaded.releases.len(), 1);
+        assert_eq!(loaded.releases[0].path, "app/1.0.0");
+    }
+
+    #[test]
+    fn hosting_urls() {
+        let mut config = MirrorHostConfig {
+            url: Some("https://mirror.example.com/dist/".to_owned()),
+            ..Default::default()
+        };
+        let hosting = mirror_hosting(&config, "app", "1.0.0").unwrap();
+        assert_eq!(
+            hosting.artifact_download_url,
+            "https://mirror.example.com/dist/app/1.0.0"
+        );
+        assert_eq!(hosting.path, "app/1.0.0");
+
+        config.url = None;
+        assert!(matches!(
+            mirror_hosting(&config, "app", "1.0.0"),
+            Err(DistError::MirrorUrlMissing)
+        ));
+    }
+}
@@ -0,0 +1,814 @@
+//! Publishing apt and yum repositories of the .deb and .rpm installers
+//!
+//! Rather than copying packages around, the repository metadata points at the files
+//! each release already uploaded, so the repositories live at the root of the tree the
+//! releases are hosted in:
+//!
+//! * `apt/` is a "flat" apt repository (`Packages`, `Release` and `InRelease`),
+//!   used with `deb <root-url> apt/`
+//! * `yum/repodata/` is a yum repository, used with `baseurl=<root-url>/yum`
+//! * `package-repo.json` lists every package published so far, so that each release
+//!   adds to the repositories instead of replacing them
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+
+use axoasset::{LocalAsset, SourceFile};
+use axoprocess::Cmd;
+use camino::Utf8Path;
+use cargo_dist_schema::DistManifest;
+use chrono::{DateTime, Utc};
+use flate2::{write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{mirror, s3};
+use crate::{
+    backend::installer::{deb::read_control_file, rpm::read_rpm_metadata},
+    config::v1::hosts::package_repo::PackageRepoConfig,
+    errors::DistResult,
+    net::create_s3_client,
+    DistError, DistGraph,
+};
+
+/// The name of the file listing every package in the repositories
+pub(crate) const PACKAGE_REPO_INDEX: &str = "package-repo.json";
+/// The name of the exported public key the repositories are signed with
+const PACKAGE_REPO_KEY: &str = "package-repo-key.asc";
+
+/// The index of every package published into the repositories
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct PackageRepoIndex {
+    /// Every package, oldest first
+    pub packages: Vec<RepoPackage>,
+}
+
+/// A package in the repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct RepoPackage {
+    /// The path of the package, relative to the root of the tree
+    pub path: String,
+    /// What the repository metadata says about the package
+    #[serde(flatten)]
+    pub entry: RepoEntry,
+}
+
+/// What the repository metadata says about a package, pre-rendered
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub(crate) enum RepoEntry {
+    /// A .deb's paragraph in `Packages`
+    Deb {
+        /// Debian architecture of the package
+        architecture: String,
+        /// The paragraph
+        paragraph: String,
+    },
+    /// An .rpm's elements in the yum metadata
+    Rpm {
+        /// The `<package>` in `primary.xml`
+        primary: String,
+        /// The `<package>` in `filelists.xml`
+        filelists: String,
+        /// The `<package>` in `other.xml`
+        other: String,
+    },
+}
+
+impl PackageRepoIndex {
+    /// Add a package to the index, replacing any previous entry for the same file
+    pub fn add_package(&mut self, package: RepoPackage) {
+        self.packages.retain(|old| old.path != package.path);
+        self.packages.push(package);
+    }
+}
+
+/// Add this release's packages to the repositories at the root of the mirror
+pub(crate) fn publish_to_mirror(dist: &DistGraph, manifest: &DistManifest) -> DistResult<()> {
+    let Some(config) = &dist.config.hosts.package_repo else {
+        return Ok(());
+    };
+    let Some(root_url) = dist
+        .config
+        .hosts
+        .mirror
+        .as_ref()
+        .and_then(|m| m.url.as_ref())
+    else {
+        return Err(DistError::MirrorUrlMissing);
+    };
+    let root = mirror::mirror_root(dist);
+    let index_path = root.join(PACKAGE_REPO_INDEX);
+    let mut index = if index_path.exists() {
+        SourceFile::load_local(&index_path)?.deserialize_json()?
+    } else {
+        PackageRepoIndex::default()
+    };
+
+    let packages = manifest
+        .releases
+        .iter()
+        .filter_map(|release| {
+            let hosting = release.hosting.mirror.as_ref()?;
+            let files = manifest
+                .artifacts_for_release(release)
+                .filter_map(|(_id, artifact)| artifact.name.clone())
+                .map(|name| (format!("{}/{name}", hosting.path), name))
+                .collect::<Vec<_>>();
+            Some(files)
+        })
+        .flatten();
+    if !add_packages(dist, manifest, config, &mut index, packages, root_url)? {
+        return Ok(());
+    }
+
+    write_repos(
+        config,
+        &index,
+        &repo_origin(dist, manifest),
+        &root,
+        Utc::now(),
+    )?;
+    eprintln!("updated the package repositories in {root}");
+    Ok(())
+}
+
+/// Add this release's packages to the repositories at the root of the bucket
+pub(crate) fn publish_to_s3(dist: &DistGraph, manifest: &DistManifest) -> DistResult<()> {
+    let Some(config) = &dist.config.hosts.package_repo else {
+        return Ok(());
+    };
+    let Some(hosting) = s3::s3_hosting_for(manifest) else {
+        return Ok(());
+    };
+    let tag = manifest
+        .announcement_tag
+        .clone()
+        .expect("s3 hosting requires a tag");
+    // Releases go in `<prefix>/<tag>/`, the repositories go in `<prefix>/`
+    let root_key = hosting.prefix.strip_suffix(&tag).unwrap_or_default();
+    let root_url = hosting
+        .artifact_download_url
+        .strip_suffix(&tag)
+        .unwrap_or_default()
+        .trim_end_matches('/');
+
+    let client = create_s3_client(&dist.client_settings, hosting)?;
+    let index_key = format!("{root_key}{PACKAGE_REPO_INDEX}");
+    let prior = tokio::runtime::Handle::current().block_on(client.get_object(&index_key))?;
+    let mut index = match prior {
+        Some(contents) => {
+            let contents = String::from_utf8_lossy(&contents).into_owned();
+            SourceFile::new(&index_key, contents).deserialize_json()?
+        }
+        None => PackageRepoIndex::default(),
+    };
+
+    let packages = manifest
+        .upload_files
+        .iter()
+        .filter_map(|file| Utf8Path::new(file).file_name())
+        .map(|name| (format!("{tag}/{name}"), name.to_owned()));
+    if !add_packages(dist, manifest, config, &mut index, packages, root_url)? {
+        return Ok(());
+    }
+
+    // Build the tree locally, then upload all of it
+    let staging = dist.dist_dir.join("package-repo");
+    if staging.exists() {
+        LocalAsset::remove_dir_all(&staging)?;
+    }
+    let files = write_repos(
+        config,
+        &index,
+        &repo_origin(dist, manifest),
+        &staging,
+        Utc::now(),
+    )?;
+    tokio::runtime::Handle::current().block_on(async {
+        for file in &files {
+            let contents = LocalAsset::load_bytes(staging.join(file))?;
+            client
+                .put_object(&format!("{root_key}{file}"), contents)
+                .await?;
+        }
+        DistResult::Ok(())
+    })?;
+    eprintln!("updated the package repositories in s3!");
+    Ok(())
+}
+
+/// Add any packages among the given `(path in tree, name in dist dir)` files to the index
+///
+/// Returns whether the repositories need updating at all.
+fn add_packages(
+    dist: &DistGraph,
+    manifest: &DistManifest,
+    config: &PackageRepoConfig,
+    index: &mut PackageRepoIndex,
+    files: impl IntoIterator<Item = (String, String)>,
+    root_url: &str,
+) -> DistResult<bool> {
+    // Anyone who adds the repository gets the newest version in it, so keep prereleases out
+    if manifest.announcement_is_prerelease {
+        eprintln!("not adding a prerelease to the package repositories");
+        return Ok(false);
+    }
+    let mut added = false;
+    for (path, name) in files {
+        let is_deb = config.apt && name.ends_with(".deb");
+        let is_rpm = config.yum && name.ends_with(".rpm");
+        if !is_deb && !is_rpm {
+            continue;
+        }
+        let contents = LocalAsset::load_bytes(dist.dist_dir.join(&name))?;
+        let entry = if is_deb {
+            deb_entry(&contents, &path)
+        } else {
+            rpm_entry(&contents, &path, root_url)
+        };
+        let Some(entry) = entry else {
+            return Err(DistError::PackageRepoBadPackage { name });
+        };
+        index.add_package(RepoPackage { path, entry });
+        added = true;
+    }
+    Ok(added)
+}
+
+/// What to call ourselves in the apt repository's `Release`
+fn repo_origin(dist: &DistGraph, manifest: &DistManifest) -> String {
+    dist.hosting
+        .as_ref()
+        .map(|hosting| hosting.project.clone())
+        .or_else(|| manifest.releases.first().map(|r| r.app_name.clone()))
+        .unwrap_or_default()
+}
+
+/// Write out both repositories (and the index) under `dir`, signing them if we have a key
+///
+/// Returns the paths of everything written, relative to `dir`.
+fn write_repos(
+    config: &PackageRepoConfig,
+    index: &PackageRepoIndex,
+    origin: &str,
+    dir: &Utf8Path,
+    now: DateTime<Utc>,
+) -> DistResult<Vec<String>> {
+    let mut files = vec![];
+    for (path, contents) in render_repos(config, index, origin, now) {
+        let dest = dir.join(&path);
+        LocalAsset::create_dir_all(dest.parent().expect("repo files are in directories"))?;
+        std::fs::write(&dest, contents)?;
+        files.push(path);
+    }
+    let contents = serde_json::to_string_pretty(index).expect("failed to serialize package index");
+    LocalAsset::write_new_all(&contents, dir.join(PACKAGE_REPO_INDEX))?;
+    files.push(PACKAGE_REPO_INDEX.to_owned());
+
+    let Some(key) = &config.gpg_key else {
+        if files.iter().any(|f| f.starts_with("apt/")) {
+            eprintln!("package-repo-gpg-key isn't set, so the apt repository won't have an InRelease and will need to be added as [trusted=yes]");
+        }
+        return Ok(files);
+    };
+    let mut signatures = vec![];
+    for file in &files {
+        match file.as_str() {
+            "apt/Release" => {
+                signatures.push(gpg_sign(key, dir, file, "apt/InRelease", "--clearsign")?);
+                signatures.push(gpg_sign(
+                    key,
+                    dir,
+                    file,
+                    "apt/Release.gpg",
+                    "--detach-sign",
+                )?);
+            }
+            "yum/repodata/repomd.xml" => {
+                let signature = "yum/repodata/repomd.xml.asc";
+                signatures.push(gpg_sign(key, dir, file, signature, "--detach-sign")?);
+            }
+            _ => {}
+        }
+    }
+    files.extend(signatures);
+
+    // Publish the public half of the key next to the repositories, so users can trust it
+    let mut cmd = Cmd::new("gpg", "export the package repository key");
+    cmd.arg("--batch").arg("--yes").arg("--armor");
+    cmd.arg("--output").arg(dir.join(PACKAGE_REPO_KEY));
+    cmd.arg("--export").arg(key);
+    cmd.stdout_to_stderr();
+    cmd.status()?;
+    files.push(PACKAGE_REPO_KEY.to_owned());
+
+    Ok(files)
+}
+
+/// Sign `file` with gpg, returning the path of the signature
+fn gpg_sign(
+    key: &str,
+    dir: &Utf8Path,
+    file: &str,
+    signature: &str,
+    mode: &str,
+) -> DistResult<String> {
+    let mut cmd = Cmd::new("gpg", "sign the package repository");
+    cmd.arg("--batch").arg("--yes").arg("--armor");
+    cmd.arg("--local-user").arg(key);
+    cmd.arg("--output").arg(dir.join(signature));
+    cmd.arg(mode).arg(dir.join(file));
+    cmd.stdout_to_stderr();
+    cmd.status()?;
+    Ok(signature.to_owned())
+}
+
+/// Render the (unsigned) repository metadata, as `(path, contents)` pairs
+fn render_repos(
+    config: &PackageRepoConfig,
+    index: &PackageRepoIndex,
+    origin: &str,
+    now: DateTime<Utc>,
+) -> Vec<(String, Vec<u8>)> {
+    let mut files = vec![];
+
+    let debs = index
+        .packages
+        .iter()
+        .filter_map(|package| match &package.entry {
+            RepoEntry::Deb {
+                architecture,
+                paragraph,
+            } => Some((architecture.as_str(), paragraph.as_str())),
+            RepoEntry::Rpm { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    if config.apt && !debs.is_empty() {
+        let packages = debs
+            .iter()
+            .map(|(_, paragraph)| format!("{paragraph}\n"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .into_bytes();
+        let packages_gz = gzip(&packages);
+
+        let mut architectures = debs.iter().map(|(arch, _)| *arch).collect::<Vec<_>>();
+        architectures.sort();
+        architectures.dedup();
+        let mut release = String::new();
+        writeln!(&mut release, "Origin: {origin}").unwrap();
+        writeln!(&mut release, "Label: {origin}").unwrap();
+        // apt checks this against the directory in the sources.list line
+        writeln!(&mut release, "Suite: apt/").unwrap();
+        writeln!(
+            &mut release,
+            "Date: {}",
+            now.format("%a, %d %b %Y %H:%M:%S UTC")
+        )
+        .unwrap();
+        writeln!(&mut release, "Architectures: {}", architectures.join(" ")).unwrap();
+        writeln!(&mut release, "SHA256:").unwrap();
+        for (name, contents) in [("Packages", &packages), ("Packages.gz", &packages_gz)] {
+            writeln!(
+                &mut release,
+                " {:x} {} {name}",
+                Sha256::digest(contents),
+                contents.len()
+            )
+            .unwrap();
+        }
+
+        files.push(("apt/Packages".to_owned(), packages));
+        files.push(("apt/Packages.gz".to_owned(), packages_gz));
+        files.push(("apt/Release".to_owned(), release.into_bytes()));
+    }
+
+    let rpms = index
+        .packages
+        .iter()
+        .filter_map(|package| match &package.entry {
+            RepoEntry::Rpm {
+                primary,
+                filelists,
+                other,
+            } => Some((primary.as_str(), filelists.as_str(), other.as_str())),
+            RepoEntry::Deb { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    if config.yum && !rpms.is_empty() {
+        let count = rpms.len();
+        let header = r#"<?xml version="1.0" encoding="UTF-8"?>"#;
+        let primary = format!(
+            "{header}\n<metadata xmlns=\"http://linux.duke.edu/metadata/common\" xmlns:rpm=\"http://linux.duke.edu/metadata/rpm\" packages=\"{count}\">\n{}</metadata>\n",
+            rpms.iter().map(|(primary, _, _)| *primary).collect::<String>()
+        );
+        let filelists = format!(
+            "{header}\n<filelists xmlns=\"http://linux.duke.edu/metadata/filelists\" packages=\"{count}\">\n{}</filelists>\n",
+            rpms.iter().map(|(_, filelists, _)| *filelists).collect::<String>()
+        );
+        let other = format!(
+            "{header}\n<otherdata xmlns=\"http://linux.duke.edu/metadata/other\" packages=\"{count}\">\n{}</otherdata>\n",
+            rpms.iter().map(|(_, _, other)| *other).collect::<String>()
+        );
+
+        let timestamp = now.timestamp();
+        let mut repomd = format!(
+            "{header}\n<repomd xmlns=\"http://linux.duke.edu/metadata/repo\" xmlns:rpm=\"http://linux.duke.edu/metadata/rpm\">\n  <revision>{timestamp}</revision>\n"
+        );
+        for (kind, contents) in [
+            ("primary", primary),
+            ("filelists", filelists),
+            ("other", other),
+        ] {
+            let contents = contents.into_bytes();
+            let compressed = gzip(&contents);
+            let path = format!("repodata/{kind}.xml.gz");
+            writeln!(&mut repomd, "  <data type=\"{kind}\">").unwrap();
+            writeln!(
+                &mut repomd,
+                "    <checksum type=\"sha256\">{:x}</checksum>",
+                Sha256::digest(&compressed)
+            )
+            .unwrap();
+            writeln!(
+                &mut repomd,
+                "    <open-checksum type=\"sha256\">{:x}</open-checksum>",
+                Sha256::digest(&contents)
+            )
+            .unwrap();
+            writeln!(&mut repomd, "    <location href=\"{path}\"/>").unwrap();
+            writeln!(&mut repomd, "    <timestamp>{timestamp}</timestamp>").unwrap();
+            writeln!(&mut repomd, "    <size>{}</size>", compressed.len()).unwrap();
+            writeln!(&mut repomd, "    <open-size>{}</open-size>", contents.len()).unwrap();
+            writeln!(&mut repomd, "  </data>").unwrap();
+            files.push((format!("yum/{path}"), compressed));
+        }
+        repomd.push_str("</repomd>\n");
+        files.push(("yum/repodata/repomd.xml".to_owned(), repomd.into_bytes()));
+    }
+
+    files
+}
+
+/// Build the `Packages` paragraph for a .deb
+fn deb_entry(deb: &[u8], path: &str) -> Option<RepoEntry> {
+    let control = read_control_file(deb)?;
+    let control = control.trim_end();
+    let architecture = control
+        .lines()
+        .find_map(|line| line.strip_prefix("Architecture:"))?
+        .trim()
+        .to_owned();
+    let paragraph = format!(
+        "{control}\nFilename: {path}\nSize: {}\nSHA256: {:x}",
+        deb.len(),
+        Sha256::digest(deb)
+    );
+    Some(RepoEntry::Deb {
+        architecture,
+        paragraph,
+    })
+}
+
+/// Build the yum metadata for an .rpm
+fn rpm_entry(rpm: &[u8], path: &str, root_url: &str) -> Option<RepoEntry> {
+    let metadata = read_rpm_metadata(rpm)?;
+    let pkgid = format!("{:x}", Sha256::digest(rpm));
+    let name = xml_escape(&metadata.name);
+    let arch = xml_escape(&metadata.arch);
+    let version = format!(
+        "<version epoch=\"0\" ver=\"{}\" rel=\"{}\"/>",
+        xml_escape(&metadata.version),
+        xml_escape(&metadata.release)
+    );
+    let optional = |value: &Option<String>| xml_escape(value.as_deref().unwrap_or_default());
+
+    let mut primary = String::new();
+    writeln!(&mut primary, "<package type=\"rpm\">").unwrap();
+    writeln!(&mut primary, "  <name>{name}</name>").unwrap();
+    writeln!(&mut primary, "  <arch>{arch}</arch>").unwrap();
+    writeln!(&mut primary, "  {version}").unwrap();
+    writeln!(
+        &mut primary,
+        "  <checksum type=\"sha256\" pkgid=\"YES\">{pkgid}</checksum>"
+    )
+    .unwrap();
+    writeln!(
+        &mut primary,
+        "  <summary>{}</summary>",
+        xml_escape(&metadata.summary)
+    )
+    .unwrap();
+    writeln!(
+        &mut primary,
+        "  <description>{}</description>",
+        xml_escape(&metadata.description)
+    )
+    .unwrap();
+    writeln!(&mut primary, "  <packager/>").unwrap();
+    writeln!(&mut primary, "  <url>{}</url>", optional(&metadata.url)).unwrap();
+    writeln!(
+        &mut primary,
+        "  <time file=\"{0}\" build=\"{0}\"/>",
+        metadata.build_time
+    )
+    .unwrap();
+    writeln!(
+        &mut primary,
+        "  <size package=\"{}\" installed=\"{}\" archive=\"{}\"/>",
+        rpm.len(),
+        metadata.installed_size,
+        metadata.archive_size
+    )
+    .unwrap();
+    // The package lives with the rest of its release, not under the repository
+    writeln!(
+        &mut primary,
+        "  <location xml:base=\"{}/\" href=\"{}\"/>",
+        xml_escape(root_url),
+        xml_escape(path)
+    )
+    .unwrap();
+    writeln!(&mut primary, "  <format>").unwrap();
+    writeln!(
+        &mut primary,
+        "    <rpm:license>{}</rpm:license>",
+        optional(&metadata.license)
+    )
+    .unwrap();
+    writeln!(&mut primary, "    <rpm:vendor/>").unwrap();
+    writeln!(
+        &mut primary,
+        "    <rpm:group>{}</rpm:group>",
+        optional(&metadata.group)
+    )
+    .unwrap();
+    writeln!(&mut primary, "    <rpm:buildhost/>").unwrap();
+    writeln!(
+        &mut primary,
+        "    <rpm:sourcerpm>{}</rpm:sourcerpm>",
+        optional(&metadata.source_rpm)
+    )
+    .unwrap();
+    writeln!(
+        &mut primary,
+        "    <rpm:header-range start=\"{}\" end=\"{}\"/>",
+        metadata.header_start, metadata.header_end
+    )
+    .unwrap();
+    let requires = metadata
+        .requires
+        .iter()
+        .filter(|dep| !dep.is_rpmlib())
+        .collect::<Vec<_>>();
+    for (kind, deps) in [
+        ("provides", metadata.provides.iter().collect::<Vec<_>>()),
+        ("requires", requires),
+    ] {
+        if deps.is_empty() {
+            continue;
+        }
+        writeln!(&mut primary, "    <rpm:{kind}>").unwrap();
+        for dep in deps {
+            let name = xml_escape(&dep.name);
+            match dep.comparison() {
+                Some(flags) if !dep.version.is_empty() => {
+                    let (ver, rel) = match dep.version.rsplit_once('-') {
+                        Some((ver, rel)) => (ver, format!(" rel=\"{}\"", xml_escape(rel))),
+                        None => (dep.version.as_str(), String::new()),
+                    };
+                    writeln!(
+                        &mut primary,
+                        "      <rpm:entry name=\"{name}\" flags=\"{flags}\" epoch=\"0\" ver=\"{}\"{rel}/>",
+                        xml_escape(ver)
+                    )
+                    .unwrap();
+                }
+                _ => writeln!(&mut primary, "      <rpm:entry name=\"{name}\"/>").unwrap(),
+            }
+        }
+        writeln!(&mut primary, "    </rpm:{kind}>").unwrap();
+    }
+    // Like createrepo, only list the files people are likely to depend on in primary
+    for (file, is_dir) in &metadata.files {
+        if file.starts_with("/etc/") || file.contains("bin/") {
+            writeln!(&mut primary, "    {}", file_element(file, *is_dir)).unwrap();
+        }
+    }
+    writeln!(&mut primary, "  </format>").unwrap();
+    writeln!(&mut primary, "</package>").unwrap();
+
+    let mut filelists = String::new();
+    writeln!(
+        &mut filelists,
+        "<package pkgid=\"{pkgid}\" name=\"{name}\" arch=\"{arch}\">"
+    )
+    .unwrap();
+    writeln!(&mut filelists, "  {version}").unwrap();
+    for (file, is_dir) in &metadata.files {
+        writeln!(&mut filelists, "  {}", file_element(file, *is_dir)).unwrap();
+    }
+    writeln!(&mut filelists, "</package>").unwrap();
+
+    let other = format!(
+        "<package pkgid=\"{pkgid}\" name=\"{name}\" arch=\"{arch}\">\n  {version}\n</package>\n"
+    );
+
+    Some(RepoEntry::Rpm {
+        primary,
+        filelists,
+        other,
+    })
+}
+
+fn file_element(path: &str, is_dir: bool) -> String {
+    if is_dir {
+        format!("<file type=\"dir\">{}</file>", xml_escape(path))
+    } else {
+        format!("<file>{}</file>", xml_escape(path))
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn gzip(contents: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(vec![], Compression::best());
+    encoder
+        .write_all(contents)
+        .expect("writing to memory can't fail");
+    encoder.finish().expect("writing to memory can't fail")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use camino::Utf8PathBuf;
+    use chrono::TimeZone;
+    use flate2::read::GzDecoder;
+
+    use super::*;
+    use crate::backend::installer::{deb::DebInstallerInfo, rpm::RpmInstallerInfo};
+
+    fn config() -> PackageRepoConfig {
+        PackageRepoConfig {
+            apt: true,
+            yum: true,
+            gpg_key: None,
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    fn temp_dir() -> (temp_dir::TempDir, Utf8PathBuf) {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        std::fs::write(dir.join("axolotlsay"), b"not really elf").unwrap();
+        (tmp, dir)
+    }
+
+    fn file<'a>(files: &'a [(String, Vec<u8>)], path: &str) -> &'a [u8] {
+        &files.iter().find(|(p, _)| p == path).unwrap().1
+    }
+
+    fn deb(version: &str) -> RepoPackage {
+        let (_tmp, dir) = temp_dir();
+        let info = DebInstallerInfo {
+            artifact_id: "axolotlsay.deb".to_owned(),
+            file_path: dir.join("axolotlsay.deb"),
+            package_dir: dir.clone(),
+            package_name: "axolotlsay".to_owned(),
+            version: version.to_owned(),
+            architecture: "amd64".to_owned(),
+            maintainer: "axodotdev <hello@axo.dev>".to_owned(),
+            description: Some("say things".to_owned()),
+            homepage: None,
+            executables: vec!["axolotlsay".to_owned()],
+            cdylibs: vec![],
+            bin_aliases: BTreeMap::new(),
+            docs: vec![],
+        };
+        info.build(&DistManifest::new(vec![], BTreeMap::new()))
+            .unwrap();
+        let path = format!("axolotlsay/{version}/axolotlsay.deb");
+        let entry = deb_entry(&std::fs::read(&info.file_path).unwrap(), &path).unwrap();
+        RepoPackage { path, entry }
+    }
+
+    fn rpm(version: &str) -> RepoPackage {
+        let (_tmp, dir) = temp_dir();
+        let info = RpmInstallerInfo {
+            file_path: dir.join("axolotlsay.rpm"),
+            package_dir: dir.clone(),
+            package_name: "axolotlsay".to_owned(),
+            version: version.to_owned(),
+            release: "1".to_owned(),
+            architecture: "x86_64".to_owned(),
+            description: Some("say things & more".to_owned()),
+            license: None,
+            url: None,
+            executables: vec!["axolotlsay".to_owned()],
+            cdylibs: vec![],
+            bin_aliases: BTreeMap::new(),
+            docs: vec![],
+            licenses: vec![],
+        };
+        info.build().unwrap();
+        let path = format!("axolotlsay/{version}/axolotlsay.rpm");
+        let rpm = std::fs::read(&info.file_path).unwrap();
+        let entry = rpm_entry(&rpm, &path, "https://example.com/dist").unwrap();
+        RepoPackage { path, entry }
+    }
+
+    #[test]
+    fn index_keeps_previous_releases() {
+        let mut index = PackageRepoIndex::default();
+        index.add_package(deb("1.0.0"));
+        index.add_package(deb("1.1.0"));
+        index.add_package(deb("1.0.0"));
+
+        // The index survives being written out and read back in
+        let contents = serde_json::to_string(&index).unwrap();
+        let index: PackageRepoIndex = serde_json::from_str(&contents).unwrap();
+        let paths = index
+            .packages
+            .iter()
+            .map(|p| p.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "axolotlsay/1.1.0/axolotlsay.deb",
+                "axolotlsay/1.0.0/axolotlsay.deb"
+            ]
+        );
+    }
+
+    #[test]
+    fn apt_repo() {
+        let mut index = PackageRepoIndex::default();
+        index.add_package(deb("1.0.0"));
+        index.add_package(deb("1.1.0"));
+        let files = render_repos(&config(), &index, "axolotlsay", now());
+
+        let packages = String::from_utf8(file(&files, "apt/Packages").to_vec()).unwrap();
+        let paragraphs = packages.split("\n\n").collect::<Vec<_>>();
+        assert_eq!(paragraphs.len(), 2);
+        assert!(paragraphs[0].starts_with("Package: axolotlsay\n"));
+        assert!(paragraphs[1].contains("\nFilename: axolotlsay/1.1.0/axolotlsay.deb\n"));
+
+        let release = String::from_utf8(file(&files, "apt/Release").to_vec()).unwrap();
+        assert!(release.contains("Date: Wed, 01 May 2024 12:00:00 UTC\n"));
+        assert!(release.contains("Architectures: amd64\n"));
+        assert!(release.contains(&format!(
+            " {:x} {} Packages\n",
+            Sha256::digest(packages.as_bytes()),
+            packages.len()
+        )));
+        // No rpms, no yum repo
+        assert!(files.iter().all(|(path, _)| path.starts_with("apt/")));
+    }
+
+    #[test]
+    fn yum_repo() {
+        let mut index = PackageRepoIndex::default();
+        index.add_package(rpm("1.0.0"));
+        let files = render_repos(&config(), &index, "axolotlsay", now());
+
+        let compressed = file(&files, "yum/repodata/primary.xml.gz");
+        let mut primary = String::new();
+        std::io::Read::read_to_string(&mut GzDecoder::new(compressed), &mut primary).unwrap();
+        assert!(primary.contains("packages=\"1\""));
+        assert!(primary.contains("<summary>say things &amp; more</summary>"));
+        assert!(primary.contains(
+            "<location xml:base=\"https://example.com/dist/\" href=\"axolotlsay/1.0.0/axolotlsay.rpm\"/>"
+        ));
+        assert!(primary.contains("<file>/usr/bin/axolotlsay</file>"));
+        assert!(!primary.contains("rpmlib("));
+
+        let repomd = String::from_utf8(file(&files, "yum/repodata/repomd.xml").to_vec()).unwrap();
+        assert!(repomd.contains(&format!(
+            "<checksum type=\"sha256\">{:x}</checksum>",
+            Sha256::digest(compressed)
+        )));
+        assert!(repomd.contains("<location href=\"repodata/other.xml.gz\"/>"));
+        // No debs, no apt repo
+        assert!(files.iter().all(|(path, _)| path.starts_with("yum/")));
+    }
+}
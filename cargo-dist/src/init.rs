@@ -364,8 +364,12 @@ fn get_new_dist_metadata(
             ci: None,
             installers: None,
             install_success_msg: None,
+            minisign_public_key: None,
             tap: None,
             formula: None,
+            scoop_bucket: None,
+            winget_package_identifier: None,
+            pypi_manylinux: None,
             system_dependencies: None,
             targets: None,
             dist: None,
@@ -374,6 +378,8 @@ fn get_new_dist_metadata(
             windows_archive: None,
             unix_archive: None,
             npm_scope: None,
+            npm_platform_packages: None,
+            shell_offline: None,
             npm_package: None,
             checksum: None,
             precise_builds: None,
@@ -395,6 +401,8 @@ fn get_new_dist_metadata(
             publish_jobs: None,
             post_announce_jobs: None,
             publish_prereleases: None,
+            npm_registry: None,
+            npm_token_env_var: None,
             force_latest: None,
             create_release: None,
             github_releases_repo: None,
@@ -407,17 +415,41 @@ fn get_new_dist_metadata(
             github_attestations: None,
             msvc_crt_static: None,
             hosting: None,
+            s3_bucket: None,
+            s3_prefix: None,
+            s3_endpoint: None,
+            s3_region: None,
+            s3_public_url: None,
+            s3_latest_alias: None,
+            mirror_root: None,
+            mirror_url: None,
+            download_urls: None,
+            apt_repo: None,
+            yum_repo: None,
+            package_repo_gpg_key: None,
+            http_proxy: None,
+            http_ca_certs: None,
+            http_connect_timeout: None,
+            http_read_timeout: None,
+            http_retries: None,
+            http_retry_backoff: None,
             extra_artifacts: None,
+            oci_image: None,
+            oci_base_image: None,
             github_custom_runners: None,
             github_custom_job_permissions: None,
             bin_aliases: None,
             tag_namespace: None,
             install_updater: None,
+            uninstallers: None,
             display: None,
             display_name: None,
             package_libraries: None,
             install_libraries: None,
             github_build_setup: None,
+            gitlab_custom_runners: None,
+            gitlab_image: None,
+            forgejo_custom_runners: None,
             mac_pkg_config: None,
         }
     };
@@ -538,7 +570,7 @@ fn get_new_dist_metadata(
         // FIXME: when there is more than one option this should be a proper
         // multiselect like the installer selector is! For now we do
         // most of the multi-select logic and then just give a prompt.
-        let known = &[CiStyle::Github];
+        let known = &[CiStyle::Github, CiStyle::Gitlab, CiStyle::Forgejo];
         let mut defaults = vec![];
         let mut keys = vec![];
         let mut github_key = 0;
@@ -552,9 +584,8 @@ fn get_new_dist_metadata(
                 .unwrap_or(false)
                 || cfg.ci.contains(item);
 
-            // Currently default to enabling github CI because we don't
-            // support anything else and we can give a good error later
-            #[allow(irrefutable_let_patterns)]
+            // Currently default to enabling github CI because it's the
+            // only one with hosting and we can give a good error later
             if let CiStyle::Github = item {
                 github_key = 0;
                 default = true;
@@ -564,6 +595,8 @@ fn get_new_dist_metadata(
             // to `known` above!
             keys.push(match item {
                 CiStyle::Github => "github",
+                CiStyle::Gitlab => "gitlab",
+                CiStyle::Forgejo => "forgejo",
             });
         }
 
@@ -582,11 +615,17 @@ fn get_new_dist_metadata(
             res
         };
 
-        let selected = if github_selected {
+        let mut selected = if github_selected {
             vec![github_key]
         } else {
             vec![]
         };
+        // Other CI backends are only enabled if explicitly requested
+        for (idx, default) in defaults.iter().enumerate() {
+            if idx != github_key && *default {
+                selected.push(idx);
+            }
+        }
 
         // Apply the results
         let ci: Vec<_> = selected.into_iter().map(|i| known[i]).collect();
@@ -605,11 +644,23 @@ fn get_new_dist_metadata(
                 InstallerStyle::Powershell,
                 InstallerStyle::Npm,
                 InstallerStyle::Homebrew,
+                InstallerStyle::Scoop,
+                InstallerStyle::Winget,
                 InstallerStyle::Msi,
+                InstallerStyle::Deb,
+                InstallerStyle::Rpm,
+                InstallerStyle::Aur,
+                InstallerStyle::Nix,
+                InstallerStyle::Pypi,
             ]
         } else {
             eprintln!("{notice} no CI backends enabled, most installers have been hidden");
-            &[InstallerStyle::Msi]
+            &[
+                InstallerStyle::Msi,
+                InstallerStyle::Deb,
+                InstallerStyle::Rpm,
+                InstallerStyle::Pypi,
+            ]
         };
         let mut defaults = vec![];
         let mut keys = vec![];
@@ -633,8 +684,15 @@ fn get_new_dist_metadata(
                 InstallerStyle::Powershell => "powershell",
                 InstallerStyle::Npm => "npm",
                 InstallerStyle::Homebrew => "homebrew",
+                InstallerStyle::Scoop => "scoop",
+                InstallerStyle::Winget => "winget",
                 InstallerStyle::Msi => "msi",
                 InstallerStyle::Pkg => "pkg",
+                InstallerStyle::Deb => "deb",
+                InstallerStyle::Rpm => "rpm",
+                InstallerStyle::Aur => "aur",
+                InstallerStyle::Nix => "nix",
+                InstallerStyle::Pypi => "pypi",
             });
         }
 
@@ -722,6 +780,172 @@ fn get_new_dist_metadata(
         }
     }
 
+    // Special handling of the Scoop installer
+    if meta
+        .installers
+        .as_deref()
+        .unwrap_or_default()
+        .contains(&InstallerStyle::Scoop)
+    {
+        let scoop_is_new = !orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Scoop);
+
+        if scoop_is_new {
+            let prompt = r#"you've enabled Scoop support; if you want dist
+    to automatically push manifest updates to a bucket (repository) for you,
+    please enter the bucket name (in GitHub owner/name format)"#;
+            let default = "".to_string();
+
+            let bucket: String = if args.yes {
+                default
+            } else {
+                let res = Input::with_theme(&theme)
+                    .with_prompt(prompt)
+                    .allow_empty(true)
+                    .interact_text()?;
+                eprintln!();
+                res
+            };
+            let bucket = bucket.trim();
+            if bucket.is_empty() {
+                eprintln!("Scoop manifests will not be automatically published");
+                meta.scoop_bucket = None;
+            } else {
+                meta.scoop_bucket = Some(bucket.to_owned());
+                publish_jobs.push(PublishStyle::Scoop);
+
+                eprintln!("{check} Scoop manifest will be published to {bucket}");
+
+                eprintln!(
+                    r#"{check} You must provision a GitHub token and expose it as a secret named
+    SCOOP_BUCKET_TOKEN in GitHub Actions. For more information,
+    see the documentation:
+    https://opensource.axo.dev/cargo-dist/book/installers/scoop.html"#
+                );
+            }
+        }
+    } else {
+        let scoop_toggled_off = orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Scoop);
+        if scoop_toggled_off {
+            meta.scoop_bucket = None;
+            publish_jobs.retain(|job| job != &PublishStyle::Scoop);
+        }
+    }
+
+    // Special handling of the AUR installer
+    if meta
+        .installers
+        .as_deref()
+        .unwrap_or_default()
+        .contains(&InstallerStyle::Aur)
+    {
+        let aur_is_new = !orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Aur);
+
+        if aur_is_new {
+            let prompt = r#"you've enabled AUR support; do you want dist
+    to automatically push PKGBUILD updates to the AUR for you?"#;
+            let default = false;
+
+            let publish: bool = if args.yes {
+                default
+            } else {
+                let res = Confirm::with_theme(&theme)
+                    .with_prompt(prompt)
+                    .default(default)
+                    .interact()?;
+                eprintln!();
+                res
+            };
+            if publish {
+                publish_jobs.push(PublishStyle::Aur);
+
+                eprintln!("{check} PKGBUILDs will be published to the AUR");
+
+                eprintln!(
+                    r#"{check} You must register an SSH key with your AUR account and expose
+    the private key as a secret named AUR_SSH_PRIVATE_KEY in GitHub Actions.
+    For more information, see the documentation:
+    https://opensource.axo.dev/cargo-dist/book/installers/aur.html"#
+                );
+            } else {
+                eprintln!("PKGBUILDs will not be automatically published");
+            }
+        }
+    } else {
+        let aur_toggled_off = orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Aur);
+        if aur_toggled_off {
+            publish_jobs.retain(|job| job != &PublishStyle::Aur);
+        }
+    }
+
+    // Special handling of the PyPI installer
+    if meta
+        .installers
+        .as_deref()
+        .unwrap_or_default()
+        .contains(&InstallerStyle::Pypi)
+    {
+        let pypi_is_new = !orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Pypi);
+
+        if pypi_is_new && has_ci {
+            let prompt = r#"you've enabled Python wheels; do you want dist
+    to automatically publish them to PyPI for you?"#;
+            let default = false;
+
+            let publish: bool = if args.yes {
+                default
+            } else {
+                let res = Confirm::with_theme(&theme)
+                    .with_prompt(prompt)
+                    .default(default)
+                    .interact()?;
+                eprintln!();
+                res
+            };
+            if publish {
+                publish_jobs.push(PublishStyle::Pypi);
+
+                eprintln!("{check} wheels will be published to PyPI");
+
+                eprintln!(
+                    r#"{check} You must create a PyPI API token and expose it as a secret
+    named PYPI_TOKEN in GitHub Actions. For more information, see the documentation:
+    https://opensource.axo.dev/cargo-dist/book/installers/pypi.html"#
+                );
+            } else {
+                eprintln!("wheels will not be automatically published");
+            }
+        }
+    } else {
+        let pypi_toggled_off = orig_meta
+            .installers
+            .as_deref()
+            .unwrap_or_default()
+            .contains(&InstallerStyle::Pypi);
+        if pypi_toggled_off {
+            publish_jobs.retain(|job| job != &PublishStyle::Pypi);
+        }
+    }
+
     meta.publish_jobs = if publish_jobs.is_empty() {
         None
     } else {
@@ -844,8 +1068,11 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         ci,
         installers,
         install_success_msg,
+        minisign_public_key,
         tap,
         formula,
+        scoop_bucket,
+        winget_package_identifier,
         targets,
         include,
         auto_includes,
@@ -853,6 +1080,8 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         unix_archive,
         npm_scope,
         npm_package,
+        npm_platform_packages,
+        shell_offline,
         checksum,
         precise_builds,
         merge_tasks,
@@ -873,6 +1102,8 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         publish_jobs,
         post_announce_jobs,
         publish_prereleases,
+        npm_registry,
+        npm_token_env_var,
         force_latest,
         create_release,
         github_releases_repo,
@@ -884,8 +1115,29 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         github_attestations,
         msvc_crt_static,
         hosting,
+        s3_bucket,
+        s3_prefix,
+        s3_endpoint,
+        s3_region,
+        s3_public_url,
+        s3_latest_alias,
+        mirror_root,
+        mirror_url,
+        download_urls,
+        apt_repo,
+        yum_repo,
+        package_repo_gpg_key,
+        oci_image,
+        oci_base_image,
+        http_proxy,
+        http_ca_certs,
+        http_connect_timeout,
+        http_read_timeout,
+        http_retries,
+        http_retry_backoff,
         tag_namespace,
         install_updater,
+        uninstallers,
         display,
         display_name,
         github_release,
@@ -899,6 +1151,10 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         bin_aliases: _,
         system_dependencies: _,
         github_build_setup: _,
+        gitlab_custom_runners: _,
+        gitlab_image: _,
+        forgejo_custom_runners: _,
+        pypi_manylinux: _,
     } = &meta;
 
     // Forcibly inline the default install_path if not specified,
@@ -961,6 +1217,20 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         formula.clone(),
     );
 
+    apply_optional_value(
+        table,
+        "scoop-bucket",
+        "# A GitHub repo to push Scoop manifests to\n",
+        scoop_bucket.clone(),
+    );
+
+    apply_optional_value(
+        table,
+        "winget-package-identifier",
+        "# Customize the WinGet package identifier (Publisher.Package)\n",
+        winget_package_identifier.clone(),
+    );
+
     apply_string_list(
         table,
         "targets",
@@ -1017,6 +1287,13 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         install_success_msg.as_deref(),
     );
 
+    apply_optional_value(
+        table,
+        "minisign-public-key",
+        "# A minisign public key to check the archives' signatures against in the installers\n",
+        minisign_public_key.as_deref(),
+    );
+
     apply_optional_value(
         table,
         "npm-scope",
@@ -1024,6 +1301,20 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         npm_scope.as_deref(),
     );
 
+    apply_optional_value(
+        table,
+        "npm-platform-packages",
+        "# Whether to publish an npm package for each platform with the binaries inside\n",
+        *npm_platform_packages,
+    );
+
+    apply_optional_value(
+        table,
+        "shell-offline",
+        "# Whether to also generate a shell installer with the archives inside\n",
+        *shell_offline,
+    );
+
     apply_optional_value(
         table,
         "checksum",
@@ -1194,6 +1485,20 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         *publish_prereleases,
     );
 
+    apply_optional_value(
+        table,
+        "npm-registry",
+        "# The npm registry to publish npm packages to\n",
+        npm_registry.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "npm-token-env-var",
+        "# The secret with the token for publishing to npm\n",
+        npm_token_env_var.as_deref(),
+    );
+
     apply_optional_value(
         table,
         "force-latest",
@@ -1250,6 +1555,146 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         hosting.as_ref(),
     );
 
+    apply_optional_value(
+        table,
+        "s3-bucket",
+        "# The S3 bucket to host releases in\n",
+        s3_bucket.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "s3-prefix",
+        "# A key prefix to put releases under in the S3 bucket\n",
+        s3_prefix.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "s3-endpoint",
+        "# The S3 API endpoint (if not AWS)\n",
+        s3_endpoint.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "s3-region",
+        "# The region of the S3 bucket\n",
+        s3_region.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "s3-public-url",
+        "# The URL installers should download S3-hosted files from\n",
+        s3_public_url.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "s3-latest-alias",
+        "# Whether to copy stable releases to a latest/ prefix in the S3 bucket\n",
+        *s3_latest_alias,
+    );
+
+    apply_optional_value(
+        table,
+        "mirror-root",
+        "# The directory to publish a static mirror of releases into\n",
+        mirror_root.as_ref().map(|p| p.to_string()),
+    );
+
+    apply_optional_value(
+        table,
+        "mirror-url",
+        "# The URL the static mirror of releases will be served from\n",
+        mirror_url.as_deref(),
+    );
+
+    apply_string_list(
+        table,
+        "download-urls",
+        "# The base URLs installers should try to download artifacts from, in order\n",
+        download_urls.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "apt-repo",
+        "# Whether to publish an apt repository of the .deb installers\n",
+        *apt_repo,
+    );
+
+    apply_optional_value(
+        table,
+        "yum-repo",
+        "# Whether to publish a yum repository of the .rpm installers\n",
+        *yum_repo,
+    );
+
+    apply_optional_value(
+        table,
+        "package-repo-gpg-key",
+        "# The GPG key to sign apt/yum repository metadata with\n",
+        package_repo_gpg_key.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "oci-image",
+        "# Whether to build an OCI image of the binaries for each linux target\n",
+        *oci_image,
+    );
+
+    apply_optional_value(
+        table,
+        "oci-base-image",
+        "# The image (pinned by digest) linux-gnu OCI images are built on\n",
+        oci_base_image.as_deref(),
+    );
+
+    apply_optional_value(
+        table,
+        "http-proxy",
+        "# A proxy to send all of dist's http requests through\n",
+        http_proxy.as_deref(),
+    );
+
+    apply_string_list(
+        table,
+        "http-ca-certs",
+        "# Extra CA certificates (PEM files) to trust when making http requests\n",
+        http_ca_certs.as_ref(),
+    );
+
+    apply_optional_value(
+        table,
+        "http-connect-timeout",
+        "# How long to wait to connect to a server, in seconds\n",
+        http_connect_timeout.and_then(|t| i64::try_from(t).ok()),
+    );
+
+    apply_optional_value(
+        table,
+        "http-read-timeout",
+        "# How long to wait for a server to send more data, in seconds\n",
+        http_read_timeout.and_then(|t| i64::try_from(t).ok()),
+    );
+
+    apply_optional_value(
+        table,
+        "http-retries",
+        "# How many times to retry a failed fetch\n",
+        http_retries.map(i64::from),
+    );
+
+    apply_optional_value(
+        table,
+        "http-retry-backoff",
+        "# How long to wait before the first retry of a failed fetch, in seconds\n",
+        http_retry_backoff.and_then(|t| i64::try_from(t).ok()),
+    );
+
     apply_optional_value(
         table,
         "tag-namespace",
@@ -1264,6 +1709,13 @@ fn apply_dist_to_metadata(metadata: &mut toml_edit::Item, meta: &DistMetadata) {
         *install_updater,
     );
 
+    apply_optional_value(
+        table,
+        "uninstallers",
+        "# Whether to generate uninstallers next to the shell/powershell installers\n",
+        *uninstallers,
+    );
+
     apply_optional_value(
         table,
         "display",
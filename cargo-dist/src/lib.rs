@@ -18,6 +18,7 @@ use axoprocess::Cmd;
 use backend::{
     ci::CiInfo,
     installer::{self, macpkg::PkgInstallerInfo, msi::MsiInstallerInfo, InstallerImpl},
+    oci::{self, OciImageImpl},
 };
 use build::generic::{build_generic_target, run_extra_artifacts_build};
 use build::{
@@ -25,7 +26,7 @@ use build::{
     fake::{build_fake_cargo_target, build_fake_generic_target},
 };
 use camino::{Utf8Path, Utf8PathBuf};
-use cargo_dist_schema::{ArtifactId, DistManifest, TargetTriple};
+use cargo_dist_schema::{ArtifactId, BuildEnvironment, DistManifest, TargetTriple};
 use config::{
     ArtifactMode, ChecksumStyle, CompressionImpl, Config, DirtyMode, GenerateMode, ZipStyle,
 };
@@ -41,6 +42,7 @@ pub use tasks::*;
 pub mod announce;
 pub mod backend;
 pub mod build;
+pub mod cache;
 pub mod config;
 pub mod env;
 pub mod errors;
@@ -48,8 +50,10 @@ pub mod host;
 mod init;
 pub mod linkage;
 pub mod manifest;
+pub mod mirror;
 pub mod net;
 pub mod platform;
+pub mod publish;
 pub mod sign;
 pub mod tasks;
 #[cfg(test)]
@@ -163,6 +167,10 @@ fn run_build_step(
         }) => generate_source_tarball(dist_graph, committish, prefix, target, working_dir)?,
         BuildStep::Extra(target) => run_extra_artifacts_build(dist_graph, target)?,
         BuildStep::Updater(updater) => fetch_updater(dist_graph, updater)?,
+        BuildStep::GenerateOciImage(image) => match image {
+            OciImageImpl::Image(info) => oci::build_oci_image(dist_graph, info)?,
+            OciImageImpl::Index(info) => oci::build_oci_index(info)?,
+        },
     };
     Ok(())
 }
@@ -173,35 +181,19 @@ const AXOUPDATER_MINIMUM_VERSION: &str = "0.7.0";
 
 /// Fetches an installer executable and installs it in the expected target path.
 pub fn fetch_updater(dist_graph: &DistGraph, updater: &UpdaterStep) -> DistResult<()> {
-    let ext = if updater.target_triple.is_windows() {
-        ".zip"
-    } else {
-        ".tar.xz"
-    };
-    let expected_url = format!(
-        "{AXOUPDATER_ASSET_ROOT}/axoupdater-cli-{}{ext}",
-        updater.target_triple
-    );
-
-    let handle = tokio::runtime::Handle::current();
-    let resp = handle
-        .block_on(dist_graph.axoclient.head(&expected_url))
-        .map_err(|_| DistError::AxoupdaterReleaseCheckFailed {})?;
-
-    // If we have a prebuilt asset, use it
-    if resp.status().is_success() {
-        fetch_updater_from_binary(dist_graph, updater, &expected_url)
-    // If we got a 404, there's no asset, so we have to build from source
-    } else if resp.status() == axoasset::reqwest::StatusCode::NOT_FOUND {
-        fetch_updater_from_source(dist_graph, updater)
-    // Some unexpected result that wasn't 200 or 404
-    } else {
-        Err(DistError::AxoupdaterReleaseCheckFailed {})
+    match cache::fetch_updater_archive(dist_graph, &updater.target_triple)? {
+        // If we have a prebuilt asset, use it
+        Some(archive) => fetch_updater_from_binary(dist_graph, updater, &archive),
+        // If there's no asset, we have to build from source
+        None => fetch_updater_from_source(dist_graph, updater),
     }
 }
 
 /// Builds an installer executable from source and installs it in the expected target path.
 pub fn fetch_updater_from_source(dist_graph: &DistGraph, updater: &UpdaterStep) -> DistResult<()> {
+    dist_graph
+        .client_settings
+        .check_online("building axoupdater from source")?;
     let (_tmp_dir, tmp_root) = create_tmp()?;
 
     // Update this to work from releases, and to fetch prebuilt binaries,
@@ -242,21 +234,12 @@ pub fn create_tmp() -> DistResult<(TempDir, Utf8PathBuf)> {
     Ok((tmp_dir, tmp_root))
 }
 
-/// Fetches an installer executable from a preexisting binary and installs it in the expected target path.
+/// Installs an installer executable from a prebuilt archive in the expected target path.
 fn fetch_updater_from_binary(
     dist_graph: &DistGraph,
     updater: &UpdaterStep,
-    asset_url: &str,
+    archive: &Utf8Path,
 ) -> DistResult<()> {
-    let (_tmp_dir, tmp_root) = create_tmp()?;
-    let zipball_target = tmp_root.join("archive");
-
-    let handle = tokio::runtime::Handle::current();
-    handle.block_on(
-        dist_graph
-            .axoclient
-            .load_and_write_to_file(asset_url, &zipball_target),
-    )?;
     let suffix = if updater.target_triple.is_windows() {
         ".exe"
     } else {
@@ -264,14 +247,14 @@ fn fetch_updater_from_binary(
     };
     let requested_filename = format!("axoupdater{suffix}");
 
-    let bytes = if asset_url.ends_with(".tar.xz") {
-        LocalAsset::untar_xz_file(&zipball_target, &requested_filename)?
-    } else if asset_url.ends_with(".tar.gz") {
-        LocalAsset::untar_gz_file(&zipball_target, &requested_filename)?
-    } else if asset_url.ends_with(".zip") {
-        LocalAsset::unzip_file(&zipball_target, &requested_filename)?
+    let bytes = if archive.as_str().ends_with(".tar.xz") {
+        LocalAsset::untar_xz_file(archive, &requested_filename)?
+    } else if archive.as_str().ends_with(".tar.gz") {
+        LocalAsset::untar_gz_file(archive, &requested_filename)?
+    } else if archive.as_str().ends_with(".zip") {
+        LocalAsset::unzip_file(archive, &requested_filename)?
     } else {
-        let extension = Utf8PathBuf::from(asset_url)
+        let extension = archive
             .extension()
             .unwrap_or("unable to determine")
             .to_owned();
@@ -354,6 +337,14 @@ fn build_fake(
         // Or extra artifacts, which may involve real builds
         BuildStep::Extra(target) => run_fake_extra_artifacts_build(dist_graph, target)?,
         BuildStep::Updater(_) => unimplemented!(),
+        // Base images have to be pulled from a registry, so don't build images at all
+        BuildStep::GenerateOciImage(image) => {
+            let file_path = match image {
+                OciImageImpl::Image(info) => &info.file_path,
+                OciImageImpl::Index(info) => &info.file_path,
+            };
+            LocalAsset::write_new_all("", file_path)?;
+        }
     }
     Ok(())
 }
@@ -695,7 +686,11 @@ pub fn run_generate(dist: &DistGraph, args: &GenerateArgs) -> DistResult<()> {
             match mode {
                 GenerateMode::Ci => {
                     // If you add a CI backend, call it here
-                    let CiInfo { github } = &dist.ci;
+                    let CiInfo {
+                        github,
+                        gitlab,
+                        forgejo,
+                    } = &dist.ci;
                     if let Some(github) = github {
                         if args.check {
                             github.check(dist)?;
@@ -703,6 +698,20 @@ pub fn run_generate(dist: &DistGraph, args: &GenerateArgs) -> DistResult<()> {
                             github.write_to_disk(dist)?;
                         }
                     }
+                    if let Some(gitlab) = gitlab {
+                        if args.check {
+                            gitlab.check(dist)?;
+                        } else {
+                            gitlab.write_to_disk(dist)?;
+                        }
+                    }
+                    if let Some(forgejo) = forgejo {
+                        if args.check {
+                            forgejo.check(dist)?;
+                        } else {
+                            forgejo.write_to_disk(dist)?;
+                        }
+                    }
                 }
                 GenerateMode::Msi => {
                     for artifact in &dist.artifacts {
@@ -739,6 +748,7 @@ pub fn check_integrity(cfg: &Config) -> DistResult<()> {
         artifact_mode: ArtifactMode::All,
         no_local_paths: false,
         allow_all_dirty: cfg.allow_all_dirty,
+        offline: cfg.offline,
         targets: vec![],
         ci: vec![],
         installers: vec![],
@@ -775,16 +785,47 @@ fn generate_installer(
     manifest: &DistManifest,
 ) -> DistResult<()> {
     match style {
-        InstallerImpl::Shell(info) => installer::shell::write_install_sh_script(dist, info)?,
+        InstallerImpl::Shell(info) => {
+            installer::shell::write_install_sh_script(dist, info, manifest)?
+        }
+        InstallerImpl::ShellOffline(info) => {
+            installer::shell::write_offline_install_sh_script(dist, info, manifest)?
+        }
         InstallerImpl::Powershell(info) => {
-            installer::powershell::write_install_ps_script(dist, info)?
+            installer::powershell::write_install_ps_script(dist, info, manifest)?
         }
-        InstallerImpl::Npm(info) => installer::npm::write_npm_project(dist, info)?,
+        InstallerImpl::ShellUninstaller(info) => {
+            installer::shell::write_uninstall_sh_script(dist, info)?
+        }
+        InstallerImpl::PowershellUninstaller(info) => {
+            installer::powershell::write_uninstall_ps_script(dist, info)?
+        }
+        InstallerImpl::Npm(info) => installer::npm::write_npm_project(dist, info, manifest)?,
+        InstallerImpl::NpmPlatform(info) => installer::npm::write_npm_platform_package(info)?,
         InstallerImpl::Homebrew(info) => {
             installer::homebrew::write_homebrew_formula(dist, info, manifest)?
         }
+        InstallerImpl::HomebrewCask(info) => {
+            installer::homebrew::write_homebrew_cask(dist, info, manifest)?
+        }
         InstallerImpl::Msi(info) => info.build(dist)?,
         InstallerImpl::Pkg(info) => info.build()?,
+        InstallerImpl::Scoop(info) => installer::scoop::write_scoop_manifest(dist, info, manifest)?,
+        InstallerImpl::Winget(info) => {
+            installer::winget::write_winget_manifests(dist, info, manifest)?
+        }
+        InstallerImpl::Deb(info) => info.build(manifest)?,
+        InstallerImpl::Rpm(info) => info.build()?,
+        InstallerImpl::Aur(info) => installer::aur::write_aur_package(dist, info, manifest)?,
+        InstallerImpl::Nix(info) => installer::nix::write_nix_expressions(dist, info, manifest)?,
+        InstallerImpl::Pypi(info) => {
+            let build_environment = manifest
+                .systems
+                .get(&dist.system_id)
+                .map(|system| system.build_environment.clone())
+                .unwrap_or(BuildEnvironment::Indeterminate);
+            info.build(&build_environment)?
+        }
     }
     Ok(())
 }
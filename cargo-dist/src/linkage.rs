@@ -254,39 +254,41 @@ pub fn library_from_apt(library: String) -> DistResult<Library> {
         });
     }
 
-    let process = Cmd::new("dpkg", "get linkage info from dpkg")
-        .arg("--search")
-        .arg(&library)
-        .output();
-    match process {
-        Ok(output) => {
-            let output = String::from_utf8(output.stdout)?;
-
-            let package = output.split(':').next().unwrap();
-            let source = if package.is_empty() {
-                None
-            } else {
-                Some(package.to_owned())
-            };
-            let package_manager = if source.is_some() {
-                Some(PackageManager::Apt)
-            } else {
-                None
-            };
+    // On merged-/usr systems the loader may report /usr/lib/... for a file
+    // that dpkg recorded as /lib/... (or vice versa), so try both spellings
+    let alternate = if let Some(rest) = library.strip_prefix("/usr/lib/") {
+        Some(format!("/lib/{rest}"))
+    } else {
+        library
+            .strip_prefix("/lib/")
+            .map(|rest| format!("/usr/lib/{rest}"))
+    };
+    for candidate in std::iter::once(&library).chain(alternate.as_ref()) {
+        let process = Cmd::new("dpkg", "get linkage info from dpkg")
+            .arg("--search")
+            .arg(candidate)
+            .output();
+        // Couldn't find a package for this file
+        let Ok(output) = process else {
+            continue;
+        };
+        let output = String::from_utf8(output.stdout)?;
 
-            Ok(Library {
+        let package = output.split(':').next().unwrap();
+        if !package.is_empty() {
+            return Ok(Library {
                 path: library,
-                source,
-                package_manager,
-            })
+                source: Some(package.to_owned()),
+                package_manager: Some(PackageManager::Apt),
+            });
         }
-        // Couldn't find a package for this file
-        Err(_) => Ok(Library {
-            path: library,
-            source: None,
-            package_manager: None,
-        }),
     }
+
+    Ok(Library {
+        path: library,
+        source: None,
+        package_manager: None,
+    })
 }
 
 fn do_otool(path: &Utf8PathBuf) -> DistResult<Vec<String>> {
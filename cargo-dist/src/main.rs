@@ -13,7 +13,9 @@ use cargo_dist::{linkage::LinkageDisplay, *};
 use cargo_dist_schema::{AssetKind, DistManifest};
 use clap::Parser;
 use cli::{
-    Cli, Commands, GenerateMode, HelpMarkdownArgs, HostArgs, ManifestArgs, OutputFormat, PlanArgs,
+    CacheCommands, CacheFillArgs, Cli, Commands, GenerateMode, HelpMarkdownArgs, HostArgs,
+    ManifestArgs, MirrorArgs, OutputFormat, PlanArgs, PublishCommands, PublishCratesArgs,
+    WingetArgs,
 };
 use console::Term;
 use miette::{miette, IntoDiagnostic};
@@ -57,6 +59,10 @@ fn real_main(cli: &axocli::CliApp<Cli>) -> Result<(), miette::Report> {
         Commands::ManifestSchema(args) => cmd_manifest_schema(config, args),
         Commands::Build(args) => cmd_build(config, args),
         Commands::Host(args) => cmd_host(config, args),
+        Commands::Mirror(args) => cmd_mirror(config, args),
+        Commands::Cache(CacheCommands::Fill(args)) => cmd_cache_fill(config, args),
+        Commands::Publish(PublishCommands::Crates(args)) => cmd_publish_crates(config, args),
+        Commands::Winget(args) => cmd_winget(config, args),
         Commands::Selfupdate(args) => runtime.block_on(cmd_update(config, args)),
     }
 }
@@ -218,6 +224,7 @@ fn cmd_build(cli: &Cli, args: &BuildArgs) -> Result<(), miette::Report> {
         artifact_mode: args.artifacts.to_lib(),
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
@@ -249,6 +256,7 @@ fn cmd_host(cli: &Cli, args: &HostArgs) -> Result<(), miette::Report> {
         artifact_mode: config::ArtifactMode::All,
         no_local_paths: true,
         allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
@@ -259,6 +267,89 @@ fn cmd_host(cli: &Cli, args: &HostArgs) -> Result<(), miette::Report> {
     print(cli, &report, false, Some("host"))
 }
 
+fn cmd_mirror(cli: &Cli, args: &MirrorArgs) -> Result<(), miette::Report> {
+    let args = cargo_dist::config::MirrorArgs {
+        manifest: args.manifest.clone(),
+        base_url: args.base_url.trim_end_matches('/').to_owned(),
+        output_dir: args.output_dir.clone(),
+    };
+    let config = cargo_dist::config::Config {
+        tag_settings: cli.tag_settings(true),
+        create_hosting: false,
+        artifact_mode: config::ArtifactMode::Global,
+        no_local_paths: true,
+        allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
+        targets: cli.target.clone(),
+        ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
+        installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
+        root_cmd: "mirror".to_owned(),
+    };
+
+    let report = cargo_dist::mirror::do_mirror(&config, &args)?;
+    print(cli, &report, false, None)
+}
+
+fn cmd_cache_fill(cli: &Cli, _args: &CacheFillArgs) -> Result<(), miette::Report> {
+    let config = cargo_dist::config::Config {
+        tag_settings: cli.tag_settings(false),
+        create_hosting: false,
+        artifact_mode: config::ArtifactMode::All,
+        no_local_paths: true,
+        allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
+        targets: cli.target.clone(),
+        ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
+        installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
+        root_cmd: "cache-fill".to_owned(),
+    };
+    cargo_dist::cache::do_cache_fill(&config)?;
+    Ok(())
+}
+
+fn cmd_publish_crates(cli: &Cli, args: &PublishCratesArgs) -> Result<(), miette::Report> {
+    let args = cargo_dist::config::PublishCratesArgs {
+        registry: args.registry.clone(),
+        index: args.index.clone(),
+        dry_run: args.dry_run,
+    };
+    let config = cargo_dist::config::Config {
+        tag_settings: cli.tag_settings(true),
+        create_hosting: false,
+        artifact_mode: config::ArtifactMode::Global,
+        no_local_paths: true,
+        allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
+        targets: cli.target.clone(),
+        ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
+        installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
+        root_cmd: "publish-crates".to_owned(),
+    };
+    cargo_dist::publish::crates::do_publish_crates(&config, &args)?;
+    Ok(())
+}
+
+fn cmd_winget(cli: &Cli, args: &WingetArgs) -> Result<(), miette::Report> {
+    let args = cargo_dist::config::WingetArgs {
+        check: args.check,
+        output_dir: args.output_dir.clone(),
+    };
+    let config = cargo_dist::config::Config {
+        tag_settings: cli.tag_settings(true),
+        create_hosting: false,
+        artifact_mode: config::ArtifactMode::Global,
+        no_local_paths: false,
+        allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
+        targets: cli.target.clone(),
+        ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
+        installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
+        root_cmd: "winget".to_owned(),
+    };
+    cargo_dist::backend::installer::winget::do_winget(&config, &args)?;
+    Ok(())
+}
+
 fn cmd_manifest(cli: &Cli, args: &ManifestArgs) -> Result<(), miette::Report> {
     let config = cargo_dist::config::Config {
         tag_settings: cli.tag_settings(true),
@@ -266,6 +357,7 @@ fn cmd_manifest(cli: &Cli, args: &ManifestArgs) -> Result<(), miette::Report> {
         artifact_mode: args.build_args.artifacts.to_lib(),
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
@@ -297,6 +389,7 @@ fn cmd_init(cli: &Cli, args: &InitArgs) -> Result<(), miette::Report> {
         artifact_mode: cargo_dist::config::ArtifactMode::All,
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
@@ -319,6 +412,7 @@ fn cmd_generate(cli: &Cli, args: &GenerateArgs) -> Result<(), miette::Report> {
         artifact_mode: cargo_dist::config::ArtifactMode::All,
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
@@ -339,6 +433,7 @@ fn cmd_linkage(cli: &Cli, args: &LinkageArgs) -> Result<(), miette::Report> {
         artifact_mode: cargo_dist::config::ArtifactMode::All,
         no_local_paths: cli.no_local_paths,
         allow_all_dirty: cli.allow_dirty,
+        offline: cli.offline,
         targets: cli.target.clone(),
         ci: cli.ci.iter().map(|ci| ci.to_lib()).collect(),
         installers: cli.installer.iter().map(|ins| ins.to_lib()).collect(),
@@ -581,7 +676,7 @@ fn perform_init(path: &Utf8PathBuf, args: &cli::UpdateArgs) -> Result<(), miette
     Ok(())
 }
 
-async fn cmd_update(_config: &Cli, args: &cli::UpdateArgs) -> Result<(), miette::ErrReport> {
+async fn cmd_update(config: &Cli, args: &cli::UpdateArgs) -> Result<(), miette::ErrReport> {
     // If the user is asking us to run init, but it doesn't look like we can, error
     // out immediately to avoid the user getting confused and thinking the update didn't work!
     if !args.skip_init {
@@ -610,7 +705,24 @@ async fn cmd_update(_config: &Cli, args: &cli::UpdateArgs) -> Result<(), miette:
     updater.configure_version_specifier(specifier);
 
     // Want this code to get updated if we develop http client opinions
-    let ClientSettings {} = ClientSettings::new();
+    //
+    // axoupdater makes its own http clients, so most of these don't apply to it,
+    // although it does respect the standard HTTPS_PROXY environment variable.
+    let ClientSettings {
+        proxy: _,
+        ca_certs: _,
+        connect_timeout: _,
+        read_timeout: _,
+        retries: _,
+        retry_backoff: _,
+        offline,
+    } = ClientSettings::new()?;
+    if offline || config.offline {
+        return Err(errors::DistError::Offline {
+            what: "dist selfupdate".to_owned(),
+        }
+        .into());
+    }
 
     // This uses debug assertions because we want to avoid this
     // being compiled into the release build; this is purely for
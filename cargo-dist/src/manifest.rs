@@ -46,8 +46,15 @@ use tracing::warn;
 use crate::{
     announce::AnnouncementTag,
     backend::{
-        installer::{homebrew::HomebrewInstallerInfo, npm::NpmInstallerInfo, InstallerImpl},
-        templates::{TemplateEntry, TEMPLATE_INSTALLER_NPM},
+        installer::{
+            aur::{self, AurInstallerInfo},
+            homebrew::HomebrewInstallerInfo,
+            nix::{self, NixInstallerInfo},
+            npm::NpmInstallerInfo,
+            scoop::ScoopInstallerInfo,
+            winget, InstallerImpl,
+        },
+        templates::TemplateEntry,
     },
     config::Config,
     errors::DistResult,
@@ -102,13 +109,32 @@ pub(crate) fn load_and_merge_manifests(
             let out_release =
                 output.ensure_release(release.app_name.clone(), release.app_version.clone());
             // If the input has hosting info, apply it
-            let Hosting { axodotdev, github } = release.hosting;
+            let Hosting {
+                axodotdev,
+                github,
+                gitea,
+                s3,
+                mirror,
+                download_urls,
+            } = release.hosting;
             if let Some(hosting) = axodotdev {
                 out_release.hosting.axodotdev = Some(hosting);
             }
             if let Some(hosting) = github {
                 out_release.hosting.github = Some(hosting);
             }
+            if let Some(hosting) = gitea {
+                out_release.hosting.gitea = Some(hosting);
+            }
+            if let Some(hosting) = s3 {
+                out_release.hosting.s3 = Some(hosting);
+            }
+            if let Some(hosting) = mirror {
+                out_release.hosting.mirror = Some(hosting);
+            }
+            if !download_urls.is_empty() {
+                out_release.hosting.download_urls = download_urls;
+            }
             // If the input has a list of artifacts for this release, merge them
             for artifact in release.artifacts {
                 if !out_release.artifacts.contains(&artifact) {
@@ -296,10 +322,11 @@ fn add_manifest_artifact(
     //
     // These can't be pre-included in the normal static assets list above because
     // they're generated from templates, and not copied from the user's project.
-    if let ArtifactKind::Installer(InstallerImpl::Npm(..)) = &artifact.kind {
+    if let ArtifactKind::Installer(InstallerImpl::Npm(info)) = &artifact.kind {
+        let (template_dir, _) = info.template_dir();
         let root_dir = dist
             .templates
-            .get_template_dir(TEMPLATE_INSTALLER_NPM)
+            .get_template_dir(template_dir)
             .expect("npm template missing!?");
         let mut queue = vec![root_dir];
         while let Some(dir) = queue.pop() {
@@ -321,6 +348,43 @@ fn add_manifest_artifact(
         }
     }
 
+    // Same for the manifests in a WinGet archive
+    if let ArtifactKind::Installer(InstallerImpl::Winget(info)) = &artifact.kind {
+        let dir = Utf8Path::new("manifests").join(info.relative_dir());
+        for name in winget::file_names(&info.package_identifier) {
+            static_assets.push(Asset {
+                id: None,
+                path: Some(dir.join(&name).to_string()),
+                name: Some(name),
+                kind: AssetKind::Unknown,
+            });
+        }
+    }
+
+    // And the PKGBUILD in an AUR archive
+    if let ArtifactKind::Installer(InstallerImpl::Aur(info)) = &artifact.kind {
+        for name in aur::AUR_FILES {
+            static_assets.push(Asset {
+                id: None,
+                name: Some(name.to_owned()),
+                path: Some(format!("{}/{name}", info.package_name)),
+                kind: AssetKind::Unknown,
+            });
+        }
+    }
+
+    // And the expressions in a Nix archive
+    if let ArtifactKind::Installer(InstallerImpl::Nix(info)) = &artifact.kind {
+        for name in nix::NIX_FILES {
+            static_assets.push(Asset {
+                id: None,
+                name: Some(name.to_owned()),
+                path: Some(format!("{}/{name}", info.pname)),
+                kind: AssetKind::Unknown,
+            });
+        }
+    }
+
     assets.extend(built_assets);
     assets.extend(static_assets);
     // Sort the assets by name to make things extra stable
@@ -344,13 +408,35 @@ fn add_manifest_artifact(
         ArtifactKind::Installer(
             InstallerImpl::Powershell(info)
             | InstallerImpl::Shell(info)
+            | InstallerImpl::ShellOffline(info)
             | InstallerImpl::Homebrew(HomebrewInstallerInfo { inner: info, .. })
-            | InstallerImpl::Npm(NpmInstallerInfo { inner: info, .. }),
+            | InstallerImpl::Npm(NpmInstallerInfo { inner: info, .. })
+            | InstallerImpl::Scoop(ScoopInstallerInfo { inner: info, .. }),
         ) => {
             install_hint = Some(info.hint.clone());
             description = Some(info.desc.clone());
             kind = cargo_dist_schema::ArtifactKind::Installer;
         }
+        ArtifactKind::Installer(InstallerImpl::ShellUninstaller(..)) => {
+            install_hint = None;
+            description = Some("uninstall what the shell installer installed".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::PowershellUninstaller(..)) => {
+            install_hint = None;
+            description = Some("uninstall what the powershell installer installed".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::HomebrewCask(info)) => {
+            install_hint = Some(info.hint.clone());
+            description = Some("Homebrew cask for the pkg installer".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::NpmPlatform(..)) => {
+            install_hint = None;
+            description = Some("npm package with the binaries for this platform".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
         ArtifactKind::Installer(InstallerImpl::Msi(..)) => {
             install_hint = None;
             description = Some("install via msi".to_owned());
@@ -361,6 +447,34 @@ fn add_manifest_artifact(
             description = Some("install via pkg".to_owned());
             kind = cargo_dist_schema::ArtifactKind::Installer;
         }
+        ArtifactKind::Installer(InstallerImpl::Deb(..)) => {
+            install_hint = None;
+            description = Some("install via apt or dpkg".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Rpm(..)) => {
+            install_hint = None;
+            description = Some("install via dnf, yum or rpm".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Pypi(..)) => {
+            install_hint = None;
+            description = Some("install via pip".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(InstallerImpl::Winget(..)) => {
+            install_hint = None;
+            description = Some("WinGet manifests, for submitting to winget-pkgs".to_owned());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
+        ArtifactKind::Installer(
+            InstallerImpl::Aur(AurInstallerInfo { inner: info, .. })
+            | InstallerImpl::Nix(NixInstallerInfo { inner: info, .. }),
+        ) => {
+            install_hint = Some(info.hint.clone());
+            description = Some(info.desc.clone());
+            kind = cargo_dist_schema::ArtifactKind::Installer;
+        }
         ArtifactKind::Checksum(_) => {
             install_hint = None;
             description = None;
@@ -386,6 +500,11 @@ fn add_manifest_artifact(
             description = None;
             kind = cargo_dist_schema::ArtifactKind::Updater;
         }
+        ArtifactKind::OciImage(_) => {
+            install_hint = None;
+            description = None;
+            kind = cargo_dist_schema::ArtifactKind::OciImage;
+        }
     };
 
     let checksum = artifact.checksum.map(|idx| dist.artifact(idx).id.clone());
@@ -0,0 +1,355 @@
+//! dist mirror -- localize a published release for offline installs
+//!
+//! This downloads every file of a release described by a published dist-manifest.json,
+//! checks them against the checksums the manifest records, and then re-renders the
+//! shell and powershell installers so that they fetch from wherever the files will
+//! be served from next.
+
+use axoasset::{AxoClient, LocalAsset, SourceFile};
+use camino::Utf8Path;
+use cargo_dist_schema::{ArtifactKind, DistManifest, Hosting, MirrorHosting};
+use tracing::warn;
+
+use crate::{
+    announce::TagMode,
+    backend::installer::{powershell, shell, InstallerImpl},
+    config::{ChecksumStyle, Config, MirrorArgs},
+    errors::DistResult,
+    gather_work, generate_checksum, generate_unified_checksum,
+    net::{create_axoasset_client, with_retries, ClientSettings},
+    write_checksum, ArtifactKind as DistArtifactKind, DistError, DistGraph, SortedSet,
+};
+
+/// Download a published release and point its installers at a new base URL
+pub fn do_mirror(cfg: &Config, args: &MirrorArgs) -> DistResult<DistManifest> {
+    // The installers are rendered from the project's config, so make sure there is
+    // one before downloading anything
+    crate::config::get_project().map_err(|cause| DistError::MirrorNotInWorkspace { cause })?;
+
+    let mut settings = ClientSettings::new()?;
+    settings.offline |= cfg.offline;
+    settings.check_online("dist mirror")?;
+    let client = create_axoasset_client(&settings)?;
+    let mut manifest = load_manifest(&client, &settings, &args.manifest)?;
+
+    // Unless told otherwise, regenerate things for the release we're mirroring
+    let mut cfg = cfg.clone();
+    if let (TagMode::Infer, Some(tag)) = (&cfg.tag_settings.tag, &manifest.announcement_tag) {
+        cfg.tag_settings.tag = TagMode::Select(tag.clone());
+    }
+
+    let (dist, _) = gather_work(&cfg)?;
+
+    LocalAsset::create_dir_all(&args.output_dir)?;
+    download_release(&dist, &manifest, &args.output_dir)?;
+    point_at_mirror(&mut manifest, &args.base_url);
+    rerender_installers(&dist, args, &mut manifest)?;
+
+    let manifest_path = args.output_dir.join("dist-manifest.json");
+    crate::manifest::save_manifest(&manifest_path, &manifest)?;
+    eprintln!("release mirrored to {}", args.output_dir);
+    Ok(manifest)
+}
+
+/// Make the mirror the only host of every release, so nothing points at the old location
+fn point_at_mirror(manifest: &mut DistManifest, base_url: &str) {
+    for release in &mut manifest.releases {
+        let old_url = release.artifact_download_url().map(ToOwned::to_owned);
+        release.hosting = Hosting {
+            mirror: Some(MirrorHosting {
+                artifact_download_url: base_url.to_owned(),
+                path: ".".to_owned(),
+            }),
+            ..Hosting::default()
+        };
+
+        // Point the install hints at the new location too
+        let Some(old_url) = old_url else {
+            continue;
+        };
+        for artifact_id in &release.artifacts {
+            if let Some(artifact) = manifest.artifacts.get_mut(artifact_id) {
+                if let Some(hint) = &mut artifact.install_hint {
+                    *hint = hint.replace(&old_url, base_url);
+                }
+            }
+        }
+    }
+}
+
+/// Load the manifest from a URL or a local path
+fn load_manifest(
+    client: &AxoClient,
+    settings: &ClientSettings,
+    manifest: &str,
+) -> DistResult<DistManifest> {
+    if manifest.starts_with("http://") || manifest.starts_with("https://") {
+        let contents = tokio::runtime::Handle::current().block_on(with_retries(
+            settings,
+            "fetching dist-manifest.json",
+            || client.load_string(manifest),
+        ))?;
+        let file = SourceFile::new(manifest, contents);
+        Ok(file.deserialize_json()?)
+    } else {
+        let file = SourceFile::load_local(manifest)?;
+        Ok(file.deserialize_json()?)
+    }
+}
+
+/// Download every file of every release and check it against the manifest
+fn download_release(
+    dist: &DistGraph,
+    manifest: &DistManifest,
+    output_dir: &Utf8Path,
+) -> DistResult<()> {
+    let mut downloaded = SortedSet::new();
+    for release in &manifest.releases {
+        let Some(base_url) = release.artifact_download_url() else {
+            return Err(DistError::MirrorNoDownloadUrl {
+                app_name: release.app_name.clone(),
+            });
+        };
+        for (_id, artifact) in manifest.artifacts_for_release(release) {
+            let Some(name) = &artifact.name else {
+                continue;
+            };
+            if !downloaded.insert(name.clone()) {
+                continue;
+            }
+            let url = format!("{base_url}/{name}");
+            let dest = output_dir.join(name);
+            eprintln!("downloading {url}");
+            tokio::runtime::Handle::current().block_on(with_retries(
+                &dist.client_settings,
+                &format!("downloading {name}"),
+                || dist.axoclient.load_and_write_to_file(&url, &dest),
+            ))?;
+
+            if artifact.checksums.is_empty() && !matches!(artifact.kind, ArtifactKind::Checksum) {
+                warn!("{name} has no recorded checksums, it can't be verified");
+            }
+            for (algorithm, expected) in &artifact.checksums {
+                let Some(style) = checksum_style(algorithm) else {
+                    warn!("don't know how to verify {algorithm} checksum of {name}");
+                    continue;
+                };
+                let actual = generate_checksum(&style, &dest)?;
+                if !actual.eq_ignore_ascii_case(expected) {
+                    return Err(DistError::MirrorChecksumMismatch {
+                        name: name.clone(),
+                        algorithm: algorithm.clone(),
+                        expected: expected.clone(),
+                        actual,
+                    });
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Regenerate the shell/powershell installers with the new base URL,
+/// and update any checksums of them we have lying around
+fn rerender_installers(
+    dist: &DistGraph,
+    args: &MirrorArgs,
+    manifest: &mut DistManifest,
+) -> DistResult<()> {
+    // The mirror only has this one version, so the installers shouldn't offer to find
+    // others (they'd look on the original host). Without the tag to work out other
+    // versions' tags from, the installers don't.
+    let mut render_manifest = manifest.clone();
+    render_manifest.announcement_tag = None;
+    for artifact in &dist.artifacts {
+        let DistArtifactKind::Installer(installer) = &artifact.kind else {
+            continue;
+        };
+        // Only touch installers that are actually part of the release we're mirroring
+        let Some(published) = manifest.artifacts.get(&artifact.id) else {
+            continue;
+        };
+        let dest_path = args.output_dir.join(&artifact.id);
+        match installer {
+            InstallerImpl::Shell(info) => {
+                let mut info = info.clone();
+                info.base_url.clone_from(&args.base_url);
+                info.dest_path.clone_from(&dest_path);
+                shell::write_install_sh_script(dist, &info, &render_manifest)?;
+            }
+            InstallerImpl::Powershell(info) => {
+                let mut info = info.clone();
+                info.base_url.clone_from(&args.base_url);
+                info.dest_path.clone_from(&dest_path);
+                powershell::write_install_ps_script(dist, &info, &render_manifest)?;
+            }
+            _ => continue,
+        }
+        eprintln!("re-rendered {}", artifact.id);
+
+        // The old checksums are now wrong
+        let checksum_artifact = published.checksum.clone();
+        let algorithms = published.checksums.keys().cloned().collect::<Vec<_>>();
+        for algorithm in algorithms {
+            let Some(style) = checksum_style(&algorithm) else {
+                continue;
+            };
+            let checksum = generate_checksum(&style, &dest_path)?;
+            if let Some(checksum_id) = &checksum_artifact {
+                let checksum_path = args.output_dir.join(checksum_id);
+                if checksum_path.exists() {
+                    write_checksum(&checksum, &dest_path, &checksum_path)?;
+                }
+            }
+            manifest
+                .artifacts
+                .get_mut(&artifact.id)
+                .expect("artifact disappeared from manifest")
+                .checksums
+                .insert(algorithm, checksum);
+        }
+    }
+
+    // Rewrite unified checksum files to match
+    let unified = manifest
+        .artifacts
+        .values()
+        .filter(|artifact| matches!(artifact.kind, ArtifactKind::UnifiedChecksum))
+        .filter_map(|artifact| artifact.name.clone())
+        .collect::<Vec<_>>();
+    for name in unified {
+        let Some(style) = unified_checksum_style(&name) else {
+            continue;
+        };
+        generate_unified_checksum(manifest, style, &args.output_dir.join(name))?;
+    }
+    Ok(())
+}
+
+/// Get the checksum style for an algorithm name recorded in a manifest
+fn checksum_style(algorithm: &str) -> Option<ChecksumStyle> {
+    [
+        ChecksumStyle::Sha256,
+        ChecksumStyle::Sha512,
+        ChecksumStyle::Sha3_256,
+        ChecksumStyle::Sha3_512,
+        ChecksumStyle::Blake2s,
+        ChecksumStyle::Blake2b,
+    ]
+    .into_iter()
+    .find(|style| style.ext() == algorithm)
+}
+
+/// Unified checksum files are named like `sha256.sum`
+fn unified_checksum_style(file_name: &str) -> Option<ChecksumStyle> {
+    checksum_style(file_name.strip_suffix(".sum")?)
+}
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+
+    use super::*;
+    use crate::{config::ArtifactMode, tests::mock::*, DistGraphBuilder};
+
+    #[test]
+    fn checksum_styles_roundtrip() {
+        assert_eq!(checksum_style("sha256"), Some(ChecksumStyle::Sha256));
+        assert_eq!(checksum_style("sha3-512"), Some(ChecksumStyle::Sha3_512));
+        assert_eq!(checksum_style("md5"), None);
+        assert_eq!(
+            unified_checksum_style("sha512.sum"),
+            Some(ChecksumStyle::Sha512)
+        );
+        assert_eq!(unified_checksum_style("sha512"), None);
+    }
+
+    #[test]
+    fn mirror_replaces_hosting() {
+        let old_url = "https://github.com/axodotdev/app/releases/download/v1.0.0";
+        let mut manifest: DistManifest = serde_json::from_value(serde_json::json!({
+            "releases": [{
+                "app_name": "app",
+                "app_version": "1.0.0",
+                "artifacts": ["app-installer.sh"],
+                "hosting": {
+                    "github": {
+                        "artifact_download_url": old_url,
+                        "owner": "axodotdev",
+                        "repo": "app"
+                    },
+                    "download_urls": [old_url, "https://cdn.example.com/app/v1.0.0"]
+                }
+            }],
+            "artifacts": {
+                "app-installer.sh": {
+                    "name": "app-installer.sh",
+                    "kind": "installer",
+                    "install_hint": format!("curl -LsSf {old_url}/app-installer.sh | sh")
+                }
+            }
+        }))
+        .unwrap();
+
+        point_at_mirror(&mut manifest, "https://mirror.example.com/app");
+
+        let hosting = &manifest.releases[0].hosting;
+        assert!(hosting.github.is_none());
+        assert!(hosting.download_urls.is_empty());
+        assert_eq!(
+            hosting.artifact_download_urls(),
+            vec!["https://mirror.example.com/app"]
+        );
+        assert_eq!(
+            manifest.artifacts["app-installer.sh"]
+                .install_hint
+                .as_deref(),
+            Some("curl -LsSf https://mirror.example.com/app/app-installer.sh | sh")
+        );
+    }
+
+    #[test]
+    fn rerender_rewrites_unified_checksums() {
+        let mut workspaces = workspace_just_axo();
+        let graph = DistGraphBuilder::new(
+            "a".to_owned(),
+            mock_tools(),
+            &mut workspaces,
+            ArtifactMode::All,
+            true,
+            false,
+            false,
+        )
+        .unwrap();
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let output_dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let unified_path = output_dir.join("sha256.sum");
+        LocalAsset::write_new("stale  app.tar.gz\n", &unified_path).unwrap();
+        let mut manifest: DistManifest = serde_json::from_value(serde_json::json!({
+            "artifacts": {
+                "app.tar.gz": {
+                    "name": "app.tar.gz",
+                    "kind": "executable-zip",
+                    "checksums": { "sha256": "abc123" }
+                },
+                "sha256.sum": {
+                    "name": "sha256.sum",
+                    "kind": "unified-checksum"
+                }
+            }
+        }))
+        .unwrap();
+        let args = MirrorArgs {
+            manifest: "dist-manifest.json".to_owned(),
+            base_url: "https://mirror.example.com/app".to_owned(),
+            output_dir,
+        };
+
+        rerender_installers(&graph.inner, &args, &mut manifest).unwrap();
+
+        assert_eq!(
+            LocalAsset::load_string(&unified_path).unwrap(),
+            "abc123  app.tar.gz\n"
+        );
+    }
+}
@@ -1,20 +1,137 @@
 //! Centralized logic for initializing http clients to
 //! ensure uniform configuration.
 
-use crate::errors::DistResult;
-use axoasset::reqwest;
+use std::{future::Future, time::Duration};
+
+use crate::{
+    backend::oci::registry::RegistryClient,
+    config::v1::net::NetConfig,
+    errors::DistResult,
+    host::{
+        gitea::GiteaClient,
+        s3::{S3Client, S3Credentials},
+    },
+    DistError,
+};
+use axoasset::{reqwest, LocalAsset};
+use camino::Utf8PathBuf;
+use cargo_dist_schema::{GiteaHosting, S3Hosting};
+use tracing::warn;
+
+/// Environment variable that overrides the http-proxy setting
+const PROXY_VAR: &str = "DIST_HTTP_PROXY";
+/// Environment variable listing extra CA certificates (like PATH)
+const CA_CERTS_VAR: &str = "DIST_HTTP_CA_CERTS";
+/// The conventional environment variable for a CA bundle, which we also trust
+const SSL_CERT_FILE_VAR: &str = "SSL_CERT_FILE";
+/// Environment variable that overrides the http-connect-timeout setting
+const CONNECT_TIMEOUT_VAR: &str = "DIST_HTTP_CONNECT_TIMEOUT";
+/// Environment variable that overrides the http-read-timeout setting
+const READ_TIMEOUT_VAR: &str = "DIST_HTTP_READ_TIMEOUT";
+/// Environment variable that overrides the http-retries setting
+const RETRIES_VAR: &str = "DIST_HTTP_RETRIES";
+/// Environment variable that overrides the http-retry-backoff setting
+const RETRY_BACKOFF_VAR: &str = "DIST_HTTP_RETRY_BACKOFF";
+/// Environment variable that does the same thing as --offline
+const OFFLINE_VAR: &str = "DIST_OFFLINE";
 
 /// Settings for http clients
 ///
 /// Any settings that should apply to all http requests should
 /// be stored here, to avoid different configurations.
-#[derive(Debug, Clone, Default)]
-pub struct ClientSettings {}
+#[derive(Debug, Clone)]
+pub struct ClientSettings {
+    /// A proxy to send all requests through
+    pub proxy: Option<String>,
+    /// Extra CA certificates (PEM files) to trust
+    pub ca_certs: Vec<Utf8PathBuf>,
+    /// How long to wait to connect to a server
+    pub connect_timeout: Option<Duration>,
+    /// How long to wait for a server to send more data
+    pub read_timeout: Option<Duration>,
+    /// How many times to retry a failed fetch
+    pub retries: u32,
+    /// How long to wait before the first retry (doubles each retry)
+    pub retry_backoff: Duration,
+    /// Whether all network access is forbidden
+    pub offline: bool,
+}
 
 impl ClientSettings {
     /// Create new ClientSettings using all necessary values
-    pub fn new() -> Self {
-        Self::default()
+    ///
+    /// This is for when there's no workspace config around, so only the
+    /// defaults and environment variables apply.
+    pub fn new() -> DistResult<Self> {
+        Self::from_config(&NetConfig::default())
+    }
+
+    /// Create new ClientSettings from the workspace's config,
+    /// with any environment variables taking priority
+    pub fn from_config(config: &NetConfig) -> DistResult<Self> {
+        Self::from_config_and_env(config, |var| std::env::var(var).ok())
+    }
+
+    fn from_config_and_env(
+        config: &NetConfig,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> DistResult<Self> {
+        let env_num = |var: &'static str| -> DistResult<Option<u64>> {
+            let Some(value) = env(var).filter(|v| !v.is_empty()) else {
+                return Ok(None);
+            };
+            let num = value
+                .trim()
+                .parse()
+                .map_err(|_| DistError::InvalidHttpEnvVar { var, value })?;
+            Ok(Some(num))
+        };
+
+        let proxy = env(PROXY_VAR)
+            .filter(|v| !v.is_empty())
+            .or_else(|| config.proxy.clone());
+        let mut ca_certs = config.ca_certs.clone();
+        if let Some(paths) = env(CA_CERTS_VAR) {
+            ca_certs.extend(
+                std::env::split_paths(&paths)
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .filter_map(|p| Utf8PathBuf::from_path_buf(p).ok()),
+            );
+        }
+        if let Some(path) = env(SSL_CERT_FILE_VAR).map(Utf8PathBuf::from) {
+            if path.is_file() {
+                ca_certs.push(path);
+            }
+        }
+        let connect_timeout = env_num(CONNECT_TIMEOUT_VAR)?.or(config.connect_timeout);
+        let read_timeout = env_num(READ_TIMEOUT_VAR)?.or(config.read_timeout);
+        let retries = match env_num(RETRIES_VAR)? {
+            Some(retries) => u32::try_from(retries).unwrap_or(u32::MAX),
+            None => config.retries,
+        };
+        let retry_backoff = env_num(RETRY_BACKOFF_VAR)?.unwrap_or(config.retry_backoff);
+        let offline = env(OFFLINE_VAR).is_some_and(|v| !matches!(v.trim(), "" | "0" | "false"));
+
+        Ok(Self {
+            proxy,
+            ca_certs,
+            connect_timeout: connect_timeout.map(Duration::from_secs),
+            read_timeout: read_timeout.map(Duration::from_secs),
+            retries,
+            retry_backoff: Duration::from_secs(retry_backoff),
+            offline,
+        })
+    }
+
+    /// Error out if we're not allowed to touch the network
+    pub fn check_online(&self, what: &str) -> DistResult<()> {
+        if self.offline {
+            Err(DistError::Offline {
+                what: what.to_owned(),
+            })
+        } else {
+            Ok(())
+        }
     }
 }
 
@@ -23,13 +140,82 @@ impl ClientSettings {
 /// As of this writing this shouldn't be used/exposed, as we'd prefer
 /// to avoid proliferating random http clients. For now AxoClient
 /// is sufficient.
-fn create_reqwest_client(ClientSettings {}: &ClientSettings) -> DistResult<reqwest::Client> {
-    let client = reqwest::Client::builder()
-        .build()
-        .expect("failed to initialize http client");
+fn create_reqwest_client(
+    ClientSettings {
+        proxy,
+        ca_certs,
+        connect_timeout,
+        read_timeout,
+        retries: _,
+        retry_backoff: _,
+        offline: _,
+    }: &ClientSettings,
+) -> DistResult<reqwest::Client> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = proxy {
+        let settings = reqwest::Proxy::all(proxy)
+            .map_err(|details| DistError::InvalidHttpProxy {
+                proxy: proxy.clone(),
+                details,
+            })?
+            .no_proxy(reqwest::NoProxy::from_env());
+        builder = builder.proxy(settings);
+    }
+    for path in ca_certs {
+        let pem = LocalAsset::load_bytes(path)?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|details| {
+            DistError::InvalidCaCert {
+                path: path.clone(),
+                details,
+            }
+        })?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    if let Some(timeout) = connect_timeout {
+        builder = builder.connect_timeout(*timeout);
+    }
+    if let Some(timeout) = read_timeout {
+        builder = builder.read_timeout(*timeout);
+    }
+    let client = builder.build().expect("failed to initialize http client");
     Ok(client)
 }
 
+/// Run a fetch, retrying it with exponential backoff if it fails
+///
+/// Only use this for requests that are safe to repeat, like downloads.
+pub async fn with_retries<T, E, F, Fut>(
+    settings: &ClientSettings,
+    what: &str,
+    mut fetch: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let mut backoff = settings.retry_backoff;
+    let mut attempt = 0;
+    loop {
+        match fetch().await {
+            Ok(val) => return Ok(val),
+            Err(e) if attempt < settings.retries => {
+                attempt += 1;
+                warn!(
+                    "{what} failed, retrying in {}s ({attempt}/{}): {e}",
+                    backoff.as_secs(),
+                    settings.retries
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 /// Create an AxoClient
 ///
 /// Ideally this should be called only once and reused!
@@ -44,11 +230,204 @@ pub fn create_axoasset_client(settings: &ClientSettings) -> DistResult<axoasset:
 /// one will error out if certain environment variables aren't set. As such,
 /// this should be called in a fairly lazy/latebound way -- only when we know
 /// for sure we HAVE to do gazenot http requests.
+///
+/// Gazenot builds its own http client (with its own 10s timeout), so we can't
+/// give it our proxy or CA certificates. Rather than quietly ignore them, we refuse
+/// to talk to axodotdev hosting when they're set. Retries are up to the caller,
+/// with [`with_retries`][].
 pub fn create_gazenot_client(
-    ClientSettings {}: &ClientSettings,
+    settings: &ClientSettings,
     source_host: &str,
     owner: &str,
 ) -> DistResult<gazenot::Gazenot> {
+    let ClientSettings {
+        proxy,
+        ca_certs,
+        connect_timeout,
+        read_timeout,
+        retries: _,
+        retry_backoff: _,
+        offline: _,
+    } = settings;
+    settings.check_online("axodotdev hosting")?;
+    if proxy.is_some() {
+        return Err(DistError::AxodotdevHostingUnsupportedNetSetting {
+            setting: "http-proxy",
+        });
+    }
+    if !ca_certs.is_empty() {
+        return Err(DistError::AxodotdevHostingUnsupportedNetSetting {
+            setting: "http-ca-certs",
+        });
+    }
+    if connect_timeout.is_some() || read_timeout.is_some() {
+        warn!("axodotdev hosting ignores http-connect-timeout and http-read-timeout, it always times out requests after 10s");
+    }
     let client = gazenot::Gazenot::into_the_abyss(source_host, owner)?;
     Ok(client)
 }
+
+/// Create a Gitea client
+///
+/// Like Gazenot clients, creating one will error out if the token for
+/// the API isn't set, so only do this when we know we need it.
+pub(crate) fn create_gitea_client(
+    settings: &ClientSettings,
+    hosting: &GiteaHosting,
+) -> DistResult<GiteaClient> {
+    settings.check_online("gitea hosting")?;
+    let client = create_reqwest_client(settings)?;
+    GiteaClient::new(client, hosting)
+}
+
+/// Create a client for pulling base images from container registries
+///
+/// Only anonymous pulls are supported, so this never needs credentials.
+pub(crate) fn create_registry_client(settings: &ClientSettings) -> DistResult<RegistryClient> {
+    settings.check_online("container registries")?;
+    let client = create_reqwest_client(settings)?;
+    Ok(RegistryClient::new(client))
+}
+
+/// Create an S3 client
+///
+/// This reads credentials from the environment and errors out if they're missing,
+/// so only do this when we know we need it.
+pub(crate) fn create_s3_client(
+    settings: &ClientSettings,
+    hosting: &S3Hosting,
+) -> DistResult<S3Client> {
+    settings.check_online("s3 hosting")?;
+    let credentials = S3Credentials::from_env()?;
+    let client = create_reqwest_client(settings)?;
+    Ok(S3Client::new(client, hosting, credentials))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_overrides_config() {
+        let config = NetConfig {
+            proxy: Some("http://config.proxy:3128".to_owned()),
+            connect_timeout: Some(5),
+            ..NetConfig::default()
+        };
+
+        let settings = ClientSettings::from_config_and_env(&config, |_| None).unwrap();
+        assert_eq!(settings.proxy.as_deref(), Some("http://config.proxy:3128"));
+        assert_eq!(settings.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(settings.read_timeout, None);
+        assert_eq!(settings.retries, 3);
+
+        let settings = ClientSettings::from_config_and_env(&config, |var| match var {
+            PROXY_VAR => Some("http://env.proxy:8080".to_owned()),
+            READ_TIMEOUT_VAR => Some("60".to_owned()),
+            RETRIES_VAR => Some("0".to_owned()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(settings.proxy.as_deref(), Some("http://env.proxy:8080"));
+        assert_eq!(settings.connect_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(settings.read_timeout, Some(Duration::from_secs(60)));
+        assert_eq!(settings.retries, 0);
+        assert!(!settings.offline);
+
+        for (value, offline) in [("1", true), ("true", true), ("0", false), ("", false)] {
+            let settings = ClientSettings::from_config_and_env(&config, |var| {
+                (var == OFFLINE_VAR).then(|| value.to_owned())
+            })
+            .unwrap();
+            assert_eq!(settings.offline, offline, "DIST_OFFLINE={value}");
+            assert_eq!(settings.check_online("test").is_err(), offline);
+        }
+
+        let err = ClientSettings::from_config_and_env(&config, |var| {
+            (var == RETRIES_VAR).then(|| "lots".to_owned())
+        })
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            DistError::InvalidHttpEnvVar {
+                var: RETRIES_VAR,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn retries_until_success() {
+        let settings = ClientSettings {
+            retries: 2,
+            retry_backoff: Duration::ZERO,
+            ..ClientSettings::new().unwrap()
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        let mut attempts = 0;
+        let result: Result<u32, String> = runtime.block_on(with_retries(&settings, "test", || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt < 3 {
+                    Err(format!("attempt {attempt} failed"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        }));
+        assert_eq!(result, Ok(3));
+
+        let mut attempts = 0;
+        let result: Result<u32, String> = runtime.block_on(with_retries(&settings, "test", || {
+            attempts += 1;
+            async { Err("nope".to_owned()) }
+        }));
+        assert_eq!(result, Err("nope".to_owned()));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn bad_proxy_is_an_error() {
+        let settings = ClientSettings {
+            proxy: Some("not a url".to_owned()),
+            ..ClientSettings::new().unwrap()
+        };
+        assert!(matches!(
+            create_reqwest_client(&settings),
+            Err(DistError::InvalidHttpProxy { .. })
+        ));
+    }
+
+    #[test]
+    fn axodotdev_hosting_refuses_unsupported_settings() {
+        let settings = ClientSettings {
+            proxy: Some("http://proxy.example.com:3128".to_owned()),
+            offline: false,
+            ..ClientSettings::new().unwrap()
+        };
+        assert!(matches!(
+            create_gazenot_client(&settings, "github", "axodotdev"),
+            Err(DistError::AxodotdevHostingUnsupportedNetSetting {
+                setting: "http-proxy"
+            })
+        ));
+
+        let settings = ClientSettings {
+            proxy: None,
+            ca_certs: vec!["corporate-ca.pem".into()],
+            offline: false,
+            ..ClientSettings::new().unwrap()
+        };
+        assert!(matches!(
+            create_gazenot_client(&settings, "github", "axodotdev"),
+            Err(DistError::AxodotdevHostingUnsupportedNetSetting {
+                setting: "http-ca-certs"
+            })
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+//! Publishing the released crates with `cargo publish`
+//!
+//! The announcement tag decides which packages are released, but a released package
+//! can depend on other crates in the workspace that have to go up first. So we publish
+//! the released packages along with any publishable workspace crates they depend on,
+//! dependencies first, skipping any version the registry already has (so a failed
+//! publish job can just be rerun).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use axoasset::AxoClient;
+use axoprocess::Cmd;
+use camino::Utf8Path;
+use cargo_metadata::DependencyKind;
+
+use crate::{
+    config::{Config, PublishCratesArgs},
+    errors::DistResult,
+    gather_work,
+    net::{with_retries, ClientSettings},
+    tasks::cargo,
+    DistError,
+};
+
+/// The sparse index of crates.io
+pub const CRATES_IO_INDEX: &str = "https://index.crates.io";
+
+/// The name crates.io goes by in `publish = [...]` lists
+const CRATES_IO_REGISTRY: &str = "crates-io";
+
+/// A crate in the workspace we might publish
+#[derive(Debug, Clone)]
+struct WorkspaceCrate {
+    name: String,
+    version: String,
+    /// The registries this can be published to (None means any)
+    publish: Option<Vec<String>>,
+    /// Other workspace crates this depends on (not counting dev-dependencies)
+    deps: Vec<String>,
+}
+
+impl WorkspaceCrate {
+    fn can_publish_to(&self, registry: &str) -> bool {
+        self.publish
+            .as_ref()
+            .map(|registries| registries.iter().any(|r| r == registry))
+            .unwrap_or(true)
+    }
+}
+
+/// Publish the announcement's packages (and the workspace crates they need)
+pub fn do_publish_crates(cfg: &Config, args: &PublishCratesArgs) -> DistResult<()> {
+    let (dist, manifest) = gather_work(cfg)?;
+    if manifest.announcement_is_prerelease && !manifest.publish_prereleases {
+        eprintln!(
+            "skipping crates publish, {} is a prerelease and publish-prereleases isn't set",
+            manifest.announcement_tag.as_deref().unwrap_or_default()
+        );
+        return Ok(());
+    }
+
+    let registry = args.registry.as_deref().unwrap_or(CRATES_IO_REGISTRY);
+    let index = registry_index(args, |var| std::env::var(var).ok())?;
+    let crates = workspace_crates(&dist.workspace_dir)?;
+    let released = manifest
+        .releases
+        .iter()
+        .map(|release| release.app_name.as_str())
+        .collect::<Vec<_>>();
+    let to_publish = publish_order(&crates, &released, registry);
+    if to_publish.is_empty() {
+        eprintln!("nothing to publish to {registry}");
+        return Ok(());
+    }
+
+    dist.client_settings
+        .check_online(&format!("publishing to {registry}"))?;
+    let handle = tokio::runtime::Handle::current();
+    let cargo = cargo()?;
+    for krate in to_publish {
+        let WorkspaceCrate { name, version, .. } = krate;
+        if handle.block_on(is_published(
+            &dist.axoclient,
+            &dist.client_settings,
+            &index,
+            name,
+            version,
+        ))? {
+            eprintln!("{name} {version} is already published to {registry}, skipping");
+            continue;
+        }
+        if args.dry_run {
+            eprintln!("would publish {name} {version} to {registry}");
+            continue;
+        }
+
+        eprintln!("publishing {name} {version} to {registry}");
+        let mut command = Cmd::new(&cargo, format!("publish {name} to {registry}"));
+        command.current_dir(&dist.workspace_dir);
+        command.stdout_to_stderr();
+        command.arg("publish").arg("-p").arg(name);
+        if let Some(registry) = &args.registry {
+            command.arg("--registry").arg(registry);
+        } else if let Some(index) = &args.index {
+            command.arg("--index").arg(format!("sparse+{index}"));
+        }
+        command.run()?;
+    }
+    Ok(())
+}
+
+/// Work out which sparse index to check for already-published versions
+fn registry_index(
+    args: &PublishCratesArgs,
+    env: impl Fn(&str) -> Option<String>,
+) -> DistResult<String> {
+    let index = if let Some(index) = &args.index {
+        index.clone()
+    } else if let Some(registry) = &args.registry {
+        // Same place cargo looks, for registries configured through the environment
+        let var = format!(
+            "CARGO_REGISTRIES_{}_INDEX",
+            registry.to_ascii_uppercase().replace('-', "_")
+        );
+        env(&var).ok_or_else(|| DistError::CratesUnknownIndex {
+            registry: registry.clone(),
+        })?
+    } else {
+        CRATES_IO_INDEX.to_owned()
+    };
+
+    let url = index.strip_prefix("sparse+").unwrap_or(&index);
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(DistError::CratesIndexNotSparse { index });
+    }
+    Ok(url.trim_end_matches('/').to_owned())
+}
+
+/// Get every crate in the workspace
+fn workspace_crates(workspace_dir: &Utf8Path) -> DistResult<BTreeMap<String, WorkspaceCrate>> {
+    let metadata = cargo_metadata::MetadataCommand::new()
+        .current_dir(workspace_dir)
+        .no_deps()
+        .exec()?;
+    let members = metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|pkg| pkg.name.clone())
+        .collect::<BTreeSet<_>>();
+    let crates = metadata
+        .workspace_packages()
+        .into_iter()
+        .map(|pkg| {
+            let deps = pkg
+                .dependencies
+                .iter()
+                .filter(|dep| dep.kind != DependencyKind::Development && dep.path.is_some())
+                .filter(|dep| members.contains(&dep.name))
+                .map(|dep| dep.name.clone())
+                .collect();
+            let krate = WorkspaceCrate {
+                name: pkg.name.clone(),
+                version: pkg.version.to_string(),
+                publish: pkg.publish.clone(),
+                deps,
+            };
+            (krate.name.clone(), krate)
+        })
+        .collect();
+    Ok(crates)
+}
+
+/// Select the crates to publish, with every crate after the crates it depends on
+fn publish_order<'a>(
+    crates: &'a BTreeMap<String, WorkspaceCrate>,
+    released: &[&str],
+    registry: &str,
+) -> Vec<&'a WorkspaceCrate> {
+    fn visit<'a>(
+        crates: &'a BTreeMap<String, WorkspaceCrate>,
+        krate: &'a WorkspaceCrate,
+        registry: &str,
+        visited: &mut BTreeSet<&'a str>,
+        order: &mut Vec<&'a WorkspaceCrate>,
+    ) {
+        if !visited.insert(&krate.name) {
+            return;
+        }
+        for dep in &krate.deps {
+            let Some(dep) = crates.get(dep) else {
+                continue;
+            };
+            // If the dependency really is needed cargo will tell them about it
+            if dep.can_publish_to(registry) {
+                visit(crates, dep, registry, visited, order);
+            }
+        }
+        order.push(krate);
+    }
+
+    let mut visited = BTreeSet::new();
+    let mut order = vec![];
+    for name in released {
+        let Some(krate) = crates.get(*name) else {
+            // Not a cargo package, nothing for us to do
+            continue;
+        };
+        if !krate.can_publish_to(registry) {
+            eprintln!("not publishing {name} (its publish setting excludes {registry})");
+            continue;
+        }
+        visit(crates, krate, registry, &mut visited, &mut order);
+    }
+    order
+}
+
+/// Get a crate's path in a sparse index
+fn index_path(name: &str) -> String {
+    let name = name.to_ascii_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// Check if a version of a crate is already in the registry's sparse index
+///
+/// Yanked versions count, the registry won't take them again either.
+async fn is_published(
+    client: &AxoClient,
+    settings: &ClientSettings,
+    index: &str,
+    name: &str,
+    version: &str,
+) -> DistResult<bool> {
+    let url = format!("{index}/{}", index_path(name));
+    let resp = with_retries(settings, &format!("checking the index for {name}"), || {
+        client.get(&url)
+    })
+    .await?;
+    let status = resp.status();
+    if status == axoasset::reqwest::StatusCode::NOT_FOUND
+        || status == axoasset::reqwest::StatusCode::GONE
+    {
+        return Ok(false);
+    }
+    if !status.is_success() {
+        return Err(DistError::CratesIndexCheckFailed {
+            url,
+            status: status.to_string(),
+        });
+    }
+    let entries = resp
+        .text()
+        .await
+        .map_err(|e| DistError::CratesIndexCheckFailed {
+            url: url.clone(),
+            status: e.to_string(),
+        })?;
+    let published = entries
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .any(|entry| entry["vers"].as_str() == Some(version));
+    Ok(published)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::tests::http::MockServer;
+
+    fn krate(name: &str, publish: Option<Vec<&str>>, deps: &[&str]) -> (String, WorkspaceCrate) {
+        let krate = WorkspaceCrate {
+            name: name.to_owned(),
+            version: "1.0.0".to_owned(),
+            publish: publish.map(|r| r.into_iter().map(str::to_owned).collect()),
+            deps: deps.iter().map(|d| (*d).to_owned()).collect(),
+        };
+        (name.to_owned(), krate)
+    }
+
+    fn names(order: Vec<&WorkspaceCrate>) -> Vec<&str> {
+        order.iter().map(|k| k.name.as_str()).collect()
+    }
+
+    #[test]
+    fn dependencies_go_first() {
+        let crates = BTreeMap::from([
+            krate("app", None, &["core", "util"]),
+            krate("core", None, &["util"]),
+            krate("util", None, &[]),
+            krate("tool", None, &["core"]),
+            krate("unreleased", None, &[]),
+        ]);
+        let order = publish_order(&crates, &["tool", "app"], CRATES_IO_REGISTRY);
+        assert_eq!(names(order), vec!["util", "core", "tool", "app"]);
+    }
+
+    #[test]
+    fn publish_setting_is_honored() {
+        let crates = BTreeMap::from([
+            krate("app", None, &["internal", "private"]),
+            krate("internal", Some(vec![]), &[]),
+            krate("private", Some(vec!["my-registry"]), &[]),
+            krate("wip", Some(vec![]), &[]),
+        ]);
+        let order = publish_order(&crates, &["app", "wip", "not-cargo"], CRATES_IO_REGISTRY);
+        assert_eq!(names(order), vec!["app"]);
+        let order = publish_order(&crates, &["app"], "my-registry");
+        assert_eq!(names(order), vec!["private", "app"]);
+    }
+
+    #[test]
+    fn sparse_index_paths() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("ab"), "2/ab");
+        assert_eq!(index_path("abc"), "3/a/abc");
+        assert_eq!(index_path("Cargo-Dist"), "ca/rg/cargo-dist");
+    }
+
+    #[test]
+    fn index_selection() {
+        let args = |registry: Option<&str>, index: Option<&str>| PublishCratesArgs {
+            registry: registry.map(str::to_owned),
+            index: index.map(str::to_owned),
+            dry_run: false,
+        };
+        let env = |var: &str| {
+            (var == "CARGO_REGISTRIES_MY_REGISTRY_INDEX")
+                .then(|| "sparse+https://crates.internal/index/".to_owned())
+        };
+        assert_eq!(
+            registry_index(&args(None, None), env).unwrap(),
+            CRATES_IO_INDEX
+        );
+        assert_eq!(
+            registry_index(&args(Some("my-registry"), None), env).unwrap(),
+            "https://crates.internal/index"
+        );
+        assert_eq!(
+            registry_index(&args(None, Some("http://127.0.0.1:1234")), env).unwrap(),
+            "http://127.0.0.1:1234"
+        );
+        assert!(matches!(
+            registry_index(&args(Some("other"), None), env),
+            Err(DistError::CratesUnknownIndex { .. })
+        ));
+        assert!(matches!(
+            registry_index(&args(None, Some("git+https://github.com/me/index")), env),
+            Err(DistError::CratesIndexNotSparse { .. })
+        ));
+    }
+
+    /// A tiny stand-in for a sparse registry index, serving a fixed set of index files
+    fn serve_index(files: HashMap<&'static str, &'static str>) -> MockServer {
+        MockServer::start(
+            move |request| match files.get(request.path.trim_start_matches('/')) {
+                Some(body) => ("200 OK", *body),
+                None => ("404 Not Found", ""),
+            },
+        )
+    }
+
+    #[test]
+    fn existing_versions_are_found() {
+        let index = serve_index(HashMap::from([(
+            "my/-a/my-app",
+            concat!(
+                r#"{"name":"my-app","vers":"0.9.0","deps":[],"cksum":"00","features":{},"yanked":false}"#,
+                "\n",
+                r#"{"name":"my-app","vers":"1.0.0","deps":[],"cksum":"00","features":{},"yanked":true}"#,
+                "\n",
+            ),
+        )]));
+        let settings = ClientSettings::from_config(&Default::default()).unwrap();
+        let client = crate::net::create_axoasset_client(&settings).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let check = |name, version| {
+            runtime
+                .block_on(is_published(&client, &settings, &index.url, name, version))
+                .unwrap()
+        };
+        assert!(check("my-app", "0.9.0"));
+        assert!(check("my-app", "1.0.0"));
+        assert!(!check("my-app", "1.1.0"));
+        assert!(!check("other-app", "1.0.0"));
+    }
+}
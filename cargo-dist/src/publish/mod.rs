@@ -0,0 +1,8 @@
+//! dist publish -- push released packages to package registries
+//!
+//! Most publishers are just jobs in the generated CI that shuffle artifacts around,
+//! but some need to know more about the workspace than a CI script reasonably can
+//! (like which packages the announcement selected, and what order they go up in).
+//! Those live here.
+
+pub mod crates;
@@ -7,11 +7,13 @@ use axoasset::AxoClient;
 use camino::Utf8Path;
 use cargo_dist_schema::TargetTripleRef;
 
-use crate::{config::ProductionMode, DistResult};
+use crate::{config::ProductionMode, net::ClientSettings, DistResult};
 
 mod macos;
 mod ssldotcom;
 
+pub(crate) use ssldotcom::fetch_code_sign_tool;
+
 /// Code/artifact signing providers
 #[derive(Debug)]
 pub struct Signing {
@@ -23,13 +25,19 @@ impl Signing {
     /// Setup signing
     pub fn new(
         client: &AxoClient,
+        client_settings: &ClientSettings,
         host_target: &TargetTripleRef,
-        dist_dir: &Utf8Path,
+        cache_dir: &Utf8Path,
         ssldotcom_windows_sign: Option<ProductionMode>,
         macos_sign: bool,
     ) -> DistResult<Self> {
-        let ssldotcom =
-            ssldotcom::CodeSignTool::new(client, host_target, dist_dir, ssldotcom_windows_sign)?;
+        let ssldotcom = ssldotcom::CodeSignTool::new(
+            client,
+            client_settings,
+            host_target,
+            cache_dir,
+            ssldotcom_windows_sign,
+        )?;
         let macos = if macos_sign {
             macos::Codesign::new(host_target)?
         } else {
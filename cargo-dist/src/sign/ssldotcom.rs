@@ -10,6 +10,7 @@ use tracing::warn;
 
 use crate::config::ProductionMode;
 use crate::errors::*;
+use crate::net::{with_retries, ClientSettings};
 use crate::platform::targets::TARGET_X64_WINDOWS;
 
 /// An instance of ssl.com's CodeSignTool
@@ -71,8 +72,9 @@ impl CodeSignToolEnv {
 impl CodeSignTool {
     pub fn new(
         client: &AxoClient,
+        client_settings: &ClientSettings,
         host_target: &TargetTripleRef,
-        dist_dir: &Utf8Path,
+        cache_dir: &Utf8Path,
         ssldotcom_windows_sign: Option<ProductionMode>,
     ) -> DistResult<Option<Self>> {
         // Feature must be enabled
@@ -85,7 +87,12 @@ impl CodeSignTool {
         }
 
         if let Some(env) = CodeSignToolEnv::new()? {
-            let tool = fetch_code_sign_tool(client, dist_dir)?;
+            let tool = fetch_code_sign_tool(client, client_settings, cache_dir)?;
+            Cmd::new(&tool, "check tool is runnable")
+                .current_dir(tool.parent().unwrap())
+                .arg("--version")
+                .stdout_to_stderr()
+                .run()?;
             let tool_dir = tool
                 .parent()
                 .expect("CodeSignTool wasn't in a directory!?")
@@ -131,19 +138,21 @@ impl CodeSignTool {
     }
 }
 
-/// Download code sign tool and prepare it for usage
-fn fetch_code_sign_tool(client: &AxoClient, dist_dir: &Utf8Path) -> DistResult<Utf8PathBuf> {
+/// Download code sign tool into the tool cache, if it's not already there
+pub(crate) fn fetch_code_sign_tool(
+    client: &AxoClient,
+    client_settings: &ClientSettings,
+    cache_dir: &Utf8Path,
+) -> DistResult<Utf8PathBuf> {
     // Download links from <https://www.ssl.com/guide/esigner-codesigntool-command-guide/>
     // On windows they provide a .bat script that we're supposed to use as the primary interface.
     const WINDOWS_CMD_NAME: &str = "CodeSignTool.bat";
     const WINDOWS_URL: &str = "https://www.ssl.com/download/codesigntool-for-windows/";
-    const EXTRA_FETCH_DIR: &str = "_extra_tools";
     const CODESIGNTOOL_SUBDIR: &str = "CodeSignTool";
     const ZIP_NAME: &str = "CodeSignTool.zip";
 
-    let fetch_dir = dist_dir.join(EXTRA_FETCH_DIR);
-    let zip_path = fetch_dir.join(ZIP_NAME);
-    let unzipped_dir = fetch_dir.join(CODESIGNTOOL_SUBDIR);
+    let zip_path = cache_dir.join(ZIP_NAME);
+    let unzipped_dir = cache_dir.join(CODESIGNTOOL_SUBDIR);
     let cmd = unzipped_dir.join(WINDOWS_CMD_NAME);
 
     // Cache the download
@@ -151,19 +160,22 @@ fn fetch_code_sign_tool(client: &AxoClient, dist_dir: &Utf8Path) -> DistResult<U
         info!("CodeSignTool already fetched");
         return Ok(cmd);
     }
+    if client_settings.offline {
+        return Err(DistError::OfflineCacheMiss {
+            tool: "CodeSignTool".to_owned(),
+            path: unzipped_dir,
+        });
+    }
 
     info!("fetching CodeSignTool");
     // Download and unpack the zip
-    LocalAsset::create_dir_all(fetch_dir)?;
-    tokio::runtime::Handle::current()
-        .block_on(client.load_and_write_to_file(WINDOWS_URL, &zip_path))?;
+    LocalAsset::create_dir_all(cache_dir)?;
+    tokio::runtime::Handle::current().block_on(with_retries(
+        client_settings,
+        "fetching CodeSignTool",
+        || client.load_and_write_to_file(WINDOWS_URL, &zip_path),
+    ))?;
     LocalAsset::unzip_all(&zip_path, unzipped_dir)?;
-
-    Cmd::new(&cmd, "check tool is runnable")
-        .current_dir(cmd.parent().unwrap())
-        .arg("--version")
-        .stdout_to_stderr()
-        .run()?;
     info!("fetched CodeSignTool");
 
     Ok(cmd)
@@ -202,3 +214,47 @@ TSA_LEGACY_URL=http://ts.ssl.com/legacy
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::create_axoasset_client;
+
+    fn offline_settings() -> ClientSettings {
+        ClientSettings {
+            offline: true,
+            ..ClientSettings::new().unwrap()
+        }
+    }
+
+    #[test]
+    fn offline_code_sign_tool_cache_hit() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let cache_dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let settings = offline_settings();
+        let client = create_axoasset_client(&settings).unwrap();
+        let cmd = cache_dir.join("CodeSignTool/CodeSignTool.bat");
+        LocalAsset::write_new_all("@echo off", &cmd).unwrap();
+
+        assert_eq!(
+            fetch_code_sign_tool(&client, &settings, &cache_dir).unwrap(),
+            cmd
+        );
+    }
+
+    #[test]
+    fn offline_code_sign_tool_cache_miss() {
+        let tmp = temp_dir::TempDir::new().unwrap();
+        let cache_dir = Utf8PathBuf::from_path_buf(tmp.path().to_owned()).unwrap();
+        let settings = offline_settings();
+        let client = create_axoasset_client(&settings).unwrap();
+
+        let err = fetch_code_sign_tool(&client, &settings, &cache_dir).unwrap_err();
+        let DistError::OfflineCacheMiss { tool, path } = err else {
+            panic!("expected a cache miss, got {err:?}");
+        };
+        assert_eq!(tool, "CodeSignTool");
+        assert_eq!(path, cache_dir.join("CodeSignTool"));
+        assert!(!cache_dir.join("CodeSignTool.zip").exists());
+    }
+}
@@ -51,14 +51,16 @@
 use std::collections::BTreeMap;
 
 use crate::platform::targets::{
-    TARGET_ARM64_LINUX_GNU, TARGET_ARM64_MAC, TARGET_X64_LINUX_GNU, TARGET_X64_MAC,
+    TARGET_ARM64_LINUX_GNU, TARGET_ARM64_MAC, TARGET_ARM64_WINDOWS, TARGET_X64_LINUX_GNU,
+    TARGET_X64_MAC, TARGET_X64_WINDOWS, TARGET_X86_WINDOWS,
 };
 use axoasset::AxoClient;
 use axoprocess::Cmd;
 use axoproject::{PackageId, PackageIdx, WorkspaceGraph};
 use camino::Utf8PathBuf;
 use cargo_dist_schema::{
-    ArtifactId, BuildEnvironment, DistManifest, SystemId, SystemInfo, TargetTriple, TargetTripleRef,
+    ArtifactId, BuildEnvironment, DistManifest, GlibcVersion, SystemId, SystemInfo, TargetTriple,
+    TargetTripleRef,
 };
 use semver::Version;
 use serde::Serialize;
@@ -66,9 +68,12 @@ use tracing::{info, warn};
 
 use crate::announce::{self, AnnouncementTag, TagMode};
 use crate::backend::ci::github::GithubCiInfo;
+use crate::backend::ci::gitlab::GitlabCiInfo;
 use crate::backend::ci::CiInfo;
 use crate::backend::installer::homebrew::to_homebrew_license_format;
 use crate::backend::installer::macpkg::PkgInstallerInfo;
+use crate::backend::oci::registry::ImageReference;
+use crate::backend::oci::{oci_platform, oci_tag, OciImageImpl, OciImageInfo, OciIndexInfo};
 use crate::config::v1::builds::cargo::AppCargoBuildConfig;
 use crate::config::v1::ci::CiConfig;
 use crate::config::v1::installers::CommonInstallerConfig;
@@ -82,10 +87,23 @@ use crate::sign::Signing;
 use crate::{
     backend::{
         installer::{
-            homebrew::{to_class_case, HomebrewInstallerInfo},
+            aur::{aur_architecture, aur_package_name, aur_pkgver, AurInstallerInfo, AurSource},
+            deb::{deb_architecture, deb_package_name, deb_version, DebInstallerInfo},
+            homebrew::{to_class_case, HomebrewCaskInstallerInfo, HomebrewInstallerInfo},
             msi::MsiInstallerInfo,
-            npm::NpmInstallerInfo,
-            InstallerImpl, InstallerInfo,
+            nix::{nix_system, NixInstallerInfo, NixSource},
+            npm::{npm_platform, NpmInstallerInfo, NpmPlatformInstallerInfo, NpmPlatformPackage},
+            pypi::{
+                parse_glibc_version, pep440_version, wheel_file_name, wheel_platform_tag,
+                PypiInstallerInfo,
+            },
+            rpm::{rpm_architecture, rpm_version, RpmInstallerInfo},
+            scoop::{autoupdate_base_url, ScoopCheckver, ScoopInstallerInfo},
+            winget::{
+                to_identifier_part, winget_architecture, WingetInstaller, WingetInstallerInfo,
+                WingetInstallerKind,
+            },
+            ExecutableZipFragment, InstallerImpl, InstallerInfo, UninstallerInfo,
         },
         templates::Templates,
     },
@@ -196,6 +214,8 @@ pub struct DistGraph {
     pub allow_dirty: DirtyMode,
     /// Homebrew tap all packages agree on
     pub global_homebrew_tap: Option<String>,
+    /// Scoop bucket all packages agree on
+    pub global_scoop_bucket: Option<String>,
     /// builtin publish jobs all packages agree on
     pub global_publishers: Option<PublisherConfig>,
     /// Whether we can just build the workspace or need to build each package
@@ -238,6 +258,8 @@ pub struct DistGraph {
     pub local_builds_are_lies: bool,
     /// HTTP client settings
     pub client_settings: ClientSettings,
+    /// Where tools we download are kept
+    pub cache_dir: Utf8PathBuf,
     /// A reusable client for basic http fetches
     pub axoclient: AxoClient,
 }
@@ -251,6 +273,8 @@ pub struct HostingInfo {
     pub repo_url: String,
     /// Source hosting provider (e.g. "github")
     pub source_host: String,
+    /// Base URL of the source hosting provider (e.g. "https://github.com")
+    pub source_host_url: String,
     /// Project owner
     pub owner: String,
     /// Project name
@@ -383,6 +407,8 @@ pub enum BuildStep {
     UnifiedChecksum(UnifiedChecksumStep),
     /// Fetch or build an updater binary
     Updater(UpdaterStep),
+    /// Build an OCI image
+    GenerateOciImage(OciImageImpl),
     // FIXME: For macos universal builds we'll want
     // Lipo(LipoStep)
 }
@@ -609,6 +635,8 @@ pub enum ArtifactKind {
     ExtraArtifact(ExtraArtifactImpl),
     /// An updater executable
     Updater(UpdaterImpl),
+    /// An OCI (container) image
+    OciImage(OciImageImpl),
 }
 
 /// An Archive containing binaries (aka ExecutableZip)
@@ -788,6 +816,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         workspaces: &'pkg_graph mut WorkspaceGraph,
         artifact_mode: ArtifactMode,
         allow_all_dirty: bool,
+        offline: bool,
         announcement_tag_is_implicit: bool,
     ) -> DistResult<Self> {
         let root_workspace_idx = workspaces.root_workspace_idx();
@@ -943,6 +972,37 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             });
         }
 
+        // Same deal for scoop buckets
+        let mut global_scoop_bucket = None;
+        let mut packages_with_mismatched_buckets = vec![];
+        for ((_idx, package), package_config) in workspaces.all_packages().zip(&package_configs) {
+            let Some(new_bucket) = package_config
+                .installers
+                .scoop
+                .as_ref()
+                .and_then(|scoop| scoop.bucket.as_ref())
+            else {
+                continue;
+            };
+            let manifest_path = package
+                .dist_manifest_path
+                .clone()
+                .unwrap_or(package.manifest_path.clone());
+            if let Some(current_bucket) = &global_scoop_bucket {
+                if current_bucket != new_bucket {
+                    packages_with_mismatched_buckets.push(manifest_path);
+                }
+            } else {
+                packages_with_mismatched_buckets.push(manifest_path);
+                global_scoop_bucket = Some(new_bucket.clone());
+            }
+        }
+        if packages_with_mismatched_buckets.len() > 1 {
+            return Err(DistError::MismatchedScoopBuckets {
+                packages: packages_with_mismatched_buckets,
+            });
+        }
+
         // check publish jobs for global publish jobs
         // FIXME: when we add `dist publish` we can drop this,
         // as we can support granular publish settings
@@ -980,10 +1040,21 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             .as_ref()
             .map(|p| {
                 // until we have `dist publish` we need to enforce everyone agreeing on `prereleases`
-                let PublisherConfig { homebrew, npm } = p;
+                let PublisherConfig {
+                    homebrew,
+                    npm,
+                    crates,
+                    scoop,
+                    aur,
+                    pypi,
+                } = p;
                 let h_pre = homebrew.as_ref().map(|p| p.prereleases);
                 let npm_pre = npm.as_ref().map(|p| p.prereleases);
-                let choices = [h_pre, npm_pre];
+                let crates_pre = crates.as_ref().map(|p| p.prereleases);
+                let scoop_pre = scoop.as_ref().map(|p| p.prereleases);
+                let aur_pre = aur.as_ref().map(|p| p.prereleases);
+                let pypi_pre = pypi.as_ref().map(|p| p.prereleases);
+                let choices = [h_pre, npm_pre, crates_pre, scoop_pre, aur_pre, pypi_pre];
                 let mut global_choice = None;
                 #[allow(clippy::manual_flatten)]
                 for choice in choices {
@@ -1022,13 +1093,16 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         };
         let systems = SortedMap::from_iter([(system_id.clone(), system)]);
 
-        let client_settings = ClientSettings::new();
+        let mut client_settings = ClientSettings::from_config(&config.net)?;
+        client_settings.offline |= offline;
         let axoclient = crate::net::create_axoasset_client(&client_settings)?;
+        let cache_dir = crate::cache::cache_dir(&target_dir);
 
         let signer = Signing::new(
             &axoclient,
+            &client_settings,
             &tools.host_target,
-            &dist_dir,
+            &cache_dir,
             config.builds.ssldotcom_windows_sign.clone(),
             config.builds.macos_sign,
         )?;
@@ -1045,6 +1119,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 is_init: config.dist_version.is_some(),
                 allow_dirty,
                 global_homebrew_tap,
+                global_scoop_bucket,
                 global_publishers,
                 precise_cargo_builds,
                 target_dir,
@@ -1065,6 +1140,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                 ci: CiInfo::default(),
                 hosting: None,
                 client_settings,
+                cache_dir,
                 axoclient,
             },
             manifest: DistManifest {
@@ -1395,6 +1471,152 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         }
     }
 
+    fn add_oci_images(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        let release = self.release(to_release);
+        let Some(config) = release.config.artifacts.oci.clone() else {
+            return Ok(());
+        };
+        // Catch a bad base image before doing any builds
+        if let Some(base_image) = &config.base_image {
+            ImageReference::parse(base_image)?;
+        }
+
+        let tag = oci_tag(&release.version);
+        let mut annotations = SortedMap::new();
+        let mut annotate = |key: &str, val: Option<&str>| {
+            if let Some(val) = val {
+                annotations.insert(format!("org.opencontainers.image.{key}"), val.to_owned());
+            }
+        };
+        annotate("title", Some(&release.app_name));
+        annotate("version", Some(&release.version.to_string()));
+        annotate(
+            "description",
+            release
+                .app_desc
+                .as_deref()
+                .and_then(|desc| desc.lines().next()),
+        );
+        annotate("url", release.app_homepage_url.as_deref());
+        annotate("source", release.app_repository_url.as_deref());
+        annotate("licenses", release.app_license.as_deref());
+        let app_name = release.app_name.clone();
+        let release_id = release.id.clone();
+        let variants = release.variants.clone();
+        let checksum = self.inner.config.artifacts.checksum;
+
+        let mut images = vec![];
+        let mut skipped_gnu = vec![];
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let target = variant.target.clone();
+            let Some(platform) = oci_platform(&target) else {
+                continue;
+            };
+            // Static musl binaries can run on an empty image, but glibc ones need
+            // an image with glibc (and friends) to run on
+            let base_image = if target.is_musl() {
+                None
+            } else if let Some(base_image) = &config.base_image {
+                Some(base_image.clone())
+            } else {
+                skipped_gnu.push(target);
+                continue;
+            };
+
+            let variant_id = variant.id.clone();
+            let artifact_name = format!("{variant_id}-oci.tar");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.inner.dist_dir.join(format!("{variant_id}_oci"));
+            images.push((target.clone(), artifact_path.clone()));
+            if !self.local_artifacts_enabled() {
+                continue;
+            }
+
+            let binaries = variant
+                .binaries
+                .iter()
+                .copied()
+                .filter(|&idx| self.binary(idx).kind == BinaryKind::Executable)
+                .collect::<Vec<_>>();
+            let executables = binaries
+                .iter()
+                .map(|&idx| self.binary(idx).file_name.clone())
+                .collect::<Vec<_>>();
+            // Running the image should run the app, if we can tell which binary that is
+            let entrypoint = executables
+                .iter()
+                .find(|exe| **exe == app_name)
+                .or_else(|| executables.first().filter(|_| executables.len() == 1))
+                .cloned();
+
+            let image_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                }),
+                checksum: None,
+                kind: ArtifactKind::OciImage(OciImageImpl::Image(OciImageInfo {
+                    file_path: artifact_path,
+                    package_dir: dir_path.clone(),
+                    platform,
+                    executables,
+                    entrypoint,
+                    base_image,
+                    tag: tag.clone(),
+                    annotations: annotations.clone(),
+                })),
+                is_global: false,
+            };
+
+            let image_idx = self.add_local_artifact(variant_idx, image_artifact);
+            for binary_idx in binaries {
+                let file_name = self.binary(binary_idx).file_name.clone();
+                self.require_binary(image_idx, variant_idx, binary_idx, dir_path.join(file_name));
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(variant_idx, image_idx, checksum);
+            }
+        }
+        if !skipped_gnu.is_empty() {
+            warn!(
+                "skipping OCI images for {}: linux-gnu images need an oci-base-image to run on",
+                skipped_gnu.join(", ")
+            );
+        }
+
+        // Tie all the images together into one multi-arch image
+        if images.is_empty() || !self.global_artifacts_enabled() {
+            return Ok(());
+        }
+        let artifact_name = format!("{release_id}-oci.tar");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let (target_triples, images) = images.into_iter().unzip();
+        let index_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            archive: None,
+            checksum: None,
+            kind: ArtifactKind::OciImage(OciImageImpl::Index(OciIndexInfo {
+                file_path: artifact_path,
+                images,
+                tag,
+                annotations,
+            })),
+            is_global: true,
+        };
+        self.add_global_artifact(to_release, index_artifact);
+        Ok(())
+    }
+
     fn add_unified_checksum_file(&mut self, to_release: ReleaseIdx) {
         if !self.global_artifacts_enabled() {
             return;
@@ -1740,6 +1962,7 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             return Ok(());
         };
         require_nonempty_installer(release, config)?;
+        let offline = config.offline;
         let release_id = &release.id;
         let schema_release = self
             .manifest
@@ -1819,10 +2042,81 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             is_global: true,
         };
 
+        let target_triples = installer_artifact.target_triples.clone();
+        let offline_artifact = offline.then(|| self.offline_shell_installer(&installer_artifact));
         self.add_global_artifact(to_release, installer_artifact);
+        if let Some(offline_artifact) = offline_artifact {
+            self.add_global_artifact(to_release, offline_artifact);
+        }
+        self.add_uninstaller(
+            to_release,
+            target_triples,
+            "sh",
+            InstallerImpl::ShellUninstaller,
+        );
         Ok(())
     }
 
+    /// Make a variant of a shell installer that has the archives appended to it,
+    /// so it can be copied to (and run on) machines without network access
+    fn offline_shell_installer(&self, installer_artifact: &Artifact) -> Artifact {
+        let ArtifactKind::Installer(InstallerImpl::Shell(info)) = &installer_artifact.kind else {
+            unreachable!("offline installers can only be made from shell installers");
+        };
+        let release = self.release(info.release);
+        let artifact_name = format!("{}-offline-installer.sh", release.id);
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let hint = format!("sh ./{artifact_name}");
+        let desc = "Install prebuilt binaries via shell script, without network access".to_owned();
+
+        Artifact {
+            id: artifact_name,
+            target_triples: installer_artifact.target_triples.clone(),
+            archive: None,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            kind: ArtifactKind::Installer(InstallerImpl::ShellOffline(InstallerInfo {
+                dest_path: artifact_path,
+                hint,
+                desc,
+                ..info.clone()
+            })),
+            is_global: true,
+        }
+    }
+
+    /// If uninstallers are enabled, add one to go with a shell/powershell installer
+    fn add_uninstaller(
+        &mut self,
+        to_release: ReleaseIdx,
+        target_triples: Vec<TargetTriple>,
+        ext: &str,
+        kind: fn(UninstallerInfo) -> InstallerImpl,
+    ) {
+        if !self.inner.config.installers.uninstallers {
+            return;
+        }
+        let release = self.release(to_release);
+        let artifact_name = format!("{}-uninstaller.{ext}", release.id);
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let uninstaller_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            archive: None,
+            checksum: None,
+            kind: ArtifactKind::Installer(kind(UninstallerInfo {
+                dest_path: artifact_path,
+                app_name: release.app_name.clone(),
+            })),
+            is_global: true,
+        };
+
+        self.add_global_artifact(to_release, uninstaller_artifact);
+    }
+
     fn add_homebrew_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
         if !self.global_artifacts_enabled() {
             return Ok(());
@@ -1990,88 +2284,148 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         Ok(())
     }
 
-    fn add_powershell_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+    fn add_scoop_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
         if !self.global_artifacts_enabled() {
             return Ok(());
         }
-
-        // Get the basic info about the installer
         let release = self.release(to_release);
-        let Some(config) = &release.config.installers.powershell else {
+        let Some(config) = &release.config.installers.scoop else {
             return Ok(());
         };
         require_nonempty_installer(release, config)?;
-        let release_id = &release.id;
-        let schema_release = self
-            .manifest
-            .release_by_name(&release.app_name)
-            .expect("couldn't find the release!?");
-
-        let env_vars = schema_release
-            .env
-            .as_ref()
-            .expect("couldn't determine app-specific environment variable!?");
-        let install_dir_env_var = env_vars.install_dir_env_var.to_owned();
-        let unmanaged_dir_env_var = env_vars.unmanaged_dir_env_var.to_owned();
-        let disable_update_env_var = env_vars.disable_update_env_var.to_owned();
-        let no_modify_path_env_var = env_vars.no_modify_path_env_var.to_owned();
-
-        let download_url = schema_release
-            .artifact_download_url()
+        let manifest_release = self.manifest.release_by_name(&release.id);
+        let download_url = manifest_release
+            .and_then(|r| r.artifact_download_url())
             .expect("couldn't compute a URL to download artifacts from!?");
-        let artifact_name = format!("{release_id}-installer.ps1");
+
+        let artifact_name = format!("{}.json", release.id);
         let artifact_path = self.inner.dist_dir.join(&artifact_name);
-        let installer_url = format!("{download_url}/{artifact_name}");
-        let hint = format!(r#"powershell -ExecutionPolicy ByPass -c "irm {installer_url} | iex""#);
-        let desc = "Install prebuilt binaries via powershell script".to_owned();
 
-        // Gather up the bundles the installer supports
+        // Scoop only knows how to unpack zips
         let artifacts = release
             .platform_support
             .fragments()
             .into_iter()
-            .filter(|a| a.target_triple.is_windows_msvc())
+            .filter(|a| a.target_triple.is_windows() && a.zip_style == ZipStyle::Zip)
+            .collect::<Vec<_>>();
+        let find_arch = |target: &TargetTripleRef| {
+            artifacts
+                .iter()
+                .find(|a| a.target_triple == target)
+                .cloned()
+        };
+        let x86_64_windows = find_arch(TARGET_X64_WINDOWS);
+        let x86_windows = find_arch(TARGET_X86_WINDOWS);
+        // Scoop falls back to 64bit on arm64 itself, so only list native builds
+        let arm64_windows = find_arch(TARGET_ARM64_WINDOWS)
+            .filter(|arm| x86_64_windows.as_ref().map_or(true, |x64| x64.id != arm.id));
+        let artifacts = [&x86_64_windows, &x86_windows, &arm64_windows]
+            .into_iter()
+            .flatten()
+            .cloned()
             .collect::<Vec<_>>();
+        if artifacts.is_empty() {
+            warn!("skipping Scoop installer: not building any supported platforms (use --artifacts=global)");
+            return Ok(());
+        };
         let target_triples = artifacts
             .iter()
             .map(|a| a.target_triple.clone())
             .collect::<Vec<_>>();
-        if artifacts.is_empty() {
-            warn!("skipping powershell installer: not building any supported platforms (use --artifacts=global)");
-            return Ok(());
+
+        // If a bucket is specified, the manifest is installed from there,
+        // otherwise it can be installed straight from the release
+        let bucket = config.bucket.clone();
+        let hint = if let Some(bucket) = &self.inner.global_scoop_bucket {
+            let bucket_name = bucket.rsplit('/').next().unwrap_or(bucket);
+            format!(
+                "scoop bucket add {bucket_name} https://github.com/{bucket}; scoop install {bucket_name}/{}",
+                release.id
+            )
+        } else {
+            format!("scoop install {download_url}/{artifact_name}")
         };
+        let desc = "Install prebuilt binaries via Scoop".to_owned();
+
+        if bucket.is_some() && release.config.publishers.scoop.is_none() {
+            warn!("A Scoop bucket was specified but the Scoop publish job is disabled\n  consider adding \"scoop\" to publish-jobs in Cargo.toml");
+        }
+        if release.config.publishers.scoop.is_some() && bucket.is_none() {
+            warn!("The Scoop publish job is enabled but no bucket was specified\n  consider setting scoop-bucket in Cargo.toml");
+        }
+
+        let app_homepage_url = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+
+        // Scoop can only find new versions of things it knows how to look up
+        let version = release.version.to_string();
+        let github = manifest_release.and_then(|r| r.hosting.github.as_ref());
+        let checkver = github.map(|github| {
+            let tag = download_url.rsplit('/').next().unwrap_or_default();
+            ScoopCheckver::for_github_release(&github.owner, &github.repo, tag, &version)
+        });
+        let autoupdate_base_url = checkver
+            .as_ref()
+            .and_then(|_| autoupdate_base_url(download_url, &version));
+
+        let bins = release
+            .bins
+            .iter()
+            .map(|(_, name)| {
+                let aliases = config.bin_aliases.get(name).cloned().unwrap_or_default();
+                (name.clone(), aliases)
+            })
+            .collect();
         let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+        let runtime_conditions = release.platform_support.safe_conflated_runtime_conditions();
+
         let installer_artifact = Artifact {
             id: artifact_name,
             target_triples,
+            archive: None,
             file_path: artifact_path.clone(),
             required_binaries: FastMap::new(),
-            archive: None,
             checksum: None,
-            kind: ArtifactKind::Installer(InstallerImpl::Powershell(InstallerInfo {
-                release: to_release,
-                dest_path: artifact_path,
-                app_name: release.app_name.clone(),
-                app_version: release.version.to_string(),
-                install_paths: config
-                    .install_path
-                    .iter()
-                    .map(|p| p.clone().into_jinja())
-                    .collect(),
-                install_success_msg: config.install_success_msg.to_owned(),
-                base_url: download_url.to_owned(),
-                artifacts,
-                hint,
-                desc,
-                receipt: InstallReceipt::from_metadata(&self.inner, release),
-                bin_aliases,
-                install_libraries: config.install_libraries.clone(),
-                runtime_conditions: RuntimeConditions::default(),
-                platform_support: None,
-                install_dir_env_var,
-                unmanaged_dir_env_var,
-                disable_update_env_var,
-                no_modify_path_env_var,
+            kind: ArtifactKind::Installer(InstallerImpl::Scoop(ScoopInstallerInfo {
+                name: release.id.clone(),
+                desc: release.app_desc.clone(),
+                homepage: app_homepage_url,
+                license: release.app_license.clone(),
+                bucket,
+                x86_64_windows,
+                x86_windows,
+                arm64_windows,
+                bins,
+                checkver,
+                autoupdate_base_url,
+                inner: InstallerInfo {
+                    release: to_release,
+                    dest_path: artifact_path,
+                    app_name: release.app_name.clone(),
+                    app_version: version,
+                    install_paths: config
+                        .install_path
+                        .iter()
+                        .map(|p| p.clone().into_jinja())
+                        .collect(),
+                    install_success_msg: config.install_success_msg.to_owned(),
+                    base_url: download_url.to_owned(),
+                    artifacts,
+                    hint,
+                    desc,
+                    receipt: None,
+                    bin_aliases,
+                    install_libraries: config.install_libraries.clone(),
+                    runtime_conditions,
+                    platform_support: None,
+                    // Not actually needed for this installer type
+                    install_dir_env_var: String::new(),
+                    unmanaged_dir_env_var: String::new(),
+                    disable_update_env_var: String::new(),
+                    no_modify_path_env_var: String::new(),
+                },
             })),
             is_global: true,
         };
@@ -2080,116 +2434,1058 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         Ok(())
     }
 
-    fn add_npm_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+    fn add_winget_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
         if !self.global_artifacts_enabled() {
             return Ok(());
         }
         let release = self.release(to_release);
-        let Some(config) = &release.config.installers.npm else {
+        let Some(config) = &release.config.installers.winget else {
             return Ok(());
         };
         require_nonempty_installer(release, config)?;
-        let release_id = &release.id;
-        let download_url = self
-            .manifest
-            .release_by_name(&release.app_name)
+        let manifest_release = self.manifest.release_by_name(&release.id);
+        let download_url = manifest_release
             .and_then(|r| r.artifact_download_url())
             .expect("couldn't compute a URL to download artifacts from!?");
 
-        let app_name = config.package.clone();
-        let npm_package_name = if let Some(scope) = &config.scope {
-            if scope.to_ascii_lowercase() != *scope {
-                return Err(DistError::ScopeMustBeLowercase {
-                    scope: scope.to_owned(),
-                });
-            }
-
-            format!("{scope}/{}", app_name)
-        } else {
-            app_name.clone()
-        };
-        let npm_package_version = release.version.to_string();
-        let npm_package_desc = release.app_desc.clone();
-        let npm_package_authors = release.app_authors.clone();
-        let npm_package_license = release.app_license.clone();
-        let npm_package_repository_url = release.app_repository_url.clone();
-        let npm_package_homepage_url = release.app_homepage_url.clone();
-        let npm_package_keywords = release.app_keywords.clone();
-
-        let static_assets = release.static_assets.clone();
-        let dir_name = format!("{release_id}-npm-package");
-        let dir_path = self.inner.dist_dir.join(&dir_name);
-        let zip_style = ZipStyle::Tar(CompressionImpl::Gzip);
-        let zip_ext = zip_style.ext();
-        let artifact_name = format!("{dir_name}{zip_ext}");
+        // Point at the msi for each windows build if there is one, otherwise its zip
+        let has_msi = release.config.installers.msi.is_some();
+        let fragments = release.platform_support.fragments();
+        let mut installers = vec![];
+        let mut target_triples = vec![];
+        let mut artifacts = vec![];
+        for &variant_idx in &release.variants {
+            let variant = self.variant(variant_idx);
+            let target = &variant.target;
+            let Some(architecture) = winget_architecture(target) else {
+                continue;
+            };
+            let zip = fragments
+                .iter()
+                .find(|a| &a.target_triple == target && a.zip_style == ZipStyle::Zip);
+            let installer = if has_msi {
+                WingetInstaller {
+                    architecture,
+                    artifact_id: format!("{}.msi", variant.id),
+                    kind: WingetInstallerKind::Msi,
+                }
+            } else if let Some(zip) = zip {
+                WingetInstaller {
+                    architecture,
+                    artifact_id: zip.id.clone(),
+                    kind: WingetInstallerKind::Zip {
+                        executables: zip.executables.clone(),
+                    },
+                }
+            } else {
+                continue;
+            };
+            installers.push(installer);
+            target_triples.push(target.clone());
+            artifacts.extend(zip.cloned());
+        }
+        if installers.is_empty() {
+            warn!("skipping WinGet installer: not building any supported platforms (use --artifacts=global)");
+            return Ok(());
+        };
+
+        // WinGet packages are conventionally namespaced by the GitHub user/org
+        let github_owner = manifest_release
+            .and_then(|r| r.hosting.github.as_ref())
+            .map(|github| github.owner.clone());
+        let first_author = release.app_authors.first().map(|author| {
+            // Strip the email from "Name <email>"
+            author
+                .split_once('<')
+                .map_or(author.as_str(), |(name, _)| name)
+                .trim()
+                .to_owned()
+        });
+        let publisher = github_owner
+            .clone()
+            .or_else(|| first_author.clone())
+            .unwrap_or_default();
+        let package_identifier = if let Some(id) = &config.package_identifier {
+            id.clone()
+        } else {
+            let namespace = to_identifier_part(&publisher);
+            if namespace.is_empty() {
+                return Err(DistError::WingetNoPackageIdentifier {
+                    app_name: release.app_name.clone(),
+                });
+            }
+            format!("{namespace}.{}", to_identifier_part(&release.app_name))
+        };
+        let publisher = first_author.clone().unwrap_or(publisher);
+        let publisher_url = github_owner.map(|owner| format!("https://github.com/{owner}"));
+        let package_url = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+        if release.app_desc.is_none() {
+            warn!("The WinGet installer is enabled but no description was specified\n  consider adding `description = ` to package in Cargo.toml");
+        }
+        if release.app_license.is_none() {
+            warn!("The WinGet installer is enabled but no license was specified\n  consider adding `license = ` to package in Cargo.toml");
+        }
+        let tags = release
+            .app_keywords
+            .iter()
+            .flatten()
+            .map(|keyword| keyword.to_lowercase().replace(' ', "-"))
+            .take(16)
+            .collect();
+
+        let artifact_name = format!("{}-winget.tar.gz", release.id);
         let artifact_path = self.inner.dist_dir.join(&artifact_name);
-        // let installer_url = format!("{download_url}/{artifact_name}");
-        let hint = format!("npm install {npm_package_name}@{npm_package_version}");
-        let desc = "Install prebuilt binaries into your npm project".to_owned();
+        let dir_path = self.inner.dist_dir.join(format!("{}-winget", release.id));
+        let hint = String::new();
+        let desc = "WinGet manifests, for submitting to winget-pkgs".to_owned();
+        let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+        let runtime_conditions = release.platform_support.safe_conflated_runtime_conditions();
 
-        let artifacts = release.platform_support.fragments();
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            archive: Some(Archive {
+                // The same layout as winget-pkgs, so this can be extracted straight into it
+                with_root: Some("manifests".into()),
+                dir_path: dir_path.clone(),
+                zip_style: ZipStyle::Tar(CompressionImpl::Gzip),
+                static_assets: vec![],
+            }),
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Winget(WingetInstallerInfo {
+                package_identifier,
+                publisher,
+                publisher_url,
+                author: first_author,
+                package_url,
+                license: release.app_license.clone(),
+                short_description: release.app_desc.clone(),
+                tags,
+                installers,
+                package_dir: dir_path,
+                inner: InstallerInfo {
+                    release: to_release,
+                    dest_path: artifact_path,
+                    app_name: release.app_name.clone(),
+                    app_version: release.version.to_string(),
+                    install_paths: config
+                        .install_path
+                        .iter()
+                        .map(|p| p.clone().into_jinja())
+                        .collect(),
+                    install_success_msg: config.install_success_msg.to_owned(),
+                    base_url: download_url.to_owned(),
+                    artifacts,
+                    hint,
+                    desc,
+                    receipt: None,
+                    bin_aliases,
+                    install_libraries: config.install_libraries.clone(),
+                    runtime_conditions,
+                    platform_support: None,
+                    // Not actually needed for this installer type
+                    install_dir_env_var: String::new(),
+                    unmanaged_dir_env_var: String::new(),
+                    disable_update_env_var: String::new(),
+                    no_modify_path_env_var: String::new(),
+                },
+            })),
+            is_global: true,
+        };
+
+        self.add_global_artifact(to_release, installer_artifact);
+        Ok(())
+    }
+
+    fn add_aur_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.global_artifacts_enabled() {
+            return Ok(());
+        }
+        let release = self.release(to_release);
+        let Some(config) = &release.config.installers.aur else {
+            return Ok(());
+        };
+        require_nonempty_installer(release, config)?;
+        let manifest_release = self.manifest.release_by_name(&release.id);
+        let download_url = manifest_release
+            .and_then(|r| r.artifact_download_url())
+            .expect("couldn't compute a URL to download artifacts from!?");
+
+        // A -bin package repackages the linux-gnu archives, which makepkg
+        // knows how to unpack whatever they're compressed with
+        let sources = release
+            .platform_support
+            .fragments()
+            .into_iter()
+            .filter_map(|fragment| {
+                let arch = aur_architecture(&fragment.target_triple)?;
+                Some(AurSource {
+                    arch: arch.to_owned(),
+                    fragment,
+                })
+            })
+            .collect::<Vec<_>>();
+        if sources.is_empty() {
+            warn!("skipping AUR installer: not building any supported platforms (use --artifacts=global)");
+            return Ok(());
+        };
+        let artifacts = sources
+            .iter()
+            .map(|source| source.fragment.clone())
+            .collect::<Vec<_>>();
         let target_triples = artifacts
             .iter()
             .map(|a| a.target_triple.clone())
             .collect::<Vec<_>>();
 
-        if artifacts.is_empty() {
-            warn!("skipping npm installer: not building any supported platforms (use --artifacts=global)");
+        let provides = aur_package_name(&release.app_name);
+        let package_name = format!("{provides}-bin");
+        let static_assets = |wanted: &[StaticAssetKind]| {
+            release
+                .static_assets
+                .iter()
+                .filter(|(kind, _)| wanted.contains(kind))
+                .filter_map(|(_, path)| path.file_name())
+                .map(|name| name.to_owned())
+                .collect::<Vec<_>>()
+        };
+        let licenses = static_assets(&[StaticAssetKind::License]);
+        let docs = static_assets(&[StaticAssetKind::Readme, StaticAssetKind::Changelog]);
+
+        let artifact_name = format!("{}-aur.tar.gz", release.id);
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let dir_path = self.inner.dist_dir.join(format!("{}-aur", release.id));
+        let hint = if release.config.publishers.aur.is_some() {
+            format!("git clone https://aur.archlinux.org/{package_name}.git && cd {package_name} && makepkg -si")
+        } else {
+            format!("curl -LsSf {download_url}/{artifact_name} | tar xz && cd {package_name} && makepkg -si")
+        };
+        let desc = "Install prebuilt binaries via the AUR".to_owned();
+        let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+        let runtime_conditions = release.platform_support.safe_conflated_runtime_conditions();
+
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            archive: Some(Archive {
+                // The same layout as `makepkg --source`
+                with_root: Some(package_name.clone().into()),
+                dir_path: dir_path.clone(),
+                zip_style: ZipStyle::Tar(CompressionImpl::Gzip),
+                static_assets: vec![],
+            }),
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Aur(AurInstallerInfo {
+                package_name,
+                provides,
+                pkgver: aur_pkgver(&release.version),
+                maintainer: release.app_authors.first().cloned(),
+                desc: release.app_desc.clone(),
+                homepage: release
+                    .app_homepage_url
+                    .clone()
+                    .or_else(|| release.app_repository_url.clone()),
+                license: release.app_license.clone(),
+                sources,
+                licenses,
+                docs,
+                package_dir: dir_path,
+                inner: InstallerInfo {
+                    release: to_release,
+                    dest_path: artifact_path,
+                    app_name: release.app_name.clone(),
+                    app_version: release.version.to_string(),
+                    install_paths: config
+                        .install_path
+                        .iter()
+                        .map(|p| p.clone().into_jinja())
+                        .collect(),
+                    install_success_msg: config.install_success_msg.to_owned(),
+                    base_url: download_url.to_owned(),
+                    artifacts,
+                    hint,
+                    desc,
+                    receipt: None,
+                    bin_aliases,
+                    install_libraries: config.install_libraries.clone(),
+                    runtime_conditions,
+                    platform_support: None,
+                    // Not actually needed for this installer type
+                    install_dir_env_var: String::new(),
+                    unmanaged_dir_env_var: String::new(),
+                    disable_update_env_var: String::new(),
+                    no_modify_path_env_var: String::new(),
+                },
+            })),
+            is_global: true,
+        };
+
+        self.add_global_artifact(to_release, installer_artifact);
+        Ok(())
+    }
+
+    fn add_nix_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.global_artifacts_enabled() {
+            return Ok(());
+        }
+        let release = self.release(to_release);
+        let Some(config) = &release.config.installers.nix else {
+            return Ok(());
+        };
+        require_nonempty_installer(release, config)?;
+        let manifest_release = self.manifest.release_by_name(&release.id);
+        let download_url = manifest_release
+            .and_then(|r| r.artifact_download_url())
+            .expect("couldn't compute a URL to download artifacts from!?");
+
+        // One archive per Nix system, preferring linux-gnu builds over musl ones
+        // since autoPatchelf can point them at nixpkgs' glibc
+        let mut by_system = SortedMap::<&str, ExecutableZipFragment>::new();
+        for fragment in release.platform_support.fragments() {
+            let Some(system) = nix_system(&fragment.target_triple) else {
+                continue;
+            };
+            match by_system.get(system) {
+                Some(existing) if !existing.target_triple.is_musl() => {}
+                _ => {
+                    by_system.insert(system, fragment);
+                }
+            }
+        }
+        if by_system.is_empty() {
+            warn!("skipping Nix installer: not building any supported platforms (use --artifacts=global)");
+            return Ok(());
+        };
+        let sources = by_system
+            .into_iter()
+            .map(|(system, fragment)| NixSource {
+                system: system.to_owned(),
+                fragment,
+            })
+            .collect::<Vec<_>>();
+        let artifacts = sources
+            .iter()
+            .map(|source| source.fragment.clone())
+            .collect::<Vec<_>>();
+        let target_triples = artifacts
+            .iter()
+            .map(|a| a.target_triple.clone())
+            .collect::<Vec<_>>();
+
+        // Nix platforms are all unix-y, so every binary has the same name everywhere
+        let mut bins = artifacts
+            .iter()
+            .flat_map(|a| a.executables.iter())
+            .map(|bin| (bin.clone(), vec![]))
+            .collect::<SortedMap<String, Vec<String>>>();
+        for (bin, aliases) in &config.bin_aliases {
+            if let Some(entry) = bins.get_mut(bin) {
+                entry.extend(aliases.iter().cloned());
+            }
+        }
+
+        let pname = release.app_name.clone();
+        let artifact_name = format!("{}-nix.tar.gz", release.id);
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let dir_path = self.inner.dist_dir.join(format!("{}-nix", release.id));
+        // Nix strips the root dir off of tarball flakes
+        let hint = format!("nix profile install {download_url}/{artifact_name}");
+        let desc = "Install prebuilt binaries via Nix".to_owned();
+        let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+        let runtime_conditions = release.platform_support.safe_conflated_runtime_conditions();
+
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            archive: Some(Archive {
+                with_root: Some(pname.clone().into()),
+                dir_path: dir_path.clone(),
+                zip_style: ZipStyle::Tar(CompressionImpl::Gzip),
+                static_assets: vec![],
+            }),
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Nix(NixInstallerInfo {
+                pname,
+                desc: release.app_desc.clone(),
+                homepage: release
+                    .app_homepage_url
+                    .clone()
+                    .or_else(|| release.app_repository_url.clone()),
+                license: release.app_license.clone(),
+                sources,
+                bins,
+                package_dir: dir_path,
+                inner: InstallerInfo {
+                    release: to_release,
+                    dest_path: artifact_path,
+                    app_name: release.app_name.clone(),
+                    app_version: release.version.to_string(),
+                    install_paths: config
+                        .install_path
+                        .iter()
+                        .map(|p| p.clone().into_jinja())
+                        .collect(),
+                    install_success_msg: config.install_success_msg.to_owned(),
+                    base_url: download_url.to_owned(),
+                    artifacts,
+                    hint,
+                    desc,
+                    receipt: None,
+                    bin_aliases,
+                    install_libraries: config.install_libraries.clone(),
+                    runtime_conditions,
+                    platform_support: None,
+                    // Not actually needed for this installer type
+                    install_dir_env_var: String::new(),
+                    unmanaged_dir_env_var: String::new(),
+                    disable_update_env_var: String::new(),
+                    no_modify_path_env_var: String::new(),
+                },
+            })),
+            is_global: true,
+        };
+
+        self.add_global_artifact(to_release, installer_artifact);
+        Ok(())
+    }
+
+    fn add_powershell_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.global_artifacts_enabled() {
+            return Ok(());
+        }
+
+        // Get the basic info about the installer
+        let release = self.release(to_release);
+        let Some(config) = &release.config.installers.powershell else {
+            return Ok(());
+        };
+        require_nonempty_installer(release, config)?;
+        let release_id = &release.id;
+        let schema_release = self
+            .manifest
+            .release_by_name(&release.app_name)
+            .expect("couldn't find the release!?");
+
+        let env_vars = schema_release
+            .env
+            .as_ref()
+            .expect("couldn't determine app-specific environment variable!?");
+        let install_dir_env_var = env_vars.install_dir_env_var.to_owned();
+        let unmanaged_dir_env_var = env_vars.unmanaged_dir_env_var.to_owned();
+        let disable_update_env_var = env_vars.disable_update_env_var.to_owned();
+        let no_modify_path_env_var = env_vars.no_modify_path_env_var.to_owned();
+
+        let download_url = schema_release
+            .artifact_download_url()
+            .expect("couldn't compute a URL to download artifacts from!?");
+        let artifact_name = format!("{release_id}-installer.ps1");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        let installer_url = format!("{download_url}/{artifact_name}");
+        let hint = format!(r#"powershell -ExecutionPolicy ByPass -c "irm {installer_url} | iex""#);
+        let desc = "Install prebuilt binaries via powershell script".to_owned();
+
+        // Gather up the bundles the installer supports
+        let artifacts = release
+            .platform_support
+            .fragments()
+            .into_iter()
+            .filter(|a| a.target_triple.is_windows_msvc())
+            .collect::<Vec<_>>();
+        let target_triples = artifacts
+            .iter()
+            .map(|a| a.target_triple.clone())
+            .collect::<Vec<_>>();
+        if artifacts.is_empty() {
+            warn!("skipping powershell installer: not building any supported platforms (use --artifacts=global)");
+            return Ok(());
+        };
+        let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            archive: None,
+            checksum: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Powershell(InstallerInfo {
+                release: to_release,
+                dest_path: artifact_path,
+                app_name: release.app_name.clone(),
+                app_version: release.version.to_string(),
+                install_paths: config
+                    .install_path
+                    .iter()
+                    .map(|p| p.clone().into_jinja())
+                    .collect(),
+                install_success_msg: config.install_success_msg.to_owned(),
+                base_url: download_url.to_owned(),
+                artifacts,
+                hint,
+                desc,
+                receipt: InstallReceipt::from_metadata(&self.inner, release),
+                bin_aliases,
+                install_libraries: config.install_libraries.clone(),
+                runtime_conditions: RuntimeConditions::default(),
+                platform_support: None,
+                install_dir_env_var,
+                unmanaged_dir_env_var,
+                disable_update_env_var,
+                no_modify_path_env_var,
+            })),
+            is_global: true,
+        };
+
+        let target_triples = installer_artifact.target_triples.clone();
+        self.add_global_artifact(to_release, installer_artifact);
+        self.add_uninstaller(
+            to_release,
+            target_triples,
+            "ps1",
+            InstallerImpl::PowershellUninstaller,
+        );
+        Ok(())
+    }
+
+    fn add_npm_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        let release = self.release(to_release);
+        let Some(config) = &release.config.installers.npm else {
+            return Ok(());
+        };
+        require_nonempty_installer(release, config)?;
+
+        let app_name = config.package.clone();
+        let npm_package_name = if let Some(scope) = &config.scope {
+            if scope.to_ascii_lowercase() != *scope {
+                return Err(DistError::ScopeMustBeLowercase {
+                    scope: scope.to_owned(),
+                });
+            }
+
+            format!("{scope}/{}", app_name)
+        } else {
+            app_name.clone()
+        };
+        let platform_packages = if config.platform_packages {
+            Some(self.add_npm_platform_packages(to_release, &npm_package_name)?)
+        } else {
+            None
+        };
+
+        if !self.global_artifacts_enabled() {
+            return Ok(());
+        }
+        let release = self.release(to_release);
+        let Some(config) = &release.config.installers.npm else {
+            return Ok(());
+        };
+        let release_id = &release.id;
+        let download_url = self
+            .manifest
+            .release_by_name(&release.app_name)
+            .and_then(|r| r.artifact_download_url())
+            .expect("couldn't compute a URL to download artifacts from!?");
+
+        let npm_package_version = release.version.to_string();
+        let npm_package_desc = release.app_desc.clone();
+        let npm_package_authors = release.app_authors.clone();
+        let npm_package_license = release.app_license.clone();
+        let npm_package_repository_url = release.app_repository_url.clone();
+        let npm_package_homepage_url = release.app_homepage_url.clone();
+        let npm_package_keywords = release.app_keywords.clone();
+
+        let static_assets = release.static_assets.clone();
+        let dir_name = format!("{release_id}-npm-package");
+        let dir_path = self.inner.dist_dir.join(&dir_name);
+        let zip_style = ZipStyle::Tar(CompressionImpl::Gzip);
+        let zip_ext = zip_style.ext();
+        let artifact_name = format!("{dir_name}{zip_ext}");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
+        // let installer_url = format!("{download_url}/{artifact_name}");
+        let hint = format!("npm install {npm_package_name}@{npm_package_version}");
+        let desc = "Install prebuilt binaries into your npm project".to_owned();
+
+        let artifacts = release.platform_support.fragments();
+        let target_triples = artifacts
+            .iter()
+            .map(|a| a.target_triple.clone())
+            .collect::<Vec<_>>();
+
+        if artifacts.is_empty() {
+            warn!("skipping npm installer: not building any supported platforms (use --artifacts=global)");
+            return Ok(());
+        };
+        let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+
+        let runtime_conditions = release.platform_support.safe_conflated_runtime_conditions();
+
+        let installer_artifact = Artifact {
+            id: artifact_name,
+            target_triples,
+            archive: Some(Archive {
+                // npm specifically expects the dir inside the tarball to be called "package"
+                with_root: Some("package".into()),
+                dir_path: dir_path.clone(),
+                zip_style,
+                static_assets,
+            }),
+            file_path: artifact_path.clone(),
+            required_binaries: FastMap::new(),
+            checksum: None,
+            kind: ArtifactKind::Installer(InstallerImpl::Npm(NpmInstallerInfo {
+                npm_package_name,
+                npm_package_version,
+                npm_package_desc,
+                npm_package_authors,
+                npm_package_license,
+                npm_package_repository_url,
+                npm_package_homepage_url,
+                npm_package_keywords,
+                package_dir: dir_path,
+                platform_packages,
+                inner: InstallerInfo {
+                    release: to_release,
+                    dest_path: artifact_path,
+                    app_name,
+                    app_version: release.version.to_string(),
+                    install_paths: config
+                        .install_path
+                        .iter()
+                        .map(|p| p.clone().into_jinja())
+                        .collect(),
+                    install_success_msg: config.install_success_msg.to_owned(),
+                    base_url: download_url.to_owned(),
+                    artifacts,
+                    hint,
+                    desc,
+                    receipt: None,
+                    bin_aliases,
+                    install_libraries: config.install_libraries.clone(),
+                    runtime_conditions,
+                    platform_support: None,
+                    // Not actually needed for this installer type
+                    install_dir_env_var: String::new(),
+                    unmanaged_dir_env_var: String::new(),
+                    disable_update_env_var: String::new(),
+                    no_modify_path_env_var: String::new(),
+                },
+            })),
+            is_global: true,
+        };
+
+        self.add_global_artifact(to_release, installer_artifact);
+        Ok(())
+    }
+
+    /// Add an npm package with the binaries for each platform, and get the
+    /// list of them for the package that depends on them
+    fn add_npm_platform_packages(
+        &mut self,
+        to_release: ReleaseIdx,
+        npm_package_name: &str,
+    ) -> DistResult<Vec<NpmPlatformPackage>> {
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let npm_package_version = release.version.to_string();
+        let npm_package_desc = release.app_desc.clone();
+        let npm_package_license = release.app_license.clone();
+        let npm_package_repository_url = release.app_repository_url.clone();
+        let npm_package_homepage_url = release.app_homepage_url.clone();
+        let licenses = release
+            .static_assets
+            .iter()
+            .filter(|(kind, _)| *kind == StaticAssetKind::License)
+            .cloned()
+            .collect::<Vec<_>>();
+        let variants = release.variants.clone();
+
+        let mut packages = vec![];
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = variant.target.clone();
+            let Some((os, cpu, libc)) = npm_platform(&target) else {
+                warn!("skipping npm package for {target}: npm doesn't support that platform");
+                continue;
+            };
+            let executables = binaries
+                .iter()
+                .map(|&idx| self.binary(idx))
+                .filter(|binary| binary.kind == BinaryKind::Executable)
+                .map(|binary| binary.file_name.clone())
+                .collect::<Vec<_>>();
+            if executables.is_empty() {
+                continue;
+            }
+            let package = NpmPlatformPackage {
+                npm_package_name: format!("{npm_package_name}-{target}"),
+                target: target.clone(),
+                os: os.to_owned(),
+                cpu: cpu.to_owned(),
+                libc: libc.map(ToOwned::to_owned),
+                executables,
+            };
+            packages.push(package.clone());
+
+            if !self.local_artifacts_enabled() {
+                continue;
+            }
+            let variant_id = &variant.id;
+            let dir_name = format!("{variant_id}-npm-package");
+            let dir_path = self.inner.dist_dir.join(&dir_name);
+            let zip_style = ZipStyle::Tar(CompressionImpl::Gzip);
+            let artifact_name = format!("{dir_name}{}", zip_style.ext());
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target],
+                archive: Some(Archive {
+                    // npm specifically expects the dir inside the tarball to be called "package"
+                    with_root: Some("package".into()),
+                    dir_path: dir_path.clone(),
+                    zip_style,
+                    static_assets: licenses.clone(),
+                }),
+                file_path: artifact_path,
+                required_binaries: FastMap::new(),
+                checksum: None,
+                kind: ArtifactKind::Installer(InstallerImpl::NpmPlatform(
+                    NpmPlatformInstallerInfo {
+                        package,
+                        npm_package_version: npm_package_version.clone(),
+                        npm_package_desc: npm_package_desc.clone(),
+                        npm_package_repository_url: npm_package_repository_url.clone(),
+                        npm_package_homepage_url: npm_package_homepage_url.clone(),
+                        npm_package_license: npm_package_license.clone(),
+                        package_dir: dir_path.clone(),
+                    },
+                )),
+                is_global: false,
+            };
+
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                if binary.kind != BinaryKind::Executable {
+                    continue;
+                }
+                let dest_path = dir_path.join(&binary.file_name);
+                self.require_binary(installer_idx, variant_idx, binary_idx, dest_path);
+            }
+        }
+
+        Ok(packages)
+    }
+
+    fn add_msi_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.local_artifacts_enabled() {
+            return Ok(());
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        // FIXME: because we use cargo-wix and cargo-wix's config,
+        // msi installers really don't respect any of our own config!
+        // (We still look it up because it determines whether enabled or not.)
+        let Some(_config) = &release.config.installers.msi else {
+            return Ok(());
+        };
+        // FIXME: MSI installer contents don't actually respect this
+        // require_nonempty_installer(release, config)?;
+        let variants = release.variants.clone();
+        let checksum = self.inner.config.artifacts.checksum;
+
+        // Make an msi for every windows platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            if !target.is_windows() {
+                continue;
+            }
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.msi");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_name = format!("{variant_id}_msi");
+            let dir_path = self.inner.dist_dir.join(&dir_name);
+
+            // Compute which package we're actually building, based on the binaries
+            let mut package_info: Option<(String, PackageIdx)> = None;
+            for &binary_idx in &binaries {
+                let binary = self.binary(binary_idx);
+                if let Some((existing_spec, _)) = &package_info {
+                    // cargo-wix doesn't clearly support multi-package, so bail
+                    if existing_spec != &binary.pkg_spec {
+                        return Err(DistError::MultiPackage {
+                            artifact_name,
+                            spec1: existing_spec.clone(),
+                            spec2: binary.pkg_spec.clone(),
+                        })?;
+                    }
+                } else {
+                    package_info = Some((binary.pkg_spec.clone(), binary.pkg_idx));
+                }
+            }
+            let Some((pkg_spec, pkg_idx)) = package_info else {
+                return Err(DistError::NoPackage { artifact_name })?;
+            };
+            let manifest_path = self.workspaces.package(pkg_idx).manifest_path.clone();
+            let wxs_path = manifest_path
+                .parent()
+                .expect("Cargo.toml had no parent dir!?")
+                .join("wix")
+                .join("main.wxs");
+
+            // Gather up the bundles the installer supports
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                }),
+                checksum: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Msi(MsiInstallerInfo {
+                    package_dir: dir_path.clone(),
+                    pkg_spec,
+                    target: target.clone(),
+                    file_path: artifact_path.clone(),
+                    wxs_path,
+                    manifest_path,
+                })),
+                is_global: false,
+            };
+
+            // Register the artifact to various things
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(variant_idx, installer_idx, checksum);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_pkg_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.local_artifacts_enabled() {
+            return Ok(());
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let Some(config) = release.config.installers.pkg.clone() else {
+            return Ok(());
+        };
+        require_nonempty_installer(release, &config)?;
+        let version = release.version.clone();
+        let fragments = release.platform_support.fragments();
+
+        let variants = release.variants.clone();
+        let checksum = self.inner.config.artifacts.checksum;
+
+        // Make a pkg for every darwin platform
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let bin_aliases = BinaryAliases(config.bin_aliases.clone());
+            let target = &variant.target;
+            if !target.is_darwin() {
+                continue;
+            }
+
+            let variant_id = &variant.id;
+            let artifact_name = format!("{variant_id}.pkg");
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_name = format!("{variant_id}_pkg");
+            let dir_path = self.inner.dist_dir.join(&dir_name);
+
+            // Compute which package we're actually building, based on the binaries
+            let mut package_info: Option<(String, PackageIdx)> = None;
+            for &binary_idx in &binaries {
+                let binary = self.binary(binary_idx);
+                if let Some((existing_spec, _)) = &package_info {
+                    // we haven't set ourselves up to bundle multiple packages yet
+                    if existing_spec != &binary.pkg_spec {
+                        return Err(DistError::MultiPackage {
+                            artifact_name,
+                            spec1: existing_spec.clone(),
+                            spec2: binary.pkg_spec.clone(),
+                        })?;
+                    }
+                } else {
+                    package_info = Some((binary.pkg_spec.clone(), binary.pkg_idx));
+                }
+            }
+
+            let Some(artifact) = fragments
+                .clone()
+                .into_iter()
+                .find(|a| a.target_triple == variant.target)
+            else {
+                return Err(DistError::NoPackage { artifact_name })?;
+            };
+
+            let bin_aliases = bin_aliases.for_target(&variant.target);
+
+            let identifier = if let Some(id) = &config.identifier {
+                id.to_owned()
+            } else {
+                return Err(DistError::MacPkgBundleIdentifierMissing {});
+            };
+
+            // Gather up the bundles the installer supports
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                }),
+                checksum: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Pkg(PkgInstallerInfo {
+                    file_path: artifact_path.clone(),
+                    artifact,
+                    package_dir: dir_path.clone(),
+                    identifier,
+                    install_location: config.install_location.clone(),
+                    version: version.to_string(),
+                    bin_aliases,
+                })),
+                is_global: false,
+            };
+
+            // Register the artifact to various things
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(variant_idx, installer_idx, checksum);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// If both the pkg and Homebrew installers are enabled, wrap the pkgs in a cask
+    fn add_homebrew_cask(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.global_artifacts_enabled() {
+            return Ok(());
+        }
+        let release = self.release(to_release);
+        let (Some(pkg_config), Some(homebrew_config)) = (
+            &release.config.installers.pkg,
+            &release.config.installers.homebrew,
+        ) else {
             return Ok(());
         };
-        let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_targets(&target_triples);
+        let Some(identifier) = pkg_config.identifier.clone() else {
+            return Err(DistError::MacPkgBundleIdentifierMissing {});
+        };
+        let token = homebrew_config
+            .formula
+            .clone()
+            .unwrap_or_else(|| release.id.clone());
 
-        let runtime_conditions = release.platform_support.safe_conflated_runtime_conditions();
+        // The pkgs are local artifacts, so they may not be in this graph;
+        // their names are all we need though, since checksums come from the manifest.
+        let mut x86_64_macos = None;
+        let mut arm64_macos = None;
+        for &variant_idx in &release.variants {
+            let variant = self.variant(variant_idx);
+            let pkg = format!("{}.pkg", variant.id);
+            if variant.target == TARGET_X64_MAC {
+                x86_64_macos = Some(pkg);
+            } else if variant.target == TARGET_ARM64_MAC {
+                arm64_macos = Some(pkg);
+            }
+        }
+        if x86_64_macos.is_none() && arm64_macos.is_none() {
+            return Ok(());
+        }
+        let target_triples = [
+            (TARGET_X64_MAC, &x86_64_macos),
+            (TARGET_ARM64_MAC, &arm64_macos),
+        ]
+        .into_iter()
+        .filter(|(_, pkg)| pkg.is_some())
+        .map(|(target, _)| target.to_owned())
+        .collect();
+
+        let download_url = self
+            .manifest
+            .release_by_name(&release.id)
+            .and_then(|r| r.artifact_download_url())
+            .expect("couldn't compute a URL to download artifacts from!?");
+
+        // Same as the formula, the tap is included in the `brew install` message
+        let install_target = if let Some(tap) = &self.inner.global_homebrew_tap {
+            let tap = tap.replace("/homebrew-", "/");
+            format!("{tap}/{token}")
+        } else {
+            token.clone()
+        };
+        let hint = format!("brew install --cask {install_target}");
 
+        let artifact_name = format!("{token}.cask.rb");
+        let artifact_path = self.inner.dist_dir.join(&artifact_name);
         let installer_artifact = Artifact {
             id: artifact_name,
             target_triples,
-            archive: Some(Archive {
-                // npm specifically expects the dir inside the tarball to be called "package"
-                with_root: Some("package".into()),
-                dir_path: dir_path.clone(),
-                zip_style,
-                static_assets,
-            }),
+            archive: None,
             file_path: artifact_path.clone(),
             required_binaries: FastMap::new(),
             checksum: None,
-            kind: ArtifactKind::Installer(InstallerImpl::Npm(NpmInstallerInfo {
-                npm_package_name,
-                npm_package_version,
-                npm_package_desc,
-                npm_package_authors,
-                npm_package_license,
-                npm_package_repository_url,
-                npm_package_homepage_url,
-                npm_package_keywords,
-                package_dir: dir_path,
-                inner: InstallerInfo {
-                    release: to_release,
-                    dest_path: artifact_path,
-                    app_name,
-                    app_version: release.version.to_string(),
-                    install_paths: config
-                        .install_path
-                        .iter()
-                        .map(|p| p.clone().into_jinja())
-                        .collect(),
-                    install_success_msg: config.install_success_msg.to_owned(),
-                    base_url: download_url.to_owned(),
-                    artifacts,
-                    hint,
-                    desc,
-                    receipt: None,
-                    bin_aliases,
-                    install_libraries: config.install_libraries.clone(),
-                    runtime_conditions,
-                    platform_support: None,
-                    // Not actually needed for this installer type
-                    install_dir_env_var: String::new(),
-                    unmanaged_dir_env_var: String::new(),
-                    disable_update_env_var: String::new(),
-                    no_modify_path_env_var: String::new(),
-                },
+            kind: ArtifactKind::Installer(InstallerImpl::HomebrewCask(HomebrewCaskInstallerInfo {
+                dest_path: artifact_path,
+                token,
+                name: release.app_name.clone(),
+                version: release.version.to_string(),
+                homepage: release
+                    .app_homepage_url
+                    .clone()
+                    .or_else(|| release.app_repository_url.clone()),
+                desc: release.app_desc.clone(),
+                base_url: download_url.to_owned(),
+                identifier,
+                x86_64_macos,
+                x86_64_macos_sha256: None,
+                arm64_macos,
+                arm64_macos_sha256: None,
+                hint,
             })),
             is_global: true,
         };
@@ -2198,69 +3494,70 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         Ok(())
     }
 
-    fn add_msi_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+    fn add_deb_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
         if !self.local_artifacts_enabled() {
             return Ok(());
         }
 
         // Clone info we need from the release to avoid borrowing across the loop
         let release = self.release(to_release);
-        // FIXME: because we use cargo-wix and cargo-wix's config,
-        // msi installers really don't respect any of our own config!
-        // (We still look it up because it determines whether enabled or not.)
-        let Some(_config) = &release.config.installers.msi else {
+        let Some(config) = release.config.installers.deb.clone() else {
             return Ok(());
         };
-        // FIXME: MSI installer contents don't actually respect this
-        // require_nonempty_installer(release, config)?;
+        require_nonempty_installer(release, &config)?;
+        let package_name = deb_package_name(&release.app_name);
+        let version = deb_version(&release.version);
+        let maintainer = release.app_authors.first().cloned();
+        let description = release.app_desc.clone();
+        let homepage = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+        let docs = release
+            .static_assets
+            .iter()
+            .filter(|(kind, _)| matches!(kind, StaticAssetKind::Readme | StaticAssetKind::License))
+            .map(|(_, path)| path.clone())
+            .collect::<Vec<_>>();
+
         let variants = release.variants.clone();
         let checksum = self.inner.config.artifacts.checksum;
 
-        // Make an msi for every windows platform
+        // Make a deb for every linux-gnu platform
         for variant_idx in variants {
             let variant = self.variant(variant_idx);
             let binaries = variant.binaries.clone();
             let target = &variant.target;
-            if !target.is_windows() {
+            let Some(architecture) = deb_architecture(target) else {
                 continue;
-            }
+            };
+            // dpkg refuses packages without a Maintainer
+            let Some(maintainer) = maintainer.clone() else {
+                return Err(DistError::DebNoMaintainer {
+                    app_name: self.release(to_release).app_name.clone(),
+                });
+            };
+            let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_target(target);
 
             let variant_id = &variant.id;
-            let artifact_name = format!("{variant_id}.msi");
+            let artifact_name = format!("{variant_id}.deb");
             let artifact_path = self.inner.dist_dir.join(&artifact_name);
-            let dir_name = format!("{variant_id}_msi");
+            let dir_name = format!("{variant_id}_deb");
             let dir_path = self.inner.dist_dir.join(&dir_name);
 
-            // Compute which package we're actually building, based on the binaries
-            let mut package_info: Option<(String, PackageIdx)> = None;
+            let mut executables = vec![];
+            let mut cdylibs = vec![];
             for &binary_idx in &binaries {
                 let binary = self.binary(binary_idx);
-                if let Some((existing_spec, _)) = &package_info {
-                    // cargo-wix doesn't clearly support multi-package, so bail
-                    if existing_spec != &binary.pkg_spec {
-                        return Err(DistError::MultiPackage {
-                            artifact_name,
-                            spec1: existing_spec.clone(),
-                            spec2: binary.pkg_spec.clone(),
-                        })?;
-                    }
-                } else {
-                    package_info = Some((binary.pkg_spec.clone(), binary.pkg_idx));
+                match binary.kind {
+                    BinaryKind::Executable => executables.push(binary.file_name.clone()),
+                    BinaryKind::DynamicLibrary => cdylibs.push(binary.file_name.clone()),
+                    BinaryKind::StaticLibrary => {}
                 }
             }
-            let Some((pkg_spec, pkg_idx)) = package_info else {
-                return Err(DistError::NoPackage { artifact_name })?;
-            };
-            let manifest_path = self.workspaces.package(pkg_idx).manifest_path.clone();
-            let wxs_path = manifest_path
-                .parent()
-                .expect("Cargo.toml had no parent dir!?")
-                .join("wix")
-                .join("main.wxs");
 
-            // Gather up the bundles the installer supports
             let installer_artifact = Artifact {
-                id: artifact_name,
+                id: artifact_name.clone(),
                 target_triples: vec![target.clone()],
                 file_path: artifact_path.clone(),
                 required_binaries: FastMap::new(),
@@ -2271,13 +3568,20 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     static_assets: vec![],
                 }),
                 checksum: None,
-                kind: ArtifactKind::Installer(InstallerImpl::Msi(MsiInstallerInfo {
-                    package_dir: dir_path.clone(),
-                    pkg_spec,
-                    target: target.clone(),
+                kind: ArtifactKind::Installer(InstallerImpl::Deb(DebInstallerInfo {
+                    artifact_id: artifact_name,
                     file_path: artifact_path.clone(),
-                    wxs_path,
-                    manifest_path,
+                    package_dir: dir_path.clone(),
+                    package_name: package_name.clone(),
+                    version: version.clone(),
+                    architecture: architecture.to_owned(),
+                    maintainer,
+                    description: description.clone(),
+                    homepage: homepage.clone(),
+                    executables,
+                    cdylibs,
+                    bin_aliases,
+                    docs: docs.clone(),
                 })),
                 is_global: false,
             };
@@ -2286,6 +3590,9 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
             for binary_idx in binaries {
                 let binary = self.binary(binary_idx);
+                if binary.kind == BinaryKind::StaticLibrary {
+                    continue;
+                }
                 self.require_binary(
                     installer_idx,
                     variant_idx,
@@ -2301,74 +3608,190 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
         Ok(())
     }
 
-    fn add_pkg_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+    fn add_rpm_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
         if !self.local_artifacts_enabled() {
             return Ok(());
         }
 
         // Clone info we need from the release to avoid borrowing across the loop
         let release = self.release(to_release);
-        let Some(config) = release.config.installers.pkg.clone() else {
+        let Some(config) = release.config.installers.rpm.clone() else {
             return Ok(());
         };
         require_nonempty_installer(release, &config)?;
-        let version = release.version.clone();
-        let fragments = release.platform_support.fragments();
+        let package_name = release.app_name.clone();
+        let version = rpm_version(&release.version);
+        let description = release.app_desc.clone();
+        let license = release.app_license.clone();
+        let url = release
+            .app_homepage_url
+            .clone()
+            .or_else(|| release.app_repository_url.clone());
+        let mut docs = vec![];
+        let mut licenses = vec![];
+        for (kind, path) in &release.static_assets {
+            match kind {
+                StaticAssetKind::Readme | StaticAssetKind::Changelog => docs.push(path.clone()),
+                StaticAssetKind::License => licenses.push(path.clone()),
+                StaticAssetKind::Other => {}
+            }
+        }
 
         let variants = release.variants.clone();
         let checksum = self.inner.config.artifacts.checksum;
 
-        // Make a pkg for every darwin platform
+        // Make an rpm for every linux platform
         for variant_idx in variants {
             let variant = self.variant(variant_idx);
             let binaries = variant.binaries.clone();
-            let bin_aliases = BinaryAliases(config.bin_aliases.clone());
             let target = &variant.target;
-            if !target.is_darwin() {
+            let Some(architecture) = rpm_architecture(target) else {
                 continue;
-            }
+            };
+            let bin_aliases = BinaryAliases(config.bin_aliases.clone()).for_target(target);
 
             let variant_id = &variant.id;
-            let artifact_name = format!("{variant_id}.pkg");
+            let artifact_name = format!("{variant_id}.rpm");
             let artifact_path = self.inner.dist_dir.join(&artifact_name);
-            let dir_name = format!("{variant_id}_pkg");
+            let dir_name = format!("{variant_id}_rpm");
             let dir_path = self.inner.dist_dir.join(&dir_name);
 
-            // Compute which package we're actually building, based on the binaries
-            let mut package_info: Option<(String, PackageIdx)> = None;
+            let mut executables = vec![];
+            let mut cdylibs = vec![];
             for &binary_idx in &binaries {
                 let binary = self.binary(binary_idx);
-                if let Some((existing_spec, _)) = &package_info {
-                    // we haven't set ourselves up to bundle multiple packages yet
-                    if existing_spec != &binary.pkg_spec {
-                        return Err(DistError::MultiPackage {
-                            artifact_name,
-                            spec1: existing_spec.clone(),
-                            spec2: binary.pkg_spec.clone(),
-                        })?;
-                    }
-                } else {
-                    package_info = Some((binary.pkg_spec.clone(), binary.pkg_idx));
+                match binary.kind {
+                    BinaryKind::Executable => executables.push(binary.file_name.clone()),
+                    BinaryKind::DynamicLibrary => cdylibs.push(binary.file_name.clone()),
+                    BinaryKind::StaticLibrary => {}
                 }
             }
 
-            let Some(artifact) = fragments
-                .clone()
-                .into_iter()
-                .find(|a| a.target_triple == variant.target)
-            else {
-                return Err(DistError::NoPackage { artifact_name })?;
+            let installer_artifact = Artifact {
+                id: artifact_name,
+                target_triples: vec![target.clone()],
+                file_path: artifact_path.clone(),
+                required_binaries: FastMap::new(),
+                archive: Some(Archive {
+                    with_root: None,
+                    dir_path: dir_path.clone(),
+                    zip_style: ZipStyle::TempDir,
+                    static_assets: vec![],
+                }),
+                checksum: None,
+                kind: ArtifactKind::Installer(InstallerImpl::Rpm(RpmInstallerInfo {
+                    file_path: artifact_path.clone(),
+                    package_dir: dir_path.clone(),
+                    package_name: package_name.clone(),
+                    version: version.clone(),
+                    release: "1".to_owned(),
+                    architecture: architecture.to_owned(),
+                    description: description.clone(),
+                    license: license.clone(),
+                    url: url.clone(),
+                    executables,
+                    cdylibs,
+                    bin_aliases,
+                    docs: docs.clone(),
+                    licenses: licenses.clone(),
+                })),
+                is_global: false,
             };
 
-            let bin_aliases = bin_aliases.for_target(&variant.target);
+            // Register the artifact to various things
+            let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
+            for binary_idx in binaries {
+                let binary = self.binary(binary_idx);
+                if binary.kind == BinaryKind::StaticLibrary {
+                    continue;
+                }
+                self.require_binary(
+                    installer_idx,
+                    variant_idx,
+                    binary_idx,
+                    dir_path.join(&binary.file_name),
+                );
+            }
+            if checksum != ChecksumStyle::False {
+                self.add_artifact_checksum(variant_idx, installer_idx, checksum);
+            }
+        }
 
-            let identifier = if let Some(id) = &config.identifier {
-                id.to_owned()
-            } else {
-                return Err(DistError::MacPkgBundleIdentifierMissing {});
+        Ok(())
+    }
+
+    fn add_pypi_installer(&mut self, to_release: ReleaseIdx) -> DistResult<()> {
+        if !self.local_artifacts_enabled() {
+            return Ok(());
+        }
+
+        // Clone info we need from the release to avoid borrowing across the loop
+        let release = self.release(to_release);
+        let Some(config) = release.config.installers.pypi.clone() else {
+            return Ok(());
+        };
+        require_nonempty_installer(release, &config)?;
+        let package_name = release.app_name.clone();
+        let version = pep440_version(&release.version);
+        let description = release.app_desc.clone();
+        let homepage = release.app_homepage_url.clone();
+        let repository = release.app_repository_url.clone();
+        let license = release.app_license.clone();
+        let mut readme = None;
+        let mut licenses = vec![];
+        for (kind, path) in &release.static_assets {
+            match kind {
+                StaticAssetKind::Readme => readme = Some(path.clone()),
+                StaticAssetKind::License => licenses.push(path.clone()),
+                _ => {}
+            }
+        }
+
+        let variants = release.variants.clone();
+        let checksum = self.inner.config.artifacts.checksum;
+
+        // Make a wheel for every platform pip knows about
+        for variant_idx in variants {
+            let variant = self.variant(variant_idx);
+            let binaries = variant.binaries.clone();
+            let target = &variant.target;
+            // linux-gnu wheels are tagged with the oldest glibc they run on
+            let glibc = match config.manylinux.get(target) {
+                Some(version) => {
+                    parse_glibc_version(version).ok_or_else(|| DistError::PypiManylinuxInvalid {
+                        target: target.clone(),
+                        version: version.clone(),
+                    })?
+                }
+                None => {
+                    let glibc = GlibcVersion::default();
+                    if target.is_linux() && !target.is_musl() {
+                        warn!(
+                            "pypi-manylinux isn't set for {target}, so its wheel assumes glibc {}.{}\n  consider setting it to the oldest glibc your binaries run on",
+                            glibc.major, glibc.series
+                        );
+                    }
+                    glibc
+                }
+            };
+            let Some(platform_tag) = wheel_platform_tag(target, &glibc) else {
+                if target.is_linux() {
+                    warn!("skipping the pypi wheel for {target}: pip has no platform tag for it");
+                }
+                continue;
             };
 
-            // Gather up the bundles the installer supports
+            let artifact_name = wheel_file_name(&package_name, &version, &platform_tag);
+            let artifact_path = self.inner.dist_dir.join(&artifact_name);
+            let dir_path = self.inner.dist_dir.join(format!("{}_pypi", variant.id));
+
+            let executables = binaries
+                .iter()
+                .map(|&idx| self.binary(idx))
+                .filter(|binary| binary.kind == BinaryKind::Executable)
+                .map(|binary| binary.file_name.clone())
+                .collect();
+
             let installer_artifact = Artifact {
                 id: artifact_name,
                 target_triples: vec![target.clone()],
@@ -2381,14 +3804,21 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     static_assets: vec![],
                 }),
                 checksum: None,
-                kind: ArtifactKind::Installer(InstallerImpl::Pkg(PkgInstallerInfo {
-                    file_path: artifact_path.clone(),
-                    artifact,
+                kind: ArtifactKind::Installer(InstallerImpl::Pypi(PypiInstallerInfo {
+                    file_path: artifact_path,
                     package_dir: dir_path.clone(),
-                    identifier,
-                    install_location: config.install_location.clone(),
-                    version: version.to_string(),
-                    bin_aliases,
+                    package_name: package_name.clone(),
+                    version: version.clone(),
+                    target: target.clone(),
+                    platform_tag,
+                    manylinux: (target.is_linux() && !target.is_musl()).then_some(glibc),
+                    description: description.clone(),
+                    homepage: homepage.clone(),
+                    repository: repository.clone(),
+                    license: license.clone(),
+                    readme: readme.clone(),
+                    licenses: licenses.clone(),
+                    executables,
                 })),
                 is_global: false,
             };
@@ -2397,6 +3827,9 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             let installer_idx = self.add_local_artifact(variant_idx, installer_artifact);
             for binary_idx in binaries {
                 let binary = self.binary(binary_idx);
+                if binary.kind != BinaryKind::Executable {
+                    continue;
+                }
                 self.require_binary(
                     installer_idx,
                     variant_idx,
@@ -2533,6 +3966,9 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                         target_filename: artifact.file_path.to_owned(),
                     }))
                 }
+                ArtifactKind::OciImage(image) => {
+                    build_steps.push(BuildStep::GenerateOciImage(image.clone()));
+                }
             }
 
             if let Some(archive) = &artifact.archive {
@@ -2690,6 +4126,9 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
             // Add any extra artifacts defined in the config
             self.add_extra_artifacts(&app_config, release);
 
+            // Add container images
+            self.add_oci_images(release)?;
+
             // Add installers to the Release
             // Prefer the CLI's choices (`cfg`) if they're non-empty
             let installers = if cfg.installers.is_empty() {
@@ -2697,9 +4136,16 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     InstallerStyle::Shell,
                     InstallerStyle::Powershell,
                     InstallerStyle::Homebrew,
+                    InstallerStyle::Scoop,
                     InstallerStyle::Npm,
                     InstallerStyle::Msi,
                     InstallerStyle::Pkg,
+                    InstallerStyle::Winget,
+                    InstallerStyle::Deb,
+                    InstallerStyle::Rpm,
+                    InstallerStyle::Aur,
+                    InstallerStyle::Nix,
+                    InstallerStyle::Pypi,
                 ]
             } else {
                 &cfg.installers[..]
@@ -2710,9 +4156,19 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     InstallerStyle::Shell => self.add_shell_installer(release)?,
                     InstallerStyle::Powershell => self.add_powershell_installer(release)?,
                     InstallerStyle::Homebrew => self.add_homebrew_installer(release)?,
+                    InstallerStyle::Scoop => self.add_scoop_installer(release)?,
                     InstallerStyle::Npm => self.add_npm_installer(release)?,
                     InstallerStyle::Msi => self.add_msi_installer(release)?,
-                    InstallerStyle::Pkg => self.add_pkg_installer(release)?,
+                    InstallerStyle::Pkg => {
+                        self.add_pkg_installer(release)?;
+                        self.add_homebrew_cask(release)?;
+                    }
+                    InstallerStyle::Winget => self.add_winget_installer(release)?,
+                    InstallerStyle::Deb => self.add_deb_installer(release)?,
+                    InstallerStyle::Rpm => self.add_rpm_installer(release)?,
+                    InstallerStyle::Aur => self.add_aur_installer(release)?,
+                    InstallerStyle::Nix => self.add_nix_installer(release)?,
+                    InstallerStyle::Pypi => self.add_pypi_installer(release)?,
                 }
             }
 
@@ -2727,18 +4183,33 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
     }
 
     fn compute_ci(&mut self) -> DistResult<()> {
-        let CiConfig { github } = &self.inner.config.ci;
+        let CiConfig {
+            github,
+            gitlab,
+            forgejo,
+        } = &self.inner.config.ci;
 
         let mut has_ci = false;
         if let Some(github_config) = github {
             has_ci = true;
             self.inner.ci.github = Some(GithubCiInfo::new(&self.inner, github_config)?);
         }
+        if let Some(gitlab_config) = gitlab {
+            self.inner.ci.gitlab = Some(GitlabCiInfo::new(&self.inner, gitlab_config)?);
+        }
+        if let Some(forgejo_config) = forgejo {
+            has_ci = true;
+            self.inner.ci.forgejo = Some(GithubCiInfo::new_forgejo(&self.inner, forgejo_config)?);
+        }
 
         // apply to manifest
         if has_ci {
-            let CiInfo { github } = &self.inner.ci;
-            let github = github.as_ref().map(|info| {
+            let CiInfo {
+                github,
+                gitlab: _,
+                forgejo,
+            } = &self.inner.ci;
+            let to_manifest = |info: &GithubCiInfo| {
                 let external_repo_commit = info
                     .github_release
                     .as_ref()
@@ -2748,9 +4219,11 @@ impl<'pkg_graph> DistGraphBuilder<'pkg_graph> {
                     pr_run_mode: Some(info.pr_run_mode),
                     external_repo_commit,
                 }
-            });
+            };
+            let github = github.as_ref().map(to_manifest);
+            let forgejo = forgejo.as_ref().map(to_manifest);
 
-            self.manifest.ci = Some(cargo_dist_schema::CiInfo { github });
+            self.manifest.ci = Some(cargo_dist_schema::CiInfo { github, forgejo });
         }
 
         Ok(())
@@ -2845,6 +4318,7 @@ pub fn gather_work(cfg: &Config) -> DistResult<(DistGraph, DistManifest)> {
         &mut workspaces,
         cfg.artifact_mode,
         cfg.allow_all_dirty,
+        cfg.offline,
         matches!(cfg.tag_settings.tag, TagMode::Infer),
     )?;
 
@@ -3080,6 +4554,9 @@ pub struct InstallReceipt {
     pub binary_aliases: BTreeMap<String, Vec<String>>,
     /// Whether or not to modify system paths when installing
     pub modify_path: bool,
+    /// The base URL the artifacts were downloaded from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
 }
 
 impl InstallReceipt {
@@ -3116,6 +4593,7 @@ impl InstallReceipt {
             },
             binary_aliases: BTreeMap::default(),
             modify_path: true,
+            download_url: None,
         })
     }
 }
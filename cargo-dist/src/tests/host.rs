@@ -20,6 +20,7 @@ fn mock_announce(workspaces: &mut WorkspaceGraph) -> (DistGraphBuilder, Announce
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -268,3 +269,47 @@ fn github_trail_slash() {
     assert_eq!(hosting.project, REPO_PROJECT);
     assert_eq!(hosting.source_host, "github");
 }
+
+#[test]
+fn s3_simple() {
+    // ci = "github" and hosting = "s3"
+    let mut workspaces = workspace_unified();
+    let hosting = Some(vec![HostingStyle::S3]);
+    let ci = Some(vec![CiStyle::Github]);
+
+    let (_graph, announcing) = mock_announce(&mut workspaces);
+    let hosting = select_hosting(&workspaces, &announcing, hosting, ci.as_deref());
+
+    let hosting = hosting.unwrap().unwrap();
+    assert_eq!(hosting.repo_url, REPO_URL);
+    assert_eq!(hosting.hosts, &[HostingStyle::S3]);
+    assert_eq!(hosting.owner, REPO_OWNER);
+    assert_eq!(hosting.project, REPO_PROJECT);
+    assert_eq!(hosting.source_host, "github");
+}
+
+#[test]
+fn s3_not_github_repository() {
+    // S3 doesn't care where the source lives
+    const NOT_GH_REPO_URL: &str = "https://mysourcehost.com/mycoolorg/radproj";
+
+    let mut workspaces = workspace_unified();
+    let num_packages = workspaces.all_packages().count();
+    for pkg_idx in 0..num_packages {
+        let package = workspaces.package_mut(PackageIdx(pkg_idx));
+        package.repository_url = Some(NOT_GH_REPO_URL.to_owned());
+    }
+
+    let hosting = Some(vec![HostingStyle::S3]);
+    let ci = Some(vec![CiStyle::Gitlab]);
+
+    let (_graph, announcing) = mock_announce(&mut workspaces);
+    let hosting = select_hosting(&workspaces, &announcing, hosting, ci.as_deref());
+
+    let hosting = hosting.unwrap().unwrap();
+    assert_eq!(hosting.repo_url, NOT_GH_REPO_URL);
+    assert_eq!(hosting.hosts, &[HostingStyle::S3]);
+    assert_eq!(hosting.owner, "mycoolorg");
+    assert_eq!(hosting.project, "radproj");
+    assert_eq!(hosting.source_host, "git");
+}
@@ -0,0 +1,105 @@
+//! A tiny HTTP server for testing the code that talks to web APIs
+//!
+//! It answers every request with whatever the given handler returns,
+//! and remembers the requests so tests can assert on them afterwards.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::SortedMap;
+
+/// A request the mock server received
+#[derive(Debug)]
+pub struct MockRequest {
+    pub method: String,
+    /// The path (and query) that was requested
+    pub path: String,
+    /// Headers, with lowercased names
+    pub headers: SortedMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    /// Get a header by (lowercase) name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|v| v.as_str())
+    }
+
+    /// The request line, like `GET /some/path`
+    pub fn call(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+}
+
+/// A running mock server, which lives until the test process exits
+pub struct MockServer {
+    /// The base URL to send requests to, like `http://127.0.0.1:1234`
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Start a server that answers each request with the `(status, body)` the handler returns
+    pub fn start(
+        respond: impl Fn(&MockRequest) -> (&'static str, &'static str) + Send + 'static,
+    ) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = read_request(&mut stream);
+                let (status, body) = respond(&request);
+                log.lock().unwrap().push(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        Self { url, requests }
+    }
+
+    /// The requests received so far, in order
+    pub fn requests(&self) -> MutexGuard<'_, Vec<MockRequest>> {
+        self.requests.lock().unwrap()
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> MockRequest {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap().to_owned();
+    let path = parts.next().unwrap().to_owned();
+
+    let mut headers = SortedMap::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).unwrap();
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (key, value) = header.split_once(": ").unwrap();
+        headers.insert(key.to_ascii_lowercase(), value.to_owned());
+    }
+    let content_length = headers
+        .get("content-length")
+        .map(|len| len.parse().unwrap())
+        .unwrap_or(0);
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    MockRequest {
+        method,
+        path,
+        headers,
+        body,
+    }
+}
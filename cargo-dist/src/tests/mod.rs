@@ -1,4 +1,5 @@
 mod config;
 mod host;
-mod mock;
+pub mod http;
+pub mod mock;
 mod tag;
@@ -25,6 +25,7 @@ fn parse_one() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -54,6 +55,7 @@ fn parse_one_v() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -83,6 +85,7 @@ fn parse_one_package_v() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -112,6 +115,7 @@ fn parse_one_package() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -141,6 +145,7 @@ fn parse_one_v_alpha() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -170,6 +175,7 @@ fn parse_one_package_v_alpha() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -199,6 +205,7 @@ fn parse_one_prefix_slashv() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -228,6 +235,7 @@ fn parse_one_prefix_slash() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -257,6 +265,7 @@ fn parse_one_prefix_slash_package_v() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -286,6 +295,7 @@ fn parse_one_prefix_slash_package_slashv() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -315,6 +325,7 @@ fn parse_one_package_slashv() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -344,6 +355,7 @@ fn parse_one_prefix_slash_package_slash() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -373,6 +385,7 @@ fn parse_one_prefix_many_slash_package_slash() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -402,6 +415,7 @@ fn parse_one_package_slash() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -431,6 +445,7 @@ fn parse_one_infer() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -460,6 +475,7 @@ fn parse_unified_v() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -492,6 +508,7 @@ fn parse_unified_infer() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -524,6 +541,7 @@ fn parse_unified_lib() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -553,6 +571,7 @@ fn parse_disjoint_v() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -586,6 +605,7 @@ fn parse_disjoint_infer() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -618,6 +638,7 @@ fn parse_disjoint_v_oddball() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -647,6 +668,7 @@ fn parse_disjoint_lib() {
         ArtifactMode::All,
         true,
         false,
+        false,
     )
     .unwrap();
     let settings = TagSettings {
@@ -0,0 +1,91 @@
+const { spawnSync } = require("child_process");
+
+const error = (msg) => {
+  console.error(msg);
+  process.exit(1);
+};
+
+const { name, supportedPlatforms, glibcMinimum } = require("./package.json");
+
+// Whether we'd rather run musl binaries than glibc ones on this machine
+const prefersMusl = () => {
+  if (process.platform !== "linux") {
+    return false;
+  }
+  // Builds of node linked against glibc report which version they're running with
+  const report =
+    process.report && typeof process.report.getReport === "function"
+      ? process.report.getReport()
+      : null;
+  const glibcVersion = report && report.header.glibcVersionRuntime;
+  if (!glibcVersion) {
+    return true;
+  }
+  const [major, minor] = glibcVersion.split(".").map(Number);
+  if (major != glibcMinimum.major || minor < glibcMinimum.series) {
+    // We can't run the glibc binaries, but we can run the musl ones
+    // if they exist
+    console.warn("Your glibc isn't compatible; trying musl binary instead");
+    return true;
+  }
+  return false;
+};
+
+const getBinaryPath = (binaryName) => {
+  // npm only installs the packages whose os/cpu/libc match this machine,
+  // but older versions of npm don't know about libc, so we might have both
+  // a glibc and a musl package to choose from.
+  const musl = prefersMusl();
+  const candidates = Object.values(supportedPlatforms)
+    .filter(
+      (platform) =>
+        platform.os === process.platform && platform.cpu === process.arch,
+    )
+    .sort(
+      (a, b) =>
+        Number((a.libc === "musl") !== musl) -
+        Number((b.libc === "musl") !== musl),
+    );
+
+  for (const platform of candidates) {
+    const binRelPath = platform.bins[binaryName];
+    if (!binRelPath) {
+      continue;
+    }
+    try {
+      return require.resolve(`${platform.packageName}/${binRelPath}`);
+    } catch (e) {
+      // This platform's package wasn't installed, try the next one
+    }
+  }
+
+  if (candidates.length === 0) {
+    error(
+      `Platform "${process.platform}" with architecture "${process.arch}" is not supported by ${name}.\nYour system must be one of the following:\n\n${Object.keys(
+        supportedPlatforms,
+      ).join(",")}`,
+    );
+  }
+  error(
+    `The package with ${binaryName} for your platform wasn't installed. Make sure you aren't installing ${name} with --no-optional or --omit=optional, and that your lockfile wasn't generated on a different platform.`,
+  );
+};
+
+const run = (binaryName) => {
+  const binPath = getBinaryPath(binaryName);
+  const [, , ...args] = process.argv;
+
+  const options = { cwd: process.cwd(), stdio: "inherit" };
+  const result = spawnSync(binPath, args, options);
+
+  if (result.error) {
+    error(result.error);
+  }
+
+  process.exit(result.status);
+};
+
+module.exports = {
+  run,
+  getBinaryPath,
+};
@@ -6,6 +6,9 @@ const { tmpdir } = require("os");
 const axios = require("axios");
 const rimraf = require("rimraf");
 const tmpDir = tmpdir();
+// How long to wait on an unresponsive server before trying the next one
+const downloadTimeout =
+  (parseInt(process.env.INSTALLER_DOWNLOAD_TIMEOUT) || 30) * 1000;
 
 const error = (msg) => {
   console.error(msg);
@@ -13,7 +16,7 @@ const error = (msg) => {
 };
 
 class Package {
-  constructor(name, url, filename, zipExt, binaries) {
+  constructor(name, url, filename, zipExt, binaries, fallbackUrls = []) {
     let errors = [];
     if (typeof url !== "string") {
       errors.push("url must be a string");
@@ -48,6 +51,7 @@ class Package {
       error(errorMsg);
     }
     this.url = url;
+    this.fallbackUrls = fallbackUrls;
     this.name = name;
     this.filename = filename;
     this.zipExt = zipExt;
@@ -86,66 +90,113 @@ class Package {
 
     mkdirSync(this.installDirectory, { recursive: true });
 
-    if (!suppressLogs) {
-      console.error(`Downloading release from ${this.url}`);
-    }
-
-    return axios({ ...fetchOptions, url: this.url, responseType: "stream" })
-      .then((res) => {
-        return new Promise((resolve, reject) => {
-          mkdtemp(`${tmpDir}${sep}`, (err, directory) => {
-            let tempFile = join(directory, this.filename);
-            const sink = res.data.pipe(createWriteStream(tempFile));
-            sink.on("error", (err) => reject(err));
-            sink.on("close", () => {
-              if (/\.tar\.*/.test(this.zipExt)) {
-                const result = spawnSync("tar", [
-                  "xf",
-                  tempFile,
-                  // The tarballs are stored with a leading directory
-                  // component; we strip one component in the
-                  // shell installers too.
-                  "--strip-components",
-                  "1",
-                  "-C",
-                  this.installDirectory,
-                ]);
-                if (result.status == 0) {
-                  resolve();
-                } else if (result.error) {
-                  reject(result.error);
-                } else {
-                  reject(
-                    new Error(
-                      `An error occurred untarring the artifact: stdout: ${result.stdout}; stderr: ${result.stderr}`,
-                    ),
-                  );
-                }
-              } else if (this.zipExt == ".zip") {
-                const result = spawnSync("unzip", [
-                  "-q",
-                  tempFile,
-                  "-d",
-                  this.installDirectory,
-                ]);
-                if (result.status == 0) {
-                  resolve();
-                } else if (result.error) {
-                  reject(result.error);
-                } else {
-                  reject(
-                    new Error(
-                      `An error occurred unzipping the artifact: stdout: ${result.stdout}; stderr: ${result.stderr}`,
-                    ),
-                  );
-                }
-              } else {
-                reject(
-                  new Error(`Unrecognized file extension: ${this.zipExt}`),
+    // Try each URL in turn, sticking with the first one that answers. A server
+    // that stops sending partway through counts as not answering, too.
+    const urls = [this.url, ...this.fallbackUrls];
+    const timeout = urls.length > 1 ? downloadTimeout : 0;
+    const download = (url, tempFile) =>
+      axios({ ...fetchOptions, url, responseType: "stream", timeout }).then(
+        (res) =>
+          new Promise((resolve, reject) => {
+            const sink = createWriteStream(tempFile);
+            let idle;
+            const fail = (err) => {
+              clearTimeout(idle);
+              reject(err);
+              res.data.destroy();
+              sink.destroy();
+            };
+            const resetIdle = () => {
+              if (timeout) {
+                clearTimeout(idle);
+                idle = setTimeout(
+                  () =>
+                    fail(new Error(`no data received for ${timeout / 1000}s`)),
+                  timeout,
                 );
               }
+            };
+            resetIdle();
+            res.data.on("data", resetIdle);
+            res.data.on("error", fail);
+            sink.on("error", fail);
+            sink.on("close", () => {
+              clearTimeout(idle);
+              resolve();
             });
-          });
+            res.data.pipe(sink);
+          }),
+      );
+    const fetchFrom = (index, tempFile) => {
+      const url = urls[index];
+      if (!suppressLogs) {
+        console.error(`Downloading release from ${url}`);
+      }
+      return download(url, tempFile).catch((e) => {
+        if (index + 1 >= urls.length) {
+          throw e;
+        }
+        console.error(`Error fetching release from ${url}: ${e.message}`);
+        return fetchFrom(index + 1, tempFile);
+      });
+    };
+
+    return new Promise((resolve, reject) => {
+      mkdtemp(`${tmpDir}${sep}`, (err, directory) => {
+        if (err) {
+          reject(err);
+        } else {
+          resolve(join(directory, this.filename));
+        }
+      });
+    })
+      .then((tempFile) => fetchFrom(0, tempFile).then(() => tempFile))
+      .then((tempFile) => {
+        return new Promise((resolve, reject) => {
+          if (/\.tar\.*/.test(this.zipExt)) {
+            const result = spawnSync("tar", [
+              "xf",
+              tempFile,
+              // The tarballs are stored with a leading directory
+              // component; we strip one component in the
+              // shell installers too.
+              "--strip-components",
+              "1",
+              "-C",
+              this.installDirectory,
+            ]);
+            if (result.status == 0) {
+              resolve();
+            } else if (result.error) {
+              reject(result.error);
+            } else {
+              reject(
+                new Error(
+                  `An error occurred untarring the artifact: stdout: ${result.stdout}; stderr: ${result.stderr}`,
+                ),
+              );
+            }
+          } else if (this.zipExt == ".zip") {
+            const result = spawnSync("unzip", [
+              "-q",
+              tempFile,
+              "-d",
+              this.installDirectory,
+            ]);
+            if (result.status == 0) {
+              resolve();
+            } else if (result.error) {
+              reject(result.error);
+            } else {
+              reject(
+                new Error(
+                  `An error occurred unzipping the artifact: stdout: ${result.stdout}; stderr: ${result.stderr}`,
+                ),
+              );
+            }
+          } else {
+            reject(new Error(`Unrecognized file extension: ${this.zipExt}`));
+          }
         });
       })
       .then(() => {
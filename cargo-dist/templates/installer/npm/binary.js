@@ -12,6 +12,7 @@ const error = (msg) => {
 const {
   name,
   artifactDownloadUrl,
+  fallbackDownloadUrls,
   supportedPlatforms,
   glibcMinimum,
 } = require("./package.json");
@@ -94,9 +95,19 @@ const getPlatform = () => {
 const getPackage = () => {
   const platform = getPlatform();
   const url = `${artifactDownloadUrl}/${platform.artifactName}`;
+  const fallbackUrls = (fallbackDownloadUrls || []).map(
+    (baseUrl) => `${baseUrl}/${platform.artifactName}`,
+  );
   let filename = platform.artifactName;
   let ext = platform.zipExt;
-  let binary = new Package(name, url, filename, ext, platform.bins);
+  let binary = new Package(
+    name,
+    url,
+    filename,
+    ext,
+    platform.bins,
+    fallbackUrls,
+  );
 
   return binary;
 };
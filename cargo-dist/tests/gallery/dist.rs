@@ -102,8 +102,15 @@ pub struct AppResult {
     bins: Vec<String>,
     shell_installer_path: Option<Utf8PathBuf>,
     homebrew_installer_path: Option<Utf8PathBuf>,
+    homebrew_cask_path: Option<Utf8PathBuf>,
     powershell_installer_path: Option<Utf8PathBuf>,
+    shell_uninstaller_path: Option<Utf8PathBuf>,
+    powershell_uninstaller_path: Option<Utf8PathBuf>,
     npm_installer_package_path: Option<Utf8PathBuf>,
+    scoop_installer_path: Option<Utf8PathBuf>,
+    winget_manifests_path: Option<Utf8PathBuf>,
+    aur_package_path: Option<Utf8PathBuf>,
+    nix_flake_path: Option<Utf8PathBuf>,
     unified_checksum_path: Option<Utf8PathBuf>,
 }
 
@@ -115,6 +122,8 @@ pub struct PlanResult {
 pub struct GenerateResult {
     test_name: String,
     github_ci_path: Option<Utf8PathBuf>,
+    gitlab_ci_path: Option<Utf8PathBuf>,
+    forgejo_ci_path: Option<Utf8PathBuf>,
     wxs_path: Option<Utf8PathBuf>,
 }
 
@@ -204,11 +213,19 @@ impl<'a> TestContext<'a, Tools> {
     ) -> Result<GenerateResult> {
         let ci_file_name = format!("{prefix}release.yml");
         let github_ci_path = Utf8Path::new(".github/workflows/").join(ci_file_name);
+        let gitlab_ci_path = Utf8Path::new(".gitlab-ci.yml").to_owned();
+        let forgejo_ci_path = Utf8Path::new(".forgejo/workflows/release.yml").to_owned();
         let wxs_path = Utf8Path::new("wix/main.wxs").to_owned();
         // Delete files if they already exist
         if github_ci_path.exists() {
             LocalAsset::remove_file(&github_ci_path)?;
         }
+        if gitlab_ci_path.exists() {
+            LocalAsset::remove_file(&gitlab_ci_path)?;
+        }
+        if forgejo_ci_path.exists() {
+            LocalAsset::remove_file(&forgejo_ci_path)?;
+        }
         if wxs_path.exists() {
             LocalAsset::remove_file(&wxs_path)?;
         }
@@ -222,6 +239,8 @@ impl<'a> TestContext<'a, Tools> {
         Ok(GenerateResult {
             test_name: test_name.to_owned(),
             github_ci_path: github_ci_path.exists().then_some(github_ci_path),
+            gitlab_ci_path: gitlab_ci_path.exists().then_some(gitlab_ci_path),
+            forgejo_ci_path: forgejo_ci_path.exists().then_some(forgejo_ci_path),
             wxs_path: wxs_path.exists().then_some(wxs_path),
         })
     }
@@ -235,10 +254,20 @@ impl<'a> TestContext<'a, Tools> {
             let target_dir = Utf8PathBuf::from("target/distrib");
             let ps_installer = Utf8PathBuf::from(format!("{target_dir}/{app_name}-installer.ps1"));
             let sh_installer = Utf8PathBuf::from(format!("{target_dir}/{app_name}-installer.sh"));
+            let ps_uninstaller =
+                Utf8PathBuf::from(format!("{target_dir}/{app_name}-uninstaller.ps1"));
+            let sh_uninstaller =
+                Utf8PathBuf::from(format!("{target_dir}/{app_name}-uninstaller.sh"));
             let brew_app_name = self.options.homebrew_package_name(&app_name);
             let homebrew_installer = Utf8PathBuf::from(format!("{target_dir}/{brew_app_name}.rb"));
+            let homebrew_cask = Utf8PathBuf::from(format!("{target_dir}/{brew_app_name}.cask.rb"));
             let npm_installer =
                 Utf8PathBuf::from(format!("{target_dir}/{app_name}-npm-package.tar.gz"));
+            let scoop_installer = Utf8PathBuf::from(format!("{target_dir}/{app_name}.json"));
+            let winget_manifests =
+                Utf8PathBuf::from(format!("{target_dir}/{app_name}-winget.tar.gz"));
+            let aur_package = Utf8PathBuf::from(format!("{target_dir}/{app_name}-aur.tar.gz"));
+            let nix_flake = Utf8PathBuf::from(format!("{target_dir}/{app_name}-nix.tar.gz"));
             let unified_checksum_path = Utf8PathBuf::from(format!("{target_dir}/sha256.sum"));
             app_results.push(AppResult {
                 test_name: test_name.to_owned(),
@@ -247,8 +276,15 @@ impl<'a> TestContext<'a, Tools> {
                 bins: app.bins.iter().map(|s| s.to_string()).collect(),
                 shell_installer_path: sh_installer.exists().then_some(sh_installer),
                 powershell_installer_path: ps_installer.exists().then_some(ps_installer),
+                shell_uninstaller_path: sh_uninstaller.exists().then_some(sh_uninstaller),
+                powershell_uninstaller_path: ps_uninstaller.exists().then_some(ps_uninstaller),
                 homebrew_installer_path: homebrew_installer.exists().then_some(homebrew_installer),
+                homebrew_cask_path: homebrew_cask.exists().then_some(homebrew_cask),
                 npm_installer_package_path: npm_installer.exists().then_some(npm_installer),
+                scoop_installer_path: scoop_installer.exists().then_some(scoop_installer),
+                winget_manifests_path: winget_manifests.exists().then_some(winget_manifests),
+                aur_package_path: aur_package.exists().then_some(aur_package),
+                nix_flake_path: nix_flake.exists().then_some(nix_flake),
                 unified_checksum_path: unified_checksum_path
                     .exists()
                     .then_some(unified_checksum_path),
@@ -104,6 +104,8 @@ impl AppResult {
                     .env("UserProfile", &tempdir)
                     .env("LOCALAPPDATA", &appdata)
                     .env("MY_ENV_VAR", &app_home)
+                    // We download the published archives, which won't match our local checksums
+                    .env("INSTALLER_NO_VERIFY", "1")
                     .env_remove("CARGO_HOME")
                     .env_remove("PSModulePath")
             })?;
@@ -184,22 +186,24 @@ impl AppResult {
 
     /// Run PSScriptAnalyzer on the powershell scripts
     pub fn psanalyzer(&self, ctx: &TestContext<Tools>) -> Result<()> {
-        // Only do this if the script is available
-        let Some(script) = &self.powershell_installer_path else {
-            return Ok(());
-        };
         // Only do this if the tool is available
         let Some(psanalyzer) = &ctx.tools.psanalyzer else {
             return Ok(());
         };
+        // Only do this for the scripts that are available
+        let scripts = [
+            &self.powershell_installer_path,
+            &self.powershell_uninstaller_path,
+        ];
+        for script in scripts.into_iter().flatten() {
+            eprintln!("PSScriptAnalyzing {script}");
+            let output = psanalyzer.output(|cmd| cmd.arg(script).arg("-EnableExit"))?;
 
-        eprintln!("PSScriptAnalyzing {script}");
-        let output = psanalyzer.output(|cmd| cmd.arg(script).arg("-EnableExit"))?;
-
-        if !output.status.success() {
-            eprintln!("{}", String::from_utf8_lossy(&output.stdout));
-            eprintln!("see https://learn.microsoft.com/en-ca/powershell/utility-modules/psscriptanalyzer/rules/readme\n");
-            return Err(miette!("PsScriptAnalyzer found issues"));
+            if !output.status.success() {
+                eprintln!("{}", String::from_utf8_lossy(&output.stdout));
+                eprintln!("see https://learn.microsoft.com/en-ca/powershell/utility-modules/psscriptanalyzer/rules/readme\n");
+                return Err(miette!("PsScriptAnalyzer found issues"));
+            }
         }
         Ok(())
     }
@@ -42,11 +42,14 @@ impl AppResult {
             // HOME="{tempdir}"            (for install-path=~/... and install-path=CARGO_HOME)
             // MY_ENV_VAR=".{app_name}"    (for install-path=$MY_ENV_VAR/...)
             // CARGO_HOME=null             (cargo test sets this so we have to clear it)
+            // INSTALLER_NO_VERIFY=1       (the script downloads the published archives,
+            //                              which won't match our local checksums)
             let app_home = tempdir.join(format!(".{app_name}"));
             let _output = script.output_checked(|cmd| {
                 cmd.env("HOME", &tempdir)
                     .env("ZDOTDIR", &tempdir)
                     .env("MY_ENV_VAR", &app_home)
+                    .env("INSTALLER_NO_VERIFY", "1")
                     .env_remove("CARGO_HOME")
             })?;
             // we could theoretically look at the above output and parse out the `source` line...
@@ -118,26 +121,67 @@ impl AppResult {
 
             // Check the install receipts
             self.check_install_receipt(ctx, &bin_dir, &receipt_file, "");
+
+            // If there's an uninstaller, check that it undoes all of the above
+            let Some(uninstaller_path) = &self.shell_uninstaller_path else {
+                return Ok(());
+            };
+            eprintln!("running uninstaller.sh...");
+            std::fs::set_permissions(uninstaller_path, std::fs::Permissions::from_mode(0o755))
+                .unwrap();
+            let script =
+                CommandInfo::new_unchecked("uninstaller.sh", Some(uninstaller_path.as_str()));
+            let _output = script.output_checked(|cmd| {
+                cmd.env("HOME", &tempdir)
+                    .env("ZDOTDIR", &tempdir)
+                    .env_remove("CARGO_HOME")
+            })?;
+
+            for bin_name in ctx.options.bins_with_aliases(&self.app_name, &self.bins) {
+                let bin_path = bin_dir.join(&bin_name);
+                assert!(!bin_path.exists(), "{bin_path} wasn't removed");
+            }
+            assert!(!env_script.exists(), "env script wasn't removed");
+            assert!(
+                !env_dir.join("env.fish").exists(),
+                "fish env script wasn't removed"
+            );
+            assert!(!receipt_file.exists(), "install receipt wasn't removed");
+            // The rcfiles themselves stay, but the lines we added to them shouldn't
+            let env_script_expr = format!(
+                "$HOME/{}",
+                env_script
+                    .strip_prefix(&tempdir)
+                    .expect("env script outside HOME?!")
+            );
+            for rcfile in rcfiles {
+                let contents = std::fs::read_to_string(rcfile).unwrap();
+                assert!(
+                    !contents.contains(&env_script_expr) && !contents.contains(env_script.as_str()),
+                    "{rcfile} still sources the env script:\n{contents}"
+                );
+            }
+            eprintln!("uninstaller.sh worked!");
         }
         Ok(())
     }
 
     /// Run shellcheck on the shell scripts
     pub fn shellcheck(&self, ctx: &TestContext<Tools>) -> Result<()> {
-        // Only do this if the script is available
-        let Some(script) = &self.shell_installer_path else {
-            return Ok(());
-        };
         // Only do this if the tool is available
         let Some(shellcheck) = &ctx.tools.shellcheck else {
             return Ok(());
         };
-        eprintln!("shellchecking {script}");
-        let output = shellcheck.output(|cmd| cmd.arg(script))?;
+        // Only do this for the scripts that are available
+        let scripts = [&self.shell_installer_path, &self.shell_uninstaller_path];
+        for script in scripts.into_iter().flatten() {
+            eprintln!("shellchecking {script}");
+            let output = shellcheck.output(|cmd| cmd.arg(script))?;
 
-        if !output.status.success() {
-            eprintln!("{}", String::from_utf8_lossy(&output.stdout));
-            return Err(miette!("shellcheck found issues"));
+            if !output.status.success() {
+                eprintln!("{}", String::from_utf8_lossy(&output.stdout));
+                return Err(miette!("shellcheck found issues"));
+            }
         }
         Ok(())
     }
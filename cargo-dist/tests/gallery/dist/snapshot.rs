@@ -30,6 +30,14 @@ impl DistResult {
                     .unwrap_or_default(),
                 app.homebrew_installer_path.as_deref(),
             )?;
+            append_snapshot_file(
+                &mut snapshots,
+                app.homebrew_cask_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.homebrew_cask_path.as_deref(),
+            )?;
             append_snapshot_file(
                 &mut snapshots,
                 app.powershell_installer_path
@@ -38,6 +46,22 @@ impl DistResult {
                     .unwrap_or_default(),
                 app.powershell_installer_path.as_deref(),
             )?;
+            append_snapshot_file(
+                &mut snapshots,
+                app.shell_uninstaller_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.shell_uninstaller_path.as_deref(),
+            )?;
+            append_snapshot_file(
+                &mut snapshots,
+                app.powershell_uninstaller_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.powershell_uninstaller_path.as_deref(),
+            )?;
             append_snapshot_tarball(
                 &mut snapshots,
                 app.npm_installer_package_path
@@ -46,6 +70,38 @@ impl DistResult {
                     .unwrap_or_default(),
                 app.npm_installer_package_path.as_deref(),
             )?;
+            append_snapshot_file(
+                &mut snapshots,
+                app.scoop_installer_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.scoop_installer_path.as_deref(),
+            )?;
+            append_snapshot_tarball(
+                &mut snapshots,
+                app.winget_manifests_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.winget_manifests_path.as_deref(),
+            )?;
+            append_snapshot_tarball(
+                &mut snapshots,
+                app.aur_package_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.aur_package_path.as_deref(),
+            )?;
+            append_snapshot_tarball(
+                &mut snapshots,
+                app.nix_flake_path
+                    .as_deref()
+                    .and_then(|p| p.file_name())
+                    .unwrap_or_default(),
+                app.nix_flake_path.as_deref(),
+            )?;
             append_snapshot_file(
                 &mut snapshots,
                 app.unified_checksum_path
@@ -97,6 +153,19 @@ impl GenerateResult {
                 .unwrap_or_default(),
             self.github_ci_path.as_deref(),
         )?;
+        append_snapshot_file(
+            &mut snapshots,
+            self.gitlab_ci_path
+                .as_deref()
+                .and_then(|p| p.file_name())
+                .unwrap_or_default(),
+            self.gitlab_ci_path.as_deref(),
+        )?;
+        append_snapshot_file(
+            &mut snapshots,
+            "forgejo/release.yml",
+            self.forgejo_ci_path.as_deref(),
+        )?;
 
         append_snapshot_file(&mut snapshots, "main.wxs", self.wxs_path.as_deref())?;
 
@@ -155,6 +224,14 @@ pub fn snapshot_settings_with_gallery_filter() -> insta::Settings {
     settings.add_filter(r#"sha256 ".*""#, r#"sha256 "CENSORED""#);
     settings.add_filter(r#""sha256": .*"#, r#""sha256": "CENSORED""#);
     settings.add_filter(r#""sha512": .*"#, r#""sha512": "CENSORED""#);
+    settings.add_filter(
+        r#"_checksum_value="[0-9a-f]+""#,
+        r#"_checksum_value="CENSORED""#,
+    );
+    settings.add_filter(
+        r#""checksum_value" = "[0-9a-f]+""#,
+        r#""checksum_value" = "CENSORED""#,
+    );
     settings.add_filter(r#""version":"[a-zA-Z\.0-9\-]*""#, r#""version":"CENSORED""#);
     settings.add_filter(
         r#""build_environment": \{\n\s+"macos": \{\n\s+"os_version": ".+"\n\s+}\n\s+}"#,
@@ -176,6 +253,14 @@ pub fn snapshot_settings_with_gallery_filter() -> insta::Settings {
         r"[0-9a-f]{64}  ([a-zA-Z0-9-_]+)\.pkg",
         "CENSORED (see https://github.com/axodotdev/cargo-dist/issues/1477)  CENSORED.pkg",
     );
+    settings.add_filter(
+        r"[0-9a-f]{64}  ([a-zA-Z0-9-_]+)\.zip",
+        "CENSORED (see https://github.com/axodotdev/cargo-dist/issues/1477)  CENSORED.zip",
+    );
+    settings.add_filter(
+        r"InstallerSha256: '?[0-9A-F]{64}'?",
+        "InstallerSha256: 'CENSORED'",
+    );
     settings
 }
 